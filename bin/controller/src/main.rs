@@ -2,65 +2,211 @@ use std::sync::Arc;
 
 use futures::StreamExt as _;
 use k8s_openapi::api::batch::v1::{CronJob, Job};
-use kube::{Api, Client, runtime::controller::Controller};
+use k8s_openapi::api::core::v1::Namespace;
+use std::fmt::Debug;
+
+use kube::core::crd::v1::CustomResourceExt as _;
+use kube::runtime::controller::{self, Action, Controller};
+use kube::runtime::reflector::ObjectRef;
+use kube::{Api, Client, Resource, ResourceExt as _};
+use scheduled::loglevel::LogLevel;
 use scheduled::{
-    Context,
-    crd::{DelayedJob, ScheduledCronJob},
-    reconciler::{reconcile_delayed_job, reconcile_scheduled_cronjob},
+    Config, Context,
+    crd::{
+        ControllerConfiguration, DelayedJob, ScheduleCalendar, ScheduledCronJob, ScheduledPatch,
+        ScheduledSuspend, TimerTrigger,
+    },
+    reconciler::{
+        reconcile_delayed_job, reconcile_namespace, reconcile_scheduled_cronjob,
+        reconcile_scheduled_patch, reconcile_scheduled_suspend, reconcile_timer_trigger,
+    },
 };
-use tracing_subscriber::filter::LevelFilter;
+use serde::de::DeserializeOwned;
 
 #[tokio::main]
 async fn main() -> Result<(), kube::Error> {
-    tracing_subscriber::fmt()
-        .with_max_level(LevelFilter::INFO)
-        .with_line_number(true)
-        .init();
+    let log_level = LogLevel::init();
 
     let client = Client::try_default().await?;
+    let server_version = scheduled::compat::detect(&client).await;
+    if let Some(version) = server_version {
+        tracing::info!(%version, "Detected API server version");
+    }
+    let events_api = scheduled::compat::detect_events_api(&client).await;
+    tracing::info!(%events_api, "Recording events");
 
     // 创建 API 客户端
     let scheduled_cronjobs = Api::<ScheduledCronJob>::all(client.clone());
     let delayed_jobs = Api::<DelayedJob>::all(client.clone());
+    let scheduled_patches = Api::<ScheduledPatch>::all(client.clone());
+    let scheduled_suspends = Api::<ScheduledSuspend>::all(client.clone());
+    let timer_triggers = Api::<TimerTrigger>::all(client.clone());
     let cronjobs = Api::<CronJob>::all(client.clone());
-    let jobs = Api::<k8s_openapi::api::batch::v1::Job>::all(client.clone());
+    let jobs = Api::<Job>::all(client.clone());
+    let namespaces = Api::<Namespace>::all(client.clone());
+    let calendars = Api::<ScheduleCalendar>::all(client.clone());
+
+    let ctx = Arc::new(
+        Context::new(client)
+            .with_config(Config::from_env())
+            .with_log_level(log_level)
+            .with_server_version(server_version)
+            .with_events_api(events_api),
+    );
+    scheduled::configuration::load(&ctx).await;
+    let crds = vec![
+        ScheduledCronJob::crd(),
+        DelayedJob::crd(),
+        ScheduledPatch::crd(),
+        ScheduledSuspend::crd(),
+        TimerTrigger::crd(),
+        ControllerConfiguration::crd(),
+        ScheduleCalendar::crd(),
+    ];
+    // Before the controllers start, so their watches find the CRDs.
+    if ctx.config().install_crds {
+        scheduled::leader::install(&ctx, &crds).await;
+    }
+    let controller_config =
+        controller::Config::default().concurrency(ctx.config().reconcile_concurrency);
+
+    let scheduled_cronjob_controller = Controller::new(scheduled_cronjobs, Default::default());
+    // A changed calendar re-reconciles every resource listing it.
+    let scheduled_cronjob_store = scheduled_cronjob_controller.store();
+    let scheduled_cronjob_controller = scheduled_cronjob_controller
+        .with_config(controller_config.clone())
+        .shutdown_on_signal()
+        .owns(cronjobs, Default::default())
+        .watches(calendars, Default::default(), move |calendar| {
+            scheduled_cronjob_store
+                .state()
+                .into_iter()
+                .filter(|resource| resource.uses_calendar(&calendar))
+                .map(|resource| ObjectRef::from_obj(resource.as_ref()))
+                .collect::<Vec<_>>()
+        });
+    let delayed_job_controller = Controller::new(delayed_jobs.clone(), Default::default());
+    // DelayedJobs waiting in runAfterJob are re-reconciled when the
+    // DelayedJob or Job they run after changes.
+    let delayed_job_store = delayed_job_controller.store();
+    let dependents = move |namespace: Option<String>, name: String| {
+        let namespace = namespace.unwrap_or_default();
+        delayed_job_store
+            .state()
+            .into_iter()
+            .filter(|resource| resource.runs_after(&namespace, &name))
+            .map(|resource| ObjectRef::from_obj(resource.as_ref()))
+            .collect::<Vec<_>>()
+    };
+    let job_dependents = dependents.clone();
+    let delayed_job_controller = delayed_job_controller
+        .with_config(controller_config.clone())
+        .shutdown_on_signal()
+        .owns(jobs.clone(), Default::default())
+        .watches(jobs, Default::default(), move |job| {
+            job_dependents(job.namespace(), job.name_any())
+        })
+        .watches(delayed_jobs, Default::default(), move |delayed_job| {
+            dependents(delayed_job.namespace(), delayed_job.name_any())
+        });
+    let scheduled_patch_controller = Controller::new(scheduled_patches, Default::default())
+        .with_config(controller_config.clone())
+        .shutdown_on_signal();
+    let scheduled_suspend_controller = Controller::new(scheduled_suspends, Default::default())
+        .with_config(controller_config.clone())
+        .shutdown_on_signal();
+    let timer_trigger_controller = Controller::new(timer_triggers, Default::default())
+        .with_config(controller_config.clone())
+        .shutdown_on_signal();
+    let namespace_controller = Controller::new(namespaces, Default::default())
+        .with_config(controller_config)
+        .shutdown_on_signal();
+
+    // Leadership is only sought once every controller's cache is populated,
+    // so a replica taking over from a rolling upgrade reconciles immediately.
+    let ready = {
+        let stores = (
+            scheduled_cronjob_controller.store(),
+            delayed_job_controller.store(),
+            scheduled_patch_controller.store(),
+            scheduled_suspend_controller.store(),
+            timer_trigger_controller.store(),
+        );
+        async move {
+            let _ = futures::join!(
+                stores.0.wait_until_ready(),
+                stores.1.wait_until_ready(),
+                stores.2.wait_until_ready(),
+                stores.3.wait_until_ready(),
+                stores.4.wait_until_ready(),
+            );
+        }
+    };
 
-    let ctx = Arc::new(Context::new(client));
+    let controllers = async {
+        futures::join!(
+            run(
+                scheduled_cronjob_controller,
+                reconcile_scheduled_cronjob,
+                ctx.clone()
+            ),
+            run(delayed_job_controller, reconcile_delayed_job, ctx.clone()),
+            run(
+                scheduled_patch_controller,
+                reconcile_scheduled_patch,
+                ctx.clone()
+            ),
+            run(
+                scheduled_suspend_controller,
+                reconcile_scheduled_suspend,
+                ctx.clone()
+            ),
+            run(
+                timer_trigger_controller,
+                reconcile_timer_trigger,
+                ctx.clone()
+            ),
+            run(namespace_controller, reconcile_namespace, ctx.clone()),
+        );
+        // All controllers have drained their in-flight reconciliations, so
+        // the lease can be handed over without waiting for it to expire.
+        scheduled::leader::release(&ctx).await;
+    };
 
     tokio::select! {
-        _ = run_scheduled_cronjob_controller(scheduled_cronjobs, cronjobs, ctx.clone()) => {},
-        _ = run_delayed_job_controller(delayed_jobs, jobs, ctx.clone()) => {},
+        _ = scheduled::leader::run(ctx.clone(), ready, crds) => {},
+        _ = scheduled::heartbeat::run(ctx.clone()) => {},
+        _ = scheduled::sweep::run(ctx.clone()) => {},
+        _ = scheduled::resize::run(ctx.clone()) => {},
+        _ = scheduled::preemption::run(ctx.clone()) => {},
+        _ = scheduled::emergency::run(ctx.clone()) => {},
+        _ = scheduled::configuration::run(ctx.clone()) => {},
+        _ = scheduled::cache::run(ctx.clone()) => {},
+        _ = scheduled::loglevel::run(ctx.clone()) => {},
+        _ = scheduled::metrics::run(ctx.clone()) => {},
+        _ = scheduled::annotated::run(ctx.clone()) => {},
+        _ = scheduled::consumer::run(ctx.clone()) => {},
+        result = scheduled::server::serve(ctx.clone()) => {
+            if let Err(e) = result {
+                tracing::error!(error = ?e, "HTTP server failed");
+            }
+        },
+        _ = controllers => {},
     }
 
     Ok(())
 }
 
-async fn run_scheduled_cronjob_controller(
-    scheduled_cronjobs: Api<ScheduledCronJob>,
-    cronjobs: Api<CronJob>,
+async fn run<K, ReconcilerFut>(
+    controller: Controller<K>,
+    reconcile: impl FnMut(Arc<K>, Arc<Context>) -> ReconcilerFut,
     ctx: Arc<Context>,
-) {
-    Controller::new(scheduled_cronjobs.clone(), Default::default())
-        .shutdown_on_signal()
-        .owns(cronjobs, Default::default())
-        .run(
-            reconcile_scheduled_cronjob,
-            scheduled::error_policy,
-            ctx.clone(),
-        )
-        .for_each(|_| futures::future::ready(()))
-        .await;
-}
-
-async fn run_delayed_job_controller(
-    delayed_jobs: Api<DelayedJob>,
-    jobs: Api<Job>,
-    ctx: Arc<Context>,
-) {
-    Controller::new(delayed_jobs.clone(), Default::default())
-        .shutdown_on_signal()
-        .owns(jobs, Default::default())
-        .run(reconcile_delayed_job, scheduled::error_policy, ctx.clone())
+) where
+    K: Resource<DynamicType = ()> + Clone + DeserializeOwned + Debug + Send + Sync + 'static,
+    ReconcilerFut: Future<Output = Result<Action, scheduled::Error>> + Send + 'static,
+{
+    controller
+        .run(reconcile, scheduled::error_policy, ctx)
         .for_each(|_| futures::future::ready(()))
         .await;
 }
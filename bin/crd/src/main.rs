@@ -4,6 +4,11 @@ fn main() {
     let crds = vec![
         // scheduled::ScheduledCronJob::crd(),
         scheduled::DelayedJob::crd(),
+        scheduled::ScheduledPatch::crd(),
+        scheduled::ScheduledSuspend::crd(),
+        scheduled::TimerTrigger::crd(),
+        scheduled::crd::ControllerConfiguration::crd(),
+        scheduled::crd::ScheduleCalendar::crd(),
     ];
     for crd in crds {
         println!("{}", serde_yaml::to_string(&crd).unwrap());
@@ -0,0 +1,102 @@
+use prometheus::proto::MetricType;
+use scheduled::Metrics;
+use scheduled::metrics::MetricDescription;
+use serde_json::{Value, json};
+
+const PANEL_WIDTH: u64 = 12;
+
+const PANEL_HEIGHT: u64 = 8;
+
+/// Prints a Grafana dashboard with one panel per metric the controller
+/// exports. The panels are built from [`Metrics::descriptions`], so their
+/// names and labels always match the code.
+fn main() {
+    let metrics = Metrics::new();
+    let panels: Vec<Value> = metrics
+        .descriptions()
+        .iter()
+        .zip(0..)
+        .map(|(metric, index)| panel(metric, index))
+        .collect();
+    let dashboard = json!({
+        "uid": "scheduled-controller",
+        "title": "Scheduled Controller",
+        "tags": ["kubernetes", Metrics::NAMESPACE],
+        "timezone": "browser",
+        "schemaVersion": 39,
+        "refresh": "1m",
+        "time": { "from": "now-6h", "to": "now" },
+        "templating": {
+            "list": [{
+                "name": "datasource",
+                "label": "Data source",
+                "type": "datasource",
+                "query": "prometheus",
+            }],
+        },
+        "panels": panels,
+    });
+    println!("{}", serde_json::to_string_pretty(&dashboard).unwrap());
+}
+
+fn panel(metric: &MetricDescription, index: u64) -> Value {
+    let by = metric.labels.join(", ");
+    let legend = if metric.labels.is_empty() {
+        metric.name.clone()
+    } else {
+        metric
+            .labels
+            .iter()
+            .map(|l| format!("{{{{{l}}}}}"))
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    let (targets, unit) = match metric.metric_type {
+        MetricType::COUNTER => {
+            let expr = format!("sum by ({by}) (rate({}[5m]))", metric.name);
+            (vec![target(expr, legend)], "ops")
+        }
+        MetricType::HISTOGRAM => {
+            let targets = [0.5, 0.99]
+                .into_iter()
+                .map(|quantile| {
+                    let expr = format!(
+                        "histogram_quantile({quantile}, sum by (le{}) (rate({}_bucket[5m])))",
+                        metric
+                            .labels
+                            .iter()
+                            .map(|l| format!(", {l}"))
+                            .collect::<String>(),
+                        metric.name
+                    );
+                    target(expr, format!("p{} {legend}", quantile * 100.0))
+                })
+                .collect();
+            (targets, "s")
+        }
+        _ => (vec![target(metric.name.clone(), legend)], "short"),
+    };
+    json!({
+        "id": index + 1,
+        "type": "timeseries",
+        "title": metric.name,
+        "description": metric.help,
+        "datasource": { "type": "prometheus", "uid": "${datasource}" },
+        "gridPos": {
+            "x": (index % 2) * PANEL_WIDTH,
+            "y": (index / 2) * PANEL_HEIGHT,
+            "w": PANEL_WIDTH,
+            "h": PANEL_HEIGHT,
+        },
+        "fieldConfig": { "defaults": { "unit": unit }, "overrides": [] },
+        "targets": targets,
+    })
+}
+
+fn target(expr: String, legend: String) -> Value {
+    json!({
+        "datasource": { "type": "prometheus", "uid": "${datasource}" },
+        "expr": expr,
+        "legendFormat": legend,
+    })
+}
@@ -0,0 +1,65 @@
+use std::process::ExitCode;
+
+use kube::Client;
+use scheduled::history::{PostgresSink, export};
+
+const USAGE: &str = "usage: export [--namespace <namespace>] [--selector <labels>] \
+                     [--source <postgres-url>] [--format csv|parquet]";
+
+/// Writes the run history to stdout as CSV or Parquet, for offline analysis
+/// of how reliably schedules run.
+///
+/// Runs are read from the finished Jobs still in the cluster, optionally
+/// limited to those matching `--selector`, or with `--source` from the
+/// Postgres table of a `HISTORY_SINK`.
+#[tokio::main]
+async fn main() -> Result<ExitCode, Box<dyn std::error::Error>> {
+    let mut namespace = None;
+    let mut selector = None;
+    let mut source = None;
+    let mut format = "csv".to_string();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let value = match arg.as_str() {
+            "--namespace" => &mut namespace,
+            "--selector" => &mut selector,
+            "--source" => &mut source,
+            "--format" => {
+                format = args.next().unwrap_or_default();
+                continue;
+            }
+            _ => {
+                eprintln!("{USAGE}");
+                return Ok(ExitCode::FAILURE);
+            }
+        };
+        *value = args.next();
+    }
+    if format != "csv" && format != "parquet" {
+        eprintln!("unsupported format {format}, use csv or parquet");
+        return Ok(ExitCode::FAILURE);
+    }
+
+    let records = match source {
+        Some(url) => {
+            if selector.is_some() {
+                eprintln!("--selector only applies to runs read from the cluster");
+                return Ok(ExitCode::FAILURE);
+            }
+            let url = reqwest::Url::parse(&url)?;
+            PostgresSink::from_url(&url)?
+                .records(namespace.as_deref())
+                .await?
+        }
+        None => {
+            let client = Client::try_default().await?;
+            export::from_cluster(client, namespace.as_deref(), selector.as_deref()).await?
+        }
+    };
+    if format == "parquet" {
+        export::write_parquet(&records, std::io::stdout())?;
+    } else {
+        export::write_csv(&records, &mut std::io::stdout().lock())?;
+    }
+    Ok(ExitCode::SUCCESS)
+}
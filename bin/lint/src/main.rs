@@ -0,0 +1,64 @@
+use std::process::ExitCode;
+
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use scheduled::lint::{Finding, Lint, Severity};
+use scheduled::{DelayedJob, ScheduledCronJob};
+use serde::Deserialize as _;
+
+/// Lints the ScheduledCronJob and DelayedJob manifests in the given files.
+/// Exits non-zero when any finding is an error.
+fn main() -> Result<ExitCode, Box<dyn std::error::Error>> {
+    let paths: Vec<String> = std::env::args().skip(1).collect();
+    if paths.is_empty() {
+        eprintln!("usage: lint <manifest.yaml>...");
+        return Ok(ExitCode::FAILURE);
+    }
+
+    let mut worst = None;
+    for path in &paths {
+        let content = std::fs::read_to_string(path)?;
+        for document in serde_yaml::Deserializer::from_str(&content) {
+            let value = serde_yaml::Value::deserialize(document)?;
+            let Some((name, findings)) = lint(value)? else {
+                continue;
+            };
+            for finding in findings {
+                println!("{path}: {name}: {finding}");
+                worst = worst.max(Some(finding.severity));
+            }
+        }
+    }
+
+    if worst == Some(Severity::Error) {
+        return Ok(ExitCode::FAILURE);
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn lint(value: serde_yaml::Value) -> Result<Option<(String, Vec<Finding>)>, serde_yaml::Error> {
+    let kind = value
+        .get("kind")
+        .and_then(|k| k.as_str())
+        .unwrap_or_default()
+        .to_string();
+    let findings = match kind.as_str() {
+        "ScheduledCronJob" => {
+            let resource: ScheduledCronJob = serde_yaml::from_value(value)?;
+            (describe(&kind, &resource.metadata), resource.lint())
+        }
+        "DelayedJob" => {
+            let resource: DelayedJob = serde_yaml::from_value(value)?;
+            (describe(&kind, &resource.metadata), resource.lint())
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(findings))
+}
+
+fn describe(kind: &str, metadata: &ObjectMeta) -> String {
+    format!(
+        "{kind} {}/{}",
+        metadata.namespace.as_deref().unwrap_or("default"),
+        metadata.name.as_deref().unwrap_or_default()
+    )
+}
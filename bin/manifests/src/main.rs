@@ -0,0 +1,210 @@
+use std::process::ExitCode;
+
+use scheduled::{Metrics, Reason};
+use serde_json::{Map, Value, json};
+
+/// Name and `app` label of the controller's Deployment.
+const APP: &str = "scheduled-cronjob-controller";
+
+/// Name of the container port serving `/metrics`.
+const METRICS_PORT: &str = "http";
+
+/// Runs failing for these reasons count towards `ScheduledRunsFailing`.
+const FAILURE_REASONS: [Reason; 5] = [
+    Reason::JobFailed,
+    Reason::TimedOut,
+    Reason::Stalled,
+    Reason::CheckFailed,
+    Reason::PreemptionLimitExceeded,
+];
+
+/// Prints a `PrometheusRule` alerting on the controller's metrics and,
+/// optionally, a `ServiceMonitor` or `PodMonitor` scraping them. Every metric
+/// an alert uses is looked up in [`Metrics::descriptions`], so a renamed
+/// metric fails generation instead of silently breaking the alert.
+///
+/// - `--namespace` sets the namespace of every object.
+/// - `--failures` sets how many failed runs within an hour raise
+///   `ScheduledRunsFailing`.
+/// - `--service-monitor` and `--pod-monitor` add the monitors, selecting the
+///   controller's Service or pods by the `--selector` labels, which default
+///   to those of the Deployment. The ServiceMonitor needs a Service with an
+///   `http` port in front of the Deployment, which `deploy.yaml` does not
+///   include.
+/// - `--label` adds a label to every object, for the `ruleSelector` and
+///   monitor selectors of the Prometheus resource.
+/// - `--route` adds a Service in front of the controller's `http` port and an
+///   OpenShift Route exposing it with edge TLS, for the calendar feeds,
+///   `/trigger` and Slack commands on clusters without an ingress controller.
+fn main() -> Result<ExitCode, Box<dyn std::error::Error>> {
+    let mut namespace = "scheduled-cronjob-system".to_string();
+    let mut failures = 3u32;
+    let mut service_monitor = false;
+    let mut pod_monitor = false;
+    let mut route = false;
+    let mut selector = Map::new();
+    let mut labels = Map::new();
+    labels.insert("app".to_string(), json!(APP));
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let flag = arg.as_str();
+        if flag == "--service-monitor" {
+            service_monitor = true;
+            continue;
+        }
+        if flag == "--pod-monitor" {
+            pod_monitor = true;
+            continue;
+        }
+        if flag == "--route" {
+            route = true;
+            continue;
+        }
+        let value = args.next();
+        match (flag, value.as_deref()) {
+            ("--namespace", Some(value)) => namespace = value.to_string(),
+            ("--failures", Some(value)) => failures = value.parse()?,
+            ("--selector", Some(value)) if let Some((key, value)) = value.split_once('=') => {
+                selector.insert(key.to_string(), json!(value));
+            }
+            ("--label", Some(value)) if let Some((key, value)) = value.split_once('=') => {
+                labels.insert(key.to_string(), json!(value));
+            }
+            _ => {
+                eprintln!(
+                    "usage: manifests [--namespace <namespace>] [--failures <count>] \
+                     [--service-monitor] [--pod-monitor] [--selector <key=value>]... \
+                     [--label <key=value>]... [--route]"
+                );
+                return Ok(ExitCode::FAILURE);
+            }
+        }
+    }
+    if selector.is_empty() {
+        selector.insert("app".to_string(), json!(APP));
+    }
+    let metadata = json!({ "name": APP, "namespace": namespace, "labels": labels });
+
+    let metrics = Metrics::new();
+    let metric = |name: &str| -> Result<String, String> {
+        let name = format!("{}_{name}", Metrics::NAMESPACE);
+        metrics
+            .descriptions()
+            .iter()
+            .any(|d| d.name == name)
+            .then_some(name.clone())
+            .ok_or(format!("no metric named {name}"))
+    };
+    let last_seen = metric("controller_last_seen")?;
+    let events = metric("events_total")?;
+    let overdue = metric("run_overdue_seconds")?;
+    let retries = metric("reconcile_retries_total")?;
+    let reasons = FAILURE_REASONS
+        .iter()
+        .map(|r| r.as_str())
+        .collect::<Vec<_>>()
+        .join("|");
+
+    let rules = vec![
+        alert(
+            "ScheduledControllerDown",
+            &format!("absent({last_seen}) or time() - {last_seen} > 60"),
+            "5m",
+            "critical",
+            "The scheduled-cronjob controller has not renewed its heartbeat for over a minute.",
+        ),
+        alert(
+            "ScheduledRunsFailing",
+            &format!(
+                r#"sum by (kind) (increase({events}{{reason=~"{reasons}"}}[1h])) >= {failures}"#
+            ),
+            "0m",
+            "warning",
+            &format!(
+                "{{{{ $labels.kind }}}} runs failed at least {failures} times in the last hour."
+            ),
+        ),
+        alert(
+            "ScheduledRunMissed",
+            &format!("max by (namespace, name, cronjob) ({overdue}) > 300"),
+            "5m",
+            "warning",
+            "CronJob {{ $labels.namespace }}/{{ $labels.cronjob }} of {{ $labels.name }} missed its scheduled run.",
+        ),
+        alert(
+            "ScheduledReconcileErrors",
+            &format!("sum by (kind) (rate({retries}[10m])) > 0.1"),
+            "15m",
+            "warning",
+            "Reconciliations of {{ $labels.kind }} keep failing.",
+        ),
+    ];
+    let mut manifests = vec![json!({
+        "apiVersion": "monitoring.coreos.com/v1",
+        "kind": "PrometheusRule",
+        "metadata": metadata,
+        "spec": {
+            "groups": [{ "name": "scheduled-cronjob", "rules": rules }],
+        },
+    })];
+    let endpoint = json!({ "port": METRICS_PORT, "path": "/metrics", "interval": "30s" });
+    if service_monitor {
+        manifests.push(json!({
+            "apiVersion": "monitoring.coreos.com/v1",
+            "kind": "ServiceMonitor",
+            "metadata": metadata,
+            "spec": {
+                "selector": { "matchLabels": selector },
+                "namespaceSelector": { "matchNames": [namespace] },
+                "endpoints": [endpoint],
+            },
+        }));
+    }
+    if pod_monitor {
+        manifests.push(json!({
+            "apiVersion": "monitoring.coreos.com/v1",
+            "kind": "PodMonitor",
+            "metadata": metadata,
+            "spec": {
+                "selector": { "matchLabels": selector },
+                "namespaceSelector": { "matchNames": [namespace] },
+                "podMetricsEndpoints": [endpoint],
+            },
+        }));
+    }
+    if route {
+        manifests.push(json!({
+            "apiVersion": "v1",
+            "kind": "Service",
+            "metadata": metadata,
+            "spec": {
+                "selector": selector,
+                "ports": [{ "name": METRICS_PORT, "port": 3000, "targetPort": METRICS_PORT }],
+            },
+        }));
+        manifests.push(json!({
+            "apiVersion": "route.openshift.io/v1",
+            "kind": "Route",
+            "metadata": metadata,
+            "spec": {
+                "to": { "kind": "Service", "name": APP },
+                "port": { "targetPort": METRICS_PORT },
+                "tls": { "termination": "edge", "insecureEdgeTerminationPolicy": "Redirect" },
+            },
+        }));
+    }
+    for manifest in manifests {
+        println!("{}---", serde_yaml::to_string(&manifest)?);
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn alert(name: &str, expr: &str, duration: &str, severity: &str, summary: &str) -> Value {
+    json!({
+        "alert": name,
+        "expr": expr,
+        "for": duration,
+        "labels": { "severity": severity },
+        "annotations": { "summary": summary },
+    })
+}
@@ -0,0 +1,99 @@
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::process::ExitCode;
+
+use kube::api::{DynamicObject, ListParams};
+use kube::core::GroupVersion;
+use kube::{Api, Client, ResourceExt as _, discovery};
+use serde::Deserialize as _;
+
+/// Prints, as JSON, the operations needed to make the cluster match the
+/// manifests in a directory. Live objects of the same kinds in the same
+/// namespaces that have no manifest are planned for deletion.
+///
+/// With `--deny-destructive` the exit code is non-zero when the plan contains
+/// deletions or schedule changes.
+#[tokio::main]
+async fn main() -> Result<ExitCode, Box<dyn std::error::Error>> {
+    let mut deny_destructive = false;
+    let mut dir = None;
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--deny-destructive" => deny_destructive = true,
+            _ => dir = Some(arg),
+        }
+    }
+    let Some(dir) = dir else {
+        eprintln!("usage: plan [--deny-destructive] <manifest-dir>");
+        return Ok(ExitCode::FAILURE);
+    };
+
+    let mut desired = Vec::new();
+    read_manifests(Path::new(&dir), &mut desired)?;
+
+    let client = Client::try_default().await?;
+    let scopes: BTreeSet<(String, String, String)> = desired
+        .iter()
+        .filter_map(|o| {
+            let types = o.types.clone()?;
+            Some((
+                types.api_version,
+                types.kind,
+                o.namespace().unwrap_or_default(),
+            ))
+        })
+        .collect();
+    let mut live = Vec::new();
+    for (api_version, kind, namespace) in scopes {
+        let gvk = api_version.parse::<GroupVersion>()?.with_kind(&kind);
+        let (resource, _) = discovery::pinned_kind(&client, &gvk).await?;
+        let api = Api::<DynamicObject>::namespaced_with(client.clone(), &namespace, &resource);
+        for mut object in api.list(&ListParams::default()).await?.items {
+            object.types = Some(kube::core::TypeMeta {
+                api_version: api_version.clone(),
+                kind: kind.clone(),
+            });
+            live.push(object);
+        }
+    }
+
+    let plan = scheduled::plan::plan(&desired, &live);
+    println!("{}", serde_json::to_string_pretty(&plan)?);
+
+    if deny_destructive && plan.destructive > 0 {
+        return Ok(ExitCode::FAILURE);
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn read_manifests(
+    dir: &Path,
+    objects: &mut Vec<DynamicObject>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            read_manifests(&path, objects)?;
+            continue;
+        }
+        if !matches!(
+            path.extension().and_then(|e| e.to_str()),
+            Some("yaml" | "yml")
+        ) {
+            continue;
+        }
+        let content = std::fs::read_to_string(&path)?;
+        for document in serde_yaml::Deserializer::from_str(&content) {
+            let value = serde_yaml::Value::deserialize(document)?;
+            if value.is_null() {
+                continue;
+            }
+            let mut object: DynamicObject = serde_yaml::from_value(value)?;
+            if object.metadata.namespace.is_none() {
+                object.metadata.namespace = Some("default".to_string());
+            }
+            objects.push(object);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,29 @@
+use std::process::ExitCode;
+
+use scheduled::invariants;
+use scheduled::replay::{self, Recording};
+
+/// Prints, as JSON, what the controller decides for a reconciliation
+/// recorded to `REPLAY_DIR`, without cluster access. Fails when the decision
+/// breaks an invariant.
+fn main() -> Result<ExitCode, Box<dyn std::error::Error>> {
+    let Some(path) = std::env::args().nth(1) else {
+        eprintln!("usage: replay <recording.json>");
+        return Ok(ExitCode::FAILURE);
+    };
+    let recording: Recording = serde_json::from_str(&std::fs::read_to_string(&path)?)?;
+    if let Some(error) = &recording.error {
+        eprintln!("recorded error: {error}");
+    }
+    let decision = replay::decide(&recording);
+    println!("{}", serde_json::to_string_pretty(&decision)?);
+    let violations = invariants::check(&recording, &decision);
+    for violation in &violations {
+        eprintln!("invariant violated: {violation}");
+    }
+    Ok(if violations.is_empty() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    })
+}
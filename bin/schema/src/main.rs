@@ -0,0 +1,78 @@
+use std::fs;
+use std::path::PathBuf;
+
+use k8s_openapi::apiextensions_apiserver::pkg::apis::apiextensions::v1::CustomResourceDefinition;
+use kube::core::crd::v1::CustomResourceExt as _;
+use scheduled::codegen;
+use serde_json::{Value, json};
+
+/// Writes standalone JSON Schemas of the CRD specs into the given directory
+/// (`schemas` by default). The schemas are cut out of the generated CRDs, so
+/// they always match what the API server validates. The labels of runs are
+/// written next to them, in `run-labels.json`.
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let out_dir = PathBuf::from(std::env::args().nth(1).unwrap_or("schemas".to_string()));
+    fs::create_dir_all(&out_dir)?;
+
+    let crds = vec![
+        scheduled::ScheduledCronJob::crd(),
+        scheduled::DelayedJob::crd(),
+    ];
+    for crd in crds {
+        let kind = crd.spec.names.kind.clone();
+        let schema = spec_schema(&crd)?;
+        let path = out_dir.join(format!("{}.spec.schema.json", kind.to_lowercase()));
+        fs::write(&path, serde_json::to_string_pretty(&schema)? + "\n")?;
+        println!("{}", path.display());
+    }
+    let path = out_dir.join("run-labels.json");
+    let labels = serde_json::to_string_pretty(scheduled::run_labels::SCHEME)?;
+    fs::write(&path, labels + "\n")?;
+    println!("{}", path.display());
+    Ok(())
+}
+
+fn spec_schema(crd: &CustomResourceDefinition) -> Result<Value, Box<dyn std::error::Error>> {
+    let version = crd
+        .spec
+        .versions
+        .iter()
+        .find(|v| v.storage)
+        .ok_or("CRD has no storage version")?;
+    let spec = codegen::spec_schema(crd)?;
+
+    let mut schema = json!({
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "$id": format!("{}/{}/{}Spec", crd.spec.group, version.name, crd.spec.names.kind),
+        "title": format!("{}Spec", crd.spec.names.kind),
+    });
+    if let (Value::Object(schema), Value::Object(spec)) = (&mut schema, serde_json::to_value(spec)?)
+    {
+        schema.extend(spec);
+    }
+    to_json_schema(&mut schema);
+    Ok(schema)
+}
+
+/// Rewrites the OpenAPI-only keywords the CRD uses into plain JSON Schema.
+fn to_json_schema(value: &mut Value) {
+    match value {
+        Value::Object(object) => {
+            if object.remove("nullable") == Some(Value::Bool(true))
+                && let Some(Value::String(type_)) = object.get("type")
+            {
+                let type_ = json!([type_, "null"]);
+                object.insert("type".to_string(), type_);
+            }
+            if object.get("x-kubernetes-int-or-string") == Some(&Value::Bool(true)) {
+                object.insert(
+                    "anyOf".to_string(),
+                    json!([{ "type": "integer" }, { "type": "string" }]),
+                );
+            }
+            object.values_mut().for_each(to_json_schema);
+        }
+        Value::Array(array) => array.iter_mut().for_each(to_json_schema),
+        _ => {}
+    }
+}
@@ -0,0 +1,34 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+use kube::core::crd::v1::CustomResourceExt as _;
+use scheduled::codegen;
+
+/// Writes TypeScript interfaces (`types.ts`) and Go structs (`types.go`) for
+/// the CRD specs into the given directory (`types` by default).
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let out_dir = PathBuf::from(std::env::args().nth(1).unwrap_or("types".to_string()));
+    fs::create_dir_all(&out_dir)?;
+
+    let crds = vec![
+        scheduled::ScheduledCronJob::crd(),
+        scheduled::DelayedJob::crd(),
+        scheduled::ScheduledPatch::crd(),
+        scheduled::ScheduledSuspend::crd(),
+        scheduled::TimerTrigger::crd(),
+    ];
+    let mut roots = BTreeMap::new();
+    for crd in &crds {
+        roots.insert(
+            format!("{}Spec", crd.spec.names.kind),
+            codegen::spec_schema(crd)?,
+        );
+    }
+
+    let version = &crds[0].spec.versions[0].name;
+    fs::write(out_dir.join("types.ts"), codegen::typescript(&roots))?;
+    fs::write(out_dir.join("types.go"), codegen::go(version, &roots))?;
+    println!("{}", out_dir.display());
+    Ok(())
+}
@@ -1,7 +1,4 @@
-use k8s_openapi::api::{
-    batch::v1::{CronJobSpec, JobSpec, JobTemplateSpec},
-    core::v1::{Container, PodSpec, PodTemplateSpec},
-};
+use k8s_openapi::api::{batch::v1::JobSpec, core::v1::PodTemplateSpec};
 use kube::{
     Api, ResourceExt as _,
     api::{DeleteParams, ObjectMeta},
@@ -62,7 +59,8 @@ async fn main() {
     };
 
     let api = Api::<DelayedJob>::namespaced(client.clone(), "default");
-    api.delete(&cronjob.name_any(), &DeleteParams::foreground())
+    let _ = api
+        .delete(&cronjob.name_any(), &DeleteParams::foreground())
         .await;
     api.create(&Default::default(), &cronjob).await.unwrap();
 }
@@ -50,7 +50,6 @@ async fn main() {
                         },
                         ..Default::default()
                     }),
-                    ..Default::default()
                 },
                 ..Default::default()
             },
@@ -60,7 +59,8 @@ async fn main() {
     };
 
     let api = Api::<ScheduledCronJob>::namespaced(client.clone(), "default");
-    api.delete(&cronjob.name_any(), &DeleteParams::foreground())
+    let _ = api
+        .delete(&cronjob.name_any(), &DeleteParams::foreground())
         .await;
     api.create(&Default::default(), &cronjob).await.unwrap();
 }
@@ -0,0 +1,119 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Local;
+use k8s_openapi::api::batch::v1::CronJob;
+use kube::api::{ListParams, Patch};
+use kube::{Api, ResourceExt as _};
+
+use crate::Context;
+use crate::reason::Reason;
+use crate::schedule::Window;
+
+/// Annotation on a plain CronJob holding the window it may run in, as
+/// `start=<time>,end=<time>` with either bound optional.
+pub const WINDOW_ANNOTATION: &str = "scheduler.divinerapier.io/window";
+
+/// Annotation marking a CronJob suspended because it was outside its window.
+/// Only CronJobs carrying it are resumed when the window opens, so a manual
+/// suspension is left alone.
+pub const WINDOW_SUSPENDED_ANNOTATION: &str = "scheduler.divinerapier.io/suspended-by-window";
+
+/// Interval between checks, bounding how late a window opens or closes.
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Periodically suspends CronJobs annotated with [`WINDOW_ANNOTATION`] outside
+/// their window and resumes them inside it, without a ScheduledCronJob.
+/// Disabled unless `ANNOTATED_CRONJOBS` is set; checks only run on the leader
+/// and are skipped while the emergency stop is engaged.
+pub async fn run(ctx: Arc<Context>) {
+    let mut interval = tokio::time::interval(CHECK_INTERVAL);
+    loop {
+        interval.tick().await;
+        if !ctx.config().annotated_cronjobs
+            || !ctx.leadership().is_leader()
+            || ctx.emergency_stop().engaged()
+        {
+            continue;
+        }
+        if let Err(e) = apply_windows(&ctx).await {
+            tracing::warn!(error = ?e, "Failed to apply annotated cronjob windows");
+        }
+    }
+}
+
+async fn apply_windows(ctx: &Context) -> Result<(), crate::Error> {
+    let cronjobs = Api::<CronJob>::all((**ctx).clone());
+    let now = Local::now();
+    for cronjob in cronjobs.list(&ListParams::default()).await? {
+        let Some(value) = cronjob.annotations().get(WINDOW_ANNOTATION) else {
+            continue;
+        };
+        let (suspend, reason, message) = match Window::parse(value).and_then(|w| w.check(now)) {
+            Ok(()) => (false, Reason::Resumed, "Window opened".to_string()),
+            Err(crate::Error::WaitFor(_)) => (
+                true,
+                Reason::WindowNotOpen,
+                "Suspended until the window opens".to_string(),
+            ),
+            Err(crate::Error::Expired(end)) => (
+                true,
+                Reason::Expired,
+                format!("Suspended after the window ended at {end}"),
+            ),
+            Err(e) => {
+                tracing::warn!(
+                    name = cronjob.name_any(),
+                    namespace = cronjob.namespace(),
+                    error = %e,
+                    "Ignoring invalid window annotation"
+                );
+                continue;
+            }
+        };
+        apply(ctx, &cronjob, suspend, reason, &message).await?;
+    }
+    Ok(())
+}
+
+async fn apply(
+    ctx: &Context,
+    cronjob: &CronJob,
+    suspend: bool,
+    reason: Reason,
+    message: &str,
+) -> Result<(), crate::Error> {
+    let suspended = cronjob
+        .spec
+        .as_ref()
+        .and_then(|s| s.suspend)
+        .unwrap_or(false);
+    let marked = cronjob
+        .annotations()
+        .contains_key(WINDOW_SUSPENDED_ANNOTATION);
+    let patch = match (suspend, suspended) {
+        // Already suspended, by the window or by hand.
+        (true, true) => return Ok(()),
+        (true, false) => serde_json::json!({
+            "metadata": { "annotations": { WINDOW_SUSPENDED_ANNOTATION: "true" } },
+            "spec": { "suspend": true },
+        }),
+        (false, true) if marked => serde_json::json!({
+            "metadata": { "annotations": { WINDOW_SUSPENDED_ANNOTATION: null } },
+            "spec": { "suspend": false },
+        }),
+        (false, _) => return Ok(()),
+    };
+
+    let namespace = cronjob.namespace().unwrap_or_default();
+    let name = cronjob.name_any();
+    tracing::info!(
+        name,
+        namespace,
+        suspend,
+        "Applying annotated cronjob window"
+    );
+    ctx.patch::<CronJob>(&namespace, &name, &Patch::Merge(patch))
+        .await?;
+    ctx.create_event(cronjob, reason, message).await
+}
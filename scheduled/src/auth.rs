@@ -0,0 +1,107 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+
+use crate::{Context, Error};
+
+/// Longest lifetime a tenant token may be issued for.
+pub const MAX_TOKEN_TTL: Duration = Duration::from_secs(90 * 24 * 60 * 60);
+
+/// Claims of a tenant token, an HS256 JWT signed with `api_token_key`.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Claims {
+    /// The tenant the token was issued to.
+    pub sub: String,
+
+    /// Namespaces the tenant may act in.
+    pub namespaces: Vec<String>,
+
+    pub iat: i64,
+    pub exp: i64,
+}
+
+/// The caller of the HTTP API.
+#[derive(Clone, Debug)]
+pub enum Principal {
+    /// Holder of the admin token, with access to every namespace.
+    Admin,
+    /// Holder of a tenant token, limited to its namespaces.
+    Tenant(Claims),
+}
+
+impl Principal {
+    pub fn may_access(&self, namespace: &str) -> bool {
+        match self {
+            Principal::Admin => true,
+            Principal::Tenant(claims) => claims.namespaces.iter().any(|n| n == namespace),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            Principal::Admin => "admin",
+            Principal::Tenant(claims) => &claims.sub,
+        }
+    }
+}
+
+/// Resolves a bearer token to the admin, compared in constant time, or to a
+/// valid, unexpired tenant token.
+pub fn authenticate(ctx: &Context, token: &str) -> Option<Principal> {
+    let config = ctx.config();
+    if let Some(admin) = &config.trigger_token
+        && constant_time_eq(admin.as_bytes(), token.as_bytes())
+    {
+        return Some(Principal::Admin);
+    }
+    let key = config.api_token_key.as_ref()?;
+    let validation = Validation::new(Algorithm::HS256);
+    jsonwebtoken::decode::<Claims>(
+        token,
+        &DecodingKey::from_secret(key.as_bytes()),
+        &validation,
+    )
+    .ok()
+    .map(|data| Principal::Tenant(data.claims))
+}
+
+/// Issues a token letting `tenant` act in `namespaces` for `ttl`, capped at
+/// [`MAX_TOKEN_TTL`].
+pub fn issue(
+    ctx: &Context,
+    tenant: &str,
+    namespaces: Vec<String>,
+    ttl: Duration,
+) -> Result<(String, DateTime<Utc>), Error> {
+    let key = ctx
+        .config()
+        .api_token_key
+        .clone()
+        .ok_or_else(|| Error::TokenRejected("token issuance is not configured".to_string()))?;
+    if tenant.is_empty() || namespaces.is_empty() {
+        return Err(Error::TokenRejected(
+            "a tenant and at least one namespace are required".to_string(),
+        ));
+    }
+    let now = Utc::now();
+    let expires = now + ttl.min(MAX_TOKEN_TTL);
+    let claims = Claims {
+        sub: tenant.to_string(),
+        namespaces,
+        iat: now.timestamp(),
+        exp: expires.timestamp(),
+    };
+    let token = jsonwebtoken::encode(
+        &Header::new(Algorithm::HS256),
+        &claims,
+        &EncodingKey::from_secret(key.as_bytes()),
+    )
+    .map_err(|e| Error::TokenRejected(e.to_string()))?;
+    Ok((token, expires))
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
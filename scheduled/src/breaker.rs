@@ -0,0 +1,136 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use kube::Resource;
+use serde::Serialize;
+
+use crate::throttle::{ResourceKey, resource_key};
+
+/// Annotation that resets a resource's failure state when set to any value.
+/// The controller removes it once the reset is done.
+pub const RESET_ANNOTATION: &str = "divinerapier.io/reset-circuit";
+
+/// Per-resource circuit breaker. Once a resource fails `threshold` times
+/// within `window`, its circuit opens and it is not reconciled again until
+/// `cooldown` has elapsed.
+pub struct CircuitBreaker {
+    threshold: usize,
+    window: Duration,
+    cooldown: Duration,
+    entries: Mutex<HashMap<ResourceKey, Entry>>,
+}
+
+/// A resource whose circuit is currently open.
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Quarantined {
+    pub kind: String,
+    pub namespace: String,
+    pub name: String,
+    pub failures: usize,
+    pub remaining_seconds: u64,
+}
+
+#[derive(Default)]
+struct Entry {
+    failures: VecDeque<Instant>,
+    open_until: Option<Instant>,
+}
+
+impl CircuitBreaker {
+    pub fn new(threshold: usize, window: Duration, cooldown: Duration) -> Self {
+        Self {
+            threshold,
+            window,
+            cooldown,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn cooldown(&self) -> Duration {
+        self.cooldown
+    }
+
+    /// Returns the remaining cool-down when the circuit of `resource` is open.
+    /// An expired circuit is closed again and its failures are forgotten.
+    pub fn open_for<K>(&self, resource: &K) -> Option<Duration>
+    where
+        K: Resource<DynamicType = ()>,
+    {
+        let key = resource_key(resource);
+        let now = Instant::now();
+        let mut entries = self.entries.lock().unwrap();
+        let open_until = entries.get(&key)?.open_until?;
+        if open_until > now {
+            return Some(open_until - now);
+        }
+        entries.remove(&key);
+        None
+    }
+
+    /// Records a failure of `resource`. Returns the number of failures within
+    /// the window when this failure opened the circuit.
+    pub fn record_failure<K>(&self, resource: &K) -> Option<usize>
+    where
+        K: Resource<DynamicType = ()>,
+    {
+        let now = Instant::now();
+        let mut entries = self.entries.lock().unwrap();
+        let entry = entries.entry(resource_key(resource)).or_default();
+        if entry.open_until.is_some() {
+            return None;
+        }
+
+        entry.failures.push_back(now);
+        while entry
+            .failures
+            .front()
+            .is_some_and(|t| now.duration_since(*t) > self.window)
+        {
+            entry.failures.pop_front();
+        }
+
+        if entry.failures.len() < self.threshold {
+            return None;
+        }
+        entry.open_until = Some(now + self.cooldown);
+        Some(entry.failures.len())
+    }
+
+    /// Forgets the failures of `resource`, closing its circuit. Returns
+    /// whether the circuit was open.
+    pub fn reset<K>(&self, resource: &K) -> bool
+    where
+        K: Resource<DynamicType = ()>,
+    {
+        self.entries
+            .lock()
+            .unwrap()
+            .remove(&resource_key(resource))
+            .is_some_and(|e| e.open_until.is_some())
+    }
+
+    /// Resources whose circuit is open, sorted by kind, namespace and name.
+    pub fn quarantined(&self) -> Vec<Quarantined> {
+        let now = Instant::now();
+        let entries = self.entries.lock().unwrap();
+        let mut quarantined: Vec<_> = entries
+            .iter()
+            .filter_map(|((kind, namespace, name), entry)| {
+                let open_until = entry.open_until.filter(|t| *t > now)?;
+                Some(Quarantined {
+                    kind: kind.clone(),
+                    namespace: namespace.clone(),
+                    name: name.clone(),
+                    failures: entry.failures.len(),
+                    remaining_seconds: (open_until - now).as_secs(),
+                })
+            })
+            .collect();
+        quarantined.sort_by(|a, b| {
+            (&a.kind, &a.namespace, &a.name).cmp(&(&b.kind, &b.namespace, &b.name))
+        });
+        quarantined
+    }
+}
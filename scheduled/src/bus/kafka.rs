@@ -0,0 +1,116 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+use chrono::Utc;
+use futures::future::BoxFuture;
+use reqwest::Url;
+use rskafka::client::partition::{Compression, PartitionClient, UnknownTopicHandling};
+use rskafka::client::{Client, ClientBuilder};
+use rskafka::record::Record;
+use tokio::sync::{Mutex, OnceCell};
+
+use super::Publisher;
+
+/// Produces to Kafka topics, which must already exist. Records with the same
+/// key always land on the same partition, so each resource's records stay
+/// ordered. The URL names one bootstrap broker, `kafka://host:port`; the rest
+/// of the cluster is discovered from it.
+pub struct KafkaPublisher {
+    bootstrap: String,
+    client: OnceCell<Client>,
+    partitions: Mutex<HashMap<String, Vec<Arc<PartitionClient>>>>,
+}
+
+impl KafkaPublisher {
+    pub fn from_url(url: &Url) -> Result<Self, crate::Error> {
+        let host = url
+            .host_str()
+            .ok_or_else(|| crate::Error::Bus("kafka url needs a host".to_string()))?;
+        Ok(Self {
+            bootstrap: format!("{host}:{}", url.port().unwrap_or(9092)),
+            client: OnceCell::new(),
+            partitions: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// The client of the partition `key` hashes to within `topic`.
+    async fn partition(
+        &self,
+        topic: &str,
+        key: &str,
+    ) -> Result<Arc<PartitionClient>, crate::Error> {
+        let client = self
+            .client
+            .get_or_try_init(|| ClientBuilder::new(vec![self.bootstrap.clone()]).build())
+            .await
+            .map_err(bus)?;
+        let mut partitions = self.partitions.lock().await;
+        if !partitions.contains_key(topic) {
+            let metadata = client
+                .list_topics()
+                .await
+                .map_err(bus)?
+                .into_iter()
+                .find(|t| t.name == topic)
+                .ok_or_else(|| crate::Error::Bus(format!("kafka topic {topic} does not exist")))?;
+            let mut clients = Vec::with_capacity(metadata.partitions.len());
+            for partition in metadata.partitions {
+                let partition = client
+                    .partition_client(topic, partition, UnknownTopicHandling::Error)
+                    .await
+                    .map_err(bus)?;
+                clients.push(Arc::new(partition));
+            }
+            partitions.insert(topic.to_string(), clients);
+        }
+        let clients = &partitions[topic];
+        if clients.is_empty() {
+            return Err(crate::Error::Bus(format!(
+                "kafka topic {topic} has no partitions"
+            )));
+        }
+        Ok(clients[fnv1a(key) as usize % clients.len()].clone())
+    }
+
+    async fn produce(
+        &self,
+        topic: &str,
+        key: &str,
+        payload: &serde_json::Value,
+    ) -> Result<(), crate::Error> {
+        let record = Record {
+            key: Some(key.as_bytes().to_vec()),
+            value: Some(serde_json::to_vec(payload)?),
+            headers: BTreeMap::new(),
+            timestamp: Utc::now(),
+        };
+        self.partition(topic, key)
+            .await?
+            .produce(vec![record], Compression::NoCompression)
+            .await
+            .map_err(bus)?;
+        Ok(())
+    }
+}
+
+impl Publisher for KafkaPublisher {
+    fn publish<'a>(
+        &'a self,
+        topic: &'a str,
+        key: &'a str,
+        payload: &'a serde_json::Value,
+    ) -> BoxFuture<'a, Result<(), crate::Error>> {
+        Box::pin(self.produce(topic, key, payload))
+    }
+}
+
+/// A hash that is stable across restarts, unlike the std `RandomState`.
+fn fnv1a(key: &str) -> u32 {
+    key.bytes().fold(0x811c9dc5, |hash, b| {
+        (hash ^ b as u32).wrapping_mul(0x01000193)
+    })
+}
+
+fn bus(e: rskafka::client::error::Error) -> crate::Error {
+    crate::Error::Bus(e.to_string())
+}
@@ -0,0 +1,48 @@
+#[cfg(feature = "kafka")]
+mod kafka;
+#[cfg(feature = "nats")]
+mod nats;
+
+use std::sync::Arc;
+
+use futures::future::BoxFuture;
+use reqwest::Url;
+
+#[cfg(feature = "kafka")]
+pub use kafka::KafkaPublisher;
+#[cfg(feature = "nats")]
+pub use nats::NatsPublisher;
+
+/// Topic suffix for CloudEvents describing phase transitions.
+pub const TRANSITIONS_TOPIC: &str = "transitions";
+
+/// Topic suffix for records of finished runs.
+pub const RUNS_TOPIC: &str = "runs";
+
+/// Pushes JSON records to a message bus. Topics are named
+/// `<EVENT_BUS_TOPIC_PREFIX>.<suffix>` and records are keyed by
+/// `<namespace>/<name>` so each resource's records stay ordered.
+pub trait Publisher: Send + Sync {
+    fn publish<'a>(
+        &'a self,
+        topic: &'a str,
+        key: &'a str,
+        payload: &'a serde_json::Value,
+    ) -> BoxFuture<'a, Result<(), crate::Error>>;
+}
+
+/// Builds the publisher for `url`: `nats://[user:password@]host:port` for
+/// NATS JetStream (`nats` feature) or `kafka://host:port` for Kafka (`kafka`
+/// feature).
+pub fn from_url(url: &str) -> Result<Arc<dyn Publisher>, crate::Error> {
+    let url = Url::parse(url).map_err(|e| crate::Error::Bus(e.to_string()))?;
+    match url.scheme() {
+        #[cfg(feature = "nats")]
+        "nats" => Ok(Arc::new(NatsPublisher::from_url(&url)?)),
+        #[cfg(feature = "kafka")]
+        "kafka" => Ok(Arc::new(KafkaPublisher::from_url(&url)?)),
+        scheme => Err(crate::Error::Bus(format!(
+            "unsupported event bus scheme {scheme}; is its feature enabled?"
+        ))),
+    }
+}
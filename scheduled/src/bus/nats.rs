@@ -0,0 +1,69 @@
+use async_nats::HeaderMap;
+use async_nats::jetstream::{self, Context};
+use futures::future::BoxFuture;
+use reqwest::Url;
+use tokio::sync::OnceCell;
+
+use super::Publisher;
+
+/// Publishes to NATS JetStream and waits for the stream's acknowledgement, so
+/// a record is only reported sent once stored. A stream must already capture
+/// the subjects. Credentials may be given in the URL userinfo.
+pub struct NatsPublisher {
+    url: String,
+    jetstream: OnceCell<Context>,
+}
+
+impl NatsPublisher {
+    pub fn from_url(url: &Url) -> Result<Self, crate::Error> {
+        Ok(Self {
+            url: url.to_string(),
+            jetstream: OnceCell::new(),
+        })
+    }
+
+    async fn send(
+        &self,
+        subject: &str,
+        key: &str,
+        payload: &serde_json::Value,
+    ) -> Result<(), crate::Error> {
+        let jetstream = self
+            .jetstream
+            .get_or_try_init(|| async {
+                async_nats::connect(&self.url)
+                    .await
+                    .map(jetstream::new)
+                    .map_err(bus)
+            })
+            .await?;
+        let mut headers = HeaderMap::new();
+        headers.insert("Key", key);
+        jetstream
+            .publish_with_headers(
+                subject.to_string(),
+                headers,
+                serde_json::to_vec(payload)?.into(),
+            )
+            .await
+            .map_err(bus)?
+            .await
+            .map_err(bus)?;
+        Ok(())
+    }
+}
+
+impl Publisher for NatsPublisher {
+    fn publish<'a>(
+        &'a self,
+        topic: &'a str,
+        key: &'a str,
+        payload: &'a serde_json::Value,
+    ) -> BoxFuture<'a, Result<(), crate::Error>> {
+        Box::pin(self.send(topic, key, payload))
+    }
+}
+
+fn bus(e: impl std::fmt::Display) -> crate::Error {
+    crate::Error::Bus(e.to_string())
+}
@@ -0,0 +1,95 @@
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use futures::StreamExt as _;
+use k8s_openapi::api::core::v1::Namespace;
+use kube::runtime::reflector::{self, ObjectRef, Store, store::Writer};
+use kube::runtime::watcher::{self, Event};
+use kube::runtime::{WatchStreamExt as _, reflector::reflector};
+use kube::{Api, Resource};
+use serde::de::DeserializeOwned;
+
+use crate::Context;
+use crate::crd::ScheduleCalendar;
+
+/// Watch-backed copy of a kind of small object read on most reconciliations,
+/// such as ScheduleCalendars and Namespaces, so they are not fetched from the
+/// API server each time. Until [`run`] has listed them, and in tools that do
+/// not run it, reads fall through to the API server.
+pub struct Cache<K>
+where
+    K: Resource<DynamicType = ()> + Clone + 'static,
+{
+    reader: Store<K>,
+    writer: Mutex<Option<Writer<K>>>,
+    ready: AtomicBool,
+}
+
+impl<K> Default for Cache<K>
+where
+    K: Resource<DynamicType = ()> + Clone + 'static,
+{
+    fn default() -> Self {
+        let (reader, writer) = reflector::store();
+        Self {
+            reader,
+            writer: Mutex::new(Some(writer)),
+            ready: AtomicBool::new(false),
+        }
+    }
+}
+
+impl<K> Cache<K>
+where
+    K: Resource<DynamicType = ()> + Clone + DeserializeOwned + Debug + Send + Sync + 'static,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether the objects have been listed and are kept up to date.
+    pub fn is_ready(&self) -> bool {
+        self.ready.load(Ordering::Relaxed)
+    }
+
+    /// The cached object `name` in `namespace`, `None` for cluster-scoped
+    /// kinds. Only meaningful once [`Cache::is_ready`].
+    pub fn get(&self, namespace: Option<&str>, name: &str) -> Option<Arc<K>> {
+        let mut key = ObjectRef::new(name);
+        if let Some(namespace) = namespace {
+            key = key.within(namespace);
+        }
+        self.reader.get(&key)
+    }
+
+    /// Keeps the cache in line with `api` until the watch ends. Only the
+    /// first call watches; later ones return at once.
+    async fn watch(&self, api: Api<K>) {
+        let Some(writer) = self.writer.lock().unwrap().take() else {
+            return;
+        };
+        let kind = K::kind(&());
+        let mut events = reflector(writer, watcher::watcher(api, Default::default()))
+            .default_backoff()
+            .boxed();
+        while let Some(event) = events.next().await {
+            match event {
+                Ok(Event::InitDone) => self.ready.store(true, Ordering::Relaxed),
+                Ok(_) => {}
+                Err(e) => tracing::warn!(error = ?e, %kind, "Failed to watch cached objects"),
+            }
+        }
+        self.ready.store(false, Ordering::Relaxed);
+    }
+}
+
+/// Watches the ScheduleCalendars and Namespaces cached in the [`Context`].
+pub async fn run(ctx: Arc<Context>) {
+    let client = (**ctx).clone();
+    futures::join!(
+        ctx.calendars()
+            .watch(Api::<ScheduleCalendar>::all(client.clone())),
+        ctx.namespaces().watch(Api::<Namespace>::all(client)),
+    );
+}
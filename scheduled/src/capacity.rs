@@ -0,0 +1,130 @@
+use std::collections::BTreeMap;
+
+use k8s_openapi::api::core::v1::{
+    Node, NodeSelectorRequirement, NodeSelectorTerm, PodSpec, Taint, Toleration,
+};
+
+/// Whether `pod` could be scheduled onto `node`: the node is Ready and not
+/// cordoned, its labels satisfy the pod's node selector and required node
+/// affinity, and the pod tolerates its `NoSchedule` and `NoExecute` taints.
+/// Resource requests are not considered; a full node still counts, as the
+/// autoscaler can add another like it.
+pub fn fits(pod: &PodSpec, node: &Node) -> bool {
+    schedulable(node)
+        && matches_node_selector(pod, node)
+        && matches_affinity(pod, node)
+        && tolerates_taints(pod, node)
+}
+
+fn schedulable(node: &Node) -> bool {
+    let cordoned = node
+        .spec
+        .as_ref()
+        .and_then(|s| s.unschedulable)
+        .unwrap_or(false);
+    let ready = node
+        .status
+        .as_ref()
+        .and_then(|s| s.conditions.as_ref())
+        .is_some_and(|c| c.iter().any(|c| c.type_ == "Ready" && c.status == "True"));
+    !cordoned && ready
+}
+
+fn labels(node: &Node) -> BTreeMap<String, String> {
+    node.metadata.labels.clone().unwrap_or_default()
+}
+
+fn matches_node_selector(pod: &PodSpec, node: &Node) -> bool {
+    let labels = labels(node);
+    pod.node_selector
+        .iter()
+        .flatten()
+        .all(|(key, value)| labels.get(key) == Some(value))
+}
+
+fn matches_affinity(pod: &PodSpec, node: &Node) -> bool {
+    let Some(required) = pod
+        .affinity
+        .as_ref()
+        .and_then(|a| a.node_affinity.as_ref())
+        .and_then(|a| {
+            a.required_during_scheduling_ignored_during_execution
+                .as_ref()
+        })
+    else {
+        return true;
+    };
+    // Terms are ORed, the requirements within a term ANDed.
+    required
+        .node_selector_terms
+        .iter()
+        .any(|term| matches_term(term, node))
+}
+
+fn matches_term(term: &NodeSelectorTerm, node: &Node) -> bool {
+    let labels = labels(node);
+    let name = node.metadata.name.clone();
+    let expressions = term
+        .match_expressions
+        .iter()
+        .flatten()
+        .all(|r| matches_requirement(r, labels.get(&r.key)));
+    let fields = term.match_fields.iter().flatten().all(|r| {
+        let value = (r.key == "metadata.name")
+            .then_some(name.as_ref())
+            .flatten();
+        matches_requirement(r, value)
+    });
+    expressions && fields
+}
+
+fn matches_requirement(requirement: &NodeSelectorRequirement, value: Option<&String>) -> bool {
+    let values = requirement.values.as_deref().unwrap_or_default();
+    let number = |s: &String| s.parse::<i64>().ok();
+    let compare = |ordering| {
+        let bound = values.first().and_then(number);
+        match (value.and_then(number), bound) {
+            (Some(value), Some(bound)) => value.cmp(&bound) == ordering,
+            _ => false,
+        }
+    };
+    match requirement.operator.as_str() {
+        "In" => value.is_some_and(|v| values.contains(v)),
+        "NotIn" => value.is_none_or(|v| !values.contains(v)),
+        "Exists" => value.is_some(),
+        "DoesNotExist" => value.is_none(),
+        "Gt" => compare(std::cmp::Ordering::Greater),
+        "Lt" => compare(std::cmp::Ordering::Less),
+        _ => false,
+    }
+}
+
+fn tolerates_taints(pod: &PodSpec, node: &Node) -> bool {
+    let tolerations = pod.tolerations.as_deref().unwrap_or_default();
+    node.spec
+        .as_ref()
+        .and_then(|s| s.taints.as_ref())
+        .into_iter()
+        .flatten()
+        .filter(|t| t.effect != "PreferNoSchedule")
+        .all(|taint| tolerations.iter().any(|t| tolerates(t, taint)))
+}
+
+fn tolerates(toleration: &Toleration, taint: &Taint) -> bool {
+    if toleration
+        .effect
+        .as_deref()
+        .is_some_and(|e| !e.is_empty() && e != taint.effect)
+    {
+        return false;
+    }
+    let key = toleration.key.as_deref().unwrap_or_default();
+    match toleration.operator.as_deref() {
+        Some("Exists") => key.is_empty() || key == taint.key,
+        _ => {
+            key == taint.key
+                && toleration.value.as_deref().unwrap_or_default()
+                    == taint.value.as_deref().unwrap_or_default()
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use chrono::{DateTime, Utc};
+use kube::{Resource, ResourceExt as _};
+use serde::Serialize;
+
+use crate::crd::{DelayedJobPhase, HasOwner, Owner, ScheduledCronJobPhase};
+
+/// Prefix of the CloudEvents `type` attribute, followed by the transition.
+pub const TYPE_PREFIX: &str = "io.divinerapier.scheduled.";
+
+/// Lifecycle transitions published as CloudEvents.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Transition {
+    Created,
+    Fired,
+    Succeeded,
+    Failed,
+    Expired,
+}
+
+impl Transition {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Transition::Created => "created",
+            Transition::Fired => "fired",
+            Transition::Succeeded => "succeeded",
+            Transition::Failed => "failed",
+            Transition::Expired => "expired",
+        }
+    }
+}
+
+impl ScheduledCronJobPhase {
+    /// The transition entering this phase represents. A ScheduledCronJob
+    /// completes when its window ends, so only the expiry is reported.
+    pub fn transition(&self) -> Option<Transition> {
+        match self {
+            ScheduledCronJobPhase::PendingActivation => Some(Transition::Created),
+            ScheduledCronJobPhase::Active => Some(Transition::Fired),
+            ScheduledCronJobPhase::Expired => Some(Transition::Expired),
+            ScheduledCronJobPhase::Failed => Some(Transition::Failed),
+            ScheduledCronJobPhase::PendingValidation
+            | ScheduledCronJobPhase::Suspended
+            | ScheduledCronJobPhase::Completed => None,
+        }
+    }
+}
+
+impl DelayedJobPhase {
+    /// The transition entering this phase represents.
+    pub fn transition(&self) -> Option<Transition> {
+        match self {
+            DelayedJobPhase::Pending => Some(Transition::Created),
+            DelayedJobPhase::Running => Some(Transition::Fired),
+            DelayedJobPhase::Completed => Some(Transition::Succeeded),
+            DelayedJobPhase::Failed | DelayedJobPhase::InvalidStartTime => Some(Transition::Failed),
+            DelayedJobPhase::Skipped => Some(Transition::Expired),
+            DelayedJobPhase::Unknown => None,
+        }
+    }
+}
+
+/// A CloudEvents 1.0 event in the structured JSON format.
+#[derive(Serialize, Clone, Debug)]
+pub struct CloudEvent {
+    pub specversion: &'static str,
+    pub id: String,
+    pub source: String,
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub subject: String,
+    pub time: DateTime<Utc>,
+    pub datacontenttype: &'static str,
+    pub data: EventData,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct EventData {
+    pub kind: String,
+    pub namespace: String,
+    pub name: String,
+    pub uid: Option<String>,
+    pub phase: String,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner: Option<Owner>,
+}
+
+impl CloudEvent {
+    /// Describes `resource` entering `phase`. The source is the resource's
+    /// API path, so consumers can filter by kind or namespace.
+    pub fn new<K>(resource: &K, transition: Transition, phase: &str, message: &str) -> Self
+    where
+        K: Resource<DynamicType = ()> + HasOwner,
+    {
+        let namespace = resource.namespace().unwrap_or_default();
+        let name = resource.name_any();
+        let time = Utc::now();
+        Self {
+            specversion: "1.0",
+            id: format!(
+                "{}-{}-{}",
+                resource.uid().unwrap_or_else(|| name.clone()),
+                transition.as_str(),
+                time.timestamp_nanos_opt().unwrap_or_default()
+            ),
+            source: format!(
+                "/apis/{}/namespaces/{namespace}/{}",
+                K::api_version(&()),
+                K::plural(&())
+            ),
+            type_: format!("{TYPE_PREFIX}{}", transition.as_str()),
+            subject: name.clone(),
+            time,
+            datacontenttype: "application/json",
+            data: EventData {
+                kind: K::kind(&()).into_owned(),
+                namespace,
+                name,
+                uid: resource.uid(),
+                phase: phase.to_string(),
+                message: message.to_string(),
+                owner: resource.owner().cloned(),
+            },
+        }
+    }
+}
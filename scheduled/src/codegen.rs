@@ -0,0 +1,202 @@
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+
+use k8s_openapi::apiextensions_apiserver::pkg::apis::apiextensions::v1::{
+    CustomResourceDefinition, JSONSchemaProps, JSONSchemaPropsOrArray, JSONSchemaPropsOrBool,
+};
+
+/// Returns the schema of `.spec` in the storage version of `crd`.
+pub fn spec_schema(crd: &CustomResourceDefinition) -> Result<&JSONSchemaProps, String> {
+    let version = crd
+        .spec
+        .versions
+        .iter()
+        .find(|v| v.storage)
+        .ok_or("CRD has no storage version")?;
+    version
+        .schema
+        .as_ref()
+        .and_then(|s| s.open_api_v3_schema.as_ref())
+        .and_then(|s| s.properties.as_ref())
+        .and_then(|p| p.get("spec"))
+        .ok_or_else(|| format!("{} has no spec schema", crd.spec.names.kind))
+}
+
+/// A named object type collected from a schema. Nested objects become types
+/// of their own, named after the path leading to them.
+struct Type<'a> {
+    name: String,
+    schema: &'a JSONSchemaProps,
+}
+
+fn collect<'a>(name: String, schema: &'a JSONSchemaProps, types: &mut Vec<Type<'a>>) {
+    for (field, property) in schema.properties.iter().flatten() {
+        collect_nested(format!("{name}{}", pascal_case(field)), property, types);
+    }
+    types.push(Type { name, schema });
+}
+
+fn collect_nested<'a>(name: String, schema: &'a JSONSchemaProps, types: &mut Vec<Type<'a>>) {
+    if schema.properties.is_some() {
+        collect(name, schema, types);
+    } else if let Some(items) = items(schema) {
+        collect_nested(name, items, types);
+    } else if let Some(values) = additional_properties(schema) {
+        collect_nested(name, values, types);
+    }
+}
+
+fn items(schema: &JSONSchemaProps) -> Option<&JSONSchemaProps> {
+    match schema.items.as_ref()? {
+        JSONSchemaPropsOrArray::Schema(items) => Some(items),
+        JSONSchemaPropsOrArray::Schemas(_) => None,
+    }
+}
+
+fn additional_properties(schema: &JSONSchemaProps) -> Option<&JSONSchemaProps> {
+    match schema.additional_properties.as_ref()? {
+        JSONSchemaPropsOrBool::Schema(values) => Some(values),
+        JSONSchemaPropsOrBool::Bool(_) => None,
+    }
+}
+
+fn pascal_case(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+fn is_required(schema: &JSONSchemaProps, field: &str) -> bool {
+    schema
+        .required
+        .as_ref()
+        .is_some_and(|r| r.iter().any(|f| f == field))
+}
+
+/// Emits TypeScript interfaces for the given root schemas, keyed by type name.
+pub fn typescript(roots: &BTreeMap<String, &JSONSchemaProps>) -> String {
+    let mut out = String::from("// Code generated by typegen. DO NOT EDIT.\n");
+    for (root, schema) in roots {
+        let mut types = Vec::new();
+        collect(root.clone(), schema, &mut types);
+        for ty in types {
+            out.push('\n');
+            doc(&mut out, "", ty.schema, "/**", " * ", " */");
+            writeln!(out, "export interface {} {{", ty.name).unwrap();
+            for (field, property) in ty.schema.properties.iter().flatten() {
+                let optional = if is_required(ty.schema, field) {
+                    ""
+                } else {
+                    "?"
+                };
+                let type_ = ts_type(&format!("{}{}", ty.name, pascal_case(field)), property);
+                doc(&mut out, "  ", property, "/**", " * ", " */");
+                writeln!(out, "  {field}{optional}: {type_};").unwrap();
+            }
+            out.push_str("}\n");
+        }
+    }
+    out
+}
+
+fn ts_type(name: &str, schema: &JSONSchemaProps) -> String {
+    if schema.x_kubernetes_int_or_string == Some(true) {
+        return "number | string".to_string();
+    }
+    if schema.properties.is_some() {
+        return name.to_string();
+    }
+    if let Some(items) = items(schema) {
+        return format!("Array<{}>", ts_type(name, items));
+    }
+    if let Some(values) = additional_properties(schema) {
+        return format!("Record<string, {}>", ts_type(name, values));
+    }
+    match schema.type_.as_deref() {
+        Some("string") => "string",
+        Some("integer" | "number") => "number",
+        Some("boolean") => "boolean",
+        Some("object") => "Record<string, unknown>",
+        _ => "unknown",
+    }
+    .to_string()
+}
+
+/// Emits Go structs in package `package` for the given root schemas, keyed
+/// by type name.
+pub fn go(package: &str, roots: &BTreeMap<String, &JSONSchemaProps>) -> String {
+    let mut out = format!("// Code generated by typegen. DO NOT EDIT.\n\npackage {package}\n");
+    for (root, schema) in roots {
+        let mut types = Vec::new();
+        collect(root.clone(), schema, &mut types);
+        for ty in types {
+            out.push('\n');
+            doc(&mut out, "", ty.schema, "", "// ", "");
+            writeln!(out, "type {} struct {{", ty.name).unwrap();
+            for (field, property) in ty.schema.properties.iter().flatten() {
+                let required = is_required(ty.schema, field);
+                let mut type_ = go_type(&format!("{}{}", ty.name, pascal_case(field)), property);
+                let mut tag = field.clone();
+                if !required {
+                    tag.push_str(",omitempty");
+                    if !type_.starts_with("[]") && !type_.starts_with("map[") && type_ != "any" {
+                        type_ = format!("*{type_}");
+                    }
+                }
+                doc(&mut out, "\t", property, "", "// ", "");
+                writeln!(out, "\t{} {type_} `json:\"{tag}\"`", pascal_case(field)).unwrap();
+            }
+            out.push_str("}\n");
+        }
+    }
+    out
+}
+
+fn go_type(name: &str, schema: &JSONSchemaProps) -> String {
+    if schema.x_kubernetes_int_or_string == Some(true) {
+        return "any".to_string();
+    }
+    if schema.properties.is_some() {
+        return name.to_string();
+    }
+    if let Some(items) = items(schema) {
+        return format!("[]{}", go_type(name, items));
+    }
+    if let Some(values) = additional_properties(schema) {
+        return format!("map[string]{}", go_type(name, values));
+    }
+    match (schema.type_.as_deref(), schema.format.as_deref()) {
+        (Some("string"), _) => "string",
+        (Some("integer"), Some("int32")) => "int32",
+        (Some("integer"), _) => "int64",
+        (Some("number"), _) => "float64",
+        (Some("boolean"), _) => "bool",
+        (Some("object"), _) => "map[string]any",
+        _ => "any",
+    }
+    .to_string()
+}
+
+fn doc(
+    out: &mut String,
+    indent: &str,
+    schema: &JSONSchemaProps,
+    open: &str,
+    line: &str,
+    close: &str,
+) {
+    let Some(description) = schema.description.as_deref() else {
+        return;
+    };
+    if !open.is_empty() {
+        writeln!(out, "{indent}{open}").unwrap();
+    }
+    for text in description.lines() {
+        writeln!(out, "{indent}{}", format!("{line}{text}").trim_end()).unwrap();
+    }
+    if !close.is_empty() {
+        writeln!(out, "{indent}{close}").unwrap();
+    }
+}
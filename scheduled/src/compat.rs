@@ -0,0 +1,191 @@
+use std::fmt;
+
+use k8s_openapi::api::batch::v1::{CronJob, JobSpec};
+use k8s_openapi::api::core::v1::{Capabilities, PodSpec, SeccompProfile};
+use kube::Client;
+
+/// A Kubernetes release, such as 1.29.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ServerVersion {
+    pub major: u32,
+    pub minor: u32,
+}
+
+impl ServerVersion {
+    pub const fn new(major: u32, minor: u32) -> Self {
+        Self { major, minor }
+    }
+
+    /// Parses the `major` and `minor` of the API server's `/version`. Some
+    /// distributions append `+` to the minor version.
+    pub fn parse(major: &str, minor: &str) -> Option<Self> {
+        let number = |s: &str| s.trim_end_matches('+').parse().ok();
+        Some(Self::new(number(major)?, number(minor)?))
+    }
+}
+
+impl fmt::Display for ServerVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+/// Asks the API server for its version. `None` when it cannot be read, in
+/// which case every feature is assumed to be supported.
+pub async fn detect(client: &Client) -> Option<ServerVersion> {
+    match client.apiserver_version().await {
+        Ok(info) => {
+            let version = ServerVersion::parse(&info.major, &info.minor);
+            if version.is_none() {
+                tracing::warn!(
+                    major = info.major,
+                    minor = info.minor,
+                    "Unrecognized API server version"
+                );
+            }
+            version
+        }
+        Err(e) => {
+            tracing::warn!(error = ?e, "Cannot read the API server version");
+            None
+        }
+    }
+}
+
+/// API Events are recorded through.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EventsApi {
+    /// `events.k8s.io/v1`, served from Kubernetes 1.19.
+    EventsV1,
+    /// `v1`, served by every release.
+    #[default]
+    CoreV1,
+}
+
+impl fmt::Display for EventsApi {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EventsApi::EventsV1 => write!(f, "events.k8s.io/v1"),
+            EventsApi::CoreV1 => write!(f, "v1"),
+        }
+    }
+}
+
+/// Asks the API server whether it serves `events.k8s.io/v1` Events, falling
+/// back to `v1` ones when it does not or cannot tell.
+pub async fn detect_events_api(client: &Client) -> EventsApi {
+    match client.list_api_group_resources("events.k8s.io/v1").await {
+        Ok(list) if list.resources.iter().any(|r| r.name == "events") => EventsApi::EventsV1,
+        Ok(_) | Err(kube::Error::Api(_)) => EventsApi::CoreV1,
+        Err(e) => {
+            tracing::warn!(error = ?e, "Cannot discover the events API, recording v1 Events");
+            EventsApi::CoreV1
+        }
+    }
+}
+
+/// A field of child manifests older API servers reject or ignore.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Feature {
+    /// `spec.timeZone` of CronJobs.
+    TimeZone,
+    /// `spec.podFailurePolicy` of Jobs, used to retry preempted pods.
+    PodFailurePolicy,
+    /// Init containers with `restartPolicy: Always`.
+    NativeSidecars,
+}
+
+impl Feature {
+    /// The first release the feature is enabled by default in.
+    pub fn since(&self) -> ServerVersion {
+        match self {
+            Feature::TimeZone => ServerVersion::new(1, 27),
+            Feature::PodFailurePolicy => ServerVersion::new(1, 26),
+            Feature::NativeSidecars => ServerVersion::new(1, 29),
+        }
+    }
+
+    /// Whether `version` supports the feature. An unknown version is assumed
+    /// to.
+    pub fn supported(&self, version: Option<ServerVersion>) -> bool {
+        version.is_none_or(|v| v >= self.since())
+    }
+}
+
+/// Removes the features of `cronjob` that `version` does not support,
+/// describing what was disabled.
+pub fn downgrade(cronjob: &mut CronJob, version: Option<ServerVersion>) -> Vec<String> {
+    let Some(spec) = cronjob.spec.as_mut() else {
+        return Vec::new();
+    };
+    let mut disabled = Vec::new();
+    if !Feature::TimeZone.supported(version)
+        && let Some(time_zone) = spec.time_zone.take()
+    {
+        disabled.push(format!(
+            "timeZone {time_zone} needs Kubernetes {}, schedules are read in the \
+             kube-controller-manager's zone",
+            Feature::TimeZone.since()
+        ));
+    }
+    if let Some(job) = spec.job_template.spec.as_mut() {
+        disabled.extend(downgrade_job(job, version));
+    }
+    disabled
+}
+
+/// [`downgrade`] for the spec of a Job. Native sidecars are only reported:
+/// running them as plain containers would keep the Job from completing.
+pub fn downgrade_job(spec: &mut JobSpec, version: Option<ServerVersion>) -> Vec<String> {
+    let mut disabled = Vec::new();
+    if !Feature::PodFailurePolicy.supported(version) && spec.pod_failure_policy.take().is_some() {
+        disabled.push(format!(
+            "podFailurePolicy needs Kubernetes {}, preempted pods count against backoffLimit",
+            Feature::PodFailurePolicy.since()
+        ));
+    }
+    let sidecars: Vec<_> = spec
+        .template
+        .spec
+        .iter()
+        .flat_map(|pod| pod.init_containers.iter().flatten())
+        .filter(|c| c.restart_policy.as_deref() == Some("Always"))
+        .map(|c| c.name.as_str())
+        .collect();
+    if !Feature::NativeSidecars.supported(version) && !sidecars.is_empty() {
+        disabled.push(format!(
+            "init containers {} run as sidecars only from Kubernetes {} and block the pod",
+            sidecars.join(", "),
+            Feature::NativeSidecars.since()
+        ));
+    }
+    disabled
+}
+
+/// Fills in the `securityContext` OpenShift's `restricted-v2` SCC requires of
+/// `pod` where it is unset: a non-root user with the runtime's seccomp
+/// profile, and containers without privilege escalation or capabilities.
+/// Explicit settings are kept, so a fixed `runAsUser` outside the namespace's
+/// UID range is still rejected.
+pub fn restrict(pod: &mut PodSpec) {
+    let context = pod.security_context.get_or_insert_default();
+    context.run_as_non_root.get_or_insert(true);
+    context
+        .seccomp_profile
+        .get_or_insert_with(|| SeccompProfile {
+            type_: "RuntimeDefault".to_string(),
+            ..Default::default()
+        });
+    let containers = pod
+        .containers
+        .iter_mut()
+        .chain(pod.init_containers.iter_mut().flatten());
+    for container in containers {
+        let context = container.security_context.get_or_insert_default();
+        context.allow_privilege_escalation.get_or_insert(false);
+        context.capabilities.get_or_insert_with(|| Capabilities {
+            drop: Some(vec!["ALL".to_string()]),
+            ..Default::default()
+        });
+    }
+}
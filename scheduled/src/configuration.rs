@@ -0,0 +1,62 @@
+use std::sync::Arc;
+
+use futures::StreamExt as _;
+use kube::Api;
+use kube::runtime::watcher::{self, Event};
+
+use crate::Context;
+use crate::crd::ControllerConfiguration;
+
+/// Reads the ControllerConfiguration once, before the controllers are built,
+/// so settings only read at startup see it.
+pub async fn load(ctx: &Context) {
+    let name = ctx.config().controller_configuration.clone();
+    let api = Api::<ControllerConfiguration>::all((**ctx).clone());
+    match api.get_opt(&name).await {
+        Ok(configuration) => {
+            ctx.set_configuration(configuration.as_ref().map(|c| &c.spec));
+        }
+        Err(e) => {
+            tracing::warn!(error = ?e, name, "Failed to read controller configuration");
+        }
+    }
+}
+
+/// Watches the ControllerConfiguration named by `CONTROLLER_CONFIGURATION`
+/// and applies its settings as it changes. Without it, the environment
+/// decides.
+pub async fn run(ctx: Arc<Context>) {
+    let name = ctx.config().controller_configuration.clone();
+    let api = Api::<ControllerConfiguration>::all((**ctx).clone());
+    let watcher_config = watcher::Config::default().fields(&format!("metadata.name={name}"));
+
+    let mut seen = false;
+    let mut events = watcher::watcher(api, watcher_config).boxed();
+    while let Some(event) = events.next().await {
+        match event {
+            Ok(Event::Init) => seen = false,
+            Ok(Event::InitApply(configuration) | Event::Apply(configuration)) => {
+                seen = true;
+                set(&ctx, Some(&configuration));
+            }
+            Ok(Event::InitDone) if !seen => set(&ctx, None),
+            Ok(Event::InitDone) => {}
+            Ok(Event::Delete(_)) => {
+                seen = false;
+                set(&ctx, None);
+            }
+            Err(e) => {
+                tracing::warn!(error = ?e, name, "Failed to watch controller configuration");
+            }
+        }
+    }
+}
+
+fn set(ctx: &Context, configuration: Option<&ControllerConfiguration>) {
+    if ctx.set_configuration(configuration.map(|c| &c.spec)) {
+        tracing::info!(
+            configured = configuration.is_some(),
+            "Applied controller configuration"
+        );
+    }
+}
@@ -0,0 +1,142 @@
+#[cfg(feature = "nats")]
+mod nats;
+mod sqs;
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use kube::Error as KubeError;
+use reqwest::Url;
+use ring::digest;
+use serde::Deserialize;
+
+use crate::crd::{DelayedJob, DelayedJobSpec};
+use crate::{Context, Error};
+
+/// Annotation recording the ID of the message a DelayedJob was created from.
+pub const MESSAGE_ID_ANNOTATION: &str = "divinerapier.io/message-id";
+
+/// How often a replica that may not consume checks again.
+const STANDBY_RECHECK: Duration = Duration::from_secs(5);
+
+/// A DelayedJob requested through a queue message.
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct JobRequest {
+    pub namespace: String,
+
+    /// Prefix of the DelayedJob name, completed with a hash of the message ID.
+    #[serde(default = "default_name")]
+    pub name: String,
+
+    #[serde(default)]
+    pub labels: BTreeMap<String, String>,
+
+    #[serde(flatten)]
+    pub spec: DelayedJobSpec,
+}
+
+fn default_name() -> String {
+    "queued".to_string()
+}
+
+/// What became of a message.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    /// A DelayedJob of this name was created.
+    Created(String),
+    /// A DelayedJob was already created from this message ID.
+    Duplicate,
+    /// The payload can never be materialized; it should be dead-lettered.
+    Invalid(String),
+}
+
+impl Outcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Outcome::Created(_) => "created",
+            Outcome::Duplicate => "duplicate",
+            Outcome::Invalid(_) => "invalid",
+        }
+    }
+}
+
+/// Consumes job requests from `consumer_source` until the future is dropped:
+/// `sqs://<queue URL host and path>` for SQS, or `nats://host:port` for a
+/// NATS JetStream stream (`nats` feature). Only the leader consumes, and not
+/// while the emergency stop is engaged.
+pub async fn run(ctx: Arc<Context>) {
+    if let Some(source) = ctx.config().consumer_source.clone() {
+        let result = match Url::parse(&source) {
+            Ok(url) => match url.scheme() {
+                "sqs" => sqs::run(ctx.clone(), &url).await,
+                #[cfg(feature = "nats")]
+                "nats" => nats::run(ctx.clone(), &url).await,
+                scheme => Err(Error::Consumer(format!(
+                    "unsupported consumer scheme {scheme}; is its feature enabled?"
+                ))),
+            },
+            Err(e) => Err(Error::Consumer(e.to_string())),
+        };
+        if let Err(e) = result {
+            tracing::error!(error = ?e, "Queue consumer stopped");
+        }
+    }
+    futures::future::pending().await
+}
+
+/// Waits until this replica leads and the emergency stop is released.
+async fn wait_until_active(ctx: &Context) {
+    while !active(ctx) {
+        tokio::time::sleep(STANDBY_RECHECK).await;
+    }
+}
+
+fn active(ctx: &Context) -> bool {
+    ctx.leadership().is_leader() && !ctx.emergency_stop().engaged()
+}
+
+/// Creates the DelayedJob requested by message `id`. Its name is derived
+/// from `id`, so a redelivered message finds the DelayedJob already exists.
+/// Errors are transient and the message should be redelivered.
+pub async fn materialize(ctx: &Context, id: &str, body: &[u8]) -> Result<Outcome, Error> {
+    let request: JobRequest = match serde_json::from_slice(body) {
+        Ok(request) => request,
+        Err(e) => return Ok(record(ctx, Outcome::Invalid(e.to_string()))),
+    };
+    let hash = digest::digest(&digest::SHA256, id.as_bytes());
+    let suffix: String = hash.as_ref()[..5]
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect();
+    let prefix: String = request.name.chars().take(52).collect();
+    let name = format!("{prefix}-{suffix}");
+
+    let mut delayed_job = DelayedJob::new(&name, request.spec);
+    delayed_job.metadata.namespace = Some(request.namespace.clone());
+    delayed_job.metadata.labels = Some(request.labels);
+    delayed_job.metadata.annotations = Some(BTreeMap::from([(
+        MESSAGE_ID_ANNOTATION.to_string(),
+        id.to_string(),
+    )]));
+
+    let outcome = match ctx.create(&request.namespace, &delayed_job).await {
+        Ok(_) => Outcome::Created(name),
+        Err(Error::Kube(KubeError::Api(e))) if e.code == 409 => Outcome::Duplicate,
+        Err(Error::Kube(KubeError::Api(e))) if matches!(e.code, 400 | 404 | 422) => {
+            Outcome::Invalid(e.message)
+        }
+        Err(e @ Error::NamespaceTerminating(_)) => Outcome::Invalid(e.to_string()),
+        Err(e) => return Err(e),
+    };
+    Ok(record(ctx, outcome))
+}
+
+fn record(ctx: &Context, outcome: Outcome) -> Outcome {
+    ctx.metrics()
+        .consumed_messages_total
+        .with_label_values(&[outcome.as_str()])
+        .inc();
+    outcome
+}
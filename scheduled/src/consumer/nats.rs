@@ -0,0 +1,143 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_nats::HeaderMap;
+use async_nats::jetstream::{self, AckKind, consumer::pull};
+use futures::StreamExt as _;
+use reqwest::Url;
+
+use super::{Outcome, active, materialize, wait_until_active};
+use crate::{Context, Error};
+
+/// Delay before a message that failed transiently is redelivered.
+const REDELIVERY_DELAY: Duration = Duration::from_secs(30);
+
+/// Consumes from a JetStream stream through a durable pull consumer.
+///
+/// The source URL's `stream` query parameter names the stream and `subject`
+/// optionally filters it. `consumer` names the durable consumer, by default
+/// `scheduled-cronjob`. Invalid payloads are published to the `deadLetter`
+/// subject with an `Error` header, or terminated when it is unset. Message
+/// IDs are taken from `Nats-Msg-Id`, falling back to the stream sequence.
+pub(super) async fn run(ctx: Arc<Context>, url: &Url) -> Result<(), Error> {
+    let query = |key: &str| {
+        url.query_pairs()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.into_owned())
+    };
+    let stream_name =
+        query("stream").ok_or_else(|| Error::Consumer("nats source needs a stream".to_string()))?;
+    let durable = query("consumer").unwrap_or_else(|| "scheduled-cronjob".to_string());
+    let dead_letter = query("deadLetter");
+    let mut server = url.clone();
+    server.set_query(None);
+
+    let client = async_nats::connect(server.as_str())
+        .await
+        .map_err(consumer)?;
+    let jetstream = jetstream::new(client);
+    let consumer = jetstream
+        .get_stream(&stream_name)
+        .await
+        .map_err(consumer)?
+        .get_or_create_consumer(
+            &durable,
+            pull::Config {
+                durable_name: Some(durable.clone()),
+                filter_subject: query("subject").unwrap_or_default(),
+                ..Default::default()
+            },
+        )
+        .await
+        .map_err(consumer)?;
+    tracing::info!(
+        stream = stream_name,
+        consumer = durable,
+        "Consuming job requests from NATS"
+    );
+
+    loop {
+        wait_until_active(&ctx).await;
+        let mut messages = match consumer.messages().await {
+            Ok(messages) => messages,
+            Err(e) => {
+                tracing::warn!(error = ?e, "Failed to pull from NATS");
+                tokio::time::sleep(REDELIVERY_DELAY).await;
+                continue;
+            }
+        };
+        while let Some(message) = messages.next().await {
+            let message = match message {
+                Ok(message) => message,
+                Err(e) => {
+                    tracing::warn!(error = ?e, "Failed to receive from NATS");
+                    break;
+                }
+            };
+            if !active(&ctx) {
+                let _ = message.ack_with(AckKind::Nak(None)).await;
+                break;
+            }
+            let id = message
+                .headers
+                .as_ref()
+                .and_then(|h| h.get("Nats-Msg-Id"))
+                .map(|v| v.to_string())
+                .or_else(|| {
+                    let info = message.info().ok()?;
+                    Some(format!("{}-{}", info.stream, info.stream_sequence))
+                })
+                .unwrap_or_default();
+            let ack = match materialize(&ctx, &id, &message.payload).await {
+                Ok(Outcome::Created(name)) => {
+                    tracing::info!(message_id = id, name, "Created DelayedJob from NATS");
+                    AckKind::Ack
+                }
+                Ok(Outcome::Duplicate) => {
+                    tracing::debug!(message_id = id, "Skipping duplicate NATS message");
+                    AckKind::Ack
+                }
+                Ok(Outcome::Invalid(reason)) => {
+                    tracing::warn!(message_id = id, reason, "Invalid NATS message");
+                    match &dead_letter {
+                        Some(subject) => {
+                            let mut headers = HeaderMap::new();
+                            headers.insert("Error", reason.as_str());
+                            headers.insert("Nats-Msg-Id", id.as_str());
+                            match jetstream
+                                .publish_with_headers(
+                                    subject.clone(),
+                                    headers,
+                                    message.payload.clone(),
+                                )
+                                .await
+                            {
+                                Ok(_) => AckKind::Ack,
+                                Err(e) => {
+                                    tracing::warn!(error = ?e, "Failed to dead-letter NATS message");
+                                    AckKind::Nak(Some(REDELIVERY_DELAY))
+                                }
+                            }
+                        }
+                        None => AckKind::Term,
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        message_id = id,
+                        error = ?e,
+                        "Failed to handle NATS message, redelivering"
+                    );
+                    AckKind::Nak(Some(REDELIVERY_DELAY))
+                }
+            };
+            if let Err(e) = message.ack_with(ack).await {
+                tracing::warn!(message_id = id, error = ?e, "Failed to acknowledge NATS message");
+            }
+        }
+    }
+}
+
+fn consumer(e: impl std::fmt::Display) -> Error {
+    Error::Consumer(e.to_string())
+}
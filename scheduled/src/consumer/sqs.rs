@@ -0,0 +1,193 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use reqwest::Url;
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use serde_json::json;
+
+use super::{Outcome, active, materialize, wait_until_active};
+use crate::aws::{self, Credentials};
+use crate::{Context, Error};
+
+/// Pause after a failed receive before polling again.
+const RETRY: Duration = Duration::from_secs(10);
+
+/// An SQS queue, reached through the JSON protocol.
+///
+/// The source URL is the queue URL with the `sqs` scheme. Its `deadLetter`
+/// query parameter names the queue invalid payloads are moved to; without it
+/// they are left for the queue's own redrive policy. `region` and `endpoint`
+/// override the region parsed from the host and the endpoint, e.g. for
+/// LocalStack.
+struct Queue {
+    http: reqwest::Client,
+    endpoint: Url,
+    queue_url: String,
+    dead_letter: Option<String>,
+    region: String,
+    credentials: Credentials,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ReceiveMessageResult {
+    #[serde(default)]
+    messages: Vec<Message>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct Message {
+    message_id: String,
+    receipt_handle: String,
+    body: String,
+}
+
+pub(super) async fn run(ctx: Arc<Context>, url: &Url) -> Result<(), Error> {
+    let queue = Queue::from_url(url, ctx.http().clone())?;
+    tracing::info!(queue = queue.queue_url, "Consuming job requests from SQS");
+    loop {
+        wait_until_active(&ctx).await;
+        let messages = match queue.receive().await {
+            Ok(messages) => messages,
+            Err(e) => {
+                tracing::warn!(error = ?e, "Failed to receive from SQS");
+                tokio::time::sleep(RETRY).await;
+                continue;
+            }
+        };
+        for message in messages {
+            // Unhandled messages become visible again after the visibility
+            // timeout, so handing over mid-batch loses nothing.
+            if !active(&ctx) {
+                break;
+            }
+            if let Err(e) = queue.handle(&ctx, &message).await {
+                tracing::warn!(
+                    message_id = message.message_id,
+                    error = ?e,
+                    "Failed to handle SQS message, leaving it for redelivery"
+                );
+            }
+        }
+    }
+}
+
+impl Queue {
+    fn from_url(url: &Url, http: reqwest::Client) -> Result<Self, Error> {
+        let host = url
+            .host_str()
+            .ok_or_else(|| Error::Consumer("sqs url needs a host".to_string()))?;
+        let query = |key: &str| {
+            url.query_pairs()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v.into_owned())
+        };
+        let region = query("region")
+            .or_else(|| {
+                host.strip_prefix("sqs.")
+                    .and_then(|h| h.split('.').next())
+                    .map(str::to_string)
+            })
+            .unwrap_or_else(aws::default_region);
+        let endpoint = query("endpoint").unwrap_or_else(|| format!("https://{host}"));
+        Ok(Self {
+            http,
+            endpoint: Url::parse(&endpoint).map_err(|e| Error::Consumer(e.to_string()))?,
+            queue_url: format!("https://{host}{}", url.path()),
+            dead_letter: query("deadLetter"),
+            region,
+            credentials: Credentials::from_env().map_err(Error::Consumer)?,
+        })
+    }
+
+    async fn call<T: DeserializeOwned>(
+        &self,
+        action: &str,
+        body: serde_json::Value,
+    ) -> Result<T, Error> {
+        let body = serde_json::to_vec(&body)?;
+        let headers = aws::sign(
+            &self.credentials,
+            &self.region,
+            "sqs",
+            "POST",
+            &self.endpoint,
+            &body,
+        );
+        let mut request = self
+            .http
+            .post(self.endpoint.clone())
+            .header("content-type", "application/x-amz-json-1.0")
+            .header("x-amz-target", format!("AmazonSQS.{action}"))
+            .body(body);
+        for (key, value) in headers {
+            request = request.header(key, value);
+        }
+        Ok(request.send().await?.error_for_status()?.json().await?)
+    }
+
+    async fn receive(&self) -> Result<Vec<Message>, Error> {
+        let result: ReceiveMessageResult = self
+            .call(
+                "ReceiveMessage",
+                json!({
+                    "QueueUrl": self.queue_url,
+                    "MaxNumberOfMessages": 10,
+                    "WaitTimeSeconds": 20,
+                }),
+            )
+            .await?;
+        Ok(result.messages)
+    }
+
+    async fn delete(&self, message: &Message) -> Result<(), Error> {
+        self.call::<serde_json::Value>(
+            "DeleteMessage",
+            json!({ "QueueUrl": self.queue_url, "ReceiptHandle": message.receipt_handle }),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn handle(&self, ctx: &Context, message: &Message) -> Result<(), Error> {
+        match materialize(ctx, &message.message_id, message.body.as_bytes()).await? {
+            Outcome::Created(name) => {
+                tracing::info!(
+                    message_id = message.message_id,
+                    name,
+                    "Created DelayedJob from SQS"
+                );
+            }
+            Outcome::Duplicate => {
+                tracing::debug!(
+                    message_id = message.message_id,
+                    "Skipping duplicate SQS message"
+                );
+            }
+            Outcome::Invalid(reason) => {
+                tracing::warn!(
+                    message_id = message.message_id,
+                    reason,
+                    "Invalid SQS message"
+                );
+                let Some(dead_letter) = &self.dead_letter else {
+                    return Ok(());
+                };
+                self.call::<serde_json::Value>(
+                    "SendMessage",
+                    json!({
+                        "QueueUrl": dead_letter,
+                        "MessageBody": message.body,
+                        "MessageAttributes": {
+                            "error": { "DataType": "String", "StringValue": reason },
+                        },
+                    }),
+                )
+                .await?;
+            }
+        }
+        self.delete(message).await
+    }
+}
@@ -0,0 +1,117 @@
+use chrono::{DateTime, Duration, Utc};
+use chrono_tz::Tz;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::schedule::Schedule;
+
+/// Annotation marking a child CronJob suspended for a blackout window, so it
+/// is only resumed once the window ends if the controller suspended it.
+pub const BLACKOUT_SUSPENDED_ANNOTATION: &str = "divinerapier.io/suspended-by-blackout";
+
+/// A period during which the child CronJobs are suspended: either a one-off
+/// range from `start` to `end`, or one recurring at `schedule` and lasting
+/// `durationMinutes`.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, JsonSchema, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BlackoutWindow {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start: Option<Time>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end: Option<Time>,
+    /// Cron expression at which each recurring blackout begins, read in the
+    /// resource's `timeZone`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_minutes: Option<u32>,
+}
+
+impl BlackoutWindow {
+    pub fn validate(&self) -> Result<(), crate::Error> {
+        let invalid = |reason: &str| {
+            crate::Error::InvalidSchedule(format!("invalid blackout window: {reason}"))
+        };
+        match (
+            &self.start,
+            &self.end,
+            &self.schedule,
+            self.duration_minutes,
+        ) {
+            (Some(start), Some(end), None, None) if start.0 < end.0 => Ok(()),
+            (Some(_), Some(_), None, None) => Err(invalid("end must be after start")),
+            (None, None, Some(schedule), Some(minutes)) if minutes > 0 => {
+                Schedule::parse(schedule).map(|_| ())
+            }
+            (None, None, Some(_), _) => Err(invalid("durationMinutes must be positive")),
+            _ => Err(invalid(
+                "set either start and end, or schedule and durationMinutes",
+            )),
+        }
+    }
+
+    /// When the blackout covering `now` ends, or `None` outside of it.
+    pub fn active_until(&self, now: DateTime<Utc>, tz: Tz) -> Option<DateTime<Utc>> {
+        if let (Some(start), Some(end)) = (&self.start, &self.end) {
+            return (start.0 <= now && now < end.0).then_some(end.0);
+        }
+        let (schedule, duration) = self.recurrence()?;
+        // The latest occurrence within one duration before `now` opened it.
+        let began = schedule
+            .next_after(&(now - duration).with_timezone(&tz))?
+            .with_timezone(&Utc);
+        (began <= now).then_some(began + duration)
+    }
+
+    /// When the next blackout after `now` begins, if any.
+    pub fn next_start(&self, now: DateTime<Utc>, tz: Tz) -> Option<DateTime<Utc>> {
+        if let Some(start) = &self.start {
+            return (start.0 > now).then_some(start.0);
+        }
+        let (schedule, _) = self.recurrence()?;
+        schedule
+            .next_after(&now.with_timezone(&tz))
+            .map(|t| t.with_timezone(&Utc))
+    }
+
+    /// The blackouts overlapping `from` to `until`, as start and end, at most
+    /// `limit` of them.
+    pub fn occurrences(
+        &self,
+        from: DateTime<Utc>,
+        until: DateTime<Utc>,
+        tz: Tz,
+        limit: usize,
+    ) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+        if let (Some(start), Some(end)) = (&self.start, &self.end) {
+            if start.0 < until && end.0 > from {
+                return vec![(start.0, end.0)];
+            }
+            return Vec::new();
+        }
+        let Some((schedule, duration)) = self.recurrence() else {
+            return Vec::new();
+        };
+        let mut occurrences = Vec::new();
+        let mut after = from - duration;
+        while occurrences.len() < limit {
+            let Some(start) = schedule
+                .next_after(&after.with_timezone(&tz))
+                .map(|t| t.with_timezone(&Utc))
+                .filter(|t| *t < until)
+            else {
+                break;
+            };
+            occurrences.push((start, start + duration));
+            after = start;
+        }
+        occurrences
+    }
+
+    fn recurrence(&self) -> Option<(Schedule, Duration)> {
+        let schedule = Schedule::parse(self.schedule.as_deref()?).ok()?;
+        let minutes = self.duration_minutes?;
+        Some((schedule, Duration::minutes(minutes.into())))
+    }
+}
@@ -0,0 +1,96 @@
+use chrono::Utc;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::{Condition, Time};
+
+/// Set on resources whose namespace is being deleted.
+pub const NAMESPACE_TERMINATING: &str = "NamespaceTerminating";
+
+/// Set while reconciliation is paused after repeated failures.
+pub const CIRCUIT_OPEN: &str = "CircuitOpen";
+
+/// Set while a run is deferred because no schedulable node matches its pod.
+pub const NO_CAPACITY: &str = "NoCapacity";
+
+/// Set while a child CronJob's latest run was skipped because its
+/// `startingDeadlineSeconds` passed; cleared once it starts another.
+pub const STARTING_DEADLINE_MISSED: &str = "StartingDeadlineMissed";
+
+/// Set while the API server is too old for features of the spec; the
+/// message says what was left out of the children.
+pub const UNSUPPORTED_FEATURES: &str = "UnsupportedFeatures";
+
+/// Set while the spec has lint findings; the message lists them.
+pub const LINT: &str = "Lint";
+
+/// Set while `spec.serviceAccount.rules` grant more than the controller
+/// allows; the Role is not provisioned until they fit.
+pub const SERVICE_ACCOUNT_REJECTED: &str = "ServiceAccountRejected";
+
+/// Longest status or condition message written; longer ones are cut at a
+/// character boundary and end with `...`.
+pub const MAX_MESSAGE_LENGTH: usize = 1024;
+
+/// `message` capped at [`MAX_MESSAGE_LENGTH`] bytes.
+pub fn truncate_message(message: &str) -> String {
+    if message.len() <= MAX_MESSAGE_LENGTH {
+        return message.to_string();
+    }
+    let mut end = MAX_MESSAGE_LENGTH - 3;
+    while !message.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}...", &message[..end])
+}
+
+/// Statuses carrying a list of standard Kubernetes conditions.
+pub trait HasConditions {
+    fn conditions(&self) -> &[Condition];
+    fn conditions_mut(&mut self) -> &mut Vec<Condition>;
+}
+
+/// Inserts or updates the condition of `type_`. `lastTransitionTime` only
+/// moves when the status flips. Returns whether anything changed.
+pub fn set_condition(
+    conditions: &mut Vec<Condition>,
+    type_: &str,
+    status: bool,
+    reason: &str,
+    message: &str,
+    observed_generation: Option<i64>,
+) -> bool {
+    let status = if status { "True" } else { "False" }.to_string();
+    let message = truncate_message(message);
+    let message = message.as_str();
+    match conditions.iter_mut().find(|c| c.type_ == type_) {
+        Some(condition) => {
+            if condition.status == status
+                && condition.reason == reason
+                && condition.message == message
+                && condition.observed_generation == observed_generation
+            {
+                return false;
+            }
+            if condition.status != status {
+                condition.last_transition_time = Time(Utc::now());
+            }
+            condition.status = status;
+            condition.reason = reason.to_string();
+            condition.message = message.to_string();
+            condition.observed_generation = observed_generation;
+        }
+        None => conditions.push(Condition {
+            type_: type_.to_string(),
+            status,
+            reason: reason.to_string(),
+            message: message.to_string(),
+            observed_generation,
+            last_transition_time: Time(Utc::now()),
+        }),
+    }
+    true
+}
+
+pub fn is_condition_true(conditions: &[Condition], type_: &str) -> bool {
+    conditions
+        .iter()
+        .any(|c| c.type_ == type_ && c.status == "True")
+}
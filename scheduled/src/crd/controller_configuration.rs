@@ -0,0 +1,119 @@
+use std::collections::BTreeMap;
+
+use k8s_openapi::api::rbac::v1::PolicyRule;
+use kube::CustomResource;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::config::{Config, EventPolicy};
+
+/// Cluster-wide controller settings, overriding those read from the
+/// environment. Only the object named by `CONTROLLER_CONFIGURATION` is used;
+/// it is watched, and every field but `concurrency` applies without a
+/// restart.
+#[derive(Debug, Serialize, Deserialize, CustomResource, Default, Clone, JsonSchema)]
+#[kube(
+    group = "batch.divinerapier.io",
+    version = "v1alpha1",
+    kind = "ControllerConfiguration",
+    shortname = "scc"
+)]
+#[serde(rename_all = "camelCase")]
+pub struct ControllerConfigurationSpec {
+    /// Reconciliations of each kind run at once, 0 for no limit. Read at
+    /// startup only (`RECONCILE_CONCURRENCY`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub concurrency: Option<u16>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backoff: Option<Backoff>,
+
+    #[serde(default)]
+    pub feature_gates: FeatureGates,
+
+    /// `All`, `TransitionsOnly`, `WarningsOnly` or `Off` (`EVENT_POLICY`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub event_policy: Option<String>,
+
+    /// Namespaces whose resources are reconciled, every namespace while
+    /// empty (`WATCH_NAMESPACES`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub namespaces: Vec<String>,
+
+    /// Mirrors of registries the images of child pods are pulled from, e.g.
+    /// `docker.io: internal.registry/dockerhub` (`REGISTRY_REWRITE`).
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub registry_rewrite: BTreeMap<String, String>,
+
+    /// Rules `spec.serviceAccount.rules` of ScheduledCronJobs may grant
+    /// (`SERVICE_ACCOUNT_RULES`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub service_account_rules: Vec<PolicyRule>,
+}
+
+/// How failed reconciliations are retried.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Backoff {
+    /// Delay before a failed reconciliation is retried
+    /// (`ERROR_REQUEUE_SECONDS`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_requeue_seconds: Option<u64>,
+}
+
+/// Optional behaviours switched on or off, each overriding its environment
+/// variable when set.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FeatureGates {
+    /// `CAPACITY_CHECK`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capacity_check: Option<bool>,
+    /// `ANNOTATED_CRONJOBS`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub annotated_cron_jobs: Option<bool>,
+    /// `EVENT_LOG`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub event_log: Option<bool>,
+    /// `OBSERVER_MODE`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub observer: Option<bool>,
+}
+
+impl ControllerConfigurationSpec {
+    /// Overrides the settings of `config` that are set here. An unknown
+    /// event policy is ignored.
+    pub fn apply(&self, config: &mut Config) {
+        if let Some(concurrency) = self.concurrency {
+            config.reconcile_concurrency = concurrency;
+        }
+        if let Some(seconds) = self.backoff.as_ref().and_then(|b| b.error_requeue_seconds) {
+            config.error_requeue = std::time::Duration::from_secs(seconds);
+        }
+        let gates = &self.feature_gates;
+        let gate = |value: Option<bool>, setting: &mut bool| {
+            if let Some(value) = value {
+                *setting = value;
+            }
+        };
+        gate(gates.capacity_check, &mut config.capacity_check);
+        gate(gates.annotated_cron_jobs, &mut config.annotated_cronjobs);
+        gate(gates.event_log, &mut config.event_log);
+        gate(gates.observer, &mut config.observer);
+        if let Some(policy) = &self.event_policy {
+            match policy.parse::<EventPolicy>() {
+                Ok(policy) => config.event_policy = policy,
+                Err(e) => tracing::warn!(error = e, "Ignoring event policy"),
+            }
+        }
+        if !self.namespaces.is_empty() {
+            config.namespaces = self.namespaces.clone();
+        }
+        if !self.registry_rewrite.is_empty() {
+            config.registry_rewrite = self.registry_rewrite.clone();
+        }
+        if !self.service_account_rules.is_empty() {
+            config.service_account_rules = self.service_account_rules.clone();
+        }
+    }
+}
@@ -1,11 +1,16 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
 use k8s_openapi::api::batch::v1::{Job, JobSpec};
-use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
-use kube::{CELSchema, Resource as _};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::{Condition, Time};
+use kube::CELSchema;
 use kube::{CustomResource, ResourceExt, api::ObjectMeta};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use super::IntoTime;
+use super::{HasConditions, IntoTime, RetryPolicy};
+use crate::object_ref::ObjectRefExt as _;
+use crate::schedule::parse_duration;
 
 #[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, JsonSchema, PartialEq, Eq)]
 pub enum DelayedJobPhase {
@@ -20,6 +25,9 @@ pub enum DelayedJobPhase {
     Failed,
     #[serde(rename = "Completed")]
     Completed,
+    /// The start time was missed and `missedRunPolicy` is `Skip`.
+    #[serde(rename = "Skipped")]
+    Skipped,
     #[serde(rename = "Unknown")]
     Unknown,
 }
@@ -32,16 +40,59 @@ impl DelayedJobPhase {
             DelayedJobPhase::InvalidStartTime => "InvalidStartTime",
             DelayedJobPhase::Failed => "Failed",
             DelayedJobPhase::Completed => "Completed",
+            DelayedJobPhase::Skipped => "Skipped",
             DelayedJobPhase::Unknown => "Unknown",
         }
     }
 }
 
+/// What happens to a DelayedJob whose start time passed more than
+/// `deadlineSeconds` ago before its Job could be created, such as while the
+/// controller was down.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, JsonSchema, PartialEq, Eq)]
+pub enum MissedRunPolicy {
+    /// The Job is created late.
+    #[default]
+    #[serde(rename = "Run")]
+    Run,
+    /// No Job is created and the phase becomes `Skipped`.
+    #[serde(rename = "Skip")]
+    Skip,
+    /// No Job is created and the phase becomes `Failed`.
+    #[serde(rename = "Fail")]
+    Fail,
+}
+
+/// Lateness tolerated without `deadlineSeconds`, covering the time the
+/// controller takes to notice the start time.
+pub const DEFAULT_MISSED_RUN_DEADLINE: Duration = Duration::from_secs(60);
+
 #[derive(Deserialize, Serialize, Clone, Debug, Default, CELSchema)]
 pub struct DelayedJobStatus {
     pub phase: DelayedJobPhase,
     pub message: Option<String>,
     pub last_update_time: Option<Time>,
+    /// Name of the Job created once the start time was reached.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub job_ref: Option<String>,
+    /// Jobs created so far, retries included.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attempts: Option<u32>,
+    /// When the failed Job is re-created, see `spec.retryPolicy`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_retry_time: Option<Time>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub conditions: Vec<Condition>,
+}
+
+impl HasConditions for DelayedJobStatus {
+    fn conditions(&self) -> &[Condition] {
+        &self.conditions
+    }
+
+    fn conditions_mut(&mut self) -> &mut Vec<Condition> {
+        &mut self.conditions
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, CustomResource, Default, Clone, CELSchema)]
@@ -50,15 +101,23 @@ pub struct DelayedJobStatus {
     version = "v1alpha1",
     kind = "DelayedJob",
     namespaced,
-    printcolumn = r#"{"name":"Phase", "type":"string", "description":"phase of the job", "jsonPath":".status.phase"}"#,
-    printcolumn = r#"{"name":"StartTime", "type":"string", "description":"start time of the job", "jsonPath":".spec.startTime"}"#,
+    printcolumn = r#"{"name":"FireAt", "type":"string", "format":"date-time", "description":"time the job is created at", "jsonPath":".spec.startTime"}"#,
+    printcolumn = r#"{"name":"Phase", "type":"string", "description":"phase of the job: Pending, Running, InvalidStartTime, Failed, Completed, Skipped or Unknown", "jsonPath":".status.phase"}"#,
+    printcolumn = r#"{"name":"JobRef", "type":"string", "description":"name of the created Job", "jsonPath":".status.jobRef"}"#,
+    printcolumn = r#"{"name":"Age", "type":"date", "jsonPath":".metadata.creationTimestamp"}"#,
     status = "DelayedJobStatus",
-    shortname = "dj"
+    shortname = "dj",
+    category = "all-scheduling"
 )]
 // #[cel_validate(rule = Rule::new("has(self.spec.concurrencyPolicy) && (self.spec.concurrencyPolicy in ['Allow', 'Forbid', 'Replace'])").message("Invalid concurrency policy").reason(Reason::FieldValueInvalid))]
 // #[cel_validate(rule = Rule::new("has(self.spec.failedJobsHistoryLimit) && self.spec.failedJobsHistoryLimit >= 0").message("Invalid failed jobs history limit").reason(Reason::FieldValueInvalid))]
 // #[cel_validate(rule = Rule::new("has(self.spec.successfulJobsHistoryLimit) && self.spec.successfulJobsHistoryLimit >= 0").message("Invalid successful jobs history limit").reason(Reason::FieldValueInvalid))]
 #[cel_validate(rule = Rule::new("has(self.spec.backoffLimit) && self.spec.backoffLimit >= 0").message("Invalid backoff limit").reason(Reason::FieldValueInvalid))]
+#[cel_validate(rule = Rule::new("!has(self.startTime) || !has(self.runAfter)").message("Set either startTime or runAfter").reason(Reason::FieldValueForbidden))]
+#[cel_validate(rule = Rule::new("!has(self.runAfter) || self.runAfter.matches('^([0-9]+[hms])+$')").message("Invalid runAfter").reason(Reason::FieldValueInvalid))]
+#[cel_validate(rule = Rule::new("!has(self.retryPolicy) || !has(self.retryPolicy.backoffFactor) || self.retryPolicy.backoffFactor >= 1").message("Invalid retry backoff factor").reason(Reason::FieldValueInvalid))]
+#[cel_validate(rule = Rule::new("!has(self.ttlSecondsAfterFinished) || self.ttlSecondsAfterFinished >= 0").message("Invalid ttlSecondsAfterFinished").reason(Reason::FieldValueInvalid))]
+#[cel_validate(rule = Rule::new("!has(self.deadlineSeconds) || self.deadlineSeconds >= 0").message("Invalid deadlineSeconds").reason(Reason::FieldValueInvalid))]
 #[cel_validate(rule = Rule::new("has(self.spec.template.spec.containers) && self.spec.template.spec.containers.size() > 0").message("Invalid containers").reason(Reason::FieldValueRequired))]
 #[cel_validate(rule = Rule::new("has(self.spec.template.spec.restartPolicy) && self.spec.template.spec.restartPolicy in ['Always', 'OnFailure', 'Never']").message("Invalid restart policy").reason(Reason::FieldValueInvalid))]
 #[serde(rename_all = "camelCase")]
@@ -66,8 +125,43 @@ pub struct DelayedJobSpec {
     /// Specifies the time to start the job.
     pub start_time: Option<Time>,
 
+    /// Delay after the DelayedJob's creation before the job starts, such as
+    /// `90s`, `15m` or `2h30m`, instead of `startTime`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_after: Option<String>,
+
+    /// Name of a DelayedJob, or else a Job, in the same namespace that has to
+    /// complete successfully before the job starts. The DelayedJob fails if
+    /// it fails. Combines with `startTime` and `runAfter`, the job starting
+    /// once both are satisfied.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_after_job: Option<String>,
+
     /// Specifies the job that will be created when executing a DelayedJob.
     pub spec: JobSpec,
+
+    /// Seconds after the Job finishes before it and its pods are deleted.
+    /// Overrides `spec.ttlSecondsAfterFinished`; the DelayedJob keeps its
+    /// final phase.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ttl_seconds_after_finished: Option<i32>,
+
+    /// Re-creates the Job once it failed, after `backoffLimit` is exhausted.
+    /// Without it the DelayedJob fails with its Job.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_policy: Option<RetryPolicy>,
+
+    /// Whether the Job is still created once its start time passed more
+    /// than `deadlineSeconds` ago: `Run` (default), `Skip` or `Fail`. Not
+    /// applied with `runAfterJob`, which may hold the job back for longer,
+    /// nor to retries.
+    #[serde(default)]
+    pub missed_run_policy: MissedRunPolicy,
+
+    /// Seconds past the start time the Job may still be created in before
+    /// the run counts as missed. Defaults to 60.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deadline_seconds: Option<i64>,
 }
 
 impl DelayedJobSpec {
@@ -77,18 +171,124 @@ impl DelayedJobSpec {
     {
         Ok(Self {
             start_time: start_time.into_time()?,
+            run_after: None,
+            run_after_job: None,
             spec,
+            ttl_seconds_after_finished: None,
+            retry_policy: None,
+            missed_run_policy: MissedRunPolicy::default(),
+            deadline_seconds: None,
         })
     }
+
+    /// Applies `policy` once the start time passed more than `deadline`
+    /// seconds ago.
+    pub fn with_missed_run_policy(
+        mut self,
+        policy: MissedRunPolicy,
+        deadline: Option<i64>,
+    ) -> Self {
+        self.missed_run_policy = policy;
+        self.deadline_seconds = deadline;
+        self
+    }
+
+    pub fn with_ttl_seconds_after_finished(mut self, seconds: i32) -> Self {
+        self.ttl_seconds_after_finished = Some(seconds);
+        self
+    }
+
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(policy);
+        self
+    }
+
+    /// Starts the job once the DelayedJob or Job `name` completed.
+    pub fn with_run_after_job<S: Into<String>>(mut self, name: S) -> Self {
+        self.run_after_job = Some(name.into());
+        self
+    }
+
+    /// Starts the job `delay`, such as `2h30m`, after the DelayedJob is
+    /// created, clearing `startTime`.
+    pub fn with_run_after<S: Into<String>>(mut self, delay: S) -> Self {
+        self.start_time = None;
+        self.run_after = Some(delay.into());
+        self
+    }
 }
 
 impl DelayedJob {
-    pub fn job(&self) -> Job {
-        Job {
+    /// When the job starts: `startTime`, or `runAfter` past the
+    /// creationTimestamp. `None` starts it right away, as does `runAfter`
+    /// before the resource has been created.
+    pub fn fire_at(&self) -> Result<Option<DateTime<Utc>>, crate::Error> {
+        if let Some(start_time) = &self.spec.start_time {
+            if self.spec.run_after.is_some() {
+                return Err(crate::Error::InvalidSchedule(
+                    "set either startTime or runAfter".to_string(),
+                ));
+            }
+            return Ok(Some(start_time.0));
+        }
+        let Some(run_after) = &self.spec.run_after else {
+            return Ok(None);
+        };
+        let delay = parse_duration(run_after)
+            .and_then(|d| chrono::Duration::from_std(d).ok())
+            .ok_or_else(|| {
+                crate::Error::InvalidSchedule(format!(
+                    "invalid runAfter {run_after}: expected a duration such as 15m or 2h30m"
+                ))
+            })?;
+        Ok(self.creation_timestamp().map(|t| t.0 + delay))
+    }
+
+    /// Seconds after the Job finishes before Kubernetes deletes it, if set by
+    /// either `ttlSecondsAfterFinished`.
+    pub fn ttl_seconds_after_finished(&self) -> Option<i32> {
+        self.spec
+            .ttl_seconds_after_finished
+            .or(self.spec.spec.ttl_seconds_after_finished)
+    }
+
+    /// How long past `deadlineSeconds` the start time `fire_at` is at `now`,
+    /// `None` while the Job may still be created on time.
+    pub fn missed_by(&self, fire_at: DateTime<Utc>, now: DateTime<Utc>) -> Option<Duration> {
+        let deadline = self
+            .spec
+            .deadline_seconds
+            .map_or(DEFAULT_MISSED_RUN_DEADLINE, |s| {
+                Duration::from_secs(s.max(0).unsigned_abs())
+            });
+        let late = (now - fire_at).to_std().ok()?;
+        late.checked_sub(deadline).filter(|d| !d.is_zero())
+    }
+
+    /// Whether this waits for the DelayedJob or Job `name` in `namespace`
+    /// through `runAfterJob`.
+    pub fn runs_after(&self, namespace: &str, name: &str) -> bool {
+        self.spec.run_after_job.as_deref() == Some(name)
+            && self.namespace().as_deref() == Some(namespace)
+    }
+
+    /// Describes how the recorded phase contradicts the observed state, if it
+    /// does. `child_exists` tells whether the child Job was found.
+    pub fn stale_reason(&self, child_exists: bool) -> Option<String> {
+        match self.status.as_ref()?.phase {
+            DelayedJobPhase::Running if !child_exists => {
+                Some("phase is Running but the child Job does not exist".to_string())
+            }
+            _ => None,
+        }
+    }
+
+    pub fn job(&self) -> Result<Job, crate::Error> {
+        Ok(Job {
             metadata: ObjectMeta {
                 name: Some(self.name_any()),
                 namespace: Some(self.namespace().unwrap_or_default()),
-                owner_references: Some(vec![self.controller_owner_ref(&()).unwrap()]),
+                owner_references: Some(self.controller_references()?),
                 labels: Some(self.labels().clone()),
                 annotations: {
                     let mut annotations = self.labels().clone();
@@ -106,8 +306,11 @@ impl DelayedJob {
                 },
                 ..Default::default()
             },
-            spec: Some(self.spec.spec.clone()),
+            spec: Some(JobSpec {
+                ttl_seconds_after_finished: self.ttl_seconds_after_finished(),
+                ..self.spec.spec.clone()
+            }),
             status: None,
-        }
+        })
     }
 }
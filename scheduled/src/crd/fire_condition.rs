@@ -0,0 +1,148 @@
+use kube::CELSchema;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// API groups a [`MetricsApiQuery`] may read from.
+pub const METRICS_API_GROUPS: [&str; 3] = [
+    "metrics.k8s.io",
+    "custom.metrics.k8s.io",
+    "external.metrics.k8s.io",
+];
+
+/// A metric check evaluated just before each scheduled fire. The run only
+/// proceeds when `value <comparator> threshold` holds; otherwise it is skipped
+/// and recorded as `SkippedByCondition`.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, JsonSchema, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FireCondition {
+    /// Instant query against a Prometheus-compatible HTTP API.
+    pub prometheus: Option<PrometheusQuery>,
+
+    /// Path on the Kubernetes API server returning a metric value list,
+    /// e.g. `/apis/external.metrics.k8s.io/v1beta1/namespaces/default/queue_depth`.
+    /// Only the metrics APIs are allowed, within the resource's own namespace.
+    pub metrics_api: Option<MetricsApiQuery>,
+
+    pub comparator: Comparator,
+
+    pub threshold: f64,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PrometheusQuery {
+    /// Base URL of the Prometheus server, e.g. `http://prometheus.monitoring:9090`.
+    pub url: String,
+    /// PromQL expression; the first sample of the result is compared.
+    pub query: String,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default, CELSchema, PartialEq, Eq)]
+#[cel_validate(rule = Rule::new(r"self.path.matches('^/apis/(metrics|custom\\.metrics|external\\.metrics)\\.k8s\\.io/[^/]+/namespaces/[^/]+/[^%]+$') && !self.path.contains('..')").message("path must be under /apis/{metrics,custom.metrics,external.metrics}.k8s.io/<version>/namespaces/<namespace>/").reason(Reason::FieldValueForbidden))]
+#[serde(rename_all = "camelCase")]
+pub struct MetricsApiQuery {
+    #[schemars(length(max = 1024))]
+    pub path: String,
+}
+
+impl MetricsApiQuery {
+    /// Checks that the path reads one of [`METRICS_API_GROUPS`] within
+    /// `namespace`, the namespace of the resource declaring the query, as the
+    /// admission rule cannot see it.
+    pub fn check(&self, namespace: &str) -> Result<(), crate::Error> {
+        let path = self.path.split('?').next().unwrap_or_default();
+        let segments: Vec<_> = path.split('/').collect();
+        let allowed = match segments.as_slice() {
+            ["", "apis", group, version, "namespaces", ns, rest @ ..] => {
+                METRICS_API_GROUPS.contains(group)
+                    && !version.is_empty()
+                    && *ns == namespace
+                    && !rest.is_empty()
+                    && rest
+                        .iter()
+                        .all(|s| !s.is_empty() && *s != "." && *s != ".." && !s.contains('%'))
+            }
+            _ => false,
+        };
+        if allowed {
+            return Ok(());
+        }
+        Err(crate::Error::InvalidTarget(format!(
+            "metricsApi path {} must be under /apis/{{metrics,custom.metrics,external.metrics}}.k8s.io/<version>/namespaces/{namespace}/",
+            self.path
+        )))
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, JsonSchema, PartialEq, Eq)]
+pub enum Comparator {
+    #[default]
+    #[serde(rename = "GreaterThan")]
+    GreaterThan,
+    #[serde(rename = "GreaterThanOrEqual")]
+    GreaterThanOrEqual,
+    #[serde(rename = "LessThan")]
+    LessThan,
+    #[serde(rename = "LessThanOrEqual")]
+    LessThanOrEqual,
+    #[serde(rename = "Equal")]
+    Equal,
+    #[serde(rename = "NotEqual")]
+    NotEqual,
+}
+
+impl Comparator {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Comparator::GreaterThan => ">",
+            Comparator::GreaterThanOrEqual => ">=",
+            Comparator::LessThan => "<",
+            Comparator::LessThanOrEqual => "<=",
+            Comparator::Equal => "==",
+            Comparator::NotEqual => "!=",
+        }
+    }
+
+    pub fn compare(&self, value: f64, threshold: f64) -> bool {
+        match self {
+            Comparator::GreaterThan => value > threshold,
+            Comparator::GreaterThanOrEqual => value >= threshold,
+            Comparator::LessThan => value < threshold,
+            Comparator::LessThanOrEqual => value <= threshold,
+            Comparator::Equal => value == threshold,
+            Comparator::NotEqual => value != threshold,
+        }
+    }
+}
+
+impl FireCondition {
+    pub fn describe(&self, value: f64) -> String {
+        format!("{} {} {}", value, self.comparator.as_str(), self.threshold)
+    }
+}
+
+/// Parses a Kubernetes quantity such as `250m`, `3k` or `1Gi` into a float.
+pub fn parse_quantity(quantity: &str) -> Option<f64> {
+    const SUFFIXES: [(&str, f64); 13] = [
+        ("Ki", 1024.0),
+        ("Mi", 1024.0 * 1024.0),
+        ("Gi", 1024.0 * 1024.0 * 1024.0),
+        ("Ti", 1024.0 * 1024.0 * 1024.0 * 1024.0),
+        ("Pi", 1024.0 * 1024.0 * 1024.0 * 1024.0 * 1024.0),
+        ("Ei", 1024.0 * 1024.0 * 1024.0 * 1024.0 * 1024.0 * 1024.0),
+        ("n", 1e-9),
+        ("u", 1e-6),
+        ("m", 1e-3),
+        ("k", 1e3),
+        ("M", 1e6),
+        ("G", 1e9),
+        ("T", 1e12),
+    ];
+    let quantity = quantity.trim();
+    for (suffix, factor) in SUFFIXES {
+        if let Some(number) = quantity.strip_suffix(suffix) {
+            return number.parse::<f64>().ok().map(|n| n * factor);
+        }
+    }
+    quantity.parse::<f64>().ok()
+}
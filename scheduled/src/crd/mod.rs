@@ -1,7 +1,33 @@
+pub(crate) mod blackout;
+pub(crate) mod condition;
+pub(crate) mod controller_configuration;
 pub(crate) mod delayed_job;
+pub(crate) mod fire_condition;
+pub(crate) mod owner;
+pub(crate) mod post_run_check;
+pub(crate) mod retry_policy;
+pub(crate) mod schedule_calendar;
 pub(crate) mod scheduled_cronjob;
+pub(crate) mod scheduled_patch;
+pub(crate) mod scheduled_suspend;
+pub(crate) mod spot_policy;
+pub(crate) mod target_ref;
 pub(crate) mod time;
+pub(crate) mod timer_trigger;
 
+pub use blackout::*;
+pub use condition::*;
+pub use controller_configuration::*;
 pub use delayed_job::*;
+pub use fire_condition::*;
+pub use owner::*;
+pub use post_run_check::*;
+pub use retry_policy::*;
+pub use schedule_calendar::*;
 pub use scheduled_cronjob::*;
+pub use scheduled_patch::*;
+pub use scheduled_suspend::*;
+pub use spot_policy::*;
+pub use target_ref::*;
 pub use time::*;
+pub use timer_trigger::*;
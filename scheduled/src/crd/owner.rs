@@ -0,0 +1,84 @@
+use std::collections::BTreeMap;
+
+use k8s_openapi::api::batch::v1::{CronJob, Job};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::crd::{DelayedJob, ScheduledCronJob, ScheduledPatch, ScheduledSuspend, TimerTrigger};
+
+/// Annotations set on events about resources with an owner.
+pub const OWNER_TEAM_ANNOTATION: &str = "divinerapier.io/owner-team";
+pub const OWNER_SLACK_CHANNEL_ANNOTATION: &str = "divinerapier.io/owner-slack-channel";
+pub const OWNER_PAGERDUTY_SERVICE_ANNOTATION: &str = "divinerapier.io/owner-pagerduty-service";
+
+/// Who is on call for a schedule. Included in its events, CloudEvents, hook
+/// payloads and the `scheduled_owner_info` metric, so alerts can be routed.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Owner {
+    pub team: String,
+    /// Channel name, starting with `#`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slack_channel: Option<String>,
+    /// PagerDuty service ID, such as `PABC123`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pagerduty_service: Option<String>,
+}
+
+impl Owner {
+    /// Repeats the CEL rules of `spec.owner`, for API servers that do not
+    /// evaluate them.
+    pub fn validate(&self) -> Result<(), crate::Error> {
+        if self.team.trim().is_empty() {
+            return Err(crate::Error::InvalidOwner("team is required".to_string()));
+        }
+        if let Some(channel) = &self.slack_channel
+            && !channel.starts_with('#')
+        {
+            return Err(crate::Error::InvalidOwner(format!(
+                "slack channel {channel} must start with #"
+            )));
+        }
+        Ok(())
+    }
+
+    pub fn annotations(&self) -> BTreeMap<String, String> {
+        let mut annotations =
+            BTreeMap::from([(OWNER_TEAM_ANNOTATION.to_string(), self.team.clone())]);
+        if let Some(channel) = &self.slack_channel {
+            annotations.insert(OWNER_SLACK_CHANNEL_ANNOTATION.to_string(), channel.clone());
+        }
+        if let Some(service) = &self.pagerduty_service {
+            annotations.insert(
+                OWNER_PAGERDUTY_SERVICE_ANNOTATION.to_string(),
+                service.clone(),
+            );
+        }
+        annotations
+    }
+}
+
+/// Resources that may name an on-call [`Owner`].
+pub trait HasOwner {
+    fn owner(&self) -> Option<&Owner> {
+        None
+    }
+}
+
+impl HasOwner for ScheduledCronJob {
+    fn owner(&self) -> Option<&Owner> {
+        self.spec.owner.as_ref()
+    }
+}
+
+impl HasOwner for DelayedJob {}
+
+impl HasOwner for ScheduledPatch {}
+
+impl HasOwner for ScheduledSuspend {}
+
+impl HasOwner for TimerTrigger {}
+
+impl HasOwner for Job {}
+
+impl HasOwner for CronJob {}
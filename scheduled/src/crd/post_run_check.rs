@@ -0,0 +1,57 @@
+use std::collections::BTreeMap;
+
+use k8s_openapi::api::batch::v1::{Job, JobSpec};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use kube::ResourceExt as _;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::crd::child_name;
+use crate::object_ref::ObjectRefExt as _;
+
+/// Annotation on run Jobs holding the outcome of their post-run check:
+/// `Passed`, `Failed`, or `Skipped` for runs that did not succeed.
+pub const POST_RUN_CHECK_ANNOTATION: &str = "divinerapier.io/post-run-check";
+
+/// Verifies the effect of each successful run. With both `http` and `job`,
+/// both must pass.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PostRunCheck {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub http: Option<HttpCheck>,
+    /// Started after the run, owned by its Job; the check passes once it
+    /// completes and fails once it fails.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub job: Option<JobSpec>,
+}
+
+/// A GET that must answer with a 2xx status.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct HttpCheck {
+    pub url: String,
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    /// Defaults to 10 seconds.
+    pub timeout_seconds: Option<u64>,
+}
+
+impl PostRunCheck {
+    /// The check Job for `run`, or `None` when no `job` is configured.
+    pub fn check_job(&self, run: &Job) -> Result<Option<Job>, crate::Error> {
+        let Some(spec) = self.job.clone() else {
+            return Ok(None);
+        };
+        Ok(Some(Job {
+            metadata: ObjectMeta {
+                name: Some(child_name(&format!("{}-check", run.name_any()))),
+                namespace: run.namespace(),
+                owner_references: Some(run.controller_references()?),
+                ..Default::default()
+            },
+            spec: Some(spec),
+            status: None,
+        }))
+    }
+}
@@ -0,0 +1,36 @@
+use std::time::Duration;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Re-creates a failed Job with exponentially growing delays: the first
+/// retry waits `backoffSeconds`, each further one `backoffFactor` times
+/// longer, up to `maxBackoffSeconds`.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RetryPolicy {
+    /// How many times the Job is re-created after failing.
+    pub max_retries: u32,
+    /// Defaults to 10 seconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backoff_seconds: Option<u64>,
+    /// Defaults to 2.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backoff_factor: Option<u32>,
+    /// Defaults to an hour.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_backoff_seconds: Option<u64>,
+}
+
+impl RetryPolicy {
+    /// The delay before the `retry`th retry, counting from 1.
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let max = self.max_backoff_seconds.unwrap_or(3600);
+        let factor = u64::from(self.backoff_factor.unwrap_or(2).max(1));
+        let seconds = factor
+            .checked_pow(retry.saturating_sub(1))
+            .and_then(|f| f.checked_mul(self.backoff_seconds.unwrap_or(10)))
+            .map_or(max, |s| s.min(max));
+        Duration::from_secs(seconds)
+    }
+}
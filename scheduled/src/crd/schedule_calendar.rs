@@ -0,0 +1,76 @@
+use chrono::{Days, NaiveDate, TimeZone as _, Utc};
+use chrono_tz::Tz;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
+use kube::{CELSchema, CustomResource};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::BlackoutWindow;
+
+/// Named date ranges, such as public holidays, shared by the
+/// ScheduledCronJobs listing the calendar in `spec.calendars`. Their child
+/// CronJobs are suspended on these dates, like during a blackout window.
+#[derive(Debug, Serialize, Deserialize, CustomResource, Default, Clone, JsonSchema)]
+#[kube(
+    group = "batch.divinerapier.io",
+    version = "v1alpha1",
+    kind = "ScheduleCalendar",
+    namespaced,
+    printcolumn = r#"{"name":"Age", "type":"date", "jsonPath":".metadata.creationTimestamp"}"#,
+    shortname = "scal",
+    category = "all-scheduling"
+)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleCalendarSpec {
+    #[serde(default)]
+    pub ranges: Vec<DateRange>,
+}
+
+/// The days from `start` to `end`, both included, read in the time zone of
+/// the referencing ScheduledCronJob.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, CELSchema, PartialEq)]
+#[cel_validate(rule = Rule::new("self.start <= self.end").message("end must not be before start").reason(Reason::FieldValueInvalid))]
+#[serde(rename_all = "camelCase")]
+pub struct DateRange {
+    /// What the range is, such as `Christmas`.
+    pub name: String,
+    /// First day, as `YYYY-MM-DD`.
+    #[schemars(length(max = 10), regex(pattern = r"^\d{4}-\d{2}-\d{2}$"))]
+    pub start: String,
+    /// Last day, as `YYYY-MM-DD`.
+    #[schemars(length(max = 10), regex(pattern = r"^\d{4}-\d{2}-\d{2}$"))]
+    pub end: String,
+}
+
+impl DateRange {
+    /// The range as a blackout window from midnight of `start` to midnight
+    /// after `end` in `tz`. `None` if a date does not parse.
+    pub fn window(&self, tz: Tz) -> Option<BlackoutWindow> {
+        let midnight = |date: NaiveDate| {
+            tz.from_local_datetime(&date.and_hms_opt(0, 0, 0)?)
+                .earliest()
+                .map(|t| Time(t.with_timezone(&Utc)))
+        };
+        let start = NaiveDate::parse_from_str(&self.start, "%Y-%m-%d").ok()?;
+        let end = NaiveDate::parse_from_str(&self.end, "%Y-%m-%d")
+            .ok()?
+            .checked_add_days(Days::new(1))?;
+        Some(BlackoutWindow {
+            start: Some(midnight(start)?),
+            end: Some(midnight(end)?),
+            ..Default::default()
+        })
+    }
+}
+
+impl ScheduleCalendar {
+    /// The ranges of the calendar as blackout windows in `tz`, skipping those
+    /// whose dates do not parse.
+    pub fn windows(&self, tz: Tz) -> Vec<BlackoutWindow> {
+        self.spec
+            .ranges
+            .iter()
+            .filter_map(|r| r.window(tz))
+            .collect()
+    }
+}
@@ -1,55 +1,534 @@
-use crate::crd::IntoTime;
-use chrono::{DateTime, Duration, Local};
+use std::collections::{BTreeMap, HashSet};
+
+use crate::crd::{
+    BlackoutWindow, HasConditions, IntoTime, Owner, PostRunCheck, ScheduleCalendar, SpotPolicy,
+    parse_quantity,
+};
+use crate::object_ref::ObjectRefExt as _;
+use crate::recurrence::{self, RecurrenceRule};
+use crate::schedule::{Every, Schedule, Seconds, Window, stagger};
+use chrono::{DateTime, Local, Utc};
+use chrono_tz::Tz;
 use k8s_openapi::api::batch::v1::{CronJob, CronJobSpec};
-use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
+use k8s_openapi::api::core::v1::{
+    Affinity, ConfigMapEnvSource, ConfigMapKeySelector, ConfigMapVolumeSource, EnvFromSource,
+    EnvVar, EnvVarSource, LocalObjectReference, ObjectFieldSelector,
+    PersistentVolumeClaimVolumeSource, PodSpec, PodTemplateSpec, ResourceRequirements,
+    SecretEnvSource, SecretKeySelector, SecretVolumeSource, ServiceAccount, Toleration, Volume,
+    VolumeMount,
+};
+use k8s_openapi::api::networking::v1::{NetworkPolicy, NetworkPolicyEgressRule, NetworkPolicySpec};
+use k8s_openapi::api::rbac::v1::{PolicyRule, Role, RoleBinding, RoleRef, Subject};
+use k8s_openapi::apimachinery::pkg::api::resource::Quantity;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::{Condition, LabelSelector, Time};
 use kube::core::object::HasStatus;
-use kube::{CELSchema, Resource as _};
+use kube::{CELSchema, Resource};
 use kube::{CustomResource, ResourceExt, api::ObjectMeta};
+use ring::digest;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+/// Lifecycle of a ScheduledCronJob:
+///
+/// ```text
+/// PendingValidation ─▶ PendingActivation ─▶ Active ◀─▶ Suspended
+///         │                    │              │           │
+///         └──────▶ Failed ◀────┴──────────────┴───────────┤
+///                                             ▼           │
+///                                          Expired ◀──────┘
+///                                             │
+///                                             ▼
+///                                         Completed
+/// ```
+///
+/// Any phase may return to `PendingValidation`, which re-validates the
+/// resource from scratch. See [`ScheduledCronJobPhase::allows`].
+///
+/// Phases written by earlier versions are read as their successors.
 #[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, JsonSchema, PartialEq, Eq)]
 pub enum ScheduledCronJobPhase {
+    /// The spec has not been validated yet.
     #[default]
-    #[serde(rename = "Pending")]
-    Pending,
-    #[serde(rename = "Running")]
-    Running,
-    #[serde(rename = "InvalidStartTime")]
-    InvalidStartTime,
-    #[serde(rename = "InvalidEndTime")]
-    InvalidEndTime,
-    #[serde(rename = "EndBeforeStart")]
-    EndBeforeStart,
-    #[serde(rename = "Failed")]
+    #[serde(rename = "PendingValidation", alias = "Unknown")]
+    PendingValidation,
+    /// The spec is valid and the window has not started.
+    #[serde(rename = "PendingActivation", alias = "Pending")]
+    PendingActivation,
+    /// The window is open and the child CronJobs are scheduling runs.
+    #[serde(rename = "Active", alias = "Running")]
+    Active,
+    /// The window is open but every child CronJob is suspended.
+    #[serde(rename = "Suspended")]
+    Suspended,
+    /// The window has ended and the child CronJobs are being deleted.
+    #[serde(rename = "Expired")]
+    Expired,
+    /// The spec is invalid or the children could not be managed.
+    #[serde(
+        rename = "Failed",
+        alias = "InvalidStartTime",
+        alias = "InvalidEndTime",
+        alias = "EndBeforeStart"
+    )]
     Failed,
+    /// The window has ended and every child CronJob is gone.
     #[serde(rename = "Completed")]
     Completed,
-    #[serde(rename = "Unknown")]
-    Unknown,
 }
 
 impl ScheduledCronJobPhase {
     pub fn as_str(&self) -> &'static str {
         match self {
-            ScheduledCronJobPhase::Pending => "Pending",
-            ScheduledCronJobPhase::Running => "Running",
-            ScheduledCronJobPhase::InvalidStartTime => "InvalidStartTime",
-            ScheduledCronJobPhase::InvalidEndTime => "InvalidEndTime",
-            ScheduledCronJobPhase::EndBeforeStart => "EndBeforeStart",
+            ScheduledCronJobPhase::PendingValidation => "PendingValidation",
+            ScheduledCronJobPhase::PendingActivation => "PendingActivation",
+            ScheduledCronJobPhase::Active => "Active",
+            ScheduledCronJobPhase::Suspended => "Suspended",
+            ScheduledCronJobPhase::Expired => "Expired",
             ScheduledCronJobPhase::Failed => "Failed",
             ScheduledCronJobPhase::Completed => "Completed",
-            ScheduledCronJobPhase::Unknown => "Unknown",
         }
     }
+
+    /// Whether the state machine allows moving from this phase to `next`.
+    /// Staying in a phase, e.g. to update the message, is always allowed.
+    pub fn allows(&self, next: ScheduledCronJobPhase) -> bool {
+        use ScheduledCronJobPhase::*;
+        if *self == next || next == PendingValidation {
+            return true;
+        }
+        match self {
+            PendingValidation => true,
+            PendingActivation => matches!(next, Active | Suspended | Expired | Failed),
+            Active => matches!(next, PendingActivation | Suspended | Expired | Failed),
+            Suspended => matches!(next, PendingActivation | Active | Expired | Failed),
+            Expired => matches!(next, Completed | Failed),
+            Failed | Completed => false,
+        }
+    }
+
+    /// Whether the resource has settled and is not reconciled until its spec
+    /// changes or it is reset to `PendingValidation`.
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            ScheduledCronJobPhase::Failed | ScheduledCronJobPhase::Completed
+        )
+    }
+}
+
+impl std::fmt::Display for ScheduledCronJobPhase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// What happens to the child CronJobs once `endTime` passed or
+/// `maxExecutions` runs succeeded.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, JsonSchema, PartialEq, Eq)]
+pub enum EndPolicy {
+    /// The CronJobs are deleted along with their Jobs and pods.
+    #[default]
+    #[serde(rename = "DeleteAll")]
+    DeleteAll,
+    /// The CronJobs are deleted, orphaning their Jobs, which stay until
+    /// their `ttlSecondsAfterFinished` passes or they are deleted by hand.
+    #[serde(rename = "DeleteChild")]
+    DeleteChild,
+    /// The CronJobs are suspended and kept with their Jobs.
+    #[serde(rename = "Keep")]
+    Keep,
 }
 
-#[derive(Deserialize, Serialize, Clone, Debug, Default, JsonSchema, PartialEq, Eq)]
+#[derive(Deserialize, Serialize, Clone, Debug, Default, JsonSchema, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct ScheduledCronJobStatus {
     pub phase: ScheduledCronJobPhase,
     pub message: Option<String>,
     pub last_update_time: Option<Time>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub conditions: Vec<Condition>,
+    /// Status of each variant's child CronJob.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub variants: Vec<VariantStatus>,
+    /// Status of the child CronJob of each entry of `spec.schedules`, per
+    /// variant. Replaces `variants` when `spec.schedules` is set.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub schedules: Vec<ScheduleStatus>,
+    /// Requests suggested by the usage of recent runs, see
+    /// [`crate::resize::run`].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub recommendations: Vec<ResourceRecommendation>,
+    /// Machine-readable diagnostics keyed by the `DETAIL_*` constants, kept
+    /// apart from the human-readable `message`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub details: BTreeMap<String, String>,
+    /// Successful runs counted towards `spec.maxExecutions`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub executions: Option<u32>,
+    /// Objects the controller created for the resource: the child CronJobs
+    /// and what `spec.serviceAccount` and `spec.network` provision, sorted
+    /// by kind and name.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub managed_resources: Vec<ManagedResource>,
+    /// Generation of the spec the phase was last set for.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub observed_generation: Option<i64>,
+}
+
+/// Detail listing the child CronJobs, separated by commas.
+pub const DETAIL_CRON_JOBS: &str = "cronJobs";
+
+/// Detail holding the earliest next fire time of the child CronJobs, in
+/// RFC 3339. Absent while every child is suspended.
+pub const DETAIL_NEXT_SCHEDULE_TIME: &str = "nextScheduleTime";
+
+/// Detail holding the reason of the last warning the phase was updated with,
+/// such as `InvalidSchedule`.
+pub const DETAIL_LAST_ERROR: &str = "lastError";
+
+/// Right-sizing suggestion for one container.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, JsonSchema, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceRecommendation {
+    pub container: String,
+    /// Highest CPU usage observed.
+    pub peak_cpu: Option<Quantity>,
+    /// Highest memory usage observed.
+    pub peak_memory: Option<Quantity>,
+    /// Suggested CPU request.
+    pub cpu: Option<Quantity>,
+    /// Suggested memory request.
+    pub memory: Option<Quantity>,
+}
+
+/// Tells every container of `pod` where to send heartbeats, keeping
+/// variables the template already sets.
+fn inject_heartbeat_env(pod: &mut PodSpec, timeout: u64) {
+    let field = |path: &str| EnvVar {
+        value_from: Some(EnvVarSource {
+            field_ref: Some(ObjectFieldSelector {
+                field_path: path.to_string(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let vars = [
+        EnvVar {
+            name: HEARTBEAT_LEASE_ENV.to_string(),
+            ..field("metadata.labels['job-name']")
+        },
+        EnvVar {
+            name: HEARTBEAT_NAMESPACE_ENV.to_string(),
+            ..field("metadata.namespace")
+        },
+        EnvVar {
+            name: HEARTBEAT_TIMEOUT_ENV.to_string(),
+            value: Some(timeout.to_string()),
+            ..Default::default()
+        },
+    ];
+    for container in &mut pod.containers {
+        let env = container.env.get_or_insert_with(Vec::new);
+        for var in &vars {
+            if !env.iter().any(|e| e.name == var.name) {
+                env.push(var.clone());
+            }
+        }
+    }
+}
+
+/// Sets the requests of the containers in `pod` to their recommendations,
+/// raising limits that would fall below them.
+pub fn apply_recommendations(pod: &mut PodSpec, recommendations: &[ResourceRecommendation]) {
+    for container in &mut pod.containers {
+        let Some(recommendation) = recommendations
+            .iter()
+            .find(|r| r.container == container.name)
+        else {
+            continue;
+        };
+        let resources = container.resources.get_or_insert_with(Default::default);
+        for (resource, quantity) in [
+            ("cpu", &recommendation.cpu),
+            ("memory", &recommendation.memory),
+        ] {
+            let Some(quantity) = quantity else {
+                continue;
+            };
+            resources
+                .requests
+                .get_or_insert_with(BTreeMap::new)
+                .insert(resource.to_string(), quantity.clone());
+            if let Some(limit) = resources.limits.as_mut().and_then(|l| l.get_mut(resource))
+                && parse_quantity(&limit.0) < parse_quantity(&quantity.0)
+            {
+                *limit = quantity.clone();
+            }
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default, JsonSchema, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct VariantStatus {
+    pub name: String,
+    /// Name of the variant's child CronJob.
+    pub cron_job: String,
+    /// Number of the child's Jobs that are running.
+    pub active: usize,
+    pub last_schedule_time: Option<Time>,
+    pub last_successful_time: Option<Time>,
+}
+
+/// Reference to an object the controller created, in the namespace of its
+/// owner.
+#[derive(
+    Deserialize, Serialize, Clone, Debug, Default, JsonSchema, PartialEq, Eq, PartialOrd, Ord,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct ManagedResource {
+    pub kind: String,
+    pub name: String,
+    pub api_version: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uid: Option<String>,
+}
+
+impl ManagedResource {
+    pub fn of<K: Resource<DynamicType = ()>>(object: &K) -> Self {
+        Self {
+            kind: K::kind(&()).into_owned(),
+            name: object.name_any(),
+            api_version: K::api_version(&()).into_owned(),
+            uid: object.uid(),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default, JsonSchema, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleStatus {
+    /// The entry of `spec.schedules`.
+    pub schedule: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variant: Option<String>,
+    /// Name of the schedule's child CronJob.
+    pub cron_job: String,
+    /// Number of the child's Jobs that are running.
+    pub active: usize,
+    pub last_schedule_time: Option<Time>,
+    pub last_successful_time: Option<Time>,
+}
+
+/// One of several child CronJobs managed by a ScheduledCronJob, e.g. one per
+/// region or shard.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Variant {
+    /// Substituted for `{{variant}}` in the child's name.
+    pub name: String,
+    #[serde(default)]
+    pub overrides: VariantOverrides,
+}
+
+/// Merges `labels` and `annotations` into `metadata`, leaving it unset when
+/// there are none.
+fn stamp(
+    metadata: &mut Option<ObjectMeta>,
+    labels: &BTreeMap<String, String>,
+    annotations: &BTreeMap<String, String>,
+) {
+    if labels.is_empty() && annotations.is_empty() {
+        return;
+    }
+    let metadata = metadata.get_or_insert_with(ObjectMeta::default);
+    if !labels.is_empty() {
+        metadata
+            .labels
+            .get_or_insert_with(BTreeMap::new)
+            .extend(labels.clone());
+    }
+    if !annotations.is_empty() {
+        metadata
+            .annotations
+            .get_or_insert_with(BTreeMap::new)
+            .extend(annotations.clone());
+    }
+}
+
+/// Metadata set on every child CronJob.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ChildMetadata {
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub labels: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub annotations: BTreeMap<String, String>,
+}
+
+/// How a variant's child differs from `spec.spec` or `spec.cronJobTemplate`.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct VariantOverrides {
+    /// Set on every container, replacing variables of the same name.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub env: Vec<EnvVar>,
+    /// Replaces the arguments of every container.
+    pub args: Option<Vec<String>>,
+    /// Merged into the pod's node selector.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub node_selector: BTreeMap<String, String>,
+}
+
+impl VariantOverrides {
+    fn apply(&self, spec: &mut CronJobSpec) {
+        let Some(pod) = spec
+            .job_template
+            .spec
+            .as_mut()
+            .and_then(|s| s.template.spec.as_mut())
+        else {
+            return;
+        };
+        for container in &mut pod.containers {
+            let env = container.env.get_or_insert_with(Vec::new);
+            for var in &self.env {
+                match env.iter_mut().find(|e| e.name == var.name) {
+                    Some(existing) => *existing = var.clone(),
+                    None => env.push(var.clone()),
+                }
+            }
+            if let Some(args) = &self.args {
+                container.args = Some(args.clone());
+            }
+        }
+        if !self.node_selector.is_empty() {
+            pod.node_selector
+                .get_or_insert_with(BTreeMap::new)
+                .extend(self.node_selector.clone());
+        }
+    }
+}
+
+/// A ServiceAccount dedicated to the schedule's pods, with a Role granting
+/// `rules` in the schedule's namespace.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ServiceAccountSpec {
+    /// Provisions the account and runs the pods under it, replacing
+    /// `serviceAccountName` of the pod template.
+    #[serde(default)]
+    pub create: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rules: Vec<PolicyRule>,
+}
+
+impl ServiceAccountSpec {
+    /// The entries of `rules` no rule of `allowed` covers. An allowed rule
+    /// covers one listing only its API groups, resources and verbs, `*`
+    /// standing for any, and only its resource names when it lists some.
+    /// Non-resource URLs are never covered, a Role cannot grant them.
+    pub fn rules_outside(&self, allowed: &[PolicyRule]) -> Vec<&PolicyRule> {
+        self.rules
+            .iter()
+            .filter(|rule| !allowed.iter().any(|a| covers(a, rule)))
+            .collect()
+    }
+}
+
+fn covers(allowed: &PolicyRule, rule: &PolicyRule) -> bool {
+    let within = |allowed: &[String], requested: &[String]| {
+        allowed.iter().any(|a| a == "*") || requested.iter().all(|r| allowed.contains(r))
+    };
+    let names = allowed.resource_names.as_deref().unwrap_or_default();
+    let requested_names = rule.resource_names.as_deref().unwrap_or_default();
+    rule.non_resource_urls.as_ref().is_none_or(Vec::is_empty)
+        && within(
+            allowed.api_groups.as_deref().unwrap_or_default(),
+            rule.api_groups.as_deref().unwrap_or_default(),
+        )
+        && within(
+            allowed.resources.as_deref().unwrap_or_default(),
+            rule.resources.as_deref().unwrap_or_default(),
+        )
+        && within(&allowed.verbs, &rule.verbs)
+        && (names.is_empty() || (!requested_names.is_empty() && within(names, requested_names)))
+}
+
+/// Annotation cluster-autoscaler reads to decide whether a node may be
+/// scaled down while the pod runs on it.
+pub const SAFE_TO_EVICT_ANNOTATION: &str = "cluster-autoscaler.kubernetes.io/safe-to-evict";
+
+/// Annotation stopping the descheduler from evicting the pod.
+pub const PREVENT_EVICTION_ANNOTATION: &str = "descheduler.alpha.kubernetes.io/prevent-eviction";
+
+/// Labels, annotations and node selection applied to every pod, so they need
+/// not be repeated in the template of each schedule. Values set by the
+/// template or a variant take precedence.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PodPlacement {
+    /// Whether cluster-autoscaler and the descheduler may evict the pods.
+    /// `false` keeps a run from being interrupted by scale-down.
+    pub safe_to_evict: Option<bool>,
+    /// Merged into the pod's node selector, e.g. to pin a batch node pool.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub node_selector: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub labels: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub annotations: BTreeMap<String, String>,
+}
+
+impl PodPlacement {
+    fn apply(&self, template: &mut PodTemplateSpec) {
+        let metadata = template.metadata.get_or_insert_with(ObjectMeta::default);
+        let labels = metadata.labels.get_or_insert_with(BTreeMap::new);
+        for (key, value) in &self.labels {
+            labels.entry(key.clone()).or_insert_with(|| value.clone());
+        }
+        let mut annotations = self.annotations.clone();
+        if let Some(safe) = self.safe_to_evict {
+            annotations.insert(SAFE_TO_EVICT_ANNOTATION.to_string(), safe.to_string());
+            annotations.insert(PREVENT_EVICTION_ANNOTATION.to_string(), (!safe).to_string());
+        }
+        if !annotations.is_empty() {
+            let existing = metadata.annotations.get_or_insert_with(BTreeMap::new);
+            for (key, value) in annotations {
+                existing.entry(key).or_insert(value);
+            }
+        }
+        if !self.node_selector.is_empty()
+            && let Some(pod) = template.spec.as_mut()
+        {
+            let selector = pod.node_selector.get_or_insert_with(BTreeMap::new);
+            for (key, value) in &self.node_selector {
+                selector.entry(key.clone()).or_insert_with(|| value.clone());
+            }
+        }
+    }
+}
+
+/// Network access of the schedule's pods.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkSpec {
+    /// The only egress the pods are allowed. An empty list denies all egress,
+    /// DNS included.
+    #[serde(default)]
+    pub allowed_egress: Vec<NetworkPolicyEgressRule>,
+}
+
+impl HasConditions for ScheduledCronJobStatus {
+    fn conditions(&self) -> &[Condition] {
+        &self.conditions
+    }
+
+    fn conditions_mut(&mut self) -> &mut Vec<Condition> {
+        &mut self.conditions
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, CELSchema, CustomResource, Default, Clone)]
@@ -60,27 +539,217 @@ pub struct ScheduledCronJobStatus {
     namespaced,
     printcolumn = r#"{"name":"StartTime", "type":"string", "description":"start time of the job", "jsonPath":".spec.startTime"}"#,
     printcolumn = r#"{"name":"EndTime", "type":"string", "description":"end time of the job", "jsonPath":".spec.endTime"}"#,
-    printcolumn = r#"{"name":"Phase", "type":"string", "description":"phase of the job", "jsonPath":".status.phase"}"#,
+    printcolumn = r#"{"name":"Phase", "type":"string", "description":"phase of the job: PendingValidation, PendingActivation, Active, Suspended, Expired, Failed or Completed", "jsonPath":".status.phase"}"#,
     status = "ScheduledCronJobStatus",
-    shortname = "scj"
+    shortname = "scj",
+    category = "all-scheduling"
 )]
 #[cel_validate(rule = Rule::new("(!has(self.startTime) && !has(self.endTime)) || (!has(self.startTime) && has(self.endTime)) || (has(self.startTime) && !has(self.endTime)) || (has(self.startTime) && has(self.endTime) && self.startTime < self.endTime)")
 .message(Message::Message("Invalid time range".to_string()))
 .reason(Reason::FieldValueForbidden))]
-#[cel_validate(rule = Rule::new("has(self.spec.concurrencyPolicy) && (self.spec.concurrencyPolicy in ['Allow', 'Forbid', 'Replace'])").message("Invalid concurrency policy").reason(Reason::FieldValueInvalid))]
-#[cel_validate(rule = Rule::new("has(self.spec.failedJobsHistoryLimit) && self.spec.failedJobsHistoryLimit >= 0").message("Invalid failed jobs history limit").reason(Reason::FieldValueInvalid))]
-#[cel_validate(rule = Rule::new("has(self.spec.successfulJobsHistoryLimit) && self.spec.successfulJobsHistoryLimit >= 0").message("Invalid successful jobs history limit").reason(Reason::FieldValueInvalid))]
-#[cel_validate(rule = Rule::new("has(self.spec.jobTemplate.spec.backoffLimit) && self.spec.jobTemplate.spec.backoffLimit >= 0").message("Invalid backoff limit").reason(Reason::FieldValueInvalid))]
-#[cel_validate(rule = Rule::new("has(self.spec.jobTemplate.spec.template.spec.containers) && self.spec.jobTemplate.spec.template.spec.containers.size() > 0").message("Invalid containers").reason(Reason::FieldValueRequired))]
-#[cel_validate(rule = Rule::new("has(self.spec.jobTemplate.spec.template.spec.restartPolicy) && self.spec.jobTemplate.spec.template.spec.restartPolicy in ['Always', 'OnFailure', 'Never']").message("Invalid restart policy").reason(Reason::FieldValueInvalid))]
+#[cel_validate(rule = Rule::new("!has(self.spec) || (has(self.spec.concurrencyPolicy) && (self.spec.concurrencyPolicy in ['Allow', 'Forbid', 'Replace']))").message("Invalid concurrency policy").reason(Reason::FieldValueInvalid))]
+#[cel_validate(rule = Rule::new("!has(self.spec) || (has(self.spec.failedJobsHistoryLimit) && self.spec.failedJobsHistoryLimit >= 0)").message("Invalid failed jobs history limit").reason(Reason::FieldValueInvalid))]
+#[cel_validate(rule = Rule::new("!has(self.spec) || (has(self.spec.successfulJobsHistoryLimit) && self.spec.successfulJobsHistoryLimit >= 0)").message("Invalid successful jobs history limit").reason(Reason::FieldValueInvalid))]
+#[cel_validate(rule = Rule::new("!has(self.spec) || (has(self.spec.jobTemplate.spec.backoffLimit) && self.spec.jobTemplate.spec.backoffLimit >= 0)").message("Invalid backoff limit").reason(Reason::FieldValueInvalid))]
+#[cel_validate(rule = Rule::new("!has(self.spec) || (has(self.spec.jobTemplate.spec.template.spec.containers) && self.spec.jobTemplate.spec.template.spec.containers.size() > 0)").message("Invalid containers").reason(Reason::FieldValueRequired))]
+#[cel_validate(rule = Rule::new("!has(self.spec) || (has(self.spec.jobTemplate.spec.template.spec.restartPolicy) && self.spec.jobTemplate.spec.template.spec.restartPolicy in ['Always', 'OnFailure', 'Never'])").message("Invalid restart policy").reason(Reason::FieldValueInvalid))]
+#[cel_validate(rule = Rule::new("has(self.spec) != has(self.cronJobTemplate)").message("Set either spec or cronJobTemplate").reason(Reason::FieldValueRequired))]
+#[cel_validate(rule = Rule::new("!has(self.owner) || self.owner.team != ''").message("Owner team is required").reason(Reason::FieldValueRequired))]
+#[cel_validate(rule = Rule::new("!has(self.owner) || !has(self.owner.slackChannel) || self.owner.slackChannel.startsWith('#')").message("Slack channel must start with #").reason(Reason::FieldValueInvalid))]
+#[cel_validate(rule = Rule::new("!has(self.owner) || !has(self.owner.pagerdutyService) || self.owner.pagerdutyService.matches('^P[A-Z0-9]{6}$')").message("Invalid PagerDuty service ID").reason(Reason::FieldValueInvalid))]
 #[serde(rename_all = "camelCase")]
 pub struct ScheduledCronJobSpec {
     start_time: Option<Time>,
     end_time: Option<Time>,
 
+    /// Name of the child CronJobs, with `{{name}}`, `{{namespace}}`,
+    /// `{{variant}}` and `{{schedule}}`, the index into `schedules`,
+    /// substituted. Defaults to `{{name}}`, followed by `-{{variant}}` when
+    /// there are variants and `-{{schedule}}` when there are `schedules`. See
+    /// [`ScheduledCronJob::child_names`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub child_name_template: Option<String>,
+
+    /// Who is on call for this schedule.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<Owner>,
+
+    /// Suspends every child CronJob. Clearing it resumes the children it
+    /// suspended, leaving those suspended by a ScheduledSuspend alone.
+    #[serde(default)]
+    pub suspend: bool,
+
+    /// Periods, such as maintenance windows, during which the child
+    /// CronJobs are suspended. They are resumed once the period ends.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub blackout_windows: Vec<BlackoutWindow>,
+
+    /// Names of ScheduleCalendars in the same namespace whose dates are
+    /// treated as blackout windows, read in `timeZone`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub calendars: Vec<String>,
+
+    /// Manages one child CronJob per variant instead of a single one.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub variants: Vec<Variant>,
+
+    /// Cron expressions fired instead of `spec.schedule`, which is then
+    /// ignored and may be empty, each by a child CronJob of its own, such as
+    /// `0 8 * * *` and `0 20 * * *`. They may have a seconds field like
+    /// `spec.schedule`. Their status is listed in
+    /// `status.schedules`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub schedules: Vec<String>,
+
+    /// RFC 5545 recurrence rule fired instead of `spec.schedule`, which is
+    /// then ignored and may be empty, such as
+    /// `FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=-1;BYHOUR=18` for the
+    /// last weekday of each month. Rules cron can express become the child
+    /// CronJobs' schedule; for the others the children are scheduled for the
+    /// next occurrence only and rescheduled after each run. See
+    /// [`RecurrenceRule`] for the supported parts.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recurrence_rule: Option<String>,
+
+    /// IANA name of the time zone the schedule is read in, such as
+    /// `Europe/Berlin`, set as the child CronJobs' `timeZone`. Defaults to
+    /// the zone of the kube-controller-manager, usually UTC. `startTime` and
+    /// `endTime` carry their own offsets and are not affected.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_zone: Option<String>,
+
+    /// Spreads the variants' fire times evenly over this many minutes, the
+    /// `i`th of `n` variants firing `i * spreadOverMinutes / n` minutes after
+    /// the schedule.
+    pub spread_over_minutes: Option<u32>,
+
+    /// Delays the fire times by up to this many seconds, by an amount derived
+    /// from the UID so it is the same on every reconciliation. Schedules have
+    /// minute resolution, so the delay is rounded down to whole minutes.
+    /// Applied when the child CronJobs are created; schedules whose shift
+    /// cannot be written as a cron expression are left as they are.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jitter_seconds: Option<u32>,
+
+    /// Provisions a ServiceAccount, Role and RoleBinding for the pods. They
+    /// are owned by the ScheduledCronJob and deleted with it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub service_account: Option<ServiceAccountSpec>,
+
+    /// Generates a NetworkPolicy restricting the pods' egress. It is owned by
+    /// the ScheduledCronJob and deleted with it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub network: Option<NetworkSpec>,
+
+    /// Placement and eviction settings applied to every pod.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pod_placement: Option<PodPlacement>,
+
+    /// Applies `status.recommendations` to the pods' requests.
+    #[serde(default)]
+    pub auto_resize: bool,
+
+    /// Runs still going this long after they started are failed by the
+    /// controller, archived as `TimedOut` and reported to the hooks. Unlike
+    /// `activeDeadlineSeconds`, which the Job controller enforces silently,
+    /// this is checked at each reconciliation, about every two minutes.
+    pub hard_timeout_seconds: Option<u64>,
+
+    /// Runs whose heartbeat stops for this long are failed like runs
+    /// exceeding `hardTimeoutSeconds`. Containers find the Lease to renew in
+    /// [`HEARTBEAT_LEASE_ENV`] and [`HEARTBEAT_NAMESPACE_ENV`]; the pods'
+    /// service account needs `update` on it.
+    pub heartbeat_timeout_seconds: Option<u64>,
+
+    /// Lets runs use spot capacity and retries them when it is reclaimed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spot_policy: Option<SpotPolicy>,
+
+    /// Verifies each successful run. Runs failing the check are archived as
+    /// `CheckFailed` and reported to the hooks like runs that failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_run_check: Option<PostRunCheck>,
+
+    /// Starts extra runs depending on how the last one ended.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub adaptive: Option<AdaptiveSchedule>,
+
+    /// Stops scheduling once this many runs succeeded: the child CronJobs are
+    /// ended as `endPolicy` says and the resource completes like after its
+    /// `endTime`. Counted runs are recorded in `status.executions`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_executions: Option<u32>,
+
+    /// What happens to the child CronJobs and their Jobs once the resource
+    /// ends, after `endTime` or `maxExecutions`. Defaults to `DeleteAll`.
+    #[serde(default)]
+    pub end_policy: EndPolicy,
+
+    /// Labels and annotations of the child CronJobs, besides those of the
+    /// ScheduledCronJob itself. Labels and annotations the controller sets
+    /// take precedence.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub child_metadata: Option<ChildMetadata>,
+
+    /// Labels of the Jobs the children start, such as for cost allocation.
+    /// Merged over those of `spec.jobTemplate.metadata`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub job_labels: BTreeMap<String, String>,
+
+    /// Annotations of the Jobs the children start.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub job_annotations: BTreeMap<String, String>,
+
+    /// Labels of the runs' pods. Merged over those of the pod template;
+    /// labels the controller sets take precedence.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub pod_labels: BTreeMap<String, String>,
+
+    /// Annotations of the runs' pods, such as those requesting sidecar
+    /// injection.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub pod_annotations: BTreeMap<String, String>,
+
+    /// Template of the child CronJobs, a full CronJobSpec; changes to its
+    /// `jobTemplate` are strategic-merged into existing children, while
+    /// `suspend` and `schedule` stay under the controller. Besides cron
+    /// syntax, `schedule` may be an interval such as `@every 5m` or
+    /// `@every 2h30m`, replaced by the closest cron expression, or have a
+    /// sixth, leading field for the seconds, such as `*/30 * * * * *`.
+    /// Intervals under a minute and schedules firing at seconds other than 0
+    /// are run by the controller. See [`Every`] and [`Seconds`].
+    ///
+    /// Left out when `cronJobTemplate` is set.
+    #[serde(default, skip_serializing_if = "is_unset")]
+    pub spec: CronJobSpec,
+
+    /// Template of the child CronJobs in place of `spec` and
+    /// `childMetadata`. Its `schedule` is read like that of `spec`. Changes
+    /// to its `jobTemplate` replace that of existing children, so fields
+    /// removed from the template are removed from the children too, which a
+    /// strategic merge of `spec` cannot do.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cron_job_template: Option<CronJobTemplate>,
+}
+
+/// Template of the child CronJobs, see `spec.cronJobTemplate`.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CronJobTemplate {
+    /// Labels and annotations of the child CronJobs. Those the controller
+    /// sets take precedence.
+    #[serde(default)]
+    pub metadata: ChildMetadata,
     pub spec: CronJobSpec,
 }
 
+/// Whether `spec.spec` was left out. Skipping it also keeps its default out
+/// of the schema, where the API server would fill it in next to
+/// `cronJobTemplate`.
+fn is_unset(spec: &CronJobSpec) -> bool {
+    *spec == CronJobSpec::default()
+}
+
 impl ScheduledCronJobSpec {
     pub fn new<S, E>(
         start_time: S,
@@ -94,25 +763,522 @@ impl ScheduledCronJobSpec {
         Ok(Self {
             start_time: start_time.into_time()?,
             end_time: end_time.into_time()?,
+            child_name_template: None,
+            owner: None,
+            suspend: false,
+            blackout_windows: Vec::new(),
+            calendars: Vec::new(),
+            variants: Vec::new(),
+            schedules: Vec::new(),
+            recurrence_rule: None,
+            time_zone: None,
+            spread_over_minutes: None,
+            jitter_seconds: None,
+            service_account: None,
+            network: None,
+            pod_placement: None,
+            auto_resize: false,
+            hard_timeout_seconds: None,
+            heartbeat_timeout_seconds: None,
+            spot_policy: None,
+            post_run_check: None,
+            adaptive: None,
+            max_executions: None,
+            end_policy: EndPolicy::default(),
+            child_metadata: None,
+            job_labels: BTreeMap::new(),
+            job_annotations: BTreeMap::new(),
+            pod_labels: BTreeMap::new(),
+            pod_annotations: BTreeMap::new(),
             spec,
+            cron_job_template: None,
         })
     }
+
+    /// The CronJobSpec of the children: that of `cronJobTemplate` when set,
+    /// `spec` otherwise.
+    pub fn cronjob_spec(&self) -> &CronJobSpec {
+        self.cron_job_template
+            .as_ref()
+            .map_or(&self.spec, |template| &template.spec)
+    }
+
+    /// Labels and annotations of the children: those of `cronJobTemplate`
+    /// when set, `childMetadata` otherwise.
+    pub fn template_metadata(&self) -> Option<&ChildMetadata> {
+        match &self.cron_job_template {
+            Some(template) => Some(&template.metadata),
+            None => self.child_metadata.as_ref(),
+        }
+    }
+
+    /// Replaces `startTime` and `endTime`.
+    pub fn with_window<S, E>(
+        mut self,
+        start_time: S,
+        end_time: E,
+    ) -> Result<Self, chrono::ParseError>
+    where
+        S: IntoTime,
+        E: IntoTime,
+    {
+        self.start_time = start_time.into_time()?;
+        self.end_time = end_time.into_time()?;
+        Ok(self)
+    }
+
+    /// Sets the `concurrencyPolicy` copied into the child CronJobs: `Allow`,
+    /// `Forbid` or `Replace`.
+    pub fn with_concurrency_policy<S: Into<String>>(mut self, policy: S) -> Self {
+        self.spec.concurrency_policy = Some(policy.into());
+        self
+    }
+
+    /// Sets how late, in seconds, the child CronJobs may start a run before
+    /// skipping it.
+    pub fn with_starting_deadline_seconds(mut self, seconds: i64) -> Self {
+        self.spec.starting_deadline_seconds = Some(seconds);
+        self
+    }
+
+    /// Sets how many finished Jobs the child CronJobs keep, by outcome.
+    pub fn with_history_limits(mut self, successful: i32, failed: i32) -> Self {
+        self.spec.successful_jobs_history_limit = Some(successful);
+        self.spec.failed_jobs_history_limit = Some(failed);
+        self
+    }
+}
+
+/// Longest CronJob name; the Jobs it creates append an 11-character suffix.
+pub const MAX_CHILD_NAME_LENGTH: usize = 52;
+
+/// Label naming the variant a child CronJob belongs to.
+pub const VARIANT_LABEL: &str = "divinerapier.io/variant";
+
+/// Label holding the index into `spec.schedules` of a child CronJob's
+/// schedule.
+pub const SCHEDULE_INDEX_LABEL: &str = "divinerapier.io/schedule-index";
+
+/// Intervals after which the controller starts a one-off run from the child
+/// CronJob's template, measured from the end of the last run. The schedule
+/// keeps firing as usual; no extra run starts while one is active.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AdaptiveSchedule {
+    /// Retries sooner than the schedule after a failed run, again after each
+    /// failed retry, until a run succeeds.
+    pub on_failure_interval_seconds: Option<u64>,
+    /// Runs again this long after a successful run. Left unset, successful
+    /// runs are followed by the schedule alone.
+    pub on_success_interval_seconds: Option<u64>,
+}
+
+impl AdaptiveSchedule {
+    /// The interval following a run that `succeeded` or not.
+    pub fn interval(&self, succeeded: bool) -> Option<std::time::Duration> {
+        let seconds = if succeeded {
+            self.on_success_interval_seconds
+        } else {
+            self.on_failure_interval_seconds
+        };
+        seconds.map(std::time::Duration::from_secs)
+    }
 }
 
+/// Annotation on the one-off Jobs started for `spec.adaptive`, holding the
+/// outcome of the run they follow, `Succeeded` or `Failed`.
+pub const ADAPTIVE_RUN_ANNOTATION: &str = "divinerapier.io/adaptive-run";
+
+/// Annotation on successful runs already counted in `status.executions`.
+pub const EXECUTION_COUNTED_ANNOTATION: &str = "divinerapier.io/execution-counted";
+
+/// Annotation on child CronJobs suspended by `spec.suspend`, so only those
+/// are resumed when it is cleared.
+pub const SPEC_SUSPENDED_ANNOTATION: &str = "divinerapier.io/suspended-by-spec";
+
+/// Annotation on child CronJobs of a sub-minute `@every` schedule, holding
+/// the interval in seconds. They stay suspended and the controller starts
+/// their runs.
+pub const EVERY_SECONDS_ANNOTATION: &str = "divinerapier.io/every-seconds";
+
+/// Annotation on child CronJobs of a six-field schedule firing at seconds
+/// other than 0, holding the expression. They stay suspended and the
+/// controller starts their runs.
+pub const SECONDS_SCHEDULE_ANNOTATION: &str = "divinerapier.io/seconds-schedule";
+
+/// Annotation on child CronJobs holding a hash of the `jobTemplate` and
+/// `spec.childMetadata` they were last brought in line with.
+pub const TEMPLATE_HASH_ANNOTATION: &str = "divinerapier.io/template-hash";
+
+/// Annotation marking a run Job failed for exceeding `hardTimeoutSeconds`,
+/// holding when.
+pub const TIMED_OUT_ANNOTATION: &str = "divinerapier.io/timed-out";
+
+/// Set on containers to the name of the Lease whose `renewTime` they update
+/// as a heartbeat, the name of their Job.
+pub const HEARTBEAT_LEASE_ENV: &str = "SCHEDULED_HEARTBEAT_LEASE";
+
+/// Set on containers to the namespace of their heartbeat Lease.
+pub const HEARTBEAT_NAMESPACE_ENV: &str = "SCHEDULED_HEARTBEAT_NAMESPACE";
+
+/// Set on containers to `heartbeatTimeoutSeconds`; heartbeats should be sent
+/// several times within it.
+pub const HEARTBEAT_TIMEOUT_ENV: &str = "SCHEDULED_HEARTBEAT_TIMEOUT_SECONDS";
+
+/// Label naming the ScheduledCronJob a run Job or pod was created for, see
+/// [`crate::run_labels`].
+pub const SCHEDULE_LABEL: &str = "divinerapier.io/scheduled-cronjob";
+
+/// The variant, with its position, and the index into `schedules` a child
+/// CronJob is managed for, `None` when there are no variants or schedules.
+type Slot<'a> = (Option<(u32, &'a Variant)>, Option<usize>);
+
 impl ScheduledCronJob {
-    pub fn cronjob(&self) -> CronJob {
-        CronJob {
-            metadata: ObjectMeta {
-                namespace: Some(self.namespace().unwrap_or_default()),
-                name: Some(self.name_any()),
-                annotations: Some(self.annotations().clone()),
-                labels: Some(self.labels().clone()),
-                owner_references: Some(vec![self.controller_owner_ref(&()).unwrap()]),
-                ..Default::default()
-            },
-            spec: Some(self.spec.spec.clone()),
-            status: None,
+    /// Name of the child CronJob of `variant` and the `schedule`th entry of
+    /// `schedules`, rendered from `childNameTemplate`.
+    fn child_name(&self, variant: Option<&str>, schedule: Option<usize>) -> String {
+        let template = match (&self.spec.child_name_template, variant, schedule) {
+            (Some(template), _, _) => template.as_str(),
+            (None, Some(_), Some(_)) => "{{name}}-{{variant}}-{{schedule}}",
+            (None, Some(_), None) => "{{name}}-{{variant}}",
+            (None, None, Some(_)) => "{{name}}-{{schedule}}",
+            (None, None, None) => "{{name}}",
+        };
+        let rendered = template
+            .replace("{{name}}", &self.name_any())
+            .replace("{{namespace}}", &self.namespace().unwrap_or_default())
+            .replace("{{variant}}", variant.unwrap_or_default())
+            .replace(
+                "{{schedule}}",
+                &schedule.map(|s| s.to_string()).unwrap_or_default(),
+            );
+        child_name(&rendered)
+    }
+
+    /// One [`Slot`] per child CronJob, for each variant and schedule.
+    fn slots(&self) -> Vec<Slot<'_>> {
+        let variants: Vec<_> = if self.spec.variants.is_empty() {
+            vec![None]
+        } else {
+            (0..).zip(&self.spec.variants).map(Some).collect()
+        };
+        let schedules: Vec<_> = if self.spec.schedules.is_empty() {
+            vec![None]
+        } else {
+            (0..self.spec.schedules.len()).map(Some).collect()
+        };
+        variants
+            .into_iter()
+            .flat_map(|v| schedules.iter().map(move |s| (v, *s)))
+            .collect()
+    }
+
+    /// Names of the child CronJobs, one per variant and entry of
+    /// `schedules`, or a single one when there are neither.
+    pub fn child_names(&self) -> Vec<String> {
+        self.slots()
+            .into_iter()
+            .map(|(variant, schedule)| {
+                self.child_name(variant.map(|(_, v)| v.name.as_str()), schedule)
+            })
+            .collect()
+    }
+
+    /// The child CronJobs, one per variant and entry of `schedules`, or a
+    /// single one when there are neither. Variant children are labelled with
+    /// [`VARIANT_LABEL`], and their schedules are staggered by
+    /// `spreadOverMinutes`; children of `schedules` are labelled with
+    /// [`SCHEDULE_INDEX_LABEL`]. Every schedule is then delayed by
+    /// [`ScheduledCronJob::jitter_minutes`].
+    pub fn cronjobs(&self) -> Result<Vec<CronJob>, crate::Error> {
+        self.cronjobs_at(Utc::now())
+    }
+
+    /// [`ScheduledCronJob::cronjobs`] with the schedule of a `recurrenceRule`
+    /// cron cannot express set to its first occurrence after `after`.
+    pub fn cronjobs_at(&self, after: DateTime<Utc>) -> Result<Vec<CronJob>, crate::Error> {
+        let jitter = self.jitter_minutes();
+        let jittered = |schedule: String| stagger(&schedule, jitter).unwrap_or(schedule);
+        let mut base = self.spec.cronjob_spec().clone();
+        base.schedule = self.base_schedule(after)?;
+        // Sub-minute intervals are run by the controller; the children only
+        // hold the template and stay suspended.
+        let driven = self.every()?.filter(Every::is_sub_minute);
+        if driven.is_some() {
+            base.suspend = Some(true);
+        }
+        let drive = |mut child: CronJob| {
+            if let Some(every) = driven {
+                child.annotations_mut().insert(
+                    EVERY_SECONDS_ANNOTATION.to_string(),
+                    every.interval().as_secs().to_string(),
+                );
+            }
+            child
+        };
+        let count = self.spec.variants.len() as u32;
+        let spread = self.spec.spread_over_minutes.unwrap_or_default();
+        self.slots()
+            .into_iter()
+            .map(|(variant, schedule)| {
+                let mut spec = base.clone();
+                if let Some(schedule) = schedule {
+                    spec.schedule = self.spec.schedules[schedule].clone();
+                }
+                // Children run the minutes of six-field schedules; those
+                // firing at other seconds are run by the controller.
+                let seconds = Seconds::parse(&spec.schedule)?;
+                if let Some(seconds) = &seconds {
+                    spec.schedule = seconds.minutes().to_string();
+                }
+                let seconds = seconds.filter(|s| !s.is_on_the_minute());
+                if seconds.is_some() {
+                    spec.suspend = Some(true);
+                }
+                let mut offset = 0;
+                if let Some((index, variant)) = variant {
+                    variant.overrides.apply(&mut spec);
+                    offset = index * spread / count;
+                }
+                spec.schedule = jittered(stagger(&spec.schedule, offset)?);
+                let seconds = seconds.map(|s| s.with_minutes(&spec.schedule));
+                let variant = variant.map(|(_, v)| v.name.as_str());
+                let mut child = drive(self.child(variant, schedule, spec)?);
+                if let Some(seconds) = seconds {
+                    child
+                        .annotations_mut()
+                        .insert(SECONDS_SCHEDULE_ANNOTATION.to_string(), seconds);
+                }
+                Ok(child)
+            })
+            .collect()
+    }
+
+    /// The parsed `recurrenceRule`, if set.
+    pub fn recurrence_rule(&self) -> Result<Option<RecurrenceRule>, crate::Error> {
+        self.spec
+            .recurrence_rule
+            .as_deref()
+            .map(RecurrenceRule::parse)
+            .transpose()
+    }
+
+    /// The `@every` interval of `spec.schedule`, unless a `recurrenceRule`
+    /// or `schedules` replace it.
+    pub fn every(&self) -> Result<Option<Every>, crate::Error> {
+        if self.spec.recurrence_rule.is_some() || !self.spec.schedules.is_empty() {
+            return Ok(None);
         }
+        Every::parse(&self.spec.cronjob_spec().schedule)
+    }
+
+    /// The schedule the children are derived from: `spec.schedule` with an
+    /// `@every` interval replaced by the closest cron expression, or the
+    /// `recurrenceRule` as a cron expression, or else a schedule firing at
+    /// the rule's first occurrence after `after`.
+    pub fn base_schedule(&self, after: DateTime<Utc>) -> Result<String, crate::Error> {
+        let Some(rule) = self.recurrence_rule()? else {
+            return Ok(match self.every()? {
+                Some(every) => every.to_cron().0,
+                None => self.spec.cronjob_spec().schedule.clone(),
+            });
+        };
+        if let Some(cron) = rule.to_cron() {
+            return Ok(cron);
+        }
+        let tz = self.time_zone()?.unwrap_or(Tz::UTC);
+        let next = rule.next_after(&after.with_timezone(&tz)).ok_or_else(|| {
+            crate::Error::InvalidSchedule(format!("recurrenceRule has no occurrence after {after}"))
+        })?;
+        Ok(recurrence::one_shot(&next))
+    }
+
+    /// Whole minutes the schedules are delayed by for `jitterSeconds`, from
+    /// the hash of the UID. Zero before the resource has a UID.
+    pub fn jitter_minutes(&self) -> u32 {
+        let Some(max) = self.spec.jitter_seconds.filter(|s| *s > 0) else {
+            return 0;
+        };
+        let Some(uid) = self.uid() else {
+            return 0;
+        };
+        let hash = digest::digest(&digest::SHA256, uid.as_bytes());
+        let mut bytes = [0; 8];
+        bytes.copy_from_slice(&hash.as_ref()[..8]);
+        let seconds = u64::from_be_bytes(bytes) % (u64::from(max) + 1);
+        (seconds / 60) as u32
+    }
+
+    fn child(
+        &self,
+        variant: Option<&str>,
+        schedule: Option<usize>,
+        mut spec: CronJobSpec,
+    ) -> Result<CronJob, crate::Error> {
+        let mut metadata = self.owned_metadata(self.child_name(variant, schedule))?;
+        metadata.annotations = Some(self.annotations().clone());
+        if let Some(child) = self.spec.template_metadata() {
+            let labels = metadata.labels.get_or_insert_with(BTreeMap::new);
+            labels.extend(child.labels.clone());
+            let annotations = metadata.annotations.get_or_insert_with(BTreeMap::new);
+            annotations.extend(child.annotations.clone());
+        }
+        if let Some(variant) = variant {
+            metadata
+                .labels
+                .get_or_insert_with(BTreeMap::new)
+                .insert(VARIANT_LABEL.to_string(), variant.to_string());
+        }
+        if let Some(schedule) = schedule {
+            metadata
+                .labels
+                .get_or_insert_with(BTreeMap::new)
+                .insert(SCHEDULE_INDEX_LABEL.to_string(), schedule.to_string());
+        }
+        if let Some(time_zone) = &self.spec.time_zone {
+            spec.time_zone = Some(time_zone.clone());
+        }
+        if self.spec.suspend {
+            spec.suspend = Some(true);
+            metadata
+                .annotations
+                .get_or_insert_with(BTreeMap::new)
+                .insert(SPEC_SUSPENDED_ANNOTATION.to_string(), "true".to_string());
+        }
+        if let Some(policy) = &self.spec.spot_policy {
+            policy.apply(&mut spec.job_template);
+        }
+        stamp(
+            &mut spec.job_template.metadata,
+            &self.spec.job_labels,
+            &self.spec.job_annotations,
+        );
+        let mut run_labels = BTreeMap::from([(SCHEDULE_LABEL.to_string(), self.schedule_label())]);
+        if let Some(variant) = variant {
+            run_labels.insert(VARIANT_LABEL.to_string(), variant.to_string());
+        }
+        stamp(
+            &mut spec.job_template.metadata,
+            &run_labels,
+            &BTreeMap::new(),
+        );
+        if let Some(template) = spec.job_template.spec.as_mut().map(|s| &mut s.template) {
+            stamp(
+                &mut template.metadata,
+                &self.spec.pod_labels,
+                &self.spec.pod_annotations,
+            );
+            stamp(&mut template.metadata, &run_labels, &BTreeMap::new());
+            if let Some(placement) = &self.spec.pod_placement {
+                placement.apply(template);
+            }
+            if let Some(account) = self.service_account_name()
+                && let Some(pod) = template.spec.as_mut()
+            {
+                pod.service_account_name = Some(account);
+                pod.service_account = None;
+            }
+            if let Some(timeout) = self.spec.heartbeat_timeout_seconds
+                && let Some(pod) = template.spec.as_mut()
+            {
+                inject_heartbeat_env(pod, timeout);
+            }
+            if self.spec.auto_resize
+                && let Some(status) = self.status()
+                && let Some(pod) = template.spec.as_mut()
+            {
+                apply_recommendations(pod, &status.recommendations);
+            }
+        }
+        Ok(CronJob {
+            metadata,
+            spec: Some(spec),
+            status: None,
+        })
+    }
+
+    /// Metadata of an object named `name` in this namespace, carrying this
+    /// resource's labels and owned by it.
+    fn owned_metadata(&self, name: String) -> Result<ObjectMeta, crate::Error> {
+        Ok(ObjectMeta {
+            namespace: Some(self.namespace().unwrap_or_default()),
+            name: Some(name),
+            labels: Some(self.labels().clone()),
+            owner_references: Some(self.controller_references()?),
+            ..Default::default()
+        })
+    }
+
+    /// Name shared by the provisioned ServiceAccount, Role and RoleBinding,
+    /// or `None` when `spec.serviceAccount.create` is not set.
+    pub fn service_account_name(&self) -> Option<String> {
+        self.spec.service_account.as_ref().filter(|s| s.create)?;
+        Some(child_name(&format!("{}-runner", self.name_any())))
+    }
+
+    /// The ServiceAccount the pods run under, its Role and the RoleBinding
+    /// between them, or `None` when they are not provisioned.
+    pub fn service_account(
+        &self,
+    ) -> Result<Option<(ServiceAccount, Role, RoleBinding)>, crate::Error> {
+        let (Some(name), Some(account)) = (
+            self.service_account_name(),
+            self.spec.service_account.as_ref(),
+        ) else {
+            return Ok(None);
+        };
+        let rules = account.rules.clone();
+        let account = ServiceAccount {
+            metadata: self.owned_metadata(name.clone())?,
+            ..Default::default()
+        };
+        let role = Role {
+            metadata: self.owned_metadata(name.clone())?,
+            rules: Some(rules),
+        };
+        let binding = RoleBinding {
+            metadata: self.owned_metadata(name.clone())?,
+            role_ref: RoleRef {
+                api_group: "rbac.authorization.k8s.io".to_string(),
+                kind: "Role".to_string(),
+                name: name.clone(),
+            },
+            subjects: Some(vec![Subject {
+                kind: "ServiceAccount".to_string(),
+                name,
+                namespace: self.namespace(),
+                ..Default::default()
+            }]),
+        };
+        Ok(Some((account, role, binding)))
+    }
+
+    /// Value of [`SCHEDULE_LABEL`] on this resource's pods.
+    pub fn schedule_label(&self) -> String {
+        child_name(&self.name_any())
+    }
+
+    /// The NetworkPolicy limiting the pods' egress to `spec.network`, or
+    /// `None` when no network restrictions are declared.
+    pub fn network_policy(&self) -> Result<Option<NetworkPolicy>, crate::Error> {
+        let Some(network) = self.spec.network.as_ref() else {
+            return Ok(None);
+        };
+        Ok(Some(NetworkPolicy {
+            metadata: self.owned_metadata(child_name(&format!("{}-egress", self.name_any())))?,
+            spec: Some(NetworkPolicySpec {
+                pod_selector: LabelSelector {
+                    match_labels: Some(BTreeMap::from([(
+                        SCHEDULE_LABEL.to_string(),
+                        self.schedule_label(),
+                    )])),
+                    ..Default::default()
+                },
+                policy_types: Some(vec!["Egress".to_string()]),
+                egress: Some(network.allowed_egress.clone()),
+                ingress: None,
+            }),
+        }))
     }
 
     pub fn start_time(&self) -> Option<DateTime<Local>> {
@@ -125,78 +1291,175 @@ impl ScheduledCronJob {
         Some(end_time.0.with_timezone(&Local))
     }
 
+    pub fn window(&self) -> Window {
+        Window::new(self.start_time(), self.end_time())
+    }
+
     pub fn validate_effective_time(&self) -> Result<(), crate::Error> {
-        let start_time = self.start_time();
-        let end_time = self.end_time();
-        let now = Local::now();
+        self.window().check(Local::now())
+    }
+
+    /// Whether `spec.maxExecutions` runs have succeeded.
+    pub fn executions_exhausted(&self) -> bool {
+        self.spec
+            .max_executions
+            .is_some_and(|max| self.status().and_then(|s| s.executions).unwrap_or_default() >= max)
+    }
+
+    pub fn can_run(&self) -> bool {
+        self.status()
+            .is_none_or(|status| !status.phase.is_terminal())
+    }
 
-        if let (Some(start_time), Some(end_time)) = (start_time, end_time) {
-            if start_time >= end_time {
-                return Err(crate::Error::EndBeforeStart);
+    /// Whether the spec changed since the resource failed, which is worth
+    /// validating again.
+    pub fn failed_before_spec_change(&self) -> bool {
+        self.status().is_some_and(|status| {
+            status.phase == ScheduledCronJobPhase::Failed
+                && status.observed_generation < self.metadata.generation
+        })
+    }
+
+    /// Describes how the recorded phase contradicts the observed state, if it
+    /// does. `child_exists` tells whether every child CronJob was found.
+    pub fn stale_reason(&self, child_exists: bool, now: DateTime<Local>) -> Option<String> {
+        let phase = self.status()?.phase;
+        match phase {
+            ScheduledCronJobPhase::Active | ScheduledCronJobPhase::Suspended if !child_exists => {
+                Some(format!(
+                    "phase is {phase} but a child CronJob does not exist"
+                ))
             }
-            if end_time - start_time < Duration::minutes(5) {
-                return Err(crate::Error::DurationTooShort(start_time, end_time));
+            ScheduledCronJobPhase::Expired | ScheduledCronJobPhase::Completed
+                if self.window().check(now).is_ok() && !self.executions_exhausted() =>
+            {
+                Some(format!(
+                    "phase is {phase} but the schedule is within its window"
+                ))
             }
+            _ => None,
         }
+    }
 
-        if let Some(start_time) = start_time {
-            if now < start_time {
-                return Err(crate::Error::WaitFor(start_time - now));
+    /// Checks that variant names are unique and give distinct child names.
+    pub fn validate_variants(&self) -> Result<(), crate::Error> {
+        let mut names = HashSet::new();
+        for variant in &self.spec.variants {
+            if variant.name.is_empty() || !names.insert(variant.name.as_str()) {
+                return Err(crate::Error::InvalidVariants(format!(
+                    "variant name {:?} is empty or repeated",
+                    variant.name
+                )));
             }
         }
-
-        if let Some(end_time) = end_time {
-            if now > end_time {
-                return Err(crate::Error::Expired(end_time));
-            }
+        let children = self.child_names();
+        if children.iter().collect::<HashSet<_>>().len() != children.len() {
+            return Err(crate::Error::InvalidVariants(
+                "childNameTemplate gives several children the same name".to_string(),
+            ));
         }
-
         Ok(())
     }
 
-    pub fn can_run(&self) -> bool {
-        match self.status() {
-            Some(status) => match status.phase {
-                ScheduledCronJobPhase::Completed
-                | ScheduledCronJobPhase::Failed
-                | ScheduledCronJobPhase::InvalidEndTime
-                | ScheduledCronJobPhase::InvalidStartTime
-                | ScheduledCronJobPhase::EndBeforeStart => {
-                    return false;
-                }
-                _ => {
-                    return true;
-                }
-            },
-            None => return true,
-        }
+    /// When the blackout windows and the dates of `calendars` covering `now`
+    /// end, or `None` outside of them.
+    pub fn blackout_until(
+        &self,
+        now: DateTime<Utc>,
+        calendars: &[ScheduleCalendar],
+    ) -> Option<DateTime<Utc>> {
+        let tz = self.time_zone().ok().flatten().unwrap_or(Tz::UTC);
+        self.blackout_windows(calendars, tz)
+            .iter()
+            .filter_map(|w| w.active_until(now, tz))
+            .max()
+    }
+
+    /// When the next blackout window or date of `calendars` after `now`
+    /// begins, if any.
+    pub fn next_blackout(
+        &self,
+        now: DateTime<Utc>,
+        calendars: &[ScheduleCalendar],
+    ) -> Option<DateTime<Utc>> {
+        let tz = self.time_zone().ok().flatten().unwrap_or(Tz::UTC);
+        self.blackout_windows(calendars, tz)
+            .iter()
+            .filter_map(|w| w.next_start(now, tz))
+            .min()
+    }
+
+    fn blackout_windows(&self, calendars: &[ScheduleCalendar], tz: Tz) -> Vec<BlackoutWindow> {
+        let mut windows = self.spec.blackout_windows.clone();
+        windows.extend(calendars.iter().flat_map(|c| c.windows(tz)));
+        windows
+    }
+
+    /// Whether `calendar` is one of `spec.calendars`.
+    pub fn uses_calendar(&self, calendar: &ScheduleCalendar) -> bool {
+        self.namespace() == calendar.namespace()
+            && self.spec.calendars.contains(&calendar.name_any())
+    }
+
+    /// The parsed `spec.timeZone`, or `None` when it is not set.
+    pub fn time_zone(&self) -> Result<Option<Tz>, crate::Error> {
+        self.spec
+            .time_zone
+            .as_deref()
+            .map(|name| {
+                name.parse::<Tz>()
+                    .map_err(|_| crate::Error::InvalidSchedule(format!("unknown time zone {name}")))
+            })
+            .transpose()
     }
 
     pub fn validate_cronjob(&self) -> Result<(), crate::Error> {
-        let spec = &self.spec.spec;
+        self.time_zone()?;
+        self.recurrence_rule()?;
+        self.every()?;
+        if self.spec.recurrence_rule.is_some() && !self.spec.schedules.is_empty() {
+            return Err(crate::Error::InvalidSchedule(
+                "set either recurrenceRule or schedules".to_string(),
+            ));
+        }
+        Seconds::parse(&self.spec.cronjob_spec().schedule)?;
+        for schedule in &self.spec.schedules {
+            if Seconds::parse(schedule)?.is_none() {
+                Schedule::parse(schedule)?;
+            }
+        }
+        for window in &self.spec.blackout_windows {
+            window.validate()?;
+        }
+        if let Some(owner) = &self.spec.owner {
+            owner.validate()?;
+        }
+        let spec = self.spec.cronjob_spec();
         match spec.concurrency_policy.as_deref() {
             Some("Forbid") | Some("Allow") | Some("Replace") | None => {}
             _ => {
                 return Err(crate::Error::InvalidConcurrencyPolicy);
             }
         }
-        match spec.failed_jobs_history_limit {
-            Some(limit) => {
-                if limit < 0 {
-                    return Err(crate::Error::InvalidFailedJobsHistoryLimit);
-                }
-            }
-            None => {}
+        if let Some(limit) = spec.failed_jobs_history_limit
+            && limit < 0
+        {
+            return Err(crate::Error::InvalidFailedJobsHistoryLimit);
+        }
+        if let Some(limit) = spec.successful_jobs_history_limit
+            && limit < 0
+        {
+            return Err(crate::Error::InvalidSuccessfulJobsHistoryLimit);
         }
 
         let Some(spec) = &spec.job_template.spec else {
             return Err(crate::Error::CronjobSpecNotFound);
         };
 
-        if let Some(backoff_limit) = spec.backoff_limit {
-            if backoff_limit < 0 {
-                return Err(crate::Error::InvalidBackoffLimit);
-            }
+        if let Some(backoff_limit) = spec.backoff_limit
+            && backoff_limit < 0
+        {
+            return Err(crate::Error::InvalidBackoffLimit);
         }
 
         let Some(spec) = &spec.template.spec else {
@@ -211,10 +1474,43 @@ impl ScheduledCronJob {
     }
 }
 
+/// Turns `rendered` into a valid CronJob name: characters outside
+/// `[a-z0-9-]` become `-`, and names longer than [`MAX_CHILD_NAME_LENGTH`]
+/// are truncated and suffixed with a hash of the full name, so they stay
+/// unique.
+pub(crate) fn child_name(rendered: &str) -> String {
+    let name: String = rendered
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    let name = name.trim_matches('-');
+    if name.len() <= MAX_CHILD_NAME_LENGTH {
+        return name.to_string();
+    }
+    let hash = digest::digest(&digest::SHA256, name.as_bytes());
+    let suffix: String = hash.as_ref()[..4]
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect();
+    let prefix = name[..MAX_CHILD_NAME_LENGTH - suffix.len() - 1].trim_end_matches('-');
+    format!("{prefix}-{suffix}")
+}
+
 #[derive(Debug, Default)]
 pub struct CronJobBuilder {
     metadata: ObjectMeta,
     spec: CronJobSpec,
+    resources: ResourceRequirements,
+    env: Vec<EnvVar>,
+    env_from: Vec<EnvFromSource>,
+    volumes: Vec<Volume>,
+    volume_mounts: Vec<VolumeMount>,
+    image_pull_secrets: Vec<String>,
+    service_account_name: Option<String>,
+    node_selector: BTreeMap<String, String>,
+    tolerations: Vec<Toleration>,
+    affinity: Option<Affinity>,
 }
 
 impl CronJobBuilder {
@@ -237,10 +1533,403 @@ impl CronJobBuilder {
         self
     }
 
+    pub fn with_concurrency_policy<S: Into<String>>(mut self, policy: S) -> Self {
+        self.spec.concurrency_policy = Some(policy.into());
+        self
+    }
+
+    pub fn with_starting_deadline_seconds(mut self, seconds: i64) -> Self {
+        self.spec.starting_deadline_seconds = Some(seconds);
+        self
+    }
+
+    pub fn with_successful_jobs_history_limit(mut self, limit: i32) -> Self {
+        self.spec.successful_jobs_history_limit = Some(limit);
+        self
+    }
+
+    pub fn with_failed_jobs_history_limit(mut self, limit: i32) -> Self {
+        self.spec.failed_jobs_history_limit = Some(limit);
+        self
+    }
+
+    /// Sets the requests and limits of every container that does not set
+    /// them itself, such as to satisfy a LimitRange.
+    pub fn with_resources(mut self, resources: ResourceRequirements) -> Self {
+        self.resources = resources;
+        self
+    }
+
+    /// Requests `request` CPU, such as `250m`, limited to `limit`.
+    pub fn with_cpu<R: Into<String>, L: Into<String>>(self, request: R, limit: L) -> Self {
+        self.with_resource("cpu", request.into(), limit.into())
+    }
+
+    /// Requests `request` memory, such as `128Mi`, limited to `limit`.
+    pub fn with_memory<R: Into<String>, L: Into<String>>(self, request: R, limit: L) -> Self {
+        self.with_resource("memory", request.into(), limit.into())
+    }
+
+    fn with_resource(mut self, resource: &str, request: String, limit: String) -> Self {
+        self.resources
+            .requests
+            .get_or_insert_default()
+            .insert(resource.to_string(), Quantity(request));
+        self.resources
+            .limits
+            .get_or_insert_default()
+            .insert(resource.to_string(), Quantity(limit));
+        self
+    }
+
+    /// Sets the environment variable `name` of every container to `value`.
+    pub fn with_env<N: Into<String>, V: Into<String>>(mut self, name: N, value: V) -> Self {
+        self.env.push(EnvVar {
+            name: name.into(),
+            value: Some(value.into()),
+            ..Default::default()
+        });
+        self
+    }
+
+    /// Sets the environment variable `name` to the `key` of the Secret
+    /// `secret`.
+    pub fn with_env_from_secret_key<N, S, K>(self, name: N, secret: S, key: K) -> Self
+    where
+        N: Into<String>,
+        S: Into<String>,
+        K: Into<String>,
+    {
+        self.with_env_source(
+            name.into(),
+            EnvVarSource {
+                secret_key_ref: Some(SecretKeySelector {
+                    name: secret.into(),
+                    key: key.into(),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Sets the environment variable `name` to the `key` of the ConfigMap
+    /// `config_map`.
+    pub fn with_env_from_config_map_key<N, C, K>(self, name: N, config_map: C, key: K) -> Self
+    where
+        N: Into<String>,
+        C: Into<String>,
+        K: Into<String>,
+    {
+        self.with_env_source(
+            name.into(),
+            EnvVarSource {
+                config_map_key_ref: Some(ConfigMapKeySelector {
+                    name: config_map.into(),
+                    key: key.into(),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Sets the environment variable `name` to a field of the pod, such as
+    /// `metadata.namespace` or `spec.nodeName`.
+    pub fn with_env_from_field<N: Into<String>, F: Into<String>>(self, name: N, field: F) -> Self {
+        self.with_env_source(
+            name.into(),
+            EnvVarSource {
+                field_ref: Some(ObjectFieldSelector {
+                    field_path: field.into(),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+        )
+    }
+
+    fn with_env_source(mut self, name: String, source: EnvVarSource) -> Self {
+        self.env.push(EnvVar {
+            name,
+            value_from: Some(source),
+            ..Default::default()
+        });
+        self
+    }
+
+    /// Exposes every key of the Secret `secret` as an environment variable.
+    pub fn with_env_from_secret<S: Into<String>>(mut self, secret: S) -> Self {
+        self.env_from.push(EnvFromSource {
+            secret_ref: Some(SecretEnvSource {
+                name: secret.into(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        });
+        self
+    }
+
+    /// Exposes every key of the ConfigMap `config_map` as an environment
+    /// variable.
+    pub fn with_env_from_config_map<C: Into<String>>(mut self, config_map: C) -> Self {
+        self.env_from.push(EnvFromSource {
+            config_map_ref: Some(ConfigMapEnvSource {
+                name: config_map.into(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        });
+        self
+    }
+
+    /// Adds `volume` to the pod. Volumes named like one of the template are
+    /// left out.
+    pub fn with_volume(mut self, volume: Volume) -> Self {
+        self.volumes.push(volume);
+        self
+    }
+
+    /// Adds the volume `name` holding the keys of the ConfigMap
+    /// `config_map` as files.
+    pub fn with_config_map_volume<N: Into<String>, C: Into<String>>(
+        self,
+        name: N,
+        config_map: C,
+    ) -> Self {
+        self.with_volume(Volume {
+            name: name.into(),
+            config_map: Some(ConfigMapVolumeSource {
+                name: config_map.into(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        })
+    }
+
+    /// Adds the volume `name` holding the keys of the Secret `secret` as
+    /// files.
+    pub fn with_secret_volume<N: Into<String>, S: Into<String>>(self, name: N, secret: S) -> Self {
+        self.with_volume(Volume {
+            name: name.into(),
+            secret: Some(SecretVolumeSource {
+                secret_name: Some(secret.into()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        })
+    }
+
+    /// Adds the empty scratch volume `name`, removed with the pod.
+    pub fn with_empty_dir_volume<N: Into<String>>(self, name: N) -> Self {
+        self.with_volume(Volume {
+            name: name.into(),
+            empty_dir: Some(Default::default()),
+            ..Default::default()
+        })
+    }
+
+    /// Adds the volume `name` backed by the PersistentVolumeClaim `claim`.
+    pub fn with_pvc_volume<N: Into<String>, C: Into<String>>(self, name: N, claim: C) -> Self {
+        self.with_volume(Volume {
+            name: name.into(),
+            persistent_volume_claim: Some(PersistentVolumeClaimVolumeSource {
+                claim_name: claim.into(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        })
+    }
+
+    /// Mounts the volume `name` at `path` in every container. Containers
+    /// that already mount something at `path` keep their mount.
+    pub fn with_volume_mount<N: Into<String>, P: Into<String>>(mut self, name: N, path: P) -> Self {
+        self.volume_mounts.push(VolumeMount {
+            name: name.into(),
+            mount_path: path.into(),
+            ..Default::default()
+        });
+        self
+    }
+
+    /// [`CronJobBuilder::with_volume_mount`], read-only.
+    pub fn with_read_only_volume_mount<N: Into<String>, P: Into<String>>(
+        mut self,
+        name: N,
+        path: P,
+    ) -> Self {
+        self.volume_mounts.push(VolumeMount {
+            name: name.into(),
+            mount_path: path.into(),
+            read_only: Some(true),
+            ..Default::default()
+        });
+        self
+    }
+
+    /// Pulls images with the credentials of the Secret `secret`, of type
+    /// `kubernetes.io/dockerconfigjson`.
+    pub fn with_image_pull_secret<S: Into<String>>(mut self, secret: S) -> Self {
+        self.image_pull_secrets.push(secret.into());
+        self
+    }
+
+    /// Runs the pods as the ServiceAccount `name`, such as one bound to a
+    /// cloud identity.
+    pub fn with_service_account_name<S: Into<String>>(mut self, name: S) -> Self {
+        self.service_account_name = Some(name.into());
+        self
+    }
+
+    /// Only schedules the pods on nodes labelled `key=value`, such as a
+    /// batch node pool.
+    pub fn with_node_selector<K: Into<String>, V: Into<String>>(
+        mut self,
+        key: K,
+        value: V,
+    ) -> Self {
+        self.node_selector.insert(key.into(), value.into());
+        self
+    }
+
+    pub fn with_toleration(mut self, toleration: Toleration) -> Self {
+        self.tolerations.push(toleration);
+        self
+    }
+
+    /// Lets the pods run on nodes tainted `key=value:effect`, such as spot
+    /// instances.
+    pub fn with_tolerated_taint<K, V, E>(self, key: K, value: V, effect: E) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+        E: Into<String>,
+    {
+        self.with_toleration(Toleration {
+            key: Some(key.into()),
+            operator: Some("Equal".to_string()),
+            value: Some(value.into()),
+            effect: Some(effect.into()),
+            ..Default::default()
+        })
+    }
+
+    /// Sets the node affinity and pod (anti-)affinity of the pods.
+    pub fn with_affinity(mut self, affinity: Affinity) -> Self {
+        self.affinity = Some(affinity);
+        self
+    }
+
+    /// The CronJob, with the resources, environment, volumes, mounts, pull
+    /// secrets, ServiceAccount and scheduling constraints set on the builder
+    /// added to the pod and every container. Settings of the template itself
+    /// win: variables, volumes, mount paths, node selector keys, a
+    /// ServiceAccount and an affinity it already defines are left alone.
     pub fn build(self) -> CronJob {
+        let Self {
+            metadata,
+            mut spec,
+            resources: defaults,
+            env,
+            env_from,
+            volumes,
+            volume_mounts,
+            image_pull_secrets,
+            service_account_name,
+            node_selector,
+            tolerations,
+            affinity,
+        } = self;
+        let pod = spec
+            .job_template
+            .spec
+            .as_mut()
+            .and_then(|s| s.template.spec.as_mut());
+        let unset = defaults.requests.is_none() && defaults.limits.is_none();
+        if let Some(pod) = pod {
+            if pod.service_account_name.is_none() {
+                pod.service_account_name = service_account_name;
+            }
+            if pod.affinity.is_none() {
+                pod.affinity = affinity;
+            }
+            if !node_selector.is_empty() {
+                let selector = pod.node_selector.get_or_insert_default();
+                for (key, value) in node_selector {
+                    selector.entry(key).or_insert(value);
+                }
+            }
+            if !tolerations.is_empty() {
+                let existing = pod.tolerations.get_or_insert_default();
+                for toleration in tolerations {
+                    if !existing.contains(&toleration) {
+                        existing.push(toleration);
+                    }
+                }
+            }
+            if !image_pull_secrets.is_empty() {
+                let secrets = pod.image_pull_secrets.get_or_insert_default();
+                for name in image_pull_secrets {
+                    if !secrets.iter().any(|s| s.name == name) {
+                        secrets.push(LocalObjectReference { name });
+                    }
+                }
+            }
+            if !volumes.is_empty() {
+                let existing = pod.volumes.get_or_insert_default();
+                for volume in volumes {
+                    if !existing.iter().any(|v| v.name == volume.name) {
+                        existing.push(volume);
+                    }
+                }
+            }
+            let containers = pod
+                .containers
+                .iter_mut()
+                .chain(pod.init_containers.iter_mut().flatten());
+            for container in containers {
+                if !unset {
+                    let resources = container.resources.get_or_insert_default();
+                    for (set, defaults) in [
+                        (&mut resources.requests, &defaults.requests),
+                        (&mut resources.limits, &defaults.limits),
+                    ] {
+                        for (name, quantity) in defaults.iter().flatten() {
+                            set.get_or_insert_default()
+                                .entry(name.clone())
+                                .or_insert_with(|| quantity.clone());
+                        }
+                    }
+                }
+                if !env.is_empty() {
+                    let vars = container.env.get_or_insert_default();
+                    for var in &env {
+                        if !vars.iter().any(|v| v.name == var.name) {
+                            vars.push(var.clone());
+                        }
+                    }
+                }
+                if !env_from.is_empty() {
+                    let sources = container.env_from.get_or_insert_default();
+                    for source in &env_from {
+                        if !sources.contains(source) {
+                            sources.push(source.clone());
+                        }
+                    }
+                }
+                if !volume_mounts.is_empty() {
+                    let mounts = container.volume_mounts.get_or_insert_default();
+                    for mount in &volume_mounts {
+                        if !mounts.iter().any(|m| m.mount_path == mount.mount_path) {
+                            mounts.push(mount.clone());
+                        }
+                    }
+                }
+            }
+        }
         CronJob {
-            metadata: self.metadata,
-            spec: Some(self.spec),
+            metadata,
+            spec: Some(spec),
             ..Default::default()
         }
     }
@@ -0,0 +1,168 @@
+use chrono::{DateTime, Local};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::{Condition, Time};
+use kube::CustomResource;
+use schemars::JsonSchema;
+use schemars::schema::{Schema, SchemaObject};
+use serde::{Deserialize, Serialize};
+
+use super::{FireCondition, HasConditions, IntoTime, TargetRef};
+use crate::schedule::{Schedule, Window};
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, JsonSchema, PartialEq, Eq)]
+pub enum ScheduledPatchPhase {
+    #[default]
+    #[serde(rename = "Pending")]
+    Pending,
+    #[serde(rename = "Running")]
+    Running,
+    #[serde(rename = "InvalidSchedule")]
+    InvalidSchedule,
+    #[serde(rename = "EndBeforeStart")]
+    EndBeforeStart,
+    #[serde(rename = "Failed")]
+    Failed,
+    #[serde(rename = "Completed")]
+    Completed,
+    #[serde(rename = "Unknown")]
+    Unknown,
+}
+
+impl ScheduledPatchPhase {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ScheduledPatchPhase::Pending => "Pending",
+            ScheduledPatchPhase::Running => "Running",
+            ScheduledPatchPhase::InvalidSchedule => "InvalidSchedule",
+            ScheduledPatchPhase::EndBeforeStart => "EndBeforeStart",
+            ScheduledPatchPhase::Failed => "Failed",
+            ScheduledPatchPhase::Completed => "Completed",
+            ScheduledPatchPhase::Unknown => "Unknown",
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default, JsonSchema, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledPatchStatus {
+    pub phase: ScheduledPatchPhase,
+    pub message: Option<String>,
+    pub last_update_time: Option<Time>,
+    /// The last time the patch was applied to the target.
+    pub last_schedule_time: Option<Time>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub conditions: Vec<Condition>,
+}
+
+impl HasConditions for ScheduledPatchStatus {
+    fn conditions(&self) -> &[Condition] {
+        &self.conditions
+    }
+
+    fn conditions_mut(&mut self) -> &mut Vec<Condition> {
+        &mut self.conditions
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, JsonSchema, PartialEq, Eq)]
+pub enum PatchType {
+    #[default]
+    #[serde(rename = "Merge")]
+    Merge,
+    #[serde(rename = "Strategic")]
+    Strategic,
+}
+
+#[derive(Debug, Serialize, Deserialize, CustomResource, Default, Clone, JsonSchema)]
+#[kube(
+    group = "batch.divinerapier.io",
+    version = "v1alpha1",
+    kind = "ScheduledPatch",
+    namespaced,
+    printcolumn = r#"{"name":"Schedule", "type":"string", "description":"schedule of the patch", "jsonPath":".spec.schedule"}"#,
+    printcolumn = r#"{"name":"Target", "type":"string", "description":"name of the patched resource", "jsonPath":".spec.target.name"}"#,
+    printcolumn = r#"{"name":"Phase", "type":"string", "description":"phase of the patch", "jsonPath":".status.phase"}"#,
+    printcolumn = r#"{"name":"LastSchedule", "type":"string", "description":"last time the patch was applied", "jsonPath":".status.lastScheduleTime"}"#,
+    status = "ScheduledPatchStatus",
+    shortname = "spatch"
+)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledPatchSpec {
+    /// Cron expression controlling when the patch is applied.
+    pub schedule: String,
+
+    /// The patch is not applied before this time.
+    pub start_time: Option<Time>,
+
+    /// The patch is not applied after this time.
+    pub end_time: Option<Time>,
+
+    /// The resource the patch is applied to.
+    pub target: TargetRef,
+
+    /// How `patch` is interpreted by the API server.
+    #[serde(default)]
+    pub patch_type: PatchType,
+
+    /// The patch body applied to the target at each scheduled time.
+    #[schemars(schema_with = "preserve_unknown_fields")]
+    pub patch: serde_json::Value,
+
+    /// Skips a scheduled patch when the metric check does not hold.
+    pub condition: Option<FireCondition>,
+}
+
+impl ScheduledPatchSpec {
+    pub fn new<S, E>(
+        schedule: &str,
+        start_time: S,
+        end_time: E,
+        target: TargetRef,
+        patch: serde_json::Value,
+    ) -> Result<Self, chrono::ParseError>
+    where
+        S: IntoTime,
+        E: IntoTime,
+    {
+        Ok(Self {
+            schedule: schedule.to_string(),
+            start_time: start_time.into_time()?,
+            end_time: end_time.into_time()?,
+            target,
+            patch_type: PatchType::Merge,
+            patch,
+            condition: None,
+        })
+    }
+}
+
+impl ScheduledPatch {
+    pub fn schedule(&self) -> Result<Schedule, crate::Error> {
+        Schedule::parse(&self.spec.schedule)
+    }
+
+    pub fn window(&self) -> Window {
+        Window::from_times(self.spec.start_time.as_ref(), self.spec.end_time.as_ref())
+    }
+
+    pub fn last_schedule_time(&self) -> Option<DateTime<Local>> {
+        let time = self.status.as_ref()?.last_schedule_time.as_ref()?;
+        Some(time.0.with_timezone(&Local))
+    }
+
+    pub fn patch(&self) -> kube::api::Patch<serde_json::Value> {
+        match self.spec.patch_type {
+            PatchType::Merge => kube::api::Patch::Merge(self.spec.patch.clone()),
+            PatchType::Strategic => kube::api::Patch::Strategic(self.spec.patch.clone()),
+        }
+    }
+}
+
+pub(crate) fn preserve_unknown_fields(_: &mut schemars::r#gen::SchemaGenerator) -> Schema {
+    let mut schema = SchemaObject::default();
+    schema.instance_type = Some(schemars::schema::InstanceType::Object.into());
+    schema.extensions.insert(
+        "x-kubernetes-preserve-unknown-fields".to_string(),
+        true.into(),
+    );
+    Schema::Object(schema)
+}
@@ -0,0 +1,120 @@
+use chrono::{DateTime, Local};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::{Condition, Time};
+use kube::CustomResource;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::{HasConditions, TargetRef};
+use crate::schedule::Schedule;
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, JsonSchema, PartialEq, Eq)]
+pub enum ScheduledSuspendPhase {
+    #[default]
+    #[serde(rename = "Pending")]
+    Pending,
+    #[serde(rename = "Active")]
+    Active,
+    #[serde(rename = "Suspended")]
+    Suspended,
+    #[serde(rename = "InvalidSchedule")]
+    InvalidSchedule,
+    #[serde(rename = "Failed")]
+    Failed,
+    #[serde(rename = "Unknown")]
+    Unknown,
+}
+
+impl ScheduledSuspendPhase {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ScheduledSuspendPhase::Pending => "Pending",
+            ScheduledSuspendPhase::Active => "Active",
+            ScheduledSuspendPhase::Suspended => "Suspended",
+            ScheduledSuspendPhase::InvalidSchedule => "InvalidSchedule",
+            ScheduledSuspendPhase::Failed => "Failed",
+            ScheduledSuspendPhase::Unknown => "Unknown",
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default, JsonSchema, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledSuspendStatus {
+    pub phase: ScheduledSuspendPhase,
+    pub message: Option<String>,
+    pub last_update_time: Option<Time>,
+    /// The last time the targets were suspended or resumed.
+    pub last_transition_time: Option<Time>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub conditions: Vec<Condition>,
+}
+
+impl HasConditions for ScheduledSuspendStatus {
+    fn conditions(&self) -> &[Condition] {
+        &self.conditions
+    }
+
+    fn conditions_mut(&mut self) -> &mut Vec<Condition> {
+        &mut self.conditions
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, CustomResource, Default, Clone, JsonSchema)]
+#[kube(
+    group = "batch.divinerapier.io",
+    version = "v1alpha1",
+    kind = "ScheduledSuspend",
+    namespaced,
+    printcolumn = r#"{"name":"Suspend", "type":"string", "description":"schedule suspending the targets", "jsonPath":".spec.suspendSchedule"}"#,
+    printcolumn = r#"{"name":"Resume", "type":"string", "description":"schedule resuming the targets", "jsonPath":".spec.resumeSchedule"}"#,
+    printcolumn = r#"{"name":"Phase", "type":"string", "description":"phase of the targets", "jsonPath":".status.phase"}"#,
+    status = "ScheduledSuspendStatus",
+    shortname = "ssus"
+)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledSuspendSpec {
+    /// Cron expression at which the targets are suspended.
+    pub suspend_schedule: String,
+
+    /// Cron expression at which the targets are resumed.
+    pub resume_schedule: String,
+
+    /// Resources toggled by this object. Each target must expose a boolean
+    /// `spec.suspend` field, as CronJobs, Jobs, Flux Kustomizations and
+    /// HelmReleases, and Argo CronWorkflows do.
+    pub targets: Vec<TargetRef>,
+}
+
+impl ScheduledSuspendSpec {
+    pub fn new(suspend_schedule: &str, resume_schedule: &str, targets: Vec<TargetRef>) -> Self {
+        Self {
+            suspend_schedule: suspend_schedule.to_string(),
+            resume_schedule: resume_schedule.to_string(),
+            targets,
+        }
+    }
+}
+
+impl ScheduledSuspend {
+    pub fn suspend_schedule(&self) -> Result<Schedule, crate::Error> {
+        Schedule::parse(&self.spec.suspend_schedule)
+    }
+
+    pub fn resume_schedule(&self) -> Result<Schedule, crate::Error> {
+        Schedule::parse(&self.spec.resume_schedule)
+    }
+
+    pub fn last_transition_time(&self) -> Option<DateTime<Local>> {
+        let time = self.status.as_ref()?.last_transition_time.as_ref()?;
+        Some(time.0.with_timezone(&Local))
+    }
+
+    pub fn is_suspended(&self) -> bool {
+        self.status.as_ref().map(|s| s.phase) == Some(ScheduledSuspendPhase::Suspended)
+    }
+
+    /// The merge patch toggling `spec.suspend` on every target.
+    pub fn suspend_patch(suspend: bool) -> kube::api::Patch<serde_json::Value> {
+        kube::api::Patch::Merge(serde_json::json!({ "spec": { "suspend": suspend } }))
+    }
+}
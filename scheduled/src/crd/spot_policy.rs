@@ -0,0 +1,118 @@
+use std::collections::BTreeMap;
+
+use k8s_openapi::api::batch::v1::{
+    JobTemplateSpec, PodFailurePolicy, PodFailurePolicyOnPodConditionsPattern, PodFailurePolicyRule,
+};
+use k8s_openapi::api::core::v1::{
+    Affinity, NodeAffinity, NodeSelectorRequirement, NodeSelectorTerm, PreferredSchedulingTerm,
+    Toleration,
+};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Label on Jobs whose preemptions are retried, holding how many times.
+/// See [`crate::preemption::run`].
+pub const PREEMPTION_RETRIES_LABEL: &str = "divinerapier.io/preemption-retries";
+
+/// Taint keys put on spot and preemptible nodes by the major providers.
+const SPOT_TAINTS: [&str; 3] = [
+    "cloud.google.com/gke-spot",
+    "cloud.google.com/gke-preemptible",
+    "kubernetes.azure.com/scalesetpriority",
+];
+
+/// Node labels, with their values, marking spot capacity.
+const SPOT_LABELS: [(&str, &str); 4] = [
+    ("karpenter.sh/capacity-type", "spot"),
+    ("eks.amazonaws.com/capacityType", "SPOT"),
+    ("cloud.google.com/gke-spot", "true"),
+    ("kubernetes.azure.com/scalesetpriority", "spot"),
+];
+
+/// Whether runs may use spot or preemptible nodes, and how preemptions are
+/// handled.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SpotPolicy {
+    /// Tolerates the spot taints of GKE and AKS and prefers nodes labelled
+    /// as spot capacity.
+    #[serde(default)]
+    pub allowed: bool,
+    /// Pods lost to node preemption are replaced without counting against
+    /// `backoffLimit`, up to this many times per run. Requires
+    /// `restartPolicy: Never`.
+    #[serde(default)]
+    pub retry_on_preemption: u32,
+}
+
+impl SpotPolicy {
+    pub fn apply(&self, template: &mut JobTemplateSpec) {
+        let Some(job) = template.spec.as_mut() else {
+            return;
+        };
+        let Some(pod) = job.template.spec.as_mut() else {
+            return;
+        };
+
+        if self.allowed {
+            let tolerations = pod.tolerations.get_or_insert_with(Vec::new);
+            for key in SPOT_TAINTS {
+                tolerations.push(Toleration {
+                    key: Some(key.to_string()),
+                    operator: Some("Exists".to_string()),
+                    effect: Some("NoSchedule".to_string()),
+                    ..Default::default()
+                });
+            }
+            let preferred = pod
+                .affinity
+                .get_or_insert_with(Affinity::default)
+                .node_affinity
+                .get_or_insert_with(NodeAffinity::default)
+                .preferred_during_scheduling_ignored_during_execution
+                .get_or_insert_with(Vec::new);
+            for (key, value) in SPOT_LABELS {
+                preferred.push(PreferredSchedulingTerm {
+                    weight: 50,
+                    preference: NodeSelectorTerm {
+                        match_expressions: Some(vec![NodeSelectorRequirement {
+                            key: key.to_string(),
+                            operator: "In".to_string(),
+                            values: Some(vec![value.to_string()]),
+                        }]),
+                        match_fields: None,
+                    },
+                });
+            }
+        }
+
+        // The API server only accepts pod failure policies with `Never`.
+        if self.retry_on_preemption > 0 && pod.restart_policy.as_deref() == Some("Never") {
+            let rules = &mut job
+                .pod_failure_policy
+                .get_or_insert_with(PodFailurePolicy::default)
+                .rules;
+            rules.insert(
+                0,
+                PodFailurePolicyRule {
+                    action: "Ignore".to_string(),
+                    on_pod_conditions: Some(vec![PodFailurePolicyOnPodConditionsPattern {
+                        type_: "DisruptionTarget".to_string(),
+                        status: "True".to_string(),
+                    }]),
+                    on_exit_codes: None,
+                },
+            );
+            template
+                .metadata
+                .get_or_insert_with(ObjectMeta::default)
+                .labels
+                .get_or_insert_with(BTreeMap::new)
+                .insert(
+                    PREEMPTION_RETRIES_LABEL.to_string(),
+                    self.retry_on_preemption.to_string(),
+                );
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use std::str::FromStr as _;
+
+use kube::core::{GroupVersion, GroupVersionKind};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Annotation of a namespace listing the namespaces, separated by commas, whose
+/// resources may target objects in it, or `*` for every namespace.
+pub const ALLOW_TARGETS_FROM_ANNOTATION: &str = "divinerapier.io/allow-targets-from";
+
+/// A reference to a resource, in the namespace of the referencing object
+/// unless `namespace` names another.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TargetRef {
+    /// API version of the target, e.g. `apps/v1`.
+    pub api_version: String,
+    /// Kind of the target, e.g. `Deployment`.
+    pub kind: String,
+    /// Name of the target.
+    pub name: String,
+    /// Namespace of the target. Another namespace than the referencing
+    /// object's requires `CROSS_NAMESPACE_TARGETS` on the controller and the
+    /// target namespace to list the referencing one in its
+    /// `divinerapier.io/allow-targets-from` annotation.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+}
+
+impl TargetRef {
+    pub fn gvk(&self) -> Result<GroupVersionKind, crate::Error> {
+        let gv = GroupVersion::from_str(&self.api_version)
+            .map_err(|e| crate::Error::InvalidTarget(format!("{}: {}", self.api_version, e)))?;
+        Ok(gv.with_kind(&self.kind))
+    }
+
+    /// Whether the namespace annotated with `allowed`, the value of its
+    /// [`ALLOW_TARGETS_FROM_ANNOTATION`], accepts targets from `from`.
+    pub fn allowed_from(allowed: &str, from: &str) -> bool {
+        allowed
+            .split(',')
+            .map(str::trim)
+            .any(|namespace| namespace == "*" || namespace == from)
+    }
+}
@@ -59,3 +59,9 @@ impl IntoTime for Option<Time> {
         }
     }
 }
+
+impl IntoTime for DateTime<chrono::Utc> {
+    fn into_time(self) -> Result<Option<Time>, chrono::ParseError> {
+        Ok(Some(Time(self)))
+    }
+}
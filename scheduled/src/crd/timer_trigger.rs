@@ -0,0 +1,138 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Local};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::{Condition, Time};
+use kube::CustomResource;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::{FireCondition, HasConditions};
+use crate::schedule::{Schedule, Window};
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, JsonSchema, PartialEq, Eq)]
+pub enum TimerTriggerPhase {
+    #[default]
+    #[serde(rename = "Pending")]
+    Pending,
+    #[serde(rename = "Running")]
+    Running,
+    #[serde(rename = "InvalidSchedule")]
+    InvalidSchedule,
+    #[serde(rename = "EndBeforeStart")]
+    EndBeforeStart,
+    #[serde(rename = "Failed")]
+    Failed,
+    #[serde(rename = "Completed")]
+    Completed,
+    #[serde(rename = "Unknown")]
+    Unknown,
+}
+
+impl TimerTriggerPhase {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TimerTriggerPhase::Pending => "Pending",
+            TimerTriggerPhase::Running => "Running",
+            TimerTriggerPhase::InvalidSchedule => "InvalidSchedule",
+            TimerTriggerPhase::EndBeforeStart => "EndBeforeStart",
+            TimerTriggerPhase::Failed => "Failed",
+            TimerTriggerPhase::Completed => "Completed",
+            TimerTriggerPhase::Unknown => "Unknown",
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default, JsonSchema, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TimerTriggerStatus {
+    pub phase: TimerTriggerPhase,
+    pub message: Option<String>,
+    pub last_update_time: Option<Time>,
+    /// The last time the trigger fired.
+    pub last_schedule_time: Option<Time>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub conditions: Vec<Condition>,
+}
+
+impl HasConditions for TimerTriggerStatus {
+    fn conditions(&self) -> &[Condition] {
+        &self.conditions
+    }
+
+    fn conditions_mut(&mut self) -> &mut Vec<Condition> {
+        &mut self.conditions
+    }
+}
+
+/// An HTTP endpoint notified with a JSON `POST` at each scheduled time.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Webhook {
+    pub url: String,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+}
+
+/// The body posted to a [`Webhook`].
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TimerTriggerPayload {
+    pub name: String,
+    pub namespace: String,
+    pub schedule: String,
+    pub scheduled_time: DateTime<Local>,
+    pub fired_time: DateTime<Local>,
+}
+
+fn default_emit_event() -> bool {
+    true
+}
+
+#[derive(Debug, Serialize, Deserialize, CustomResource, Default, Clone, JsonSchema)]
+#[kube(
+    group = "batch.divinerapier.io",
+    version = "v1alpha1",
+    kind = "TimerTrigger",
+    namespaced,
+    printcolumn = r#"{"name":"Schedule", "type":"string", "description":"schedule of the trigger", "jsonPath":".spec.schedule"}"#,
+    printcolumn = r#"{"name":"Phase", "type":"string", "description":"phase of the trigger", "jsonPath":".status.phase"}"#,
+    printcolumn = r#"{"name":"LastSchedule", "type":"string", "description":"last time the trigger fired", "jsonPath":".status.lastScheduleTime"}"#,
+    status = "TimerTriggerStatus",
+    shortname = "tt"
+)]
+#[serde(rename_all = "camelCase")]
+pub struct TimerTriggerSpec {
+    /// Cron expression controlling when the trigger fires.
+    pub schedule: String,
+
+    /// The trigger does not fire before this time.
+    pub start_time: Option<Time>,
+
+    /// The trigger does not fire after this time.
+    pub end_time: Option<Time>,
+
+    /// Emits a `Fired` Kubernetes Event on this object at each scheduled time.
+    #[serde(default = "default_emit_event")]
+    pub emit_event: bool,
+
+    /// Optional webhook notified at each scheduled time.
+    pub webhook: Option<Webhook>,
+
+    /// Skips a scheduled fire when the metric check does not hold.
+    pub condition: Option<FireCondition>,
+}
+
+impl TimerTrigger {
+    pub fn schedule(&self) -> Result<Schedule, crate::Error> {
+        Schedule::parse(&self.spec.schedule)
+    }
+
+    pub fn window(&self) -> Window {
+        Window::from_times(self.spec.start_time.as_ref(), self.spec.end_time.as_ref())
+    }
+
+    pub fn last_schedule_time(&self) -> Option<DateTime<Local>> {
+        let time = self.status.as_ref()?.last_schedule_time.as_ref()?;
+        Some(time.0.with_timezone(&Local))
+    }
+}
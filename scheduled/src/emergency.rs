@@ -0,0 +1,85 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use futures::StreamExt as _;
+use k8s_openapi::api::core::v1::ConfigMap;
+use kube::Api;
+use kube::runtime::watcher::{self, Event};
+
+use crate::Context;
+
+/// Key of the control ConfigMap that halts all mutating operations when `"true"`.
+pub const EMERGENCY_STOP_KEY: &str = "emergencyStop";
+
+/// Cluster-wide kill switch. While engaged, the controller keeps its watches,
+/// heartbeat and metrics running but performs no writes.
+#[derive(Default)]
+pub struct EmergencyStop {
+    engaged: AtomicBool,
+}
+
+impl EmergencyStop {
+    pub fn new(engaged: bool) -> Self {
+        Self {
+            engaged: AtomicBool::new(engaged),
+        }
+    }
+
+    pub fn engaged(&self) -> bool {
+        self.engaged.load(Ordering::Relaxed)
+    }
+
+    /// Returns whether the state changed.
+    pub fn set(&self, engaged: bool) -> bool {
+        self.engaged.swap(engaged, Ordering::Relaxed) != engaged
+    }
+}
+
+/// Watches the control ConfigMap and flips the kill switch as its
+/// `emergencyStop` key changes. Without the ConfigMap, `EMERGENCY_STOP`
+/// decides.
+pub async fn run(ctx: Arc<Context>) {
+    let config = ctx.config().clone();
+    let api = Api::<ConfigMap>::namespaced((**ctx).clone(), &config.namespace);
+    let watcher_config =
+        watcher::Config::default().fields(&format!("metadata.name={}", config.control_configmap));
+
+    set(&ctx, config.emergency_stop);
+    let mut seen = false;
+    let mut events = watcher::watcher(api, watcher_config).boxed();
+    while let Some(event) = events.next().await {
+        match event {
+            Ok(Event::Init) => seen = false,
+            Ok(Event::InitApply(cm) | Event::Apply(cm)) => {
+                seen = true;
+                set(&ctx, engaged(&cm).unwrap_or(config.emergency_stop));
+            }
+            Ok(Event::InitDone) if !seen => set(&ctx, config.emergency_stop),
+            Ok(Event::InitDone) => {}
+            Ok(Event::Delete(_)) => {
+                seen = false;
+                set(&ctx, config.emergency_stop);
+            }
+            Err(e) => {
+                tracing::warn!(error = ?e, configmap = config.control_configmap, "Failed to watch control ConfigMap");
+            }
+        }
+    }
+}
+
+fn engaged(cm: &ConfigMap) -> Option<bool> {
+    let value = cm.data.as_ref()?.get(EMERGENCY_STOP_KEY)?;
+    Some(value.trim().eq_ignore_ascii_case("true"))
+}
+
+fn set(ctx: &Context, engaged: bool) {
+    ctx.metrics().emergency_stop.set(engaged as i64);
+    if !ctx.emergency_stop().set(engaged) {
+        return;
+    }
+    if engaged {
+        tracing::warn!("Emergency stop engaged, halting all mutating operations");
+    } else {
+        tracing::info!("Emergency stop released, resuming operations");
+    }
+}
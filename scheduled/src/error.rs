@@ -1,5 +1,7 @@
 use chrono::{DateTime, Local};
 
+use crate::reason::Reason;
+
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
     #[error("not found")]
@@ -26,18 +28,132 @@ pub enum Error {
     #[error("k8s error: {0}")]
     Kube(#[from] kube::Error),
 
+    #[error("http error: {0}")]
+    Http(#[from] reqwest::Error),
+
     #[error("serialization error: {0}")]
     Serialization(#[from] serde_json::Error),
 
+    #[error("protobuf decode error: {0}")]
+    Protobuf(#[from] prost::DecodeError),
+
     #[error("invalid concurrency policy")]
     InvalidConcurrencyPolicy,
 
     #[error("invalid failed jobs history limit")]
     InvalidFailedJobsHistoryLimit,
 
+    #[error("invalid successful jobs history limit")]
+    InvalidSuccessfulJobsHistoryLimit,
+
     #[error("cronjob spec not found")]
     CronjobSpecNotFound,
 
     #[error("invalid backoff limit")]
     InvalidBackoffLimit,
+
+    #[error("invalid schedule: {0}")]
+    InvalidSchedule(String),
+
+    #[error("invalid target: {0}")]
+    InvalidTarget(String),
+
+    #[error("invalid variants: {0}")]
+    InvalidVariants(String),
+
+    #[error("invalid owner: {0}")]
+    InvalidOwner(String),
+
+    #[error("condition evaluation failed: {0}")]
+    Condition(String),
+
+    #[error("namespace {0} is terminating")]
+    NamespaceTerminating(String),
+
+    #[error("emergency stop is engaged")]
+    EmergencyStop,
+
+    #[error("history sink error: {0}")]
+    History(String),
+
+    #[error("event bus error: {0}")]
+    Bus(String),
+
+    #[error("trigger forbidden: {0}")]
+    TriggerForbidden(String),
+
+    #[error("invalid template: {0}")]
+    InvalidTemplate(String),
+
+    #[error("queue consumer error: {0}")]
+    Consumer(String),
+
+    #[error("token rejected: {0}")]
+    TokenRejected(String),
+
+    #[error("usage query failed: {0}")]
+    Usage(String),
+
+    #[error("phase cannot move from {0} to {1}")]
+    InvalidTransition(String, String),
+
+    #[error("{0} has no UID to own its children")]
+    Unowned(String),
+}
+
+impl Error {
+    /// The reason reported for this error in events and conditions.
+    pub fn reason(&self) -> Reason {
+        match self {
+            Error::InvalidStartTime
+            | Error::InvalidEndTime
+            | Error::EndBeforeStart
+            | Error::DurationTooShort(..) => Reason::InvalidTimeRange,
+            Error::WaitFor(_) => Reason::WindowNotOpen,
+            Error::Expired(_) => Reason::Expired,
+            Error::Kube(e) => Reason::of_kube_error(e),
+            Error::Http(_)
+            | Error::Serialization(_)
+            | Error::Protobuf(_)
+            | Error::History(_)
+            | Error::Bus(_)
+            | Error::Consumer(_)
+            | Error::Usage(_)
+            | Error::TokenRejected(_)
+            | Error::Unowned(_) => Reason::ApiError,
+            Error::InvalidConcurrencyPolicy
+            | Error::InvalidFailedJobsHistoryLimit
+            | Error::InvalidSuccessfulJobsHistoryLimit
+            | Error::CronjobSpecNotFound
+            | Error::InvalidBackoffLimit
+            | Error::InvalidVariants(_)
+            | Error::InvalidOwner(_)
+            | Error::InvalidTemplate(_)
+            | Error::InvalidTransition(..) => Reason::InvalidSpec,
+            Error::InvalidSchedule(_) => Reason::InvalidSchedule,
+            Error::NotFound | Error::InvalidTarget(_) | Error::TriggerForbidden(_) => {
+                Reason::InvalidTarget
+            }
+            Error::Condition(_) => Reason::ConditionFailed,
+            Error::NamespaceTerminating(_) => Reason::NamespaceTerminating,
+            Error::EmergencyStop => Reason::EmergencyStop,
+        }
+    }
+
+    /// Whether the error is a failure talking to the API server or another
+    /// endpoint, as opposed to a validation result or a scheduling outcome.
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            Error::Kube(_)
+                | Error::Http(_)
+                | Error::Serialization(_)
+                | Error::Protobuf(_)
+                | Error::Condition(_)
+                | Error::History(_)
+                | Error::Bus(_)
+                | Error::Consumer(_)
+                | Error::Usage(_)
+        )
+    }
 }
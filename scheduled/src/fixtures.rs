@@ -0,0 +1,285 @@
+//! Constructors for resources in controller tests, such as
+//! `ScheduledCronJob::test("report").schedule("*/5 * * * *").build()`.
+//!
+//! Fixtures start out valid: namespaced in `default`, with a UID and a job
+//! template running one [`IMAGE`] container with `restartPolicy: OnFailure`,
+//! so only what a test is about needs to be set.
+//!
+//! # Panics
+//!
+//! [`ScheduledCronJobFixture::window`] and [`DelayedJobFixture::start_time`]
+//! panic on times that do not parse, failing the test.
+
+use k8s_openapi::api::batch::v1::{CronJobSpec, JobSpec, JobTemplateSpec};
+use k8s_openapi::api::core::v1::{Container, PodSpec, PodTemplateSpec};
+use kube::api::ObjectMeta;
+
+use crate::crd::{
+    BlackoutWindow, CronJobTemplate, DelayedJob, DelayedJobPhase, DelayedJobSpec, DelayedJobStatus,
+    IntoTime, MissedRunPolicy, RetryPolicy, ScheduledCronJob, ScheduledCronJobPhase,
+    ScheduledCronJobSpec, ScheduledCronJobStatus, Variant,
+};
+
+/// Namespace of fixtures unless set otherwise.
+pub const NAMESPACE: &str = "default";
+
+/// Image of the fixtures' container.
+pub const IMAGE: &str = "busybox:1.36";
+
+impl ScheduledCronJob {
+    /// A ScheduledCronJob named `name` firing hourly, without a window.
+    pub fn test<S: Into<String>>(name: S) -> ScheduledCronJobFixture {
+        ScheduledCronJobFixture::new(name)
+    }
+}
+
+impl DelayedJob {
+    /// A DelayedJob named `name` without a start time.
+    pub fn test<S: Into<String>>(name: S) -> DelayedJobFixture {
+        DelayedJobFixture::new(name)
+    }
+}
+
+/// Builds a [`ScheduledCronJob`], see [`ScheduledCronJob::test`].
+#[derive(Debug, Clone)]
+pub struct ScheduledCronJobFixture {
+    metadata: ObjectMeta,
+    spec: ScheduledCronJobSpec,
+    status: Option<ScheduledCronJobStatus>,
+}
+
+impl ScheduledCronJobFixture {
+    fn new<S: Into<String>>(name: S) -> Self {
+        let spec = CronJobSpec {
+            schedule: "0 * * * *".to_string(),
+            concurrency_policy: Some("Allow".to_string()),
+            successful_jobs_history_limit: Some(3),
+            failed_jobs_history_limit: Some(1),
+            job_template: JobTemplateSpec {
+                spec: Some(job_spec()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        Self {
+            metadata: metadata(name.into()),
+            spec: ScheduledCronJobSpec::new(None::<&str>, None::<&str>, spec)
+                .expect("no times to parse"),
+            status: None,
+        }
+    }
+
+    pub fn namespace<S: Into<String>>(mut self, namespace: S) -> Self {
+        self.metadata.namespace = Some(namespace.into());
+        self
+    }
+
+    pub fn uid<S: Into<String>>(mut self, uid: S) -> Self {
+        self.metadata.uid = Some(uid.into());
+        self
+    }
+
+    pub fn label<K: Into<String>, V: Into<String>>(mut self, key: K, value: V) -> Self {
+        self.metadata
+            .labels
+            .get_or_insert_default()
+            .insert(key.into(), value.into());
+        self
+    }
+
+    pub fn annotation<K: Into<String>, V: Into<String>>(mut self, key: K, value: V) -> Self {
+        self.metadata
+            .annotations
+            .get_or_insert_default()
+            .insert(key.into(), value.into());
+        self
+    }
+
+    /// Sets `spec.spec.schedule`, which may also be an `@every` interval.
+    pub fn schedule<S: Into<String>>(mut self, schedule: S) -> Self {
+        self.spec.spec.schedule = schedule.into();
+        self
+    }
+
+    /// Sets `startTime` and `endTime`, either of which may be `None::<&str>`.
+    pub fn window<S: IntoTime, E: IntoTime>(mut self, start: S, end: E) -> Self {
+        self.spec = self.spec.with_window(start, end).expect("invalid window");
+        self
+    }
+
+    pub fn time_zone<S: Into<String>>(mut self, time_zone: S) -> Self {
+        self.spec.time_zone = Some(time_zone.into());
+        self
+    }
+
+    pub fn suspend(mut self, suspend: bool) -> Self {
+        self.spec.suspend = suspend;
+        self
+    }
+
+    /// Adds a variant without overrides.
+    pub fn variant<S: Into<String>>(mut self, name: S) -> Self {
+        self.spec.variants.push(Variant {
+            name: name.into(),
+            overrides: Default::default(),
+        });
+        self
+    }
+
+    /// Adds an entry to `spec.schedules`.
+    pub fn also_at<S: Into<String>>(mut self, schedule: S) -> Self {
+        self.spec.schedules.push(schedule.into());
+        self
+    }
+
+    pub fn recurrence_rule<S: Into<String>>(mut self, rule: S) -> Self {
+        self.spec.recurrence_rule = Some(rule.into());
+        self
+    }
+
+    pub fn blackout(mut self, window: BlackoutWindow) -> Self {
+        self.spec.blackout_windows.push(window);
+        self
+    }
+
+    pub fn concurrency_policy<S: Into<String>>(mut self, policy: S) -> Self {
+        self.spec.spec.concurrency_policy = Some(policy.into());
+        self
+    }
+
+    /// Changes the spec beyond what the other methods cover.
+    pub fn with_spec(mut self, f: impl FnOnce(&mut ScheduledCronJobSpec)) -> Self {
+        f(&mut self.spec);
+        self
+    }
+
+    /// Moves `spec.spec` and `childMetadata` into `cronJobTemplate`. Later
+    /// calls setting the schedule or concurrency policy are ignored.
+    pub fn templated(mut self) -> Self {
+        self.spec.cron_job_template = Some(CronJobTemplate {
+            metadata: self.spec.child_metadata.take().unwrap_or_default(),
+            spec: std::mem::take(&mut self.spec.spec),
+        });
+        self
+    }
+
+    /// Sets `status.phase`, as if the controller had reconciled it.
+    pub fn phase(mut self, phase: ScheduledCronJobPhase) -> Self {
+        self.status.get_or_insert_default().phase = phase;
+        self
+    }
+
+    pub fn build(self) -> ScheduledCronJob {
+        let mut job =
+            ScheduledCronJob::new(self.metadata.name.as_deref().unwrap_or_default(), self.spec);
+        job.metadata = self.metadata;
+        job.status = self.status;
+        job
+    }
+}
+
+/// Builds a [`DelayedJob`], see [`DelayedJob::test`].
+#[derive(Debug, Clone)]
+pub struct DelayedJobFixture {
+    metadata: ObjectMeta,
+    spec: DelayedJobSpec,
+    status: Option<DelayedJobStatus>,
+}
+
+impl DelayedJobFixture {
+    fn new<S: Into<String>>(name: S) -> Self {
+        Self {
+            metadata: metadata(name.into()),
+            spec: DelayedJobSpec::new(None::<&str>, job_spec()).expect("no time to parse"),
+            status: None,
+        }
+    }
+
+    pub fn namespace<S: Into<String>>(mut self, namespace: S) -> Self {
+        self.metadata.namespace = Some(namespace.into());
+        self
+    }
+
+    pub fn uid<S: Into<String>>(mut self, uid: S) -> Self {
+        self.metadata.uid = Some(uid.into());
+        self
+    }
+
+    pub fn start_time<T: IntoTime>(mut self, start_time: T) -> Self {
+        self.spec.start_time = start_time.into_time().expect("invalid start time");
+        self
+    }
+
+    /// Sets `runAfter`, such as `2h30m`, clearing `startTime`.
+    pub fn run_after<S: Into<String>>(mut self, delay: S) -> Self {
+        self.spec = self.spec.with_run_after(delay);
+        self
+    }
+
+    pub fn run_after_job<S: Into<String>>(mut self, name: S) -> Self {
+        self.spec = self.spec.with_run_after_job(name);
+        self
+    }
+
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.spec = self.spec.with_retry_policy(policy);
+        self
+    }
+
+    pub fn ttl_seconds_after_finished(mut self, seconds: i32) -> Self {
+        self.spec.ttl_seconds_after_finished = Some(seconds);
+        self
+    }
+
+    pub fn missed_run_policy(mut self, policy: MissedRunPolicy, deadline: Option<i64>) -> Self {
+        self.spec = self.spec.with_missed_run_policy(policy, deadline);
+        self
+    }
+
+    /// Changes the Job spec beyond what the other methods cover.
+    pub fn with_spec(mut self, f: impl FnOnce(&mut JobSpec)) -> Self {
+        f(&mut self.spec.spec);
+        self
+    }
+
+    /// Sets `status.phase`, as if the controller had reconciled it.
+    pub fn phase(mut self, phase: DelayedJobPhase) -> Self {
+        self.status.get_or_insert_default().phase = phase;
+        self
+    }
+
+    pub fn build(self) -> DelayedJob {
+        let mut job = DelayedJob::new(self.metadata.name.as_deref().unwrap_or_default(), self.spec);
+        job.metadata = self.metadata;
+        job.status = self.status;
+        job
+    }
+}
+
+fn metadata(name: String) -> ObjectMeta {
+    ObjectMeta {
+        uid: Some(format!("{name}-uid")),
+        name: Some(name),
+        namespace: Some(NAMESPACE.to_string()),
+        ..Default::default()
+    }
+}
+
+fn job_spec() -> JobSpec {
+    JobSpec {
+        backoff_limit: Some(0),
+        template: PodTemplateSpec {
+            spec: Some(PodSpec {
+                containers: vec![Container {
+                    name: "main".to_string(),
+                    image: Some(IMAGE.to_string()),
+                    ..Default::default()
+                }],
+                restart_policy: Some("OnFailure".to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
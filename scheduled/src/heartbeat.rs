@@ -0,0 +1,62 @@
+use std::sync::Arc;
+
+use chrono::Utc;
+use k8s_openapi::api::coordination::v1::{Lease, LeaseSpec};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::{MicroTime, ObjectMeta};
+use kube::Api;
+use kube::api::{Patch, PatchParams};
+
+use crate::Context;
+
+const FIELD_MANAGER: &str = "scheduled-cronjob-heartbeat";
+
+/// Renews the heartbeat Lease every `heartbeat_interval` until the future is
+/// dropped. External monitors can alert when `renewTime` goes stale, which
+/// distinguishes a dead controller from one with nothing due.
+pub async fn run(ctx: Arc<Context>) {
+    let config = ctx.config().clone();
+    let mut interval = tokio::time::interval(config.heartbeat_interval);
+    loop {
+        interval.tick().await;
+        match renew(&ctx).await {
+            Ok(()) => {
+                ctx.metrics()
+                    .controller_last_seen
+                    .set(Utc::now().timestamp() as f64);
+            }
+            Err(e) => {
+                tracing::warn!(
+                    lease = config.heartbeat_lease_name,
+                    namespace = config.namespace,
+                    error = ?e,
+                    "Failed to renew heartbeat lease"
+                );
+            }
+        }
+    }
+}
+
+async fn renew(ctx: &Context) -> Result<(), crate::Error> {
+    let config = ctx.config();
+    let api = Api::<Lease>::namespaced((**ctx).clone(), &config.namespace);
+    let lease = Lease {
+        metadata: ObjectMeta {
+            name: Some(config.heartbeat_lease_name.clone()),
+            namespace: Some(config.namespace.clone()),
+            ..Default::default()
+        },
+        spec: Some(LeaseSpec {
+            holder_identity: Some(config.identity.clone()),
+            lease_duration_seconds: Some((config.heartbeat_interval.as_secs() * 3) as i32),
+            renew_time: Some(MicroTime(Utc::now())),
+            ..Default::default()
+        }),
+    };
+    api.patch(
+        &config.heartbeat_lease_name,
+        &PatchParams::apply(FIELD_MANAGER).force(),
+        &Patch::Apply(&lease),
+    )
+    .await?;
+    Ok(())
+}
@@ -0,0 +1,226 @@
+use std::io::{self, Write};
+
+use k8s_openapi::api::batch::v1::Job;
+use kube::api::ListParams;
+use kube::{Api, Client, ResourceExt as _};
+
+use super::RunRecord;
+use crate::crd::{DelayedJobPhase, SCHEDULE_LABEL};
+
+/// Columns written by [`write_csv`], in order.
+pub const CSV_HEADER: [&str; 10] = [
+    "kind",
+    "namespace",
+    "name",
+    "uid",
+    "job",
+    "phase",
+    "message",
+    "startTime",
+    "completionTime",
+    "durationSeconds",
+];
+
+/// Finished runs whose Jobs still exist, in `namespace` or every namespace,
+/// optionally limited to Jobs matching the label `selector`. Only Jobs of
+/// ScheduledCronJobs and DelayedJobs are included; their phase is
+/// `Completed` or `Failed`.
+pub async fn from_cluster(
+    client: Client,
+    namespace: Option<&str>,
+    selector: Option<&str>,
+) -> Result<Vec<RunRecord>, crate::Error> {
+    let api = match namespace {
+        Some(namespace) => Api::<Job>::namespaced(client, namespace),
+        None => Api::<Job>::all(client),
+    };
+    let mut params = ListParams::default();
+    if let Some(selector) = selector {
+        params = params.labels(selector);
+    }
+    let mut records: Vec<RunRecord> = api.list(&params).await?.iter().filter_map(record).collect();
+    records.sort_by_key(|r| r.completion_time);
+    Ok(records)
+}
+
+fn record(job: &Job) -> Option<RunRecord> {
+    // Pods of ScheduledCronJobs carry its label; the Jobs do not.
+    let schedule = job
+        .spec
+        .as_ref()
+        .and_then(|s| s.template.metadata.as_ref())
+        .and_then(|m| m.labels.as_ref())
+        .and_then(|l| l.get(SCHEDULE_LABEL));
+    let (kind, name, uid) = if let Some(schedule) = schedule {
+        ("ScheduledCronJob", schedule.clone(), None)
+    } else {
+        let owner = job
+            .owner_references()
+            .iter()
+            .find(|o| o.kind == "DelayedJob")?;
+        ("DelayedJob", owner.name.clone(), Some(owner.uid.clone()))
+    };
+    let status = job.status.as_ref()?;
+    let finished = status
+        .conditions
+        .as_ref()?
+        .iter()
+        .find(|c| (c.type_ == "Complete" || c.type_ == "Failed") && c.status == "True")?;
+    let phase = if finished.type_ == "Complete" {
+        DelayedJobPhase::Completed
+    } else {
+        DelayedJobPhase::Failed
+    };
+    Some(RunRecord {
+        kind: kind.to_string(),
+        namespace: job.namespace().unwrap_or_default(),
+        name,
+        uid,
+        job: job.name_any(),
+        phase: phase.as_str().to_string(),
+        message: finished.message.clone().unwrap_or_default(),
+        start_time: status.start_time.as_ref().map(|t| t.0),
+        completion_time: status
+            .completion_time
+            .as_ref()
+            .or(finished.last_transition_time.as_ref())?
+            .0,
+    })
+}
+
+/// Writes `records` as CSV with a [`CSV_HEADER`] row. Times are RFC 3339;
+/// `durationSeconds` is empty when the start time is unknown.
+pub fn write_csv(records: &[RunRecord], out: &mut impl Write) -> io::Result<()> {
+    writeln!(out, "{}", CSV_HEADER.join(","))?;
+    for record in records {
+        let duration = record
+            .start_time
+            .map(|start| (record.completion_time - start).num_seconds().to_string());
+        let fields = [
+            record.kind.as_str(),
+            &record.namespace,
+            &record.name,
+            record.uid.as_deref().unwrap_or_default(),
+            &record.job,
+            &record.phase,
+            &record.message,
+            &record
+                .start_time
+                .map(|t| t.to_rfc3339())
+                .unwrap_or_default(),
+            &record.completion_time.to_rfc3339(),
+            duration.as_deref().unwrap_or_default(),
+        ];
+        let row: Vec<_> = fields.into_iter().map(escape).collect();
+        writeln!(out, "{}", row.join(","))?;
+    }
+    Ok(())
+}
+
+/// Quotes `field` when it holds a comma, quote or line break, doubling the
+/// quotes, as RFC 4180 requires.
+fn escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Schema of [`write_parquet`], with the columns of [`CSV_HEADER`]. Times are
+/// milliseconds since the epoch.
+#[cfg(feature = "parquet")]
+const PARQUET_SCHEMA: &str = "
+message run {
+    required binary kind (STRING);
+    required binary namespace (STRING);
+    required binary name (STRING);
+    optional binary uid (STRING);
+    required binary job (STRING);
+    required binary phase (STRING);
+    required binary message (STRING);
+    optional int64 startTime (TIMESTAMP(MILLIS, true));
+    required int64 completionTime (TIMESTAMP(MILLIS, true));
+    optional int64 durationSeconds;
+}";
+
+/// Values of one Parquet column, `None` for nulls.
+#[cfg(feature = "parquet")]
+enum Column {
+    Text(Vec<Option<parquet::data_type::ByteArray>>),
+    Int(Vec<Option<i64>>),
+}
+
+/// Writes `records` as a Snappy-compressed Parquet file of one row group,
+/// with the columns of [`CSV_HEADER`].
+#[cfg(feature = "parquet")]
+pub fn write_parquet<W: Write + Send>(
+    records: &[RunRecord],
+    out: W,
+) -> Result<(), parquet::errors::ParquetError> {
+    use std::sync::Arc;
+
+    use parquet::basic::Compression;
+    use parquet::data_type::{ByteArray, ByteArrayType, Int64Type};
+    use parquet::file::properties::WriterProperties;
+    use parquet::file::writer::SerializedFileWriter;
+    use parquet::schema::parser::parse_message_type;
+
+    let text = |field: fn(&RunRecord) -> Option<&str>| {
+        Column::Text(
+            records
+                .iter()
+                .map(|r| field(r).map(ByteArray::from))
+                .collect(),
+        )
+    };
+    let int =
+        |field: fn(&RunRecord) -> Option<i64>| Column::Int(records.iter().map(field).collect());
+    let columns = [
+        text(|r| Some(&r.kind)),
+        text(|r| Some(&r.namespace)),
+        text(|r| Some(&r.name)),
+        text(|r| r.uid.as_deref()),
+        text(|r| Some(&r.job)),
+        text(|r| Some(&r.phase)),
+        text(|r| Some(&r.message)),
+        int(|r| r.start_time.map(|t| t.timestamp_millis())),
+        int(|r| Some(r.completion_time.timestamp_millis())),
+        int(|r| {
+            r.start_time
+                .map(|start| (r.completion_time - start).num_seconds())
+        }),
+    ];
+
+    let schema = Arc::new(parse_message_type(PARQUET_SCHEMA)?);
+    let properties = WriterProperties::builder()
+        .set_compression(Compression::SNAPPY)
+        .build();
+    let mut writer = SerializedFileWriter::new(out, schema, Arc::new(properties))?;
+    let mut group = writer.next_row_group()?;
+    for column in columns {
+        let Some(mut out) = group.next_column()? else {
+            break;
+        };
+        // Definition levels mark the non-null values; required columns
+        // ignore them.
+        match column {
+            Column::Text(values) => {
+                let levels: Vec<i16> = values.iter().map(|v| v.is_some().into()).collect();
+                let values: Vec<_> = values.into_iter().flatten().collect();
+                out.typed::<ByteArrayType>()
+                    .write_batch(&values, Some(&levels), None)?;
+            }
+            Column::Int(values) => {
+                let levels: Vec<i16> = values.iter().map(|v| v.is_some().into()).collect();
+                let values: Vec<_> = values.into_iter().flatten().collect();
+                out.typed::<Int64Type>()
+                    .write_batch(&values, Some(&levels), None)?;
+            }
+        }
+        out.close()?;
+    }
+    group.close()?;
+    writer.close()?;
+    Ok(())
+}
@@ -0,0 +1,120 @@
+use futures::future::BoxFuture;
+use reqwest::Url;
+use tokio::sync::Mutex;
+use tokio_postgres::{Client, NoTls};
+
+use super::{HistorySink, RunRecord};
+
+/// Inserts each record into a `jsonb` table, created on first connection.
+///
+/// The connection is opened lazily and reopened after it drops. It is
+/// plaintext, so the database should be reached over a trusted network. The
+/// `table` query parameter of the sink URL overrides the default
+/// `scheduled_run_history`.
+pub struct PostgresSink {
+    config: String,
+    table: String,
+    client: Mutex<Option<Client>>,
+}
+
+impl PostgresSink {
+    pub fn from_url(url: &Url) -> Result<Self, crate::Error> {
+        let table = url
+            .query_pairs()
+            .find(|(k, _)| k == "table")
+            .map(|(_, v)| v.into_owned())
+            .unwrap_or_else(|| "scheduled_run_history".to_string());
+        if !table.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(crate::Error::History(format!("invalid table name {table}")));
+        }
+        let mut config = url.clone();
+        config.set_query(None);
+        Ok(Self {
+            config: config.to_string(),
+            table,
+            client: Mutex::new(None),
+        })
+    }
+
+    async fn connect(&self) -> Result<Client, tokio_postgres::Error> {
+        let (client, connection) = tokio_postgres::connect(&self.config, NoTls).await?;
+        tokio::spawn(async move {
+            if let Err(e) = connection.await {
+                tracing::warn!(error = ?e, "History database connection closed");
+            }
+        });
+        client
+            .batch_execute(&format!(
+                "CREATE TABLE IF NOT EXISTS {} (
+                    kind text NOT NULL,
+                    namespace text NOT NULL,
+                    name text NOT NULL,
+                    completion_time timestamptz NOT NULL,
+                    record jsonb NOT NULL
+                )",
+                self.table
+            ))
+            .await?;
+        Ok(client)
+    }
+
+    /// The archived records, in `namespace` or every namespace, oldest
+    /// first.
+    pub async fn records(&self, namespace: Option<&str>) -> Result<Vec<RunRecord>, crate::Error> {
+        let mut client = self.client.lock().await;
+        if client.as_ref().is_none_or(Client::is_closed) {
+            *client = Some(self.connect().await.map_err(history)?);
+        }
+        let client = client.as_ref().unwrap();
+        let rows = client
+            .query(
+                &format!(
+                    "SELECT record FROM {} WHERE $1::text IS NULL OR namespace = $1 \
+                     ORDER BY completion_time",
+                    self.table
+                ),
+                &[&namespace],
+            )
+            .await
+            .map_err(history)?;
+        rows.into_iter()
+            .map(|row| Ok(serde_json::from_value(row.get(0))?))
+            .collect()
+    }
+
+    async fn insert(&self, record: &RunRecord) -> Result<(), crate::Error> {
+        let mut client = self.client.lock().await;
+        if client.as_ref().is_none_or(Client::is_closed) {
+            *client = Some(self.connect().await.map_err(history)?);
+        }
+        let client = client.as_ref().unwrap();
+        client
+            .execute(
+                &format!(
+                    "INSERT INTO {} (kind, namespace, name, completion_time, record) \
+                     VALUES ($1, $2, $3, $4, $5)",
+                    self.table
+                ),
+                &[
+                    &record.kind,
+                    &record.namespace,
+                    &record.name,
+                    &record.completion_time,
+                    &serde_json::to_value(record)?,
+                ],
+            )
+            .await
+            .map_err(history)?;
+        Ok(())
+    }
+}
+
+impl HistorySink for PostgresSink {
+    fn archive<'a>(&'a self, record: &'a RunRecord) -> BoxFuture<'a, Result<(), crate::Error>> {
+        Box::pin(self.insert(record))
+    }
+}
+
+fn history(e: tokio_postgres::Error) -> crate::Error {
+    crate::Error::History(e.to_string())
+}
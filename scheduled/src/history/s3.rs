@@ -0,0 +1,94 @@
+use futures::future::BoxFuture;
+use reqwest::Url;
+
+use super::{HistorySink, RunRecord};
+use crate::aws::{self, Credentials};
+
+/// Writes each record as a JSON object under
+/// `<prefix>/<namespace>/<kind>/<name>/<completion time>.json`.
+///
+/// Credentials come from the `AWS_*` environment variables. The region
+/// defaults to `AWS_REGION`, and the `region` and `endpoint` query parameters
+/// of the sink URL override it and the AWS endpoint, e.g. for MinIO.
+pub struct S3Sink {
+    http: reqwest::Client,
+    endpoint: Url,
+    bucket: String,
+    prefix: String,
+    region: String,
+    credentials: Credentials,
+}
+
+impl S3Sink {
+    pub fn from_url(url: &Url, http: reqwest::Client) -> Result<Self, crate::Error> {
+        let bucket = url
+            .host_str()
+            .ok_or_else(|| crate::Error::History("s3 sink needs a bucket".to_string()))?
+            .to_string();
+        let query = |key: &str| {
+            url.query_pairs()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v.into_owned())
+        };
+        let region = query("region").unwrap_or_else(aws::default_region);
+        let endpoint =
+            query("endpoint").unwrap_or_else(|| format!("https://s3.{region}.amazonaws.com"));
+        let endpoint = Url::parse(&endpoint).map_err(|e| crate::Error::History(e.to_string()))?;
+        Ok(Self {
+            http,
+            endpoint,
+            bucket,
+            prefix: url.path().trim_matches('/').to_string(),
+            region,
+            credentials: Credentials::from_env().map_err(crate::Error::History)?,
+        })
+    }
+
+    fn key(&self, record: &RunRecord) -> String {
+        let object = format!(
+            "{}/{}/{}/{}.json",
+            record.namespace,
+            record.kind,
+            record.name,
+            record.completion_time.format("%Y%m%dT%H%M%SZ")
+        );
+        if self.prefix.is_empty() {
+            object
+        } else {
+            format!("{}/{object}", self.prefix)
+        }
+    }
+
+    async fn put(&self, key: &str, body: Vec<u8>) -> Result<(), crate::Error> {
+        let mut url = self.endpoint.clone();
+        url.set_path(&format!(
+            "{}/{}/{}",
+            self.endpoint.path().trim_end_matches('/'),
+            aws::encode(&self.bucket),
+            key.split('/')
+                .map(aws::encode)
+                .collect::<Vec<_>>()
+                .join("/")
+        ));
+        let headers = aws::sign(&self.credentials, &self.region, "s3", "PUT", &url, &body);
+        let mut request = self
+            .http
+            .put(url)
+            .header("content-type", "application/json")
+            .body(body);
+        for (key, value) in headers {
+            request = request.header(key, value);
+        }
+        request.send().await?.error_for_status()?;
+        Ok(())
+    }
+}
+
+impl HistorySink for S3Sink {
+    fn archive<'a>(&'a self, record: &'a RunRecord) -> BoxFuture<'a, Result<(), crate::Error>> {
+        Box::pin(async move {
+            let body = serde_json::to_vec(record)?;
+            self.put(&self.key(record), body).await
+        })
+    }
+}
@@ -0,0 +1,78 @@
+use futures::future::BoxFuture;
+
+use crate::crd::Owner;
+use crate::reason::Reason;
+
+/// A resource moving between phases.
+#[derive(Clone, Debug)]
+pub struct PhaseChange {
+    pub kind: String,
+    pub namespace: String,
+    pub name: String,
+    /// The phase left, or `None` if the resource had no status yet.
+    pub from: Option<String>,
+    pub to: String,
+    pub message: String,
+    pub owner: Option<Owner>,
+}
+
+/// An object created by the controller.
+#[derive(Clone, Debug)]
+pub struct ChildCreated {
+    pub kind: String,
+    pub namespace: String,
+    pub name: String,
+    /// Kind and name of the object's controller, if it has one.
+    pub owner: Option<(String, String)>,
+}
+
+/// A run failed by the controller for exceeding its hard timeout, for
+/// stalling, or for failing its post-run check.
+#[derive(Clone, Debug)]
+pub struct RunFailed {
+    pub kind: String,
+    pub namespace: String,
+    pub name: String,
+    /// Name of the run's Job.
+    pub job: String,
+    /// [`Reason::TimedOut`], [`Reason::Stalled`] or [`Reason::CheckFailed`].
+    pub reason: Reason,
+    pub elapsed: std::time::Duration,
+    pub owner: Option<Owner>,
+}
+
+/// A reconciliation that failed and will be retried.
+#[derive(Clone, Debug)]
+pub struct ReconcileFailure {
+    pub kind: String,
+    pub namespace: String,
+    pub name: String,
+}
+
+/// Side effects added by binaries embedding the reconcilers, such as opening
+/// tickets or updating a CMDB, installed with [`crate::Context::with_hooks`].
+///
+/// Every method does nothing by default. Hooks run inline with
+/// reconciliation, so slow work should be spawned; they cannot fail it.
+/// Nothing is reported for writes skipped in observer mode.
+pub trait ReconcileHooks: Send + Sync {
+    fn on_phase_change<'a>(&'a self, change: &'a PhaseChange) -> BoxFuture<'a, ()> {
+        let _ = change;
+        Box::pin(async {})
+    }
+
+    fn on_child_created<'a>(&'a self, child: &'a ChildCreated) -> BoxFuture<'a, ()> {
+        let _ = child;
+        Box::pin(async {})
+    }
+
+    fn on_run_failed<'a>(&'a self, run: &'a RunFailed) -> BoxFuture<'a, ()> {
+        let _ = run;
+        Box::pin(async {})
+    }
+
+    /// Called from the error policy, which cannot wait on futures.
+    fn on_error(&self, failure: &ReconcileFailure, error: &crate::Error) {
+        let _ = (failure, error);
+    }
+}
@@ -0,0 +1,269 @@
+use chrono::{DateTime, Days, NaiveDate, Utc};
+use chrono_tz::Tz;
+use kube::api::ListParams;
+use kube::{Api, ResourceExt as _};
+
+use crate::crd::{ScheduleCalendar, ScheduledCronJob};
+use crate::reconciler::scheduled_cronjob::next_fire;
+use crate::schedule::Schedule;
+use crate::{Context, Error};
+
+/// Most events listed per child CronJob, blackout window or calendar, so
+/// frequent schedules do not bloat the feed.
+const MAX_EVENTS: usize = 100;
+
+/// An entry of the feed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Event {
+    uid: String,
+    when: When,
+    summary: String,
+    description: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum When {
+    /// A fire, without a duration.
+    At(DateTime<Utc>),
+    /// A blackout window.
+    Between(DateTime<Utc>, DateTime<Utc>),
+    /// The dates of a calendar range, `end` included.
+    Days(NaiveDate, NaiveDate),
+}
+
+/// The iCalendar feed of `namespace`: the fires of its ScheduledCronJobs from
+/// `now` until `until`, and the blackout windows and calendar dates
+/// overlapping that period.
+///
+/// Fires of suspended resources, outside their window or within a blackout
+/// are left out, as are sub-minute `@every` runs and six-field schedules
+/// firing at seconds other than 0, which the controller starts itself.
+pub async fn feed(
+    ctx: &Context,
+    namespace: &str,
+    now: DateTime<Utc>,
+    until: DateTime<Utc>,
+) -> Result<String, Error> {
+    let client = (*ctx).clone();
+    let jobs = Api::<ScheduledCronJob>::namespaced(client.clone(), namespace)
+        .list(&ListParams::default())
+        .await?;
+    let calendars = Api::<ScheduleCalendar>::namespaced(client, namespace)
+        .list(&ListParams::default())
+        .await?
+        .items;
+    Ok(calendar(namespace, &jobs.items, &calendars, now, until))
+}
+
+/// The iCalendar feed of `namespace` listing `jobs` and `calendars`, see
+/// [`feed`].
+pub fn calendar(
+    namespace: &str,
+    jobs: &[ScheduledCronJob],
+    calendars: &[ScheduleCalendar],
+    now: DateTime<Utc>,
+    until: DateTime<Utc>,
+) -> String {
+    let mut events = Vec::new();
+    for job in jobs {
+        let used: Vec<_> = calendars
+            .iter()
+            .filter(|c| job.uses_calendar(c))
+            .cloned()
+            .collect();
+        events.extend(fires(job, &used, now, until));
+        events.extend(blackouts(job, now, until));
+    }
+    for calendar in calendars {
+        events.extend(calendar_days(calendar, now, until));
+    }
+    render(namespace, now, &events)
+}
+
+fn fires(
+    job: &ScheduledCronJob,
+    calendars: &[ScheduleCalendar],
+    now: DateTime<Utc>,
+    until: DateTime<Utc>,
+) -> Vec<Event> {
+    if !job.can_run() || job.spec.suspend {
+        return Vec::new();
+    }
+    let window = job.window();
+    let name = job.name_any();
+    let mut events = Vec::new();
+    let Ok(children) = job.cronjobs_at(now) else {
+        return events;
+    };
+    // Recurrence rules cron cannot express change the children's schedules
+    // after every fire.
+    let rescheduled = job
+        .recurrence_rule()
+        .ok()
+        .flatten()
+        .is_some_and(|r| r.to_cron().is_none());
+    for (index, child) in children.into_iter().enumerate() {
+        let child_name = child.name_any();
+        let Some(mut spec) = child.spec else {
+            continue;
+        };
+        let mut after = now;
+        let mut count = 0;
+        // Bounds the fires skipped within blackouts or before the window.
+        for _ in 0..MAX_EVENTS * 10 {
+            if count == MAX_EVENTS || spec.suspend == Some(true) {
+                break;
+            }
+            if rescheduled
+                && let Some(next) = job
+                    .cronjobs_at(after)
+                    .ok()
+                    .and_then(|c| c.into_iter().nth(index))
+                    .and_then(|c| c.spec)
+            {
+                spec = next;
+            }
+            let Some(fire) = Schedule::parse(&spec.schedule)
+                .ok()
+                .and_then(|s| next_fire(&spec, &s, after))
+                .filter(|t| *t < until)
+            else {
+                break;
+            };
+            after = fire;
+            if window.end.is_some_and(|end| fire > end) {
+                break;
+            }
+            if window.start.is_some_and(|start| fire < start)
+                || job.blackout_until(fire, calendars).is_some()
+            {
+                continue;
+            }
+            count += 1;
+            events.push(Event {
+                uid: uid("fire", job, &child_name, fire),
+                when: When::At(fire),
+                summary: name.clone(),
+                description: format!("CronJob {child_name} runs {}", spec.schedule),
+            });
+        }
+    }
+    events
+}
+
+fn blackouts(job: &ScheduledCronJob, now: DateTime<Utc>, until: DateTime<Utc>) -> Vec<Event> {
+    let tz = job.time_zone().ok().flatten().unwrap_or(Tz::UTC);
+    let name = job.name_any();
+    job.spec
+        .blackout_windows
+        .iter()
+        .flat_map(|w| w.occurrences(now, until, tz, MAX_EVENTS))
+        .map(|(start, end)| Event {
+            uid: uid("blackout", job, &name, start),
+            when: When::Between(start, end),
+            summary: format!("{name} blackout"),
+            description: format!("The CronJobs of {name} are suspended"),
+        })
+        .collect()
+}
+
+fn calendar_days(
+    calendar: &ScheduleCalendar,
+    now: DateTime<Utc>,
+    until: DateTime<Utc>,
+) -> Vec<Event> {
+    let name = calendar.name_any();
+    let namespace = calendar.namespace().unwrap_or_default();
+    calendar
+        .spec
+        .ranges
+        .iter()
+        .filter_map(|range| {
+            let start = NaiveDate::parse_from_str(&range.start, "%Y-%m-%d").ok()?;
+            let end = NaiveDate::parse_from_str(&range.end, "%Y-%m-%d").ok()?;
+            // Calendar dates are read in each resource's time zone, so allow
+            // a day either way.
+            let overlaps = start <= until.date_naive().checked_add_days(Days::new(1))?
+                && end >= now.date_naive().checked_sub_days(Days::new(1))?;
+            overlaps.then(|| Event {
+                uid: format!("calendar-{namespace}-{name}-{start}@scheduled.divinerapier.io"),
+                when: When::Days(start, end),
+                summary: range.name.clone(),
+                description: format!("ScheduleCalendar {name}"),
+            })
+        })
+        .take(MAX_EVENTS)
+        .collect()
+}
+
+fn uid(kind: &str, job: &ScheduledCronJob, name: &str, at: DateTime<Utc>) -> String {
+    format!(
+        "{kind}-{}-{name}-{}@scheduled.divinerapier.io",
+        job.namespace().unwrap_or_default(),
+        at.timestamp()
+    )
+}
+
+/// Renders `events` as an RFC 5545 calendar.
+fn render(namespace: &str, now: DateTime<Utc>, events: &[Event]) -> String {
+    let stamp = |t: &DateTime<Utc>| t.format("%Y%m%dT%H%M%SZ").to_string();
+    let date = |d: &NaiveDate| d.format("%Y%m%d").to_string();
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//divinerapier//scheduled-cronjob//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        format!(
+            "X-WR-CALNAME:{}",
+            escape(&format!("Batch schedule of {namespace}"))
+        ),
+    ];
+    for event in events {
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:{}", escape(&event.uid)));
+        lines.push(format!("DTSTAMP:{}", stamp(&now)));
+        match &event.when {
+            When::At(at) => lines.push(format!("DTSTART:{}", stamp(at))),
+            When::Between(start, end) => {
+                lines.push(format!("DTSTART:{}", stamp(start)));
+                lines.push(format!("DTEND:{}", stamp(end)));
+            }
+            When::Days(start, end) => {
+                let end = end.succ_opt().unwrap_or(*end);
+                lines.push(format!("DTSTART;VALUE=DATE:{}", date(start)));
+                lines.push(format!("DTEND;VALUE=DATE:{}", date(&end)));
+            }
+        }
+        lines.push(format!("SUMMARY:{}", escape(&event.summary)));
+        lines.push(format!("DESCRIPTION:{}", escape(&event.description)));
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+    lines.iter().map(|l| fold(l)).collect()
+}
+
+/// Escapes a TEXT value.
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Folds `line` into lines of at most 75 octets, continuation lines starting
+/// with a space, each ending in CRLF.
+fn fold(line: &str) -> String {
+    let mut folded = String::new();
+    let mut length = 0;
+    for c in line.chars() {
+        if length + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            length = 1;
+        }
+        folded.push(c);
+        length += c.len_utf8();
+    }
+    folded.push_str("\r\n");
+    folded
+}
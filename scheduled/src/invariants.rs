@@ -0,0 +1,164 @@
+use std::fmt;
+
+use chrono::{DateTime, Local, Utc};
+use k8s_openapi::api::batch::v1::CronJob;
+use kube::ResourceExt as _;
+use kube::api::ObjectMeta;
+
+use crate::Context;
+use crate::crd::{ScheduledCronJob, ScheduledCronJobPhase};
+use crate::reconciler::scheduled_cronjob::{driven, list_runs, runs_of};
+use crate::replay::{self, ChildAction, Decision, Recording};
+
+/// A decision of the controller breaking one of its invariants.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    pub invariant: &'static str,
+    pub message: String,
+}
+
+impl Violation {
+    fn new(invariant: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            invariant,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.invariant, self.message)
+    }
+}
+
+/// Checks `decision`, made for `recording` by [`replay::decide`]:
+///
+/// - `outside-window`: no child is created or resumed before `startTime` or
+///   after `endTime`;
+/// - `left-expired`: once `Expired`, `Completed` or `Failed`, no child is
+///   created or resumed;
+/// - `invalid-transition`: the phase only moves as
+///   [`ScheduledCronJobPhase::allows`].
+pub fn check(recording: &Recording, decision: &Decision) -> Vec<Violation> {
+    let job = &recording.resource;
+    let mut violations = Vec::new();
+    let started: Vec<_> = decision
+        .children
+        .iter()
+        .filter(|c| {
+            c.actions
+                .iter()
+                .any(|a| matches!(a, ChildAction::Create | ChildAction::Resume))
+        })
+        .map(|c| c.name.as_str())
+        .collect();
+
+    let window = job.window();
+    let now = recording.now.with_timezone(&Local);
+    let outside =
+        window.start.is_some_and(|start| now < start) || window.end.is_some_and(|end| now > end);
+    if outside && !started.is_empty() {
+        violations.push(Violation::new(
+            "outside-window",
+            format!(
+                "{} created or resumed at {} outside the window",
+                started.join(", "),
+                recording.now
+            ),
+        ));
+    }
+
+    let current = job.status.as_ref().map(|s| s.phase).unwrap_or_default();
+    let settled = current == ScheduledCronJobPhase::Expired || current.is_terminal();
+    if settled && !started.is_empty() {
+        violations.push(Violation::new(
+            "left-expired",
+            format!("{} created or resumed while {current}", started.join(", ")),
+        ));
+    }
+    if let Some(next) = decision.phase
+        && !current.allows(next)
+    {
+        violations.push(Violation::new(
+            "invalid-transition",
+            format!("phase moves from {current} to {next}"),
+        ));
+    }
+    violations
+}
+
+/// Checks the `runs` of `cronjob` for `fired-twice`: two runs the controller
+/// started for the same occurrence of a sub-minute `@every` or six-field
+/// schedule. Runs the CronJob controller starts are named after their
+/// occurrence and cannot collide, so only controller-driven runs are checked.
+pub fn check_runs(cronjob: &CronJob, runs: &[ObjectMeta]) -> Vec<Violation> {
+    let Some(drive) = driven(cronjob) else {
+        return Vec::new();
+    };
+    let Some(spec) = &cronjob.spec else {
+        return Vec::new();
+    };
+    let prefix = format!("{}-{}-", cronjob.name_any(), drive.kind());
+    let mut created: Vec<(DateTime<Utc>, &str)> = runs
+        .iter()
+        .filter_map(|run| {
+            let name = run.name.as_deref()?;
+            name.starts_with(&prefix)
+                .then_some((run.creation_timestamp.as_ref()?.0, name))
+        })
+        .collect();
+    created.sort();
+    created
+        .windows(2)
+        .filter_map(|pair| {
+            let [(previous, first), (next, second)] = pair else {
+                return None;
+            };
+            let due = drive.due_after(spec, *previous)?;
+            let elapsed = (*next - *previous).to_std().unwrap_or_default();
+            (elapsed < due).then(|| {
+                Violation::new(
+                    "fired-twice",
+                    format!(
+                        "{second} started {}s after {first}, before the next occurrence",
+                        elapsed.as_secs()
+                    ),
+                )
+            })
+        })
+        .collect()
+}
+
+/// Checks what the controller decides for `job` as of `now` against the live
+/// children and their runs, logging and counting violations. Run in observer
+/// mode, where the decisions are taken but not applied.
+pub async fn observe(ctx: &Context, job: &ScheduledCronJob, now: DateTime<Utc>) {
+    let recording = replay::capture(ctx, job, now).await;
+    let decision = replay::decide(&recording);
+    let mut violations = check(&recording, &decision);
+    let namespace = job.namespace().unwrap_or_default();
+    let runs = list_runs(ctx, job).await.unwrap_or_default();
+    for cronjob in &recording.children {
+        if driven(cronjob).is_none() {
+            continue;
+        }
+        let runs: Vec<_> = runs_of(&runs, cronjob)
+            .map(|r| r.metadata.clone())
+            .collect();
+        violations.extend(check_runs(cronjob, &runs));
+    }
+    for violation in violations {
+        tracing::warn!(
+            name = job.name_any(),
+            namespace,
+            invariant = violation.invariant,
+            message = violation.message,
+            "Invariant violated"
+        );
+        ctx.metrics()
+            .invariant_violations_total
+            .with_label_values(&[violation.invariant])
+            .inc();
+    }
+}
@@ -0,0 +1,205 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use chrono::Utc;
+use k8s_openapi::api::coordination::v1::{Lease, LeaseSpec};
+use k8s_openapi::apiextensions_apiserver::pkg::apis::apiextensions::v1::CustomResourceDefinition;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::{MicroTime, ObjectMeta};
+use kube::api::{DynamicObject, PostParams};
+use kube::{Api, Error as KubeError};
+
+use crate::Context;
+
+/// How often CRD compatibility is rechecked while it does not hold.
+const VERIFY_RETRY: Duration = Duration::from_secs(10);
+
+/// Whether this replica currently holds the leader Lease. Only the leader
+/// reconciles; other replicas keep their watches warm so they can take over
+/// without a gap.
+#[derive(Default)]
+pub struct Leadership {
+    leader: AtomicBool,
+}
+
+impl Leadership {
+    pub fn is_leader(&self) -> bool {
+        self.leader.load(Ordering::Relaxed)
+    }
+
+    fn set(&self, leader: bool) -> bool {
+        self.leader.swap(leader, Ordering::Relaxed) != leader
+    }
+}
+
+/// Competes for the leader Lease until the future is dropped. Leadership is
+/// only sought once `ready` resolves (caches warmed) and the installed CRDs
+/// serve the versions this build expects, so a new replica never takes over
+/// before it can reconcile.
+pub async fn run(
+    ctx: Arc<Context>,
+    ready: impl Future<Output = ()>,
+    crds: Vec<CustomResourceDefinition>,
+) {
+    ready.await;
+    while let Err(reason) = verify_crds(&ctx, &crds).await {
+        tracing::warn!(
+            reason,
+            "Installed CRDs are incompatible, not seeking leadership"
+        );
+        tokio::time::sleep(VERIFY_RETRY).await;
+    }
+    tracing::info!("Caches warmed and CRDs verified, seeking leadership");
+
+    let mut interval = tokio::time::interval(ctx.config().leader_lease_duration / 3);
+    loop {
+        interval.tick().await;
+        let leader = match try_acquire(&ctx).await {
+            Ok(leader) => leader,
+            Err(e) => {
+                tracing::warn!(error = ?e, "Failed to renew leader lease");
+                false
+            }
+        };
+        if ctx.leadership().set(leader) {
+            if leader {
+                tracing::info!(identity = ctx.config().identity, "Acquired leadership");
+            } else {
+                tracing::warn!(identity = ctx.config().identity, "Lost leadership");
+            }
+        }
+    }
+}
+
+/// Applies `crds` with [`Context::apply_all`], so a failed upgrade rolls the
+/// CRDs already applied back instead of leaving a mix of versions installed.
+/// Whether they are served is left to the check [`run`] does before seeking
+/// leadership.
+pub async fn install(ctx: &Context, crds: &[CustomResourceDefinition]) {
+    let objects = crds
+        .iter()
+        .map(|crd| serde_json::to_value(crd).and_then(serde_json::from_value))
+        .collect::<Result<Vec<DynamicObject>, _>>();
+    let result = match objects {
+        Ok(objects) => ctx.apply_all(objects).await.map(|applied| applied.len()),
+        Err(e) => Err(e.into()),
+    };
+    match result {
+        Ok(count) => tracing::info!(count, "Installed CRDs"),
+        Err(e) => tracing::warn!(error = ?e, "Failed to install CRDs"),
+    }
+}
+
+/// Gives up leadership after the controllers have drained, so the next
+/// replica can take over immediately instead of waiting for the lease to
+/// expire.
+pub async fn release(ctx: &Context) {
+    if !ctx.leadership().set(false) {
+        return;
+    }
+    let config = ctx.config();
+    let api = Api::<Lease>::namespaced((**ctx).clone(), &config.namespace);
+    let result = async {
+        let mut lease = api.get(&config.leader_lease_name).await?;
+        let spec = lease.spec.get_or_insert_with(Default::default);
+        if spec.holder_identity.as_deref() != Some(config.identity.as_str()) {
+            return Ok(());
+        }
+        spec.holder_identity = None;
+        spec.renew_time = None;
+        api.replace(&config.leader_lease_name, &PostParams::default(), &lease)
+            .await
+            .map(|_| ())
+    }
+    .await;
+    match result {
+        Ok(()) => tracing::info!("Released leadership"),
+        Err(e) => tracing::warn!(error = ?e, "Failed to release leader lease"),
+    }
+}
+
+/// Checks that every CRD this build reconciles is installed and serves the
+/// expected version.
+async fn verify_crds(ctx: &Context, crds: &[CustomResourceDefinition]) -> Result<(), String> {
+    let api = Api::<CustomResourceDefinition>::all((**ctx).clone());
+    for expected in crds {
+        let name = expected.metadata.name.clone().unwrap_or_default();
+        let installed = api
+            .get_opt(&name)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("{name} is not installed"))?;
+        for version in &expected.spec.versions {
+            let served = installed
+                .spec
+                .versions
+                .iter()
+                .any(|v| v.name == version.name && v.served);
+            if !served {
+                return Err(format!("{name} does not serve {}", version.name));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Acquires or renews the leader Lease. Returns whether this replica holds it.
+async fn try_acquire(ctx: &Context) -> Result<bool, KubeError> {
+    let config = ctx.config();
+    let api = Api::<Lease>::namespaced((**ctx).clone(), &config.namespace);
+    let now = Utc::now();
+    let duration = config.leader_lease_duration.as_secs() as i32;
+
+    let Some(mut lease) = api.get_opt(&config.leader_lease_name).await? else {
+        let lease = Lease {
+            metadata: ObjectMeta {
+                name: Some(config.leader_lease_name.clone()),
+                namespace: Some(config.namespace.clone()),
+                ..Default::default()
+            },
+            spec: Some(LeaseSpec {
+                holder_identity: Some(config.identity.clone()),
+                lease_duration_seconds: Some(duration),
+                acquire_time: Some(MicroTime(now)),
+                renew_time: Some(MicroTime(now)),
+                lease_transitions: Some(0),
+            }),
+        };
+        return match api.create(&PostParams::default(), &lease).await {
+            Ok(_) => Ok(true),
+            Err(KubeError::Api(e)) if e.code == 409 => Ok(false),
+            Err(e) => Err(e),
+        };
+    };
+
+    let spec = lease.spec.get_or_insert_with(Default::default);
+    let held_by_us = spec.holder_identity.as_deref() == Some(config.identity.as_str());
+    let expired = match (&spec.renew_time, spec.lease_duration_seconds) {
+        (Some(renewed), Some(seconds)) => {
+            renewed.0 + chrono::Duration::seconds(seconds as i64) < now
+        }
+        _ => true,
+    };
+    if !held_by_us && spec.holder_identity.is_some() && !expired {
+        return Ok(false);
+    }
+
+    if !held_by_us {
+        spec.holder_identity = Some(config.identity.clone());
+        spec.acquire_time = Some(MicroTime(now));
+        spec.lease_transitions = Some(spec.lease_transitions.unwrap_or(0) + 1);
+    }
+    spec.lease_duration_seconds = Some(duration);
+    spec.renew_time = Some(MicroTime(now));
+
+    // The resourceVersion from the read makes this a compare-and-swap, so
+    // two replicas racing for an expired lease cannot both win.
+    match api
+        .replace(&config.leader_lease_name, &PostParams::default(), &lease)
+        .await
+    {
+        Ok(_) => Ok(true),
+        Err(KubeError::Api(e)) if e.code == 409 => Ok(false),
+        Err(e) => Err(e),
+    }
+}
@@ -1,13 +1,67 @@
+pub mod annotated;
+pub mod auth;
+pub mod aws;
+pub mod breaker;
+pub mod bus;
+pub mod cache;
+pub mod capacity;
+pub mod cloudevents;
+pub mod codegen;
+pub mod compat;
+pub mod config;
+pub mod configuration;
+pub mod consumer;
 pub mod crd;
+pub mod emergency;
 pub mod error;
+pub mod fixtures;
+pub mod heartbeat;
+pub mod history;
+pub mod hooks;
+pub mod ical;
+pub mod invariants;
+pub mod leader;
+pub mod lint;
+pub mod loglevel;
+pub mod metrics;
+pub mod object_ref;
+pub mod observer;
+pub mod plan;
+pub mod preemption;
+pub mod protobuf;
+pub mod queue;
 pub mod rbac;
+pub mod reason;
 pub mod reconciler;
+pub mod recurrence;
+pub mod registry;
+pub mod render;
+pub mod replay;
+pub mod resize;
+pub mod run_labels;
+pub mod schedule;
+pub mod server;
+pub mod slack;
+pub mod sweep;
+pub mod tenant;
+pub mod throttle;
+pub mod trigger;
 
+pub use config::Config;
 pub use crd::{
     CronJobBuilder, DelayedJob, DelayedJobSpec, ScheduledCronJob, ScheduledCronJobSpec,
-    ScheduledCronJobStatus,
+    ScheduledCronJobStatus, ScheduledPatch, ScheduledPatchSpec, ScheduledPatchStatus,
+    ScheduledSuspend, ScheduledSuspendSpec, ScheduledSuspendStatus, TargetRef, TimerTrigger,
+    TimerTriggerSpec, TimerTriggerStatus,
 };
 pub use error::Error;
+pub use hooks::ReconcileHooks;
+pub use metrics::Metrics;
 pub use rbac::{RbacRule, get_rbac_rules};
+pub use reason::Reason;
 pub use reconciler::Context;
-pub use reconciler::{error_policy, reconcile_scheduled_cronjob};
+pub use reconciler::{
+    error_policy, reconcile_scheduled_cronjob, reconcile_scheduled_patch,
+    reconcile_scheduled_suspend, reconcile_timer_trigger,
+};
+pub use schedule::{Schedule, Tick, Window};
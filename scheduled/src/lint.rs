@@ -0,0 +1,311 @@
+use std::fmt;
+
+use chrono::{DateTime, Duration, Local, TimeZone, Utc};
+use chrono_tz::Tz;
+use k8s_openapi::api::core::v1::PodSpec;
+
+use crate::crd::{DelayedJob, ScheduledCronJob};
+use crate::schedule::{Every, Schedule, Seconds, stagger};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        }
+    }
+}
+
+/// A best-practice violation found in a spec. Unlike validation errors,
+/// findings never stop a resource from being reconciled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    pub severity: Severity,
+    pub code: &'static str,
+    pub message: String,
+}
+
+impl Finding {
+    fn new(severity: Severity, code: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            severity,
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[{}] {}: {}",
+            self.severity.as_str(),
+            self.code,
+            self.message
+        )
+    }
+}
+
+pub trait Lint {
+    fn lint(&self) -> Vec<Finding>;
+}
+
+impl Lint for ScheduledCronJob {
+    fn lint(&self) -> Vec<Finding> {
+        let spec = self.spec.cronjob_spec();
+        let mut findings = Vec::new();
+
+        // A recurrenceRule or schedules replace spec.schedule, which may then
+        // be empty. Rules cron cannot express are not linted as schedules.
+        let schedules: Vec<String> = match self.recurrence_rule() {
+            Ok(_) if !self.spec.schedules.is_empty() => self.spec.schedules.clone(),
+            Ok(Some(rule)) => rule.to_cron().into_iter().collect(),
+            Ok(None) => match Every::parse(&spec.schedule) {
+                Ok(Some(every)) => vec![lint_every(every, &mut findings)],
+                Ok(None) => vec![spec.schedule.clone()],
+                Err(e) => {
+                    findings.push(Finding::new(
+                        Severity::Error,
+                        "unparsable-schedule",
+                        e.to_string(),
+                    ));
+                    Vec::new()
+                }
+            },
+            Err(e) => {
+                findings.push(Finding::new(
+                    Severity::Error,
+                    "invalid-recurrence-rule",
+                    e.to_string(),
+                ));
+                Vec::new()
+            }
+        };
+        match self.time_zone() {
+            Ok(time_zone) => {
+                for schedule in &schedules {
+                    lint_schedule(schedule, time_zone, &mut findings)
+                }
+            }
+            Err(e) => findings.push(Finding::new(
+                Severity::Error,
+                "unknown-time-zone",
+                e.to_string(),
+            )),
+        }
+        if spec.successful_jobs_history_limit.is_none() || spec.failed_jobs_history_limit.is_none()
+        {
+            findings.push(Finding::new(
+                Severity::Info,
+                "no-history-limit",
+                "history limits are not set, the cluster defaults apply",
+            ));
+        }
+        if self.spec.spread_over_minutes.is_some() && self.spec.variants.len() < 2 {
+            findings.push(Finding::new(
+                Severity::Info,
+                "spread-without-variants",
+                "spreadOverMinutes only staggers two or more variants",
+            ));
+        }
+        if let Some(jitter) = self.spec.jitter_seconds {
+            if jitter < 60 {
+                findings.push(Finding::new(
+                    Severity::Warning,
+                    "jitter-below-a-minute",
+                    "schedules have minute resolution, so a jitterSeconds under 60 has no effect",
+                ));
+            } else if schedules.iter().any(|s| {
+                let minutes = match Seconds::parse(s) {
+                    Ok(Some(seconds)) => seconds.minutes().to_string(),
+                    _ => s.clone(),
+                };
+                stagger(&minutes, self.jitter_minutes()).is_err()
+            }) {
+                findings.push(Finding::new(
+                    Severity::Warning,
+                    "jitter-unsupported",
+                    "the schedule cannot be shifted by jitterSeconds and is not jittered",
+                ));
+            }
+        }
+        if self.spec.heartbeat_timeout_seconds.is_some() && self.service_account_name().is_none() {
+            findings.push(Finding::new(
+                Severity::Info,
+                "heartbeat-without-service-account",
+                "heartbeats need a service account allowed to update the run's Lease",
+            ));
+        }
+        if let Some(pod) = spec
+            .job_template
+            .spec
+            .as_ref()
+            .and_then(|s| s.template.spec.as_ref())
+        {
+            lint_pod_spec(pod, &mut findings);
+            if self
+                .spec
+                .spot_policy
+                .as_ref()
+                .is_some_and(|p| p.retry_on_preemption > 0)
+                && pod.restart_policy.as_deref() != Some("Never")
+            {
+                findings.push(Finding::new(
+                    Severity::Warning,
+                    "preemption-retry-needs-never",
+                    "retryOnPreemption only applies with restartPolicy Never",
+                ));
+            }
+        }
+        findings
+    }
+}
+
+impl Lint for DelayedJob {
+    fn lint(&self) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        if let Some(pod) = &self.spec.spec.template.spec {
+            lint_pod_spec(pod, &mut findings);
+        }
+        findings
+    }
+}
+
+/// Schedules firing more often than this are reported.
+const MIN_INTERVAL: Duration = Duration::hours(1);
+
+/// Lints `expression` as read in `time_zone`, or the local zone when `None`.
+fn lint_schedule(expression: &str, time_zone: Option<Tz>, findings: &mut Vec<Finding>) {
+    let parsed = Seconds::parse(expression).and_then(|seconds| match seconds {
+        Some(seconds) => {
+            if !seconds.is_on_the_minute() {
+                findings.push(Finding::new(
+                    Severity::Info,
+                    "seconds-schedule",
+                    format!(
+                        "schedule {expression} fires at seconds other than 0, so the controller starts the runs"
+                    ),
+                ));
+            }
+            seconds.schedule()
+        }
+        None => Schedule::parse(expression),
+    });
+    let schedule = match parsed {
+        Ok(schedule) => schedule,
+        Err(e) => {
+            findings.push(Finding::new(
+                Severity::Error,
+                "unparsable-schedule",
+                e.to_string(),
+            ));
+            return;
+        }
+    };
+
+    let now = Utc::now();
+    let frequent = match time_zone {
+        Some(time_zone) => is_frequent(&schedule, now.with_timezone(&time_zone)),
+        None => is_frequent(&schedule, now.with_timezone(&Local)),
+    };
+    if frequent {
+        findings.push(Finding::new(
+            Severity::Warning,
+            "frequent-schedule",
+            format!("schedule {expression} fires more than once an hour"),
+        ));
+    }
+}
+
+/// Reports `@every` intervals cron only approximates, returning the cron
+/// expression the children use.
+fn lint_every(every: Every, findings: &mut Vec<Finding>) -> String {
+    let (cron, interval) = every.to_cron();
+    if every.is_sub_minute() {
+        findings.push(Finding::new(
+            Severity::Info,
+            "every-sub-minute",
+            format!("{every} is shorter than a minute, so the controller starts the runs"),
+        ));
+    } else if interval != every.interval() {
+        findings.push(Finding::new(
+            Severity::Warning,
+            "every-approximated",
+            format!(
+                "{every} cannot be written as cron and runs every {} minutes as {cron}",
+                interval.as_secs() / 60
+            ),
+        ));
+    }
+    cron
+}
+
+/// Whether `schedule` fires more often than [`MIN_INTERVAL`] after `from`.
+fn is_frequent<Z: TimeZone>(schedule: &Schedule, from: DateTime<Z>) -> bool {
+    // Sample several consecutive fires, as schedules like `*/10 9 * * *` are
+    // only frequent part of the day.
+    let Some(mut previous) = schedule.next_after(&from) else {
+        return false;
+    };
+    for _ in 0..24 {
+        let Some(next) = schedule.next_after(&previous) else {
+            return false;
+        };
+        if next.clone() - previous < MIN_INTERVAL {
+            return true;
+        }
+        previous = next;
+    }
+    false
+}
+
+fn lint_pod_spec(spec: &PodSpec, findings: &mut Vec<Finding>) {
+    for container in &spec.containers {
+        let has_limits = container
+            .resources
+            .as_ref()
+            .and_then(|r| r.limits.as_ref())
+            .is_some_and(|l| !l.is_empty());
+        if !has_limits {
+            findings.push(Finding::new(
+                Severity::Warning,
+                "no-resource-limits",
+                format!("container {} has no resource limits", container.name),
+            ));
+        }
+
+        if let Some(image) = &container.image
+            && uses_latest(image)
+        {
+            findings.push(Finding::new(
+                Severity::Warning,
+                "latest-image",
+                format!(
+                    "container {} uses the mutable image {image}",
+                    container.name
+                ),
+            ));
+        }
+    }
+}
+
+/// Whether `image` is untagged or tagged `latest`, ignoring digests.
+fn uses_latest(image: &str) -> bool {
+    if image.contains('@') {
+        return false;
+    }
+    let name = image.rsplit('/').next().unwrap_or(image);
+    match name.split_once(':') {
+        Some((_, tag)) => tag == "latest",
+        None => true,
+    }
+}
@@ -0,0 +1,166 @@
+use std::sync::{Arc, Mutex, PoisonError};
+
+use futures::StreamExt as _;
+use k8s_openapi::api::core::v1::ConfigMap;
+use kube::Api;
+use kube::runtime::watcher::{self, Event};
+use tracing_subscriber::layer::SubscriberExt as _;
+use tracing_subscriber::util::SubscriberInitExt as _;
+use tracing_subscriber::{EnvFilter, Registry, fmt, reload};
+
+use crate::Context;
+
+/// Key of the control ConfigMap holding tracing filter directives, such as
+/// `info,kube=debug`, that replace [`DEFAULT_DIRECTIVES`].
+pub const LOG_LEVEL_KEY: &str = "logLevel";
+
+/// Directives in effect unless the control ConfigMap or `PUT /debug/loglevel`
+/// say otherwise.
+pub const DEFAULT_DIRECTIVES: &str = "info";
+
+/// The tracing filter of the process, which can be changed while it runs.
+///
+/// The directives of the control ConfigMap apply until they are removed. An
+/// override set through [`LogLevel::set_temporary`] takes precedence over them
+/// until it expires.
+pub struct LogLevel {
+    handle: reload::Handle<EnvFilter, Registry>,
+    state: Mutex<State>,
+}
+
+#[derive(Default)]
+struct State {
+    configured: Option<String>,
+    temporary: Option<String>,
+    /// Bumped by every temporary override, so only the latest one expires it.
+    generation: u64,
+}
+
+impl LogLevel {
+    /// Installs the global tracing subscriber, logging at
+    /// [`DEFAULT_DIRECTIVES`].
+    pub fn init() -> Self {
+        let (filter, handle) = reload::Layer::new(EnvFilter::new(DEFAULT_DIRECTIVES));
+        tracing_subscriber::registry()
+            .with(filter)
+            .with(fmt::layer().with_line_number(true))
+            .init();
+        Self {
+            handle,
+            state: Mutex::default(),
+        }
+    }
+
+    /// The directives in effect.
+    pub fn current(&self) -> String {
+        let state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        state
+            .temporary
+            .clone()
+            .or_else(|| state.configured.clone())
+            .unwrap_or_else(|| DEFAULT_DIRECTIVES.to_string())
+    }
+
+    /// Sets the directives of the control ConfigMap, `None` once they are
+    /// removed.
+    pub fn set_configured(&self, directives: Option<&str>) -> Result<(), String> {
+        let directives = directives.map(str::trim).filter(|d| !d.is_empty());
+        if let Some(directives) = directives {
+            parse(directives)?;
+        }
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        if state.configured.as_deref() == directives {
+            return Ok(());
+        }
+        state.configured = directives.map(str::to_string);
+        self.reload(&state)
+    }
+
+    /// Overrides the directives until [`LogLevel::expire`] is called with the
+    /// returned generation.
+    pub fn set_temporary(&self, directives: &str) -> Result<u64, String> {
+        parse(directives)?;
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        state.temporary = Some(directives.trim().to_string());
+        state.generation += 1;
+        self.reload(&state)?;
+        Ok(state.generation)
+    }
+
+    /// Drops the temporary override of `generation`, unless a later one
+    /// replaced it. Returns whether it was dropped.
+    pub fn expire(&self, generation: u64) -> bool {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        if state.generation != generation || state.temporary.is_none() {
+            return false;
+        }
+        state.temporary = None;
+        if let Err(e) = self.reload(&state) {
+            tracing::warn!(error = e, "Failed to restore the log level");
+        }
+        true
+    }
+
+    fn reload(&self, state: &State) -> Result<(), String> {
+        let directives = state
+            .temporary
+            .as_deref()
+            .or(state.configured.as_deref())
+            .unwrap_or(DEFAULT_DIRECTIVES);
+        self.handle
+            .reload(parse(directives)?)
+            .map_err(|e| e.to_string())?;
+        tracing::info!(directives, "Changed the log level");
+        Ok(())
+    }
+}
+
+fn parse(directives: &str) -> Result<EnvFilter, String> {
+    EnvFilter::try_new(directives.trim()).map_err(|e| e.to_string())
+}
+
+/// Watches the control ConfigMap and applies its `logLevel` key. Does nothing
+/// when the context has no [`LogLevel`].
+pub async fn run(ctx: Arc<Context>) {
+    if ctx.log_level().is_none() {
+        return futures::future::pending().await;
+    }
+    let config = ctx.config().clone();
+    let api = Api::<ConfigMap>::namespaced((**ctx).clone(), &config.namespace);
+    let watcher_config =
+        watcher::Config::default().fields(&format!("metadata.name={}", config.control_configmap));
+
+    let mut seen = false;
+    let mut events = watcher::watcher(api, watcher_config).boxed();
+    while let Some(event) = events.next().await {
+        match event {
+            Ok(Event::Init) => seen = false,
+            Ok(Event::InitApply(cm) | Event::Apply(cm)) => {
+                seen = true;
+                set(&ctx, directives(&cm));
+            }
+            Ok(Event::InitDone) if !seen => set(&ctx, None),
+            Ok(Event::InitDone) => {}
+            Ok(Event::Delete(_)) => {
+                seen = false;
+                set(&ctx, None);
+            }
+            Err(e) => {
+                tracing::warn!(error = ?e, configmap = config.control_configmap, "Failed to watch control ConfigMap");
+            }
+        }
+    }
+}
+
+fn directives(cm: &ConfigMap) -> Option<&str> {
+    cm.data.as_ref()?.get(LOG_LEVEL_KEY).map(String::as_str)
+}
+
+fn set(ctx: &Context, directives: Option<&str>) {
+    let Some(log_level) = ctx.log_level() else {
+        return;
+    };
+    if let Err(e) = log_level.set_configured(directives) {
+        tracing::warn!(error = e, directives, "Ignoring invalid log level");
+    }
+}
@@ -0,0 +1,422 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+use futures::StreamExt as _;
+use kube::ResourceExt as _;
+use kube::runtime::WatchStreamExt as _;
+use kube::runtime::watcher::Event;
+use prometheus::core::Collector;
+use prometheus::proto::MetricType;
+use prometheus::{
+    Encoder as _, Gauge, GaugeVec, HistogramOpts, HistogramVec, IntCounterVec, IntGauge,
+    IntGaugeVec, Opts, Registry, TextEncoder,
+};
+
+use crate::Context;
+use crate::crd::{Owner, ScheduledCronJob};
+
+/// Namespace and name of a ScheduledCronJob.
+type Key = (String, String);
+
+/// Prometheus metrics exported by the controller under the `scheduled_` prefix.
+#[derive(Clone)]
+pub struct Metrics {
+    registry: Registry,
+    descriptions: Vec<MetricDescription>,
+
+    /// Unix time of the last successful heartbeat renewal.
+    pub controller_last_seen: Gauge,
+
+    /// Resources whose stale phase was repaired by the sweep, by kind.
+    pub repairs_total: IntCounterVec,
+
+    /// Times a resource's circuit was opened, by kind.
+    pub circuit_opens_total: IntCounterVec,
+
+    /// Resources whose circuit is currently open.
+    pub quarantined_resources: IntGauge,
+
+    /// 1 while the emergency stop is engaged.
+    pub emergency_stop: IntGauge,
+
+    /// Child writes skipped in observer mode, by child kind and action.
+    pub observer_actions_total: IntCounterVec,
+
+    /// Decisions breaking an invariant, found in observer mode, by invariant.
+    pub invariant_violations_total: IntCounterVec,
+
+    /// Requeues that are due but have not started, by kind.
+    pub queue_depth: IntGaugeVec,
+
+    /// Time from a requeue being due to its reconciliation starting, by kind.
+    pub queue_latency_seconds: HistogramVec,
+
+    /// Reconciliations retried after a transient failure, by kind.
+    pub reconcile_retries_total: IntCounterVec,
+
+    /// Child CronJob renderings by result, `Hit` when they were cached or
+    /// `Miss`.
+    pub render_cache_total: IntCounterVec,
+
+    /// Runs started through the trigger endpoint, by kind.
+    pub triggers_total: IntCounterVec,
+
+    /// Queue messages handled by the consumer, by outcome.
+    pub consumed_messages_total: IntCounterVec,
+
+    /// Events emitted, by kind and [`crate::Reason`].
+    pub events_total: IntCounterVec,
+
+    /// Runs failed for exceeding their hard timeout or stalling, by kind.
+    pub runs_timed_out_total: IntCounterVec,
+
+    /// Post-run checks by kind and result, `Passed` or `Failed`.
+    pub post_run_checks_total: IntCounterVec,
+
+    /// Seconds a child CronJob is past a fire time without having started a
+    /// run, by ScheduledCronJob and CronJob. 0 while on time or suspended.
+    pub run_overdue_seconds: GaugeVec,
+
+    /// 1 for each ScheduledCronJob with `spec.owner`, labelled with its
+    /// owner, for joining onto alerts.
+    pub owner_info: GaugeVec,
+
+    /// Label values of the `owner_info` series of each ScheduledCronJob.
+    owners: Arc<Mutex<HashMap<Key, [String; 5]>>>,
+
+    /// Recommended requests in cores or bytes, by ScheduledCronJob, container
+    /// and resource.
+    pub resource_recommendation: GaugeVec,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Metrics {
+    pub const NAMESPACE: &'static str = "scheduled";
+
+    pub fn new() -> Self {
+        let registry = Registry::new_custom(Some(Self::NAMESPACE.to_string()), None).unwrap();
+        let mut descriptions = Vec::new();
+        let mut register = |collector: Box<dyn Collector>| {
+            descriptions.extend(describe(collector.as_ref()));
+            registry.register(collector).unwrap();
+        };
+
+        let controller_last_seen = Gauge::with_opts(Opts::new(
+            "controller_last_seen",
+            "Unix time of the last successful controller heartbeat",
+        ))
+        .unwrap();
+        register(Box::new(controller_last_seen.clone()));
+
+        let repairs_total = IntCounterVec::new(
+            Opts::new(
+                "repairs_total",
+                "Resources whose stale phase was repaired by the sweep",
+            ),
+            &["kind"],
+        )
+        .unwrap();
+        register(Box::new(repairs_total.clone()));
+
+        let circuit_opens_total = IntCounterVec::new(
+            Opts::new(
+                "circuit_opens_total",
+                "Times a resource's circuit was opened after repeated failures",
+            ),
+            &["kind"],
+        )
+        .unwrap();
+        register(Box::new(circuit_opens_total.clone()));
+
+        let quarantined_resources = IntGauge::with_opts(Opts::new(
+            "quarantined_resources",
+            "Resources whose circuit is currently open",
+        ))
+        .unwrap();
+        register(Box::new(quarantined_resources.clone()));
+
+        let emergency_stop = IntGauge::with_opts(Opts::new(
+            "emergency_stop",
+            "1 while the emergency stop halts all mutating operations",
+        ))
+        .unwrap();
+        register(Box::new(emergency_stop.clone()));
+
+        let observer_actions_total = IntCounterVec::new(
+            Opts::new(
+                "observer_actions_total",
+                "Child writes skipped in observer mode",
+            ),
+            &["kind", "action"],
+        )
+        .unwrap();
+        register(Box::new(observer_actions_total.clone()));
+
+        let invariant_violations_total = IntCounterVec::new(
+            Opts::new(
+                "invariant_violations_total",
+                "Decisions breaking an invariant, found in observer mode",
+            ),
+            &["invariant"],
+        )
+        .unwrap();
+        register(Box::new(invariant_violations_total.clone()));
+
+        let queue_depth = IntGaugeVec::new(
+            Opts::new("queue_depth", "Requeues that are due but have not started"),
+            &["kind"],
+        )
+        .unwrap();
+        register(Box::new(queue_depth.clone()));
+
+        let queue_latency_seconds = HistogramVec::new(
+            HistogramOpts::new(
+                "queue_latency_seconds",
+                "Time from a requeue being due to its reconciliation starting",
+            ),
+            &["kind"],
+        )
+        .unwrap();
+        register(Box::new(queue_latency_seconds.clone()));
+
+        let reconcile_retries_total = IntCounterVec::new(
+            Opts::new(
+                "reconcile_retries_total",
+                "Reconciliations retried after a transient failure",
+            ),
+            &["kind"],
+        )
+        .unwrap();
+        register(Box::new(reconcile_retries_total.clone()));
+
+        let render_cache_total = IntCounterVec::new(
+            Opts::new(
+                "render_cache_total",
+                "Child CronJob renderings by whether they were cached",
+            ),
+            &["result"],
+        )
+        .unwrap();
+        register(Box::new(render_cache_total.clone()));
+
+        let triggers_total = IntCounterVec::new(
+            Opts::new(
+                "triggers_total",
+                "Runs started through the trigger endpoint",
+            ),
+            &["kind"],
+        )
+        .unwrap();
+        register(Box::new(triggers_total.clone()));
+
+        let consumed_messages_total = IntCounterVec::new(
+            Opts::new(
+                "consumed_messages_total",
+                "Queue messages handled by the consumer",
+            ),
+            &["outcome"],
+        )
+        .unwrap();
+        register(Box::new(consumed_messages_total.clone()));
+
+        let events_total = IntCounterVec::new(
+            Opts::new("events_total", "Events emitted, by reason"),
+            &["kind", "reason"],
+        )
+        .unwrap();
+        register(Box::new(events_total.clone()));
+
+        let runs_timed_out_total = IntCounterVec::new(
+            Opts::new(
+                "runs_timed_out_total",
+                "Runs failed for exceeding their hard timeout or stalling",
+            ),
+            &["kind"],
+        )
+        .unwrap();
+        register(Box::new(runs_timed_out_total.clone()));
+
+        let post_run_checks_total = IntCounterVec::new(
+            Opts::new(
+                "post_run_checks_total",
+                "Post-run checks of successful runs",
+            ),
+            &["kind", "result"],
+        )
+        .unwrap();
+        register(Box::new(post_run_checks_total.clone()));
+
+        let run_overdue_seconds = GaugeVec::new(
+            Opts::new(
+                "run_overdue_seconds",
+                "Seconds a CronJob is past a fire time without having started a run",
+            ),
+            &["namespace", "name", "cronjob"],
+        )
+        .unwrap();
+        register(Box::new(run_overdue_seconds.clone()));
+
+        let owner_info = GaugeVec::new(
+            Opts::new("owner_info", "On-call owner of a ScheduledCronJob"),
+            &[
+                "namespace",
+                "name",
+                "team",
+                "slack_channel",
+                "pagerduty_service",
+            ],
+        )
+        .unwrap();
+        register(Box::new(owner_info.clone()));
+
+        let resource_recommendation = GaugeVec::new(
+            Opts::new(
+                "resource_recommendation",
+                "Requests recommended by the usage of recent runs, in cores or bytes",
+            ),
+            &["namespace", "name", "container", "resource"],
+        )
+        .unwrap();
+        register(Box::new(resource_recommendation.clone()));
+
+        Self {
+            registry,
+            descriptions,
+            controller_last_seen,
+            repairs_total,
+            circuit_opens_total,
+            quarantined_resources,
+            emergency_stop,
+            observer_actions_total,
+            invariant_violations_total,
+            queue_depth,
+            queue_latency_seconds,
+            reconcile_retries_total,
+            render_cache_total,
+            triggers_total,
+            consumed_messages_total,
+            events_total,
+            runs_timed_out_total,
+            post_run_checks_total,
+            run_overdue_seconds,
+            owner_info,
+            owners: Default::default(),
+            resource_recommendation,
+        }
+    }
+
+    pub fn registry(&self) -> &Registry {
+        &self.registry
+    }
+
+    /// Every registered metric, including those without samples yet, which
+    /// [`Registry::gather`] leaves out.
+    pub fn descriptions(&self) -> &[MetricDescription] {
+        &self.descriptions
+    }
+
+    /// Sets `owner_info` of the ScheduledCronJob `namespace/name` to `owner`,
+    /// dropping the series of a previous owner.
+    pub fn set_owner(&self, namespace: &str, name: &str, owner: Option<&Owner>) {
+        let key = (namespace.to_string(), name.to_string());
+        let mut owners = self.owners.lock().unwrap();
+        let previous = match owner {
+            Some(o) => {
+                let values = [
+                    namespace.to_string(),
+                    name.to_string(),
+                    o.team.clone(),
+                    o.slack_channel.clone().unwrap_or_default(),
+                    o.pagerduty_service.clone().unwrap_or_default(),
+                ];
+                self.owner_info.with_label_values(&values).set(1.0);
+                owners.insert(key, values.clone()).filter(|p| *p != values)
+            }
+            None => owners.remove(&key),
+        };
+        if let Some(previous) = previous {
+            let _ = self.owner_info.remove_label_values(&previous);
+        }
+    }
+
+    /// Drops the `owner_info` series of the ScheduledCronJobs not in `keep`.
+    fn retain_owners(&self, keep: &HashSet<Key>) {
+        let mut owners = self.owners.lock().unwrap();
+        owners.retain(|key, values| {
+            let kept = keep.contains(key);
+            if !kept {
+                let _ = self.owner_info.remove_label_values(values);
+            }
+            kept
+        });
+    }
+
+    /// Renders all metrics in the Prometheus text exposition format.
+    pub fn encode(&self) -> String {
+        let mut buffer = Vec::new();
+        TextEncoder::new()
+            .encode(&self.registry.gather(), &mut buffer)
+            .unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+}
+
+/// Watches ScheduledCronJobs to drop the `owner_info` series of those that
+/// are deleted, including while the watch was down.
+pub async fn run(ctx: Arc<Context>) {
+    let metrics = ctx.metrics();
+    let mut listed = HashSet::new();
+    let mut events = ctx
+        .watch_metadata::<ScheduledCronJob>(None)
+        .default_backoff()
+        .boxed();
+    while let Some(event) = events.next().await {
+        match event {
+            Ok(Event::Init) => listed.clear(),
+            Ok(Event::InitApply(o)) => {
+                listed.insert((o.namespace().unwrap_or_default(), o.name_any()));
+            }
+            Ok(Event::InitDone) => metrics.retain_owners(&std::mem::take(&mut listed)),
+            Ok(Event::Apply(_)) => {}
+            Ok(Event::Delete(o)) => {
+                metrics.set_owner(&o.namespace().unwrap_or_default(), &o.name_any(), None)
+            }
+            Err(e) => tracing::warn!(error = ?e, "Failed to watch scheduled cronjobs for metrics"),
+        }
+    }
+}
+
+/// Name, type and labels of a registered metric.
+#[derive(Clone, Debug)]
+pub struct MetricDescription {
+    /// Full name, including the [`Metrics::NAMESPACE`] prefix.
+    pub name: String,
+    pub help: String,
+    pub metric_type: MetricType,
+    pub labels: Vec<String>,
+}
+
+fn describe(collector: &dyn Collector) -> Vec<MetricDescription> {
+    // Vectors without children still collect their family, with its type.
+    let types: Vec<_> = collector
+        .collect()
+        .into_iter()
+        .map(|f| f.get_field_type())
+        .collect();
+    collector
+        .desc()
+        .into_iter()
+        .zip(types)
+        .map(|(desc, metric_type)| MetricDescription {
+            name: format!("{}_{}", Metrics::NAMESPACE, desc.fq_name),
+            help: desc.help.clone(),
+            metric_type,
+            labels: desc.variable_labels.clone(),
+        })
+        .collect()
+}
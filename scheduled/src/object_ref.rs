@@ -0,0 +1,41 @@
+use k8s_openapi::api::core::v1::ObjectReference;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::OwnerReference;
+use kube::Resource;
+
+/// References to a resource, with its kind and apiVersion taken from its
+/// type rather than spelled out at each use.
+pub trait ObjectRefExt {
+    /// Reference for the `involvedObject` of events and status fields: kind,
+    /// apiVersion, namespace, name, uid and resourceVersion.
+    fn object_reference(&self) -> ObjectReference;
+
+    /// Controller owner reference for objects created on the resource's
+    /// behalf, `None` before it has been created and has no UID.
+    fn controller_reference(&self) -> Option<OwnerReference>;
+
+    /// [`ObjectRefExt::controller_reference`] as the `ownerReferences` of
+    /// metadata. Errors without a UID rather than leaving the child unowned,
+    /// where the garbage collector would never delete it.
+    fn controller_references(&self) -> Result<Vec<OwnerReference>, crate::Error>;
+}
+
+impl<K> ObjectRefExt for K
+where
+    K: Resource<DynamicType = ()>,
+{
+    fn object_reference(&self) -> ObjectReference {
+        self.object_ref(&())
+    }
+
+    fn controller_reference(&self) -> Option<OwnerReference> {
+        self.controller_owner_ref(&())
+    }
+
+    fn controller_references(&self) -> Result<Vec<OwnerReference>, crate::Error> {
+        let reference = self.controller_reference().ok_or_else(|| {
+            let name = self.meta().name.clone().unwrap_or_default();
+            crate::Error::Unowned(format!("{} {name}", K::kind(&())))
+        })?;
+        Ok(vec![reference])
+    }
+}
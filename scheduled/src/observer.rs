@@ -0,0 +1,24 @@
+use std::sync::{Arc, Mutex};
+
+/// Annotation summarizing the child writes an observer-mode controller
+/// skipped during the last reconciliation of a resource.
+pub const WOULD_APPLY_ANNOTATION: &str = "divinerapier.io/would-apply";
+
+tokio::task_local! {
+    static ACTIONS: Arc<Mutex<Vec<String>>>;
+}
+
+/// Runs `fut`, collecting every action passed to [`record`] while it runs.
+pub async fn collect<F: Future>(fut: F) -> (F::Output, Vec<String>) {
+    let actions = Arc::new(Mutex::new(Vec::new()));
+    let output = ACTIONS.scope(actions.clone(), fut).await;
+    let actions = std::mem::take(&mut *actions.lock().unwrap());
+    (output, actions)
+}
+
+/// Records an action that was skipped in observer mode. Outside of
+/// [`collect`] the action is only logged.
+pub fn record(action: String) {
+    tracing::info!(action, "Observer mode, skipping write");
+    let _ = ACTIONS.try_with(|actions| actions.lock().unwrap().push(action));
+}
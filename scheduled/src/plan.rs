@@ -0,0 +1,132 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use kube::ResourceExt as _;
+use kube::api::DynamicObject;
+use serde::Serialize;
+use serde_json::Value;
+
+/// Spec fields whose change alters when or what a resource acts on. Changing
+/// them, or deleting the resource, is reported as destructive.
+const DESTRUCTIVE_FIELDS: &[&str] = &[
+    "/spec/schedule",
+    "/spec/spec/schedule",
+    "/spec/suspendSchedule",
+    "/spec/resumeSchedule",
+    "/spec/startTime",
+    "/spec/endTime",
+    "/spec/target",
+    "/spec/targets",
+];
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum Action {
+    Create,
+    Update,
+    Delete,
+}
+
+/// A single change needed to make the cluster match the manifests.
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Operation {
+    pub action: Action,
+    pub api_version: String,
+    pub kind: String,
+    pub namespace: String,
+    pub name: String,
+    /// JSON pointers of the changed `spec` fields, for updates.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub changes: Vec<String>,
+    pub destructive: bool,
+}
+
+#[derive(Serialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Plan {
+    pub operations: Vec<Operation>,
+    pub destructive: usize,
+}
+
+type Key = (String, String, String, String);
+
+fn key(object: &DynamicObject) -> Key {
+    let types = object.types.clone().unwrap_or_default();
+    (
+        types.api_version,
+        types.kind,
+        object.namespace().unwrap_or_default(),
+        object.name_any(),
+    )
+}
+
+/// Computes the operations turning `live` into `desired`. Every live object
+/// without a desired counterpart is planned for deletion, so `live` should
+/// only hold objects the manifests are authoritative for.
+pub fn plan(desired: &[DynamicObject], live: &[DynamicObject]) -> Plan {
+    let desired: BTreeMap<Key, &DynamicObject> = desired.iter().map(|o| (key(o), o)).collect();
+    let live: BTreeMap<Key, &DynamicObject> = live.iter().map(|o| (key(o), o)).collect();
+    let keys: BTreeSet<&Key> = desired.keys().chain(live.keys()).collect();
+
+    let mut plan = Plan::default();
+    for key in keys {
+        let (action, changes) = match (desired.get(key), live.get(key)) {
+            (Some(_), None) => (Action::Create, Vec::new()),
+            (None, Some(_)) => (Action::Delete, Vec::new()),
+            (Some(desired), Some(live)) => {
+                let mut changes = Vec::new();
+                diff(
+                    "/spec",
+                    desired.data.get("spec").unwrap_or(&Value::Null),
+                    live.data.get("spec").unwrap_or(&Value::Null),
+                    &mut changes,
+                );
+                if changes.is_empty() {
+                    continue;
+                }
+                (Action::Update, changes)
+            }
+            (None, None) => unreachable!(),
+        };
+
+        let destructive = action == Action::Delete
+            || changes
+                .iter()
+                .any(|c| DESTRUCTIVE_FIELDS.iter().any(|f| c.starts_with(f)));
+        if destructive {
+            plan.destructive += 1;
+        }
+        let (api_version, kind, namespace, name) = key.clone();
+        plan.operations.push(Operation {
+            action,
+            api_version,
+            kind,
+            namespace,
+            name,
+            changes,
+            destructive,
+        });
+    }
+    plan
+}
+
+/// Collects the JSON pointers below `path` where `desired` and `live`
+/// differ. Fields only present in `live` are ignored, as they are usually
+/// defaulted by the API server.
+fn diff(path: &str, desired: &Value, live: &Value, changes: &mut Vec<String>) {
+    match (desired, live) {
+        (Value::Object(desired), Value::Object(live)) => {
+            for (field, value) in desired {
+                let path = format!("{path}/{}", field.replace('~', "~0").replace('/', "~1"));
+                diff(
+                    &path,
+                    value,
+                    live.get(field).unwrap_or(&Value::Null),
+                    changes,
+                );
+            }
+        }
+        (desired, live) if desired != live => changes.push(path.to_string()),
+        _ => {}
+    }
+}
@@ -0,0 +1,96 @@
+use std::sync::Arc;
+
+use k8s_openapi::api::batch::v1::Job;
+use k8s_openapi::api::core::v1::Pod;
+use kube::api::{ListParams, Patch};
+use kube::{Api, ResourceExt as _};
+
+use crate::Context;
+use crate::crd::PREEMPTION_RETRIES_LABEL;
+use crate::reason::Reason;
+
+/// Periodically fails running Jobs labelled with [`PREEMPTION_RETRIES_LABEL`]
+/// once more of their pods were preempted than the label allows. Their pod
+/// failure policy ignores preemptions, which Kubernetes cannot bound itself.
+/// Checks only run on the leader and are skipped while the emergency stop is
+/// engaged.
+pub async fn run(ctx: Arc<Context>) {
+    let mut interval = tokio::time::interval(ctx.config().repair_interval);
+    loop {
+        interval.tick().await;
+        if !ctx.leadership().is_leader() || ctx.emergency_stop().engaged() {
+            continue;
+        }
+        if let Err(e) = enforce(&ctx).await {
+            tracing::warn!(error = ?e, "Failed to enforce preemption retries");
+        }
+    }
+}
+
+async fn enforce(ctx: &Context) -> Result<(), crate::Error> {
+    let jobs = Api::<Job>::all((**ctx).clone());
+    let params = ListParams::default().labels(PREEMPTION_RETRIES_LABEL);
+    for job in jobs.list(&params).await? {
+        if finished(&job) {
+            continue;
+        }
+        let Some(limit) = job
+            .labels()
+            .get(PREEMPTION_RETRIES_LABEL)
+            .and_then(|v| v.parse::<usize>().ok())
+        else {
+            continue;
+        };
+        let namespace = job.namespace().unwrap_or_default();
+        let name = job.name_any();
+        let preempted = preempted_pods(ctx, &namespace, &name).await?;
+        if preempted <= limit {
+            continue;
+        }
+
+        tracing::warn!(name, namespace, preempted, limit, "Failing preempted job");
+        // An elapsed active deadline fails the Job and stops its pods.
+        let patch = serde_json::json!({ "spec": { "activeDeadlineSeconds": 1 } });
+        ctx.patch::<Job>(&namespace, &name, &Patch::Merge(patch))
+            .await?;
+        ctx.create_event(
+            &job,
+            Reason::PreemptionLimitExceeded,
+            &format!("{preempted} pods were preempted, more than the {limit} retries allowed"),
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+fn finished(job: &Job) -> bool {
+    job.status
+        .as_ref()
+        .and_then(|s| s.conditions.as_ref())
+        .is_some_and(|c| {
+            c.iter()
+                .any(|c| (c.type_ == "Complete" || c.type_ == "Failed") && c.status == "True")
+        })
+}
+
+/// Number of pods of the Job `name` that were evicted by a disruption, such
+/// as node preemption.
+async fn preempted_pods(ctx: &Context, namespace: &str, name: &str) -> Result<usize, crate::Error> {
+    let pods = Api::<Pod>::namespaced((**ctx).clone(), namespace);
+    let params = ListParams::default().labels(&format!("job-name={name}"));
+    let preempted = pods
+        .list(&params)
+        .await?
+        .into_iter()
+        .filter(|pod| {
+            pod.status
+                .as_ref()
+                .and_then(|s| s.conditions.as_ref())
+                .is_some_and(|c| {
+                    c.iter()
+                        .any(|c| c.type_ == "DisruptionTarget" && c.status == "True")
+                })
+        })
+        .count();
+    Ok(preempted)
+}
@@ -0,0 +1,166 @@
+//! Metadata-only lists in the API server's protobuf encoding.
+//!
+//! k8s-openapi only has serde codecs, so the native types stay JSON
+//! everywhere else. Metadata lists are the bulk of the controller's list
+//! traffic and only need `PartialObjectMetadataList`, which k8s-pb decodes;
+//! the result is converted back into the k8s-openapi types the rest of the
+//! controller uses. Custom resources cannot be served as protobuf, so the
+//! request also accepts JSON and whichever the server answers with is decoded.
+
+use std::collections::BTreeMap;
+
+use chrono::DateTime;
+use http::header::{ACCEPT, CONTENT_TYPE, HeaderValue};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::{ListMeta, ObjectMeta, OwnerReference, Time};
+use k8s_pb::apimachinery::pkg::apis::meta::v1 as pb;
+use k8s_pb::apimachinery::pkg::runtime::Unknown;
+use kube::api::{ListParams, ObjectList, PartialObjectMeta, PartialObjectMetaExt, TypeMeta};
+use kube::client::Body;
+use kube::core::{ErrorResponse, Request, Resource};
+use kube::{Client, Error as KubeError};
+use prost::Message;
+
+/// Media type of the protobuf encoding.
+const PROTOBUF: &str = "application/vnd.kubernetes.protobuf";
+
+/// Prefix of every protobuf-encoded body, before the `runtime.Unknown`
+/// envelope.
+const MAGIC: &[u8] = b"k8s\0";
+
+/// Protobuf first, JSON for the resources without a protobuf encoding.
+const ACCEPT_METADATA_LIST: &str = concat!(
+    "application/vnd.kubernetes.protobuf;as=PartialObjectMetadataList;g=meta.k8s.io;v=v1,",
+    "application/json;as=PartialObjectMetadataList;g=meta.k8s.io;v=v1",
+);
+
+/// One page of the metadata of objects of kind `K`, in `namespace` or across
+/// all namespaces, like [`kube::Api::list_metadata`] but preferring protobuf.
+pub async fn list_metadata<K>(
+    client: &Client,
+    namespace: Option<&str>,
+    params: &ListParams,
+) -> Result<ObjectList<PartialObjectMeta<K>>, crate::Error>
+where
+    K: Resource + Clone,
+    K::DynamicType: Default,
+{
+    let url = K::url_path(&K::DynamicType::default(), namespace);
+    let mut request = Request::new(url)
+        .list_metadata(params)
+        .map_err(KubeError::BuildRequest)?;
+    request
+        .headers_mut()
+        .insert(ACCEPT, HeaderValue::from_static(ACCEPT_METADATA_LIST));
+    let response = client.send(request.map(Body::from)).await?;
+    let status = response.status();
+    let encoded = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|t| t.to_str().ok())
+        .is_some_and(|t| t.starts_with(PROTOBUF));
+    let body = response.into_body().collect_bytes().await?;
+
+    if status.is_client_error() || status.is_server_error() {
+        let error = if encoded {
+            let status = pb::Status::decode(unwrap(&body)?.as_slice())?;
+            ErrorResponse {
+                status: status.status.unwrap_or_default(),
+                message: status.message.unwrap_or_default(),
+                reason: status.reason.unwrap_or_default(),
+                code: status.code.map_or(0, |c| c as u16),
+            }
+        } else {
+            serde_json::from_slice(&body).unwrap_or_else(|_| ErrorResponse {
+                status: status.to_string(),
+                message: String::from_utf8_lossy(&body).into_owned(),
+                reason: "Failed to parse error data".to_string(),
+                code: status.as_u16(),
+            })
+        };
+        return Err(KubeError::Api(error).into());
+    }
+    if !encoded {
+        return Ok(serde_json::from_slice(&body)?);
+    }
+    decode_list(&body)
+}
+
+/// Decodes a protobuf-encoded `PartialObjectMetadataList`.
+pub fn decode_list<K: Clone>(
+    body: &[u8],
+) -> Result<ObjectList<PartialObjectMeta<K>>, crate::Error> {
+    let list = pb::PartialObjectMetadataList::decode(unwrap(body)?.as_slice())?;
+    Ok(ObjectList {
+        types: TypeMeta {
+            api_version: "meta.k8s.io/v1".to_string(),
+            kind: "PartialObjectMetadataList".to_string(),
+        },
+        metadata: list.metadata.map(list_meta).unwrap_or_default(),
+        items: list
+            .items
+            .into_iter()
+            .map(|item| object_meta(item.metadata.unwrap_or_default()).into_response_partial())
+            .collect(),
+    })
+}
+
+/// The serialized object inside the `runtime.Unknown` envelope of `body`.
+fn unwrap(body: &[u8]) -> Result<Vec<u8>, crate::Error> {
+    let envelope = body.strip_prefix(MAGIC).unwrap_or(body);
+    Ok(Unknown::decode(envelope)?.raw.unwrap_or_default())
+}
+
+fn list_meta(meta: pb::ListMeta) -> ListMeta {
+    ListMeta {
+        continue_: meta.r#continue,
+        remaining_item_count: meta.remaining_item_count,
+        resource_version: meta.resource_version,
+        self_link: meta.self_link,
+    }
+}
+
+/// `meta` without its managed fields, which metadata lists are not asked for.
+fn object_meta(meta: pb::ObjectMeta) -> ObjectMeta {
+    ObjectMeta {
+        annotations: non_empty(meta.annotations),
+        creation_timestamp: meta.creation_timestamp.and_then(time),
+        deletion_grace_period_seconds: meta.deletion_grace_period_seconds,
+        deletion_timestamp: meta.deletion_timestamp.and_then(time),
+        finalizers: (!meta.finalizers.is_empty()).then_some(meta.finalizers),
+        generate_name: meta.generate_name,
+        generation: meta.generation,
+        labels: non_empty(meta.labels),
+        managed_fields: None,
+        name: meta.name,
+        namespace: meta.namespace,
+        owner_references: (!meta.owner_references.is_empty()).then(|| {
+            meta.owner_references
+                .into_iter()
+                .map(owner_reference)
+                .collect()
+        }),
+        resource_version: meta.resource_version,
+        self_link: meta.self_link,
+        uid: meta.uid,
+    }
+}
+
+fn owner_reference(owner: pb::OwnerReference) -> OwnerReference {
+    OwnerReference {
+        api_version: owner.api_version.unwrap_or_default(),
+        block_owner_deletion: owner.block_owner_deletion,
+        controller: owner.controller,
+        kind: owner.kind.unwrap_or_default(),
+        name: owner.name.unwrap_or_default(),
+        uid: owner.uid.unwrap_or_default(),
+    }
+}
+
+fn time(time: pb::Time) -> Option<Time> {
+    let nanos = time.nanos.unwrap_or_default().try_into().ok()?;
+    DateTime::from_timestamp(time.seconds.unwrap_or_default(), nanos).map(Time)
+}
+
+fn non_empty(map: BTreeMap<String, String>) -> Option<BTreeMap<String, String>> {
+    (!map.is_empty()).then_some(map)
+}
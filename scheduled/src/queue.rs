@@ -0,0 +1,61 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use kube::Resource;
+
+use crate::throttle::{ResourceKey, resource_key};
+
+/// Requeues overdue by more than this are assumed to belong to deleted
+/// resources, which the runtime never reconciles again, and are dropped.
+const STALE_AFTER: Duration = Duration::from_secs(300);
+
+/// Tracks when each resource's requeue is due, so the wrapper around the
+/// controller runtime can report how long due work waits before it starts.
+#[derive(Default)]
+pub struct QueueTracker {
+    due: Mutex<HashMap<ResourceKey, Instant>>,
+}
+
+impl QueueTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records that `resource` was requeued to run again after `after`.
+    pub fn schedule<K>(&self, resource: &K, after: Duration)
+    where
+        K: Resource<DynamicType = ()>,
+    {
+        self.due
+            .lock()
+            .unwrap()
+            .insert(resource_key(resource), Instant::now() + after);
+    }
+
+    /// Records that a reconciliation of `resource` started. Returns how long
+    /// it waited past its due time, or `None` when it was not a due requeue,
+    /// e.g. a watch event arrived first.
+    pub fn start<K>(&self, resource: &K) -> Option<Duration>
+    where
+        K: Resource<DynamicType = ()>,
+    {
+        let due = self.due.lock().unwrap().remove(&resource_key(resource))?;
+        Instant::now().checked_duration_since(due)
+    }
+
+    /// Due requeues that have not started yet, by kind.
+    pub fn depth(&self) -> BTreeMap<String, usize> {
+        let now = Instant::now();
+        let mut due = self.due.lock().unwrap();
+        due.retain(|_, t| now.saturating_duration_since(*t) < STALE_AFTER);
+
+        let mut depth = BTreeMap::new();
+        for ((kind, _, _), t) in due.iter() {
+            if *t <= now {
+                *depth.entry(kind.clone()).or_default() += 1;
+            }
+        }
+        depth
+    }
+}
@@ -30,6 +30,136 @@ pub fn get_rbac_rules() -> HashMap<String, RbacRule> {
         },
     );
 
+    // ScheduledPatch rules
+    rules.insert(
+        "ScheduledPatch".to_string(),
+        RbacRule {
+            name: "ScheduledPatch".to_string(),
+            api_groups: Some(vec!["batch.divinerapier.io".to_string()]),
+            resources: Some(vec![
+                "scheduledpatches".to_string(),
+                "scheduledpatches/status".to_string(),
+            ]),
+            verbs: vec![
+                "get".to_string(),
+                "list".to_string(),
+                "watch".to_string(),
+                "update".to_string(),
+                "patch".to_string(),
+            ],
+        },
+    );
+
+    // ScheduledSuspend rules
+    rules.insert(
+        "ScheduledSuspend".to_string(),
+        RbacRule {
+            name: "ScheduledSuspend".to_string(),
+            api_groups: Some(vec!["batch.divinerapier.io".to_string()]),
+            resources: Some(vec![
+                "scheduledsuspends".to_string(),
+                "scheduledsuspends/status".to_string(),
+            ]),
+            verbs: vec![
+                "get".to_string(),
+                "list".to_string(),
+                "watch".to_string(),
+                "update".to_string(),
+                "patch".to_string(),
+            ],
+        },
+    );
+
+    // ControllerConfiguration rules, to watch the settings overriding the
+    // environment
+    rules.insert(
+        "ControllerConfiguration".to_string(),
+        RbacRule {
+            name: "ControllerConfiguration".to_string(),
+            api_groups: Some(vec!["batch.divinerapier.io".to_string()]),
+            resources: Some(vec!["controllerconfigurations".to_string()]),
+            verbs: vec!["get".to_string(), "list".to_string(), "watch".to_string()],
+        },
+    );
+
+    // ScheduleCalendar rules
+    rules.insert(
+        "ScheduleCalendar".to_string(),
+        RbacRule {
+            name: "ScheduleCalendar".to_string(),
+            api_groups: Some(vec!["batch.divinerapier.io".to_string()]),
+            resources: Some(vec!["schedulecalendars".to_string()]),
+            verbs: vec!["get".to_string(), "list".to_string(), "watch".to_string()],
+        },
+    );
+
+    // TimerTrigger rules
+    rules.insert(
+        "TimerTrigger".to_string(),
+        RbacRule {
+            name: "TimerTrigger".to_string(),
+            api_groups: Some(vec!["batch.divinerapier.io".to_string()]),
+            resources: Some(vec![
+                "timertriggers".to_string(),
+                "timertriggers/status".to_string(),
+            ]),
+            verbs: vec![
+                "get".to_string(),
+                "list".to_string(),
+                "watch".to_string(),
+                "update".to_string(),
+                "patch".to_string(),
+            ],
+        },
+    );
+
+    // Targets toggled by ScheduledSuspend
+    rules.insert(
+        "ScheduledSuspendTarget".to_string(),
+        RbacRule {
+            name: "ScheduledSuspendTarget".to_string(),
+            api_groups: Some(vec![
+                "batch".to_string(),
+                "kustomize.toolkit.fluxcd.io".to_string(),
+                "helm.toolkit.fluxcd.io".to_string(),
+                "argoproj.io".to_string(),
+            ]),
+            resources: Some(vec![
+                "jobs".to_string(),
+                "kustomizations".to_string(),
+                "helmreleases".to_string(),
+                "cronworkflows".to_string(),
+            ]),
+            verbs: vec!["get".to_string(), "patch".to_string()],
+        },
+    );
+
+    // Targets patched by ScheduledPatch
+    rules.insert(
+        "ScheduledPatchTarget".to_string(),
+        RbacRule {
+            name: "ScheduledPatchTarget".to_string(),
+            api_groups: Some(vec!["apps".to_string()]),
+            resources: Some(vec!["deployments".to_string(), "statefulsets".to_string()]),
+            verbs: vec!["get".to_string(), "patch".to_string()],
+        },
+    );
+
+    // Metrics read by fire conditions and right-sizing
+    rules.insert(
+        "Metrics".to_string(),
+        RbacRule {
+            name: "Metrics".to_string(),
+            api_groups: Some(vec![
+                "custom.metrics.k8s.io".to_string(),
+                "external.metrics.k8s.io".to_string(),
+                "metrics.k8s.io".to_string(),
+            ]),
+            resources: Some(vec!["*".to_string()]),
+            verbs: vec!["get".to_string(), "list".to_string()],
+        },
+    );
+
     // CronJob rules
     rules.insert(
         "CronJob".to_string(),
@@ -49,12 +179,167 @@ pub fn get_rbac_rules() -> HashMap<String, RbacRule> {
         },
     );
 
+    // ServiceAccount rules, to provision accounts for ScheduledCronJob pods
+    rules.insert(
+        "ServiceAccount".to_string(),
+        RbacRule {
+            name: "ServiceAccount".to_string(),
+            api_groups: Some(vec!["".to_string()]),
+            resources: Some(vec!["serviceaccounts".to_string()]),
+            verbs: vec![
+                "get".to_string(),
+                "list".to_string(),
+                "create".to_string(),
+                "patch".to_string(),
+                "delete".to_string(),
+            ],
+        },
+    );
+
+    // Role rules, to grant provisioned accounts the rules a ScheduledCronJob
+    // asks for. Without `escalate` and `bind` the API server only admits
+    // rules the controller holds itself.
+    rules.insert(
+        "Role".to_string(),
+        RbacRule {
+            name: "Role".to_string(),
+            api_groups: Some(vec!["rbac.authorization.k8s.io".to_string()]),
+            resources: Some(vec!["roles".to_string(), "rolebindings".to_string()]),
+            verbs: vec![
+                "get".to_string(),
+                "list".to_string(),
+                "create".to_string(),
+                "patch".to_string(),
+                "delete".to_string(),
+            ],
+        },
+    );
+
+    // NetworkPolicy rules, to restrict the egress of ScheduledCronJob pods
+    rules.insert(
+        "NetworkPolicy".to_string(),
+        RbacRule {
+            name: "NetworkPolicy".to_string(),
+            api_groups: Some(vec!["networking.k8s.io".to_string()]),
+            resources: Some(vec!["networkpolicies".to_string()]),
+            verbs: vec![
+                "get".to_string(),
+                "list".to_string(),
+                "create".to_string(),
+                "patch".to_string(),
+                "delete".to_string(),
+            ],
+        },
+    );
+
+    // Node rules, to defer runs no schedulable node can take
+    rules.insert(
+        "Node".to_string(),
+        RbacRule {
+            name: "Node".to_string(),
+            api_groups: Some(vec!["".to_string()]),
+            resources: Some(vec!["nodes".to_string()]),
+            verbs: vec!["list".to_string()],
+        },
+    );
+
+    // Pod rules, to count the preemptions of runs
+    rules.insert(
+        "Pod".to_string(),
+        RbacRule {
+            name: "Pod".to_string(),
+            api_groups: Some(vec!["".to_string()]),
+            resources: Some(vec!["pods".to_string()]),
+            verbs: vec!["list".to_string()],
+        },
+    );
+
+    // Lease rules
+    rules.insert(
+        "Lease".to_string(),
+        RbacRule {
+            name: "Lease".to_string(),
+            api_groups: Some(vec!["coordination.k8s.io".to_string()]),
+            resources: Some(vec!["leases".to_string()]),
+            verbs: vec![
+                "get".to_string(),
+                "create".to_string(),
+                "update".to_string(),
+                "patch".to_string(),
+            ],
+        },
+    );
+
+    // Namespace rules, to stop creating children in terminating namespaces
+    rules.insert(
+        "Namespace".to_string(),
+        RbacRule {
+            name: "Namespace".to_string(),
+            api_groups: Some(vec!["".to_string()]),
+            resources: Some(vec!["namespaces".to_string()]),
+            verbs: vec!["get".to_string(), "list".to_string(), "watch".to_string()],
+        },
+    );
+
+    // ResourceQuota rules, to provision the quota of tenant namespaces
+    rules.insert(
+        "ResourceQuota".to_string(),
+        RbacRule {
+            name: "ResourceQuota".to_string(),
+            api_groups: Some(vec!["".to_string()]),
+            resources: Some(vec!["resourcequotas".to_string()]),
+            verbs: vec![
+                "get".to_string(),
+                "list".to_string(),
+                "create".to_string(),
+                "patch".to_string(),
+                "delete".to_string(),
+            ],
+        },
+    );
+
+    // ConfigMap rules, to watch the control ConfigMap
+    rules.insert(
+        "ConfigMap".to_string(),
+        RbacRule {
+            name: "ConfigMap".to_string(),
+            api_groups: Some(vec!["".to_string()]),
+            resources: Some(vec!["configmaps".to_string()]),
+            verbs: vec!["get".to_string(), "list".to_string(), "watch".to_string()],
+        },
+    );
+
+    // CustomResourceDefinition rules, to verify compatibility before leading
+    rules.insert(
+        "CustomResourceDefinition".to_string(),
+        RbacRule {
+            name: "CustomResourceDefinition".to_string(),
+            api_groups: Some(vec!["apiextensions.k8s.io".to_string()]),
+            resources: Some(vec!["customresourcedefinitions".to_string()]),
+            verbs: vec!["get".to_string()],
+        },
+    );
+
+    // Trigger rules, to fire runs and instantiate templates on request
+    rules.insert(
+        "Trigger".to_string(),
+        RbacRule {
+            name: "Trigger".to_string(),
+            api_groups: Some(vec![
+                "batch".to_string(),
+                "batch.divinerapier.io".to_string(),
+            ]),
+            resources: Some(vec!["jobs".to_string(), "delayedjobs".to_string()]),
+            verbs: vec!["create".to_string()],
+        },
+    );
+
     // Event rules
     rules.insert(
         "Event".to_string(),
         RbacRule {
             name: "Event".to_string(),
-            api_groups: Some(vec!["".to_string()]),
+            api_groups: Some(vec!["".to_string(), "events.k8s.io".to_string()]),
             resources: Some(vec!["events".to_string()]),
             verbs: vec!["create".to_string(), "patch".to_string()],
         },
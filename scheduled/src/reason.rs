@@ -0,0 +1,180 @@
+use std::fmt;
+
+/// Stable identifiers for why something happened, shared by event reasons,
+/// condition reasons and the `reason` label of `scheduled_events_total`, so
+/// alerting can key off them instead of messages.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Reason {
+    /// A schedule or cron expression cannot be parsed.
+    InvalidSchedule,
+    /// The start and end times are invalid or too close together.
+    InvalidTimeRange,
+    /// The spec is otherwise invalid.
+    InvalidSpec,
+    /// A target cannot be resolved or modified.
+    InvalidTarget,
+    /// A fire condition could not be evaluated.
+    ConditionFailed,
+    /// The resource waits for its window to open.
+    WindowNotOpen,
+    /// The window is open and the resource is acting on its schedule.
+    Activated,
+    /// A child object was created.
+    ChildCreated,
+    /// Every child, or a target, is suspended.
+    Suspended,
+    /// A suspended target was resumed.
+    Resumed,
+    /// The window ended.
+    Expired,
+    /// The resource finished its work.
+    Completed,
+    /// A scheduled action ran.
+    Fired,
+    /// A target was patched.
+    Patched,
+    /// A run was skipped because its fire condition was not met.
+    SkippedByCondition,
+    /// A Job failed.
+    JobFailed,
+    /// A phase contradicting the cluster was reset by the sweep.
+    DriftRepaired,
+    /// A create was rejected by a ResourceQuota.
+    QuotaExceeded,
+    /// A call to the Kubernetes API or another service failed.
+    ApiError,
+    /// The namespace is being deleted.
+    NamespaceTerminating,
+    /// Mutating operations are halted by the emergency stop.
+    EmergencyStop,
+    /// Reconciliation is paused after repeated failures.
+    CircuitOpen,
+    /// The failure state was reset by annotation.
+    CircuitReset,
+    /// Reconciliation succeeded after the circuit was opened.
+    Recovered,
+    /// Child writes were skipped in observer mode.
+    WouldApply,
+    /// The spec has lint findings.
+    LintFindings,
+    /// The spec has no lint findings.
+    LintClean,
+    /// A run is deferred because no schedulable node matches its pod.
+    NoCapacity,
+    /// Requests were adjusted to the usage of recent runs.
+    Resized,
+    /// A run was failed after more preemptions than it may retry.
+    PreemptionLimitExceeded,
+    /// A run was failed for exceeding its hard timeout.
+    TimedOut,
+    /// A run was failed after its heartbeat stopped.
+    Stalled,
+    /// A successful run failed its post-run check.
+    CheckFailed,
+    /// A one-off run was started for `spec.adaptive`.
+    AdaptiveRun,
+    /// A run was skipped or failed because `startingDeadlineSeconds` or
+    /// `deadlineSeconds` passed.
+    DeadlineMissed,
+    /// The API server is too old for a feature of the spec.
+    FeatureUnsupported,
+    /// A run waits for the job it runs after to succeed.
+    DependencyPending,
+    /// The job a run was to run after failed.
+    DependencyFailed,
+    /// A DelayedJob was to run after itself.
+    SelfDependency,
+    /// `spec.serviceAccount.rules` grant more than the controller allows.
+    RulesNotAllowed,
+}
+
+impl Reason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Reason::InvalidSchedule => "InvalidSchedule",
+            Reason::InvalidTimeRange => "InvalidTimeRange",
+            Reason::InvalidSpec => "InvalidSpec",
+            Reason::InvalidTarget => "InvalidTarget",
+            Reason::ConditionFailed => "ConditionFailed",
+            Reason::WindowNotOpen => "WindowNotOpen",
+            Reason::Activated => "Activated",
+            Reason::ChildCreated => "ChildCreated",
+            Reason::Suspended => "Suspended",
+            Reason::Resumed => "Resumed",
+            Reason::Expired => "Expired",
+            Reason::Completed => "Completed",
+            Reason::Fired => "Fired",
+            Reason::Patched => "Patched",
+            Reason::SkippedByCondition => "SkippedByCondition",
+            Reason::JobFailed => "JobFailed",
+            Reason::DriftRepaired => "DriftRepaired",
+            Reason::QuotaExceeded => "QuotaExceeded",
+            Reason::ApiError => "ApiError",
+            Reason::NamespaceTerminating => "NamespaceTerminating",
+            Reason::EmergencyStop => "EmergencyStop",
+            Reason::CircuitOpen => "CircuitOpen",
+            Reason::CircuitReset => "CircuitReset",
+            Reason::Recovered => "Recovered",
+            Reason::WouldApply => "WouldApply",
+            Reason::LintFindings => "LintFindings",
+            Reason::LintClean => "LintClean",
+            Reason::NoCapacity => "NoCapacity",
+            Reason::Resized => "Resized",
+            Reason::PreemptionLimitExceeded => "PreemptionLimitExceeded",
+            Reason::TimedOut => "TimedOut",
+            Reason::Stalled => "Stalled",
+            Reason::CheckFailed => "CheckFailed",
+            Reason::AdaptiveRun => "AdaptiveRun",
+            Reason::DeadlineMissed => "DeadlineMissed",
+            Reason::FeatureUnsupported => "FeatureUnsupported",
+            Reason::DependencyPending => "DependencyPending",
+            Reason::DependencyFailed => "DependencyFailed",
+            Reason::SelfDependency => "SelfDependency",
+            Reason::RulesNotAllowed => "RulesNotAllowed",
+        }
+    }
+
+    /// The type of events with this reason, `Normal` or `Warning`.
+    pub fn event_type(&self) -> &'static str {
+        match self {
+            Reason::InvalidSchedule
+            | Reason::InvalidTimeRange
+            | Reason::InvalidSpec
+            | Reason::InvalidTarget
+            | Reason::ConditionFailed
+            | Reason::JobFailed
+            | Reason::DriftRepaired
+            | Reason::QuotaExceeded
+            | Reason::ApiError
+            | Reason::CircuitOpen
+            | Reason::LintFindings
+            | Reason::NoCapacity
+            | Reason::PreemptionLimitExceeded
+            | Reason::TimedOut
+            | Reason::Stalled
+            | Reason::CheckFailed
+            | Reason::DeadlineMissed
+            | Reason::FeatureUnsupported
+            | Reason::DependencyFailed
+            | Reason::SelfDependency
+            | Reason::RulesNotAllowed => "Warning",
+            _ => "Normal",
+        }
+    }
+
+    /// The reason for a failed Kubernetes API call.
+    pub fn of_kube_error(error: &kube::Error) -> Self {
+        match error {
+            kube::Error::Api(e) if e.code == 403 && e.message.contains("exceeded quota") => {
+                Reason::QuotaExceeded
+            }
+            _ => Reason::ApiError,
+        }
+    }
+}
+
+impl fmt::Display for Reason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
@@ -0,0 +1,144 @@
+use std::time::Duration;
+
+use k8s_openapi::apiextensions_apiserver::pkg::apis::apiextensions::v1::CustomResourceDefinition;
+use kube::api::{DeleteParams, DynamicObject, Patch, PatchParams};
+use kube::core::GroupVersion;
+use kube::discovery::{self, Scope};
+use kube::runtime::wait::{await_condition, conditions};
+use kube::{Api, Error as KubeError, ResourceExt as _};
+
+use super::Context;
+
+pub(super) const FIELD_MANAGER: &str = "scheduled-cronjob";
+
+/// How long to wait for an applied CRD to be served before applying the
+/// custom resources that depend on it.
+const CRD_ESTABLISH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Apply order: definitions first, then the permissions to use them, then
+/// everything else.
+fn rank(kind: &str) -> u8 {
+    match kind {
+        "CustomResourceDefinition" => 0,
+        "Namespace" => 1,
+        "ServiceAccount" | "ClusterRole" | "ClusterRoleBinding" | "Role" | "RoleBinding" => 2,
+        _ => 3,
+    }
+}
+
+impl Context {
+    /// Server-side applies `objects` in dependency order (CRDs, then RBAC,
+    /// then the rest). When one fails, the objects applied before it are
+    /// rolled back on a best-effort basis: created ones are deleted and
+    /// updated ones are re-applied in their previous state.
+    pub async fn apply_all(
+        &self,
+        mut objects: Vec<DynamicObject>,
+    ) -> Result<Vec<DynamicObject>, crate::Error> {
+        if self.emergency_stop().engaged() {
+            return Err(crate::Error::EmergencyStop);
+        }
+        objects.sort_by_key(|o| rank(&kind_of(o)));
+
+        let mut applied = Vec::new();
+        let mut undo = Vec::new();
+        for object in &objects {
+            match self.apply_one(object).await {
+                Ok((api, previous, result)) => {
+                    undo.push((api, result.name_any(), previous));
+                    applied.push(result);
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        kind = kind_of(object),
+                        name = object.name_any(),
+                        error = ?e,
+                        "Apply failed, rolling back"
+                    );
+                    // Dry runs in observer mode left nothing to roll back.
+                    if !self.config().observer {
+                        self.rollback(undo).await;
+                    }
+                    return Err(e);
+                }
+            }
+        }
+        Ok(applied)
+    }
+
+    /// Applies `object`, returning its API, its state before the apply and
+    /// the applied result.
+    async fn apply_one(
+        &self,
+        object: &DynamicObject,
+    ) -> Result<(Api<DynamicObject>, Option<DynamicObject>, DynamicObject), crate::Error> {
+        let types = object.types.as_ref().ok_or_else(|| {
+            crate::Error::InvalidTarget(format!("{} has no apiVersion/kind", object.name_any()))
+        })?;
+        let gvk = types
+            .api_version
+            .parse::<GroupVersion>()
+            .map_err(|e| crate::Error::InvalidTarget(e.to_string()))?
+            .with_kind(&types.kind);
+        let (resource, capabilities) = discovery::pinned_kind(self, &gvk).await?;
+        let api = match capabilities.scope {
+            Scope::Cluster => Api::<DynamicObject>::all_with((**self).clone(), &resource),
+            Scope::Namespaced => {
+                let namespace = object.namespace().unwrap_or("default".to_string());
+                Api::namespaced_with((**self).clone(), &namespace, &resource)
+            }
+        };
+
+        let name = object.name_any();
+        let previous = api.get_opt(&name).await?;
+        let mut params = PatchParams::apply(FIELD_MANAGER).force();
+        params.dry_run = self.config().observer;
+        let result = api.patch(&name, &params, &Patch::Apply(object)).await?;
+
+        if types.kind == "CustomResourceDefinition" && !params.dry_run {
+            let crds = Api::<CustomResourceDefinition>::all((**self).clone());
+            let established = await_condition(crds, &name, conditions::is_crd_established());
+            if tokio::time::timeout(CRD_ESTABLISH_TIMEOUT, established)
+                .await
+                .is_err()
+            {
+                tracing::warn!(name, "Timed out waiting for CRD to be established");
+            }
+        }
+        Ok((api, previous, result))
+    }
+
+    async fn rollback(&self, undo: Vec<(Api<DynamicObject>, String, Option<DynamicObject>)>) {
+        for (api, name, previous) in undo.into_iter().rev() {
+            let result = match previous {
+                None => match api.delete(&name, &DeleteParams::foreground()).await {
+                    Err(KubeError::Api(e)) if e.code == 404 => Ok(()),
+                    result => result.map(|_| ()),
+                },
+                Some(mut previous) => {
+                    previous.metadata.managed_fields = None;
+                    previous.metadata.resource_version = None;
+                    previous.metadata.uid = None;
+                    if let Some(data) = previous.data.as_object_mut() {
+                        data.remove("status");
+                    }
+                    let params = PatchParams::apply(FIELD_MANAGER).force();
+                    api.patch(&name, &params, &Patch::Apply(&previous))
+                        .await
+                        .map(|_| ())
+                }
+            };
+            if let Err(e) = result {
+                tracing::warn!(name, error = ?e, "Failed to roll back applied object");
+            }
+        }
+    }
+}
+
+fn kind_of(object: &DynamicObject) -> String {
+    object
+        .types
+        .as_ref()
+        .map(|t| t.kind.clone())
+        .unwrap_or_default()
+}
@@ -1,30 +1,285 @@
+use std::collections::BTreeMap;
 use std::ops::Deref;
+use std::pin::pin;
+use std::sync::{Arc, PoisonError, RwLock};
+use std::time::Duration;
 
 use crate::ScheduledCronJobStatus;
+use crate::breaker::CircuitBreaker;
+use crate::bus::{self, Publisher, RUNS_TOPIC, TRANSITIONS_TOPIC};
+use crate::cache::Cache;
+use crate::capacity;
+use crate::cloudevents::{CloudEvent, Transition};
+use crate::compat::{EventsApi, ServerVersion};
+use crate::config::Config;
 use crate::crd::{
-    DelayedJob, DelayedJobPhase, DelayedJobStatus, ScheduledCronJob, ScheduledCronJobPhase,
+    ALLOW_TARGETS_FROM_ANNOTATION, ControllerConfigurationSpec, DETAIL_LAST_ERROR, FireCondition,
+    HasConditions, HasOwner, MetricsApiQuery, Owner, PrometheusQuery, parse_quantity,
+    set_condition, truncate_message,
 };
-use chrono::Utc;
+use crate::crd::{
+    DelayedJob, DelayedJobPhase, DelayedJobStatus, ManagedResource, ResourceRecommendation,
+    ScheduleCalendar, ScheduleStatus, ScheduledCronJob, ScheduledCronJobPhase, ScheduledPatch,
+    ScheduledPatchPhase, ScheduledPatchStatus, ScheduledSuspend, ScheduledSuspendPhase,
+    ScheduledSuspendStatus, TargetRef, TimerTrigger, TimerTriggerPhase, TimerTriggerStatus,
+    VariantStatus, Webhook,
+};
+use crate::emergency::EmergencyStop;
+use crate::history::{self, HistorySink, RunRecord};
+use crate::hooks::{ChildCreated, PhaseChange, ReconcileHooks};
+use crate::leader::Leadership;
+use crate::loglevel::LogLevel;
+use crate::metrics::Metrics;
+use crate::object_ref::ObjectRefExt as _;
+use crate::observer;
+use crate::protobuf;
+use crate::queue::QueueTracker;
+use crate::reason::Reason;
+use crate::reconciler::apply::FIELD_MANAGER;
+use crate::render::RenderCache;
+use crate::throttle::LogThrottle;
+use chrono::{DateTime, Utc};
+use futures::{Stream, TryStreamExt as _, stream};
 use k8s_openapi::NamespaceResourceScope;
-use k8s_openapi::api::batch::v1::CronJob;
-use k8s_openapi::api::core::v1::{Event, EventSeries};
+use k8s_openapi::api::batch::v1::{CronJob, Job};
+use k8s_openapi::api::core::v1::{Event, EventSeries, Namespace, Node, PodSpec};
+use k8s_openapi::api::events;
 use k8s_openapi::apimachinery::pkg::apis::meta::v1::{MicroTime, ObjectMeta, Time};
 use kube::ResourceExt;
-use kube::api::{DeleteParams, PostParams};
+use kube::api::{
+    DeleteParams, DynamicObject, ListParams, ObjectList, PartialObjectMeta, Patch, PatchParams,
+    PostParams,
+};
 use kube::core::Resource as KubeResource;
 use kube::core::object::HasStatus;
-use kube::{Api, Client, Error as KubeError};
+use kube::runtime::controller::Action;
+use kube::runtime::watcher;
+use kube::{Api, Client, Error as KubeError, discovery};
 use serde::Serialize;
 use serde::de::DeserializeOwned;
 use serde_json;
 
 pub struct Context {
+    /// Objects are read and written as JSON, the only codec of k8s-openapi;
+    /// metadata-only lists are negotiated as protobuf, see [`crate::protobuf`].
     client: Client,
+    http: reqwest::Client,
+    /// Settings read from the environment at startup.
+    base_config: Config,
+    /// `base_config` with the overrides of the ControllerConfiguration, see
+    /// [`crate::configuration::run`].
+    config: RwLock<Arc<Config>>,
+    metrics: Metrics,
+    log_throttle: LogThrottle,
+    render_cache: RenderCache,
+    calendars: Cache<ScheduleCalendar>,
+    namespaces: Cache<Namespace>,
+    circuit_breaker: CircuitBreaker,
+    queue: QueueTracker,
+    emergency_stop: EmergencyStop,
+    leadership: Leadership,
+    log_level: Option<LogLevel>,
+    history: Option<Arc<dyn HistorySink>>,
+    bus: Option<Arc<dyn Publisher>>,
+    hooks: Option<Arc<dyn ReconcileHooks>>,
+    /// Release of the API server, read at startup. Child manifests leave out
+    /// what it does not support, see [`crate::compat`].
+    server_version: Option<ServerVersion>,
+    /// API Events are recorded through, discovered at startup.
+    events_api: EventsApi,
 }
 
 impl Context {
     pub fn new(client: Client) -> Self {
-        Self { client }
+        Self {
+            client,
+            http: reqwest::Client::new(),
+            base_config: Config::default(),
+            config: RwLock::new(Arc::new(Config::default())),
+            metrics: Metrics::new(),
+            log_throttle: LogThrottle::new(Config::default().log_throttle_interval),
+            render_cache: RenderCache::new(),
+            calendars: Cache::new(),
+            namespaces: Cache::new(),
+            circuit_breaker: Config::default().circuit_breaker(),
+            queue: QueueTracker::new(),
+            emergency_stop: EmergencyStop::default(),
+            leadership: Leadership::default(),
+            log_level: None,
+            history: None,
+            bus: None,
+            hooks: None,
+            server_version: None,
+            events_api: EventsApi::default(),
+        }
+    }
+
+    pub fn with_config(mut self, config: Config) -> Self {
+        self.log_throttle = LogThrottle::new(config.log_throttle_interval);
+        self.circuit_breaker = config.circuit_breaker();
+        self.emergency_stop = EmergencyStop::new(config.emergency_stop);
+        self.history = config.history_sink.as_deref().and_then(|url| {
+            history::from_url(url, self.http.clone())
+                .inspect_err(|e| tracing::error!(error = ?e, "Run history will not be archived"))
+                .ok()
+        });
+        self.bus = config.event_bus_url.as_deref().and_then(|url| {
+            bus::from_url(url)
+                .inspect_err(
+                    |e| tracing::error!(error = ?e, "Nothing will be published to the event bus"),
+                )
+                .ok()
+        });
+        self.config = RwLock::new(Arc::new(config.clone()));
+        self.base_config = config;
+        self
+    }
+
+    pub fn with_log_level(mut self, log_level: LogLevel) -> Self {
+        self.log_level = Some(log_level);
+        self
+    }
+
+    pub fn with_hooks(mut self, hooks: Arc<dyn ReconcileHooks>) -> Self {
+        self.hooks = Some(hooks);
+        self
+    }
+
+    pub fn with_server_version(mut self, version: Option<ServerVersion>) -> Self {
+        self.server_version = version;
+        self
+    }
+
+    pub fn with_events_api(mut self, api: EventsApi) -> Self {
+        self.events_api = api;
+        self
+    }
+
+    /// The effective settings. Those applied by a ControllerConfiguration
+    /// change while the controller runs, so they are read anew each time.
+    pub fn config(&self) -> Arc<Config> {
+        self.config
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Replaces the effective settings with the environment's, overridden by
+    /// `configuration` when one exists. Returns whether they changed.
+    pub fn set_configuration(&self, configuration: Option<&ControllerConfigurationSpec>) -> bool {
+        let mut config = self.base_config.clone();
+        if let Some(configuration) = configuration {
+            configuration.apply(&mut config);
+        }
+        let mut current = self.config.write().unwrap_or_else(PoisonError::into_inner);
+        if **current == config {
+            return false;
+        }
+        *current = Arc::new(config);
+        true
+    }
+
+    pub fn http(&self) -> &reqwest::Client {
+        &self.http
+    }
+
+    pub fn metrics(&self) -> &Metrics {
+        &self.metrics
+    }
+
+    pub fn log_throttle(&self) -> &LogThrottle {
+        &self.log_throttle
+    }
+
+    pub fn calendars(&self) -> &Cache<ScheduleCalendar> {
+        &self.calendars
+    }
+
+    pub fn namespaces(&self) -> &Cache<Namespace> {
+        &self.namespaces
+    }
+
+    /// The ScheduleCalendar `name`, from the cache once it is synced.
+    pub async fn calendar(
+        &self,
+        namespace: &str,
+        name: &str,
+    ) -> Result<ScheduleCalendar, crate::Error> {
+        if !self.calendars.is_ready() {
+            return self.get::<ScheduleCalendar>(namespace, name).await;
+        }
+        self.calendars
+            .get(Some(namespace), name)
+            .map(|calendar| (*calendar).clone())
+            .ok_or(crate::Error::NotFound)
+    }
+
+    /// The child CronJobs of `job`, rendered again only once it changed, see
+    /// [`RenderCache`].
+    pub fn cronjobs(&self, job: &ScheduledCronJob) -> Result<Vec<CronJob>, crate::Error> {
+        let (children, cached) = self.render_cache.cronjobs(job, Utc::now())?;
+        self.metrics
+            .render_cache_total
+            .with_label_values(&[if cached { "Hit" } else { "Miss" }])
+            .inc();
+        Ok(children)
+    }
+
+    pub fn circuit_breaker(&self) -> &CircuitBreaker {
+        &self.circuit_breaker
+    }
+
+    pub fn queue(&self) -> &QueueTracker {
+        &self.queue
+    }
+
+    pub fn emergency_stop(&self) -> &EmergencyStop {
+        &self.emergency_stop
+    }
+
+    pub fn leadership(&self) -> &Leadership {
+        &self.leadership
+    }
+
+    /// The tracing filter, when the process installed one that can change.
+    pub fn log_level(&self) -> Option<&LogLevel> {
+        self.log_level.as_ref()
+    }
+
+    pub fn hooks(&self) -> Option<&dyn ReconcileHooks> {
+        self.hooks.as_deref()
+    }
+
+    /// `None` when it could not be read, in which case every feature is
+    /// used.
+    pub fn server_version(&self) -> Option<ServerVersion> {
+        self.server_version
+    }
+
+    /// Records a child write skipped in observer mode.
+    fn observe(&self, kind: &str, action: &str, description: String) {
+        self.metrics
+            .observer_actions_total
+            .with_label_values(&[kind, action])
+            .inc();
+        observer::record(description);
+    }
+
+    fn ensure_not_stopped(&self) -> Result<(), crate::Error> {
+        if self.emergency_stop.engaged() {
+            return Err(crate::Error::EmergencyStop);
+        }
+        Ok(())
+    }
+
+    /// Requeues `resource` after `after`, recording when it is due so the
+    /// queue metrics can tell how long it waits to start.
+    pub fn requeue<K>(&self, resource: &K, after: Duration) -> Action
+    where
+        K: KubeResource<DynamicType = ()>,
+    {
+        self.queue.schedule(resource, after);
+        Action::requeue(after)
     }
 
     pub async fn get<K>(&self, namespace: &str, name: &str) -> Result<K, crate::Error>
@@ -42,50 +297,875 @@ impl Context {
         }
     }
 
-    pub async fn create<K>(&self, namespace: &str, object: &K) -> Result<K, crate::Error>
-    where
-        K: KubeResource<Scope = NamespaceResourceScope>,
-        K: KubeResource,
-        K: Clone + DeserializeOwned + Serialize + std::fmt::Debug,
-        K::DynamicType: Default,
-    {
-        let api = Api::<K>::namespaced(self.client.clone(), namespace);
-        match api.create(&PostParams::default(), object).await {
-            Ok(object) => Ok(object),
-            Err(e) => Err(crate::Error::Kube(e)),
-        }
-    }
+    /// Whether `namespace` has entered the `Terminating` phase. Children must not
+    /// be created there, as the API server rejects every create.
+    pub async fn namespace_terminating(&self, namespace: &str) -> Result<bool, crate::Error> {
+        let found = if self.namespaces.is_ready() {
+            self.namespaces.get(None, namespace)
+        } else {
+            let api = Api::<Namespace>::all(self.client.clone());
+            api.get_opt(namespace).await?.map(Arc::new)
+        };
+        match found {
+            Some(ns) => Ok(ns.metadata.deletion_timestamp.is_some()
+                || ns.status.as_ref().and_then(|s| s.phase.as_deref()) == Some("Terminating")),
+            None => Ok(true),
+        }
+    }
+
+    /// Whether resources in `from` may target objects in `namespace`, which
+    /// requires `cross_namespace_targets` and `namespace` to list `from` in
+    /// its [`ALLOW_TARGETS_FROM_ANNOTATION`].
+    pub async fn allows_targets_from(
+        &self,
+        namespace: &str,
+        from: &str,
+    ) -> Result<bool, crate::Error> {
+        if !self.config().cross_namespace_targets {
+            return Ok(false);
+        }
+        let found = if self.namespaces.is_ready() {
+            self.namespaces.get(None, namespace)
+        } else {
+            let api = Api::<Namespace>::all(self.client.clone());
+            api.get_opt(namespace).await?.map(Arc::new)
+        };
+        Ok(found.is_some_and(|ns| {
+            ns.annotations()
+                .get(ALLOW_TARGETS_FROM_ANNOTATION)
+                .is_some_and(|allowed| TargetRef::allowed_from(allowed, from))
+        }))
+    }
+
+    /// Whether some node could run `pod`, see [`capacity::fits`]. Always true
+    /// while `capacity_check` is disabled. Failing to list nodes is logged and
+    /// treated as capacity being available, so runs are never held back by a
+    /// missing permission.
+    pub async fn has_capacity(&self, pod: &PodSpec) -> bool {
+        if !self.config().capacity_check {
+            return true;
+        }
+        let api = Api::<Node>::all(self.client.clone());
+        let params = ListParams::default().limit(self.config().list_page_size);
+        let mut nodes = pin!(paginate(params, move |params| {
+            let api = api.clone();
+            async move { api.list(&params).await }
+        }));
+        loop {
+            match nodes.try_next().await {
+                Ok(Some(node)) if capacity::fits(pod, &node) => return true,
+                Ok(Some(_)) => {}
+                Ok(None) => return false,
+                Err(e) => {
+                    tracing::warn!(error = ?e, "Failed to list nodes, skipping capacity check");
+                    return true;
+                }
+            }
+        }
+    }
+
+    /// Lists objects of kind `K` across all namespaces, fetching them in
+    /// pages of `list_page_size`.
+    pub async fn list_all<K>(&self) -> Result<Vec<K>, crate::Error>
+    where
+        K: KubeResource<Scope = NamespaceResourceScope>,
+        K: KubeResource,
+        K: Clone + DeserializeOwned + std::fmt::Debug,
+        K::DynamicType: Default,
+    {
+        self.list_all_stream().try_collect().await
+    }
+
+    /// Streams objects of kind `K` across all namespaces. Only one page of
+    /// `list_page_size` objects is held in memory at a time.
+    pub fn list_all_stream<K>(&self) -> impl Stream<Item = Result<K, crate::Error>> + use<K>
+    where
+        K: KubeResource<Scope = NamespaceResourceScope>,
+        K: KubeResource,
+        K: Clone + DeserializeOwned + std::fmt::Debug,
+        K::DynamicType: Default,
+    {
+        let api = Api::<K>::all(self.client.clone());
+        let params = ListParams::default().limit(self.config().list_page_size);
+        paginate(params, move |params| {
+            let api = api.clone();
+            async move { api.list(&params).await }
+        })
+    }
+
+    /// Streams objects of kind `K` in `namespace`, a page at a time like
+    /// [`Context::list_all_stream`].
+    pub fn list_namespaced_stream<K>(
+        &self,
+        namespace: &str,
+    ) -> impl Stream<Item = Result<K, crate::Error>> + use<K>
+    where
+        K: KubeResource<Scope = NamespaceResourceScope>,
+        K: Clone + DeserializeOwned + std::fmt::Debug,
+        K::DynamicType: Default,
+    {
+        let api = Api::<K>::namespaced(self.client.clone(), namespace);
+        let params = ListParams::default().limit(self.config().list_page_size);
+        paginate(params, move |params| {
+            let api = api.clone();
+            async move { api.list(&params).await }
+        })
+    }
+
+    /// Streams only the metadata of objects of kind `K` across all
+    /// namespaces, optionally narrowed by a field selector. Sweeps that only
+    /// need names, labels or owner references should prefer this over
+    /// [`Context::list_all_stream`].
+    pub fn list_metadata_stream<K>(
+        &self,
+        field_selector: Option<&str>,
+    ) -> impl Stream<Item = Result<PartialObjectMeta<K>, crate::Error>> + use<K>
+    where
+        K: KubeResource<Scope = NamespaceResourceScope>,
+        K: KubeResource,
+        K: Clone + DeserializeOwned + std::fmt::Debug,
+        K::DynamicType: Default,
+    {
+        let mut params = ListParams::default().limit(self.config().list_page_size);
+        if let Some(fields) = field_selector {
+            params = params.fields(fields);
+        }
+        self.paginate_metadata(None, params)
+    }
+
+    /// Metadata of the objects of kind `K` in `namespace` whose owner
+    /// references include `owner_uid`.
+    pub async fn list_owned_metadata<K>(
+        &self,
+        namespace: &str,
+        owner_uid: &str,
+    ) -> Result<Vec<PartialObjectMeta<K>>, crate::Error>
+    where
+        K: KubeResource<Scope = NamespaceResourceScope>,
+        K: KubeResource,
+        K: Clone + DeserializeOwned + std::fmt::Debug,
+        K::DynamicType: Default,
+    {
+        let params = ListParams::default().limit(self.config().list_page_size);
+        self.paginate_metadata(Some(namespace), params)
+            .try_filter(|o| {
+                let owned = o.owner_references().iter().any(|r| r.uid == owner_uid);
+                futures::future::ready(owned)
+            })
+            .try_collect()
+            .await
+    }
+
+    /// Metadata of the objects of kind `K` in `namespace` matching the label
+    /// selector `labels`, filtered by the API server.
+    pub async fn list_labelled_metadata<K>(
+        &self,
+        namespace: &str,
+        labels: &str,
+    ) -> Result<Vec<PartialObjectMeta<K>>, crate::Error>
+    where
+        K: KubeResource<Scope = NamespaceResourceScope>,
+        K: Clone + DeserializeOwned + std::fmt::Debug,
+        K::DynamicType: Default,
+    {
+        let params = ListParams::default()
+            .labels(labels)
+            .limit(self.config().list_page_size);
+        self.paginate_metadata(Some(namespace), params)
+            .try_collect()
+            .await
+    }
+
+    /// Pages through the metadata of objects of kind `K` in `namespace`, or
+    /// across all namespaces, in protobuf unless turned off.
+    fn paginate_metadata<K>(
+        &self,
+        namespace: Option<&str>,
+        params: ListParams,
+    ) -> impl Stream<Item = Result<PartialObjectMeta<K>, crate::Error>> + use<K>
+    where
+        K: KubeResource<Scope = NamespaceResourceScope>,
+        K: Clone + DeserializeOwned + std::fmt::Debug,
+        K::DynamicType: Default,
+    {
+        let client = self.client.clone();
+        let namespace = namespace.map(str::to_string);
+        let protobuf = self.config().protobuf;
+        paginate(params, move |params| {
+            let client = client.clone();
+            let namespace = namespace.clone();
+            async move {
+                if protobuf {
+                    return protobuf::list_metadata(&client, namespace.as_deref(), &params).await;
+                }
+                let api = match namespace {
+                    Some(namespace) => Api::<K>::namespaced(client, &namespace),
+                    None => Api::<K>::all(client),
+                };
+                Ok(api.list_metadata(&params).await?)
+            }
+        })
+    }
+
+    /// Watches only the metadata of objects of kind `K` across all
+    /// namespaces, optionally narrowed by a field selector.
+    pub fn watch_metadata<K>(
+        &self,
+        field_selector: Option<&str>,
+    ) -> impl Stream<Item = Result<watcher::Event<PartialObjectMeta<K>>, watcher::Error>> + use<K>
+    where
+        K: KubeResource<Scope = NamespaceResourceScope>,
+        K: KubeResource,
+        K: Clone + DeserializeOwned + std::fmt::Debug + Send + 'static,
+        K::DynamicType: Default,
+    {
+        let api = Api::<K>::all(self.client.clone());
+        let mut config = watcher::Config::default().page_size(self.config().list_page_size);
+        if let Some(fields) = field_selector {
+            config = config.fields(fields);
+        }
+        watcher::metadata_watcher(api, config)
+    }
+
+    pub async fn create<K>(&self, namespace: &str, object: &K) -> Result<K, crate::Error>
+    where
+        K: KubeResource<Scope = NamespaceResourceScope>,
+        K: KubeResource,
+        K: Clone + DeserializeOwned + Serialize + std::fmt::Debug,
+        K::DynamicType: Default,
+    {
+        self.ensure_not_stopped()?;
+        let api = Api::<K>::namespaced(self.client.clone(), namespace);
+        let params = PostParams {
+            dry_run: self.config().observer,
+            ..Default::default()
+        };
+        if self.config().observer {
+            let kind = K::kind(&Default::default()).into_owned();
+            let description = format!("create {kind} {namespace}/{}", object.name_any());
+            self.observe(&kind, "create", description);
+        }
+        match api.create(&params, object).await {
+            Ok(object) => {
+                if let Some(hooks) = &self.hooks
+                    && !self.config().observer
+                {
+                    let child = ChildCreated {
+                        kind: K::kind(&Default::default()).into_owned(),
+                        namespace: namespace.to_string(),
+                        name: object.name_any(),
+                        owner: object
+                            .owner_references()
+                            .iter()
+                            .find(|r| r.controller == Some(true))
+                            .map(|r| (r.kind.clone(), r.name.clone())),
+                    };
+                    hooks.on_child_created(&child).await;
+                }
+                Ok(object)
+            }
+            Err(KubeError::Api(e)) if e.code == 403 && e.message.contains("being terminated") => {
+                Err(crate::Error::NamespaceTerminating(namespace.to_string()))
+            }
+            Err(e) => Err(crate::Error::Kube(e)),
+        }
+    }
+
+    pub async fn delete<K>(&self, namespace: &str, name: &str) -> Result<(), crate::Error>
+    where
+        K: KubeResource<Scope = NamespaceResourceScope>,
+        K: KubeResource,
+        K: Clone + DeserializeOwned + Serialize + std::fmt::Debug,
+        K::DynamicType: Default,
+    {
+        self.delete_with::<K>(namespace, name, DeleteParams::foreground())
+            .await
+    }
+
+    /// [`Context::delete`] with other `params`, such as
+    /// [`DeleteParams::orphan`] to keep the dependents.
+    pub async fn delete_with<K>(
+        &self,
+        namespace: &str,
+        name: &str,
+        mut params: DeleteParams,
+    ) -> Result<(), crate::Error>
+    where
+        K: KubeResource<Scope = NamespaceResourceScope>,
+        K: KubeResource,
+        K: Clone + DeserializeOwned + Serialize + std::fmt::Debug,
+        K::DynamicType: Default,
+    {
+        self.ensure_not_stopped()?;
+        let api = Api::<K>::namespaced(self.client.clone(), namespace);
+        if self.config().observer {
+            params = params.dry_run();
+            let kind = K::kind(&Default::default()).into_owned();
+            self.observe(&kind, "delete", format!("delete {kind} {namespace}/{name}"));
+        }
+        if let Err(e) = api.delete(name, &params).await {
+            match e {
+                KubeError::Api(e) if e.code == 404 => return Ok(()),
+                _ => return Err(crate::Error::Kube(e)),
+            }
+        }
+        Ok(())
+    }
+
+    /// Server-side applies `object`, creating it or bringing an existing one in
+    /// line with it.
+    pub async fn apply<K>(&self, namespace: &str, object: &K) -> Result<K, crate::Error>
+    where
+        K: KubeResource<Scope = NamespaceResourceScope>,
+        K: KubeResource,
+        K: Clone + DeserializeOwned + Serialize + std::fmt::Debug,
+        K::DynamicType: Default,
+    {
+        self.ensure_not_stopped()?;
+        let api = Api::<K>::namespaced(self.client.clone(), namespace);
+        let name = object.name_any();
+        let mut params = PatchParams::apply(FIELD_MANAGER).force();
+        if self.config().observer {
+            params = params.dry_run();
+            let kind = K::kind(&Default::default()).into_owned();
+            self.observe(&kind, "apply", format!("apply {kind} {namespace}/{name}"));
+        }
+        match api.patch(&name, &params, &Patch::Apply(object)).await {
+            Ok(object) => Ok(object),
+            Err(KubeError::Api(e)) if e.code == 403 && e.message.contains("being terminated") => {
+                Err(crate::Error::NamespaceTerminating(namespace.to_string()))
+            }
+            Err(e) => Err(crate::Error::Kube(e)),
+        }
+    }
+
+    /// Patches the object `name` of kind `K`, failing with
+    /// [`crate::Error::NotFound`] when it does not exist.
+    pub async fn patch<K>(
+        &self,
+        namespace: &str,
+        name: &str,
+        patch: &Patch<serde_json::Value>,
+    ) -> Result<(), crate::Error>
+    where
+        K: KubeResource<Scope = NamespaceResourceScope>,
+        K: KubeResource,
+        K: Clone + DeserializeOwned + Serialize + std::fmt::Debug,
+        K::DynamicType: Default,
+    {
+        self.ensure_not_stopped()?;
+        let api = Api::<K>::namespaced(self.client.clone(), namespace);
+        let mut params = PatchParams::default();
+        if self.config().observer {
+            params = params.dry_run();
+            let kind = K::kind(&Default::default()).into_owned();
+            self.observe(&kind, "patch", format!("patch {kind} {namespace}/{name}"));
+        }
+        match api.patch(name, &params, patch).await {
+            Ok(_) => Ok(()),
+            Err(KubeError::Api(e)) if e.code == 404 => Err(crate::Error::NotFound),
+            Err(e) => Err(crate::Error::Kube(e)),
+        }
+    }
+
+    /// Sets a status condition on `resource`, writing the status only when the
+    /// condition actually changed.
+    pub async fn set_condition<K>(
+        &self,
+        resource: &K,
+        type_: &str,
+        status: bool,
+        reason: Reason,
+        message: &str,
+    ) -> Result<(), crate::Error>
+    where
+        K: KubeResource<Scope = NamespaceResourceScope, DynamicType = ()>,
+        K: HasStatus + Clone + DeserializeOwned + Serialize + std::fmt::Debug,
+        K::Status: HasConditions + Default,
+    {
+        let namespace = resource.namespace().unwrap_or_default();
+        let name = resource.name_any();
+        let api = Api::<K>::namespaced(self.client.clone(), &namespace);
+
+        let mut resource = match api.get(&name).await {
+            Ok(resource) => resource,
+            Err(KubeError::Api(e)) if e.code == 404 => return Ok(()),
+            Err(e) => return Err(crate::Error::Kube(e)),
+        };
+        let generation = resource.meta().generation;
+        let conditions = resource
+            .status_mut()
+            .get_or_insert_with(Default::default)
+            .conditions_mut();
+        if !set_condition(
+            conditions,
+            type_,
+            status,
+            reason.as_str(),
+            message,
+            generation,
+        ) {
+            return Ok(());
+        }
+
+        let bytes = serde_json::to_vec(&resource)?;
+        api.replace_status(&name, &PostParams::default(), bytes)
+            .await?;
+        Ok(())
+    }
+
+    /// Removes the annotation `key` from `resource` with a merge patch.
+    pub async fn remove_annotation<K>(&self, resource: &K, key: &str) -> Result<(), crate::Error>
+    where
+        K: KubeResource<Scope = NamespaceResourceScope, DynamicType = ()>,
+        K: Clone + DeserializeOwned + std::fmt::Debug,
+    {
+        self.annotate(resource, key, None).await
+    }
+
+    /// Sets the annotation `key` on `resource`, or removes it when `value` is
+    /// `None`, with a merge patch.
+    pub async fn annotate<K>(
+        &self,
+        resource: &K,
+        key: &str,
+        value: Option<&str>,
+    ) -> Result<(), crate::Error>
+    where
+        K: KubeResource<Scope = NamespaceResourceScope, DynamicType = ()>,
+        K: Clone + DeserializeOwned + std::fmt::Debug,
+    {
+        self.patch_metadata(resource, "annotations", key, value)
+            .await
+    }
+
+    /// [`Context::annotate`] for labels.
+    pub async fn label<K>(
+        &self,
+        resource: &K,
+        key: &str,
+        value: Option<&str>,
+    ) -> Result<(), crate::Error>
+    where
+        K: KubeResource<Scope = NamespaceResourceScope, DynamicType = ()>,
+        K: Clone + DeserializeOwned + std::fmt::Debug,
+    {
+        self.patch_metadata(resource, "labels", key, value).await
+    }
+
+    async fn patch_metadata<K>(
+        &self,
+        resource: &K,
+        field: &str,
+        key: &str,
+        value: Option<&str>,
+    ) -> Result<(), crate::Error>
+    where
+        K: KubeResource<Scope = NamespaceResourceScope, DynamicType = ()>,
+        K: Clone + DeserializeOwned + std::fmt::Debug,
+    {
+        let namespace = resource.namespace().unwrap_or_default();
+        let api = Api::<K>::namespaced(self.client.clone(), &namespace);
+        let patch = serde_json::json!({ "metadata": { field: { key: value } } });
+        match api
+            .patch(
+                &resource.name_any(),
+                &PatchParams::default(),
+                &Patch::Merge(&patch),
+            )
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(KubeError::Api(e)) if e.code == 404 => Ok(()),
+            Err(e) => Err(crate::Error::Kube(e)),
+        }
+    }
+
+    pub async fn create_cronjob(
+        &self,
+        namespace: &str,
+        object: &CronJob,
+    ) -> Result<CronJob, crate::Error> {
+        self.create(namespace, object).await
+    }
+
+    pub async fn update_scheduled_cronjob(
+        &self,
+        resource: &ScheduledCronJob,
+        status: ScheduledCronJobPhase,
+        reason: Reason,
+        message: &str,
+    ) -> Result<(), crate::Error> {
+        tracing::info!(
+            name = resource.name_any(),
+            namespace = resource.namespace().unwrap_or_default(),
+            status = status.as_str(),
+            message = message,
+            "Updating status for scheduled cronjob",
+        );
+        if let Some(current) = resource.status().map(|s| s.phase)
+            && !current.allows(status)
+        {
+            return Err(crate::Error::InvalidTransition(
+                current.to_string(),
+                status.to_string(),
+            ));
+        }
+        let previous = resource.status.as_ref().map(|s| s.phase);
+        self.record_event(resource, reason, message, previous != Some(status))
+            .await?;
+        let last_error = (reason.event_type() == "Warning").then_some(reason);
+        self.write_scheduled_cronjob_status(resource, status, message, last_error)
+            .await?;
+        if previous != Some(status) {
+            self.phase_changed(
+                resource,
+                previous.map(|p| p.as_str()),
+                status.as_str(),
+                message,
+            )
+            .await;
+        }
+        if resource.status.as_ref().map(|s| s.phase) != Some(status)
+            && let Some(transition) = status.transition()
+        {
+            self.report_transition(resource, transition, status.as_str(), message)
+                .await;
+        }
+        Ok(())
+    }
+
+    pub async fn update_scheduled_cronjob_status(
+        &self,
+        resource: &ScheduledCronJob,
+        phase: ScheduledCronJobPhase,
+        message: &str,
+    ) -> Result<(), crate::Error> {
+        self.write_scheduled_cronjob_status(resource, phase, message, None)
+            .await
+    }
+
+    /// Writes the phase and message, recording `last_error` as the
+    /// [`DETAIL_LAST_ERROR`] detail when set.
+    async fn write_scheduled_cronjob_status(
+        &self,
+        resource: &ScheduledCronJob,
+        phase: ScheduledCronJobPhase,
+        message: &str,
+        last_error: Option<Reason>,
+    ) -> Result<(), crate::Error> {
+        let namespace = resource.namespace().unwrap_or_default();
+        let name = resource.name_any();
+        let generation = resource.metadata.generation;
+        let api = Api::<ScheduledCronJob>::namespaced(self.client.clone(), &namespace);
+
+        let mut resource = match api.get(&name).await {
+            Ok(resource) => resource,
+            Err(KubeError::Api(e)) if e.code == 404 => return Ok(()),
+            Err(e) => return Err(crate::Error::Kube(e)),
+        };
+        let previous = resource.status.take().unwrap_or_default();
+        if !previous.phase.allows(phase) {
+            return Err(crate::Error::InvalidTransition(
+                previous.phase.to_string(),
+                phase.to_string(),
+            ));
+        }
+        let message = truncate_message(message);
+        let mut details = previous.details;
+        if let Some(reason) = last_error {
+            details.insert(DETAIL_LAST_ERROR.to_string(), reason.as_str().to_string());
+        }
+        resource.status = Some(ScheduledCronJobStatus {
+            phase,
+            message: Some(message.clone()),
+            last_update_time: Some(Time(Utc::now())),
+            conditions: previous.conditions,
+            variants: previous.variants,
+            schedules: previous.schedules,
+            recommendations: previous.recommendations,
+            details,
+            executions: previous.executions,
+            managed_resources: previous.managed_resources,
+            observed_generation: generation,
+        });
+
+        assert_eq!(resource.status().unwrap().phase, phase);
+        assert_eq!(resource.status().unwrap().message, Some(message));
+
+        let bytes = serde_json::to_vec(&resource)?;
+        api.replace_status(&name, &PostParams::default(), bytes)
+            .await?;
+        Ok(())
+    }
+
+    /// Records the status of the children of each variant and entry of
+    /// `spec.schedules`, and the objects managed for `resource`, writing the
+    /// status only when it changed.
+    pub async fn update_scheduled_cronjob_children(
+        &self,
+        resource: &ScheduledCronJob,
+        variants: Vec<VariantStatus>,
+        schedules: Vec<ScheduleStatus>,
+        managed_resources: Vec<ManagedResource>,
+    ) -> Result<(), crate::Error> {
+        let current = resource.status().map(|s| {
+            (
+                s.variants.as_slice(),
+                s.schedules.as_slice(),
+                s.managed_resources.as_slice(),
+            )
+        });
+        let desired = (
+            variants.as_slice(),
+            schedules.as_slice(),
+            managed_resources.as_slice(),
+        );
+        if current.unwrap_or_default() == desired {
+            return Ok(());
+        }
+        let namespace = resource.namespace().unwrap_or_default();
+        let name = resource.name_any();
+        let api = Api::<ScheduledCronJob>::namespaced(self.client.clone(), &namespace);
+
+        let mut resource = match api.get(&name).await {
+            Ok(resource) => resource,
+            Err(KubeError::Api(e)) if e.code == 404 => return Ok(()),
+            Err(e) => return Err(crate::Error::Kube(e)),
+        };
+        let status = resource.status.get_or_insert_with(Default::default);
+        status.variants = variants;
+        status.schedules = schedules;
+        status.managed_resources = managed_resources;
+
+        let bytes = serde_json::to_vec(&resource)?;
+        api.replace_status(&name, &PostParams::default(), bytes)
+            .await?;
+        Ok(())
+    }
+
+    /// Sets the given details of `resource`, removing those whose value is
+    /// `None`, and writes the status only when they changed.
+    pub async fn update_scheduled_cronjob_details(
+        &self,
+        resource: &ScheduledCronJob,
+        updates: &[(&str, Option<String>)],
+    ) -> Result<(), crate::Error> {
+        let apply = |details: &mut BTreeMap<String, String>| {
+            for (key, value) in updates {
+                match value {
+                    Some(value) => details.insert(key.to_string(), value.clone()),
+                    None => details.remove(*key),
+                };
+            }
+        };
+        let current = resource
+            .status()
+            .map(|s| s.details.clone())
+            .unwrap_or_default();
+        let mut desired = current.clone();
+        apply(&mut desired);
+        if desired == current {
+            return Ok(());
+        }
+        let namespace = resource.namespace().unwrap_or_default();
+        let name = resource.name_any();
+        let api = Api::<ScheduledCronJob>::namespaced(self.client.clone(), &namespace);
+
+        let mut resource = match api.get(&name).await {
+            Ok(resource) => resource,
+            Err(KubeError::Api(e)) if e.code == 404 => return Ok(()),
+            Err(e) => return Err(crate::Error::Kube(e)),
+        };
+        apply(&mut resource.status.get_or_insert_with(Default::default).details);
+
+        let bytes = serde_json::to_vec(&resource)?;
+        api.replace_status(&name, &PostParams::default(), bytes)
+            .await?;
+        Ok(())
+    }
+
+    /// Records the runs counted towards `spec.maxExecutions`.
+    pub async fn update_scheduled_cronjob_executions(
+        &self,
+        resource: &ScheduledCronJob,
+        executions: u32,
+    ) -> Result<(), crate::Error> {
+        let namespace = resource.namespace().unwrap_or_default();
+        let name = resource.name_any();
+        let api = Api::<ScheduledCronJob>::namespaced(self.client.clone(), &namespace);
+
+        let mut resource = match api.get(&name).await {
+            Ok(resource) => resource,
+            Err(KubeError::Api(e)) if e.code == 404 => return Ok(()),
+            Err(e) => return Err(crate::Error::Kube(e)),
+        };
+        resource
+            .status
+            .get_or_insert_with(Default::default)
+            .executions = Some(executions);
+
+        let bytes = serde_json::to_vec(&resource)?;
+        api.replace_status(&name, &PostParams::default(), bytes)
+            .await?;
+        Ok(())
+    }
+
+    /// Records the right-sizing recommendations of `resource`.
+    pub async fn update_scheduled_cronjob_recommendations(
+        &self,
+        resource: &ScheduledCronJob,
+        recommendations: Vec<ResourceRecommendation>,
+    ) -> Result<(), crate::Error> {
+        let namespace = resource.namespace().unwrap_or_default();
+        let name = resource.name_any();
+        let api = Api::<ScheduledCronJob>::namespaced(self.client.clone(), &namespace);
+
+        let mut resource = match api.get(&name).await {
+            Ok(resource) => resource,
+            Err(KubeError::Api(e)) if e.code == 404 => return Ok(()),
+            Err(e) => return Err(crate::Error::Kube(e)),
+        };
+        resource
+            .status
+            .get_or_insert_with(Default::default)
+            .recommendations = recommendations;
+
+        let bytes = serde_json::to_vec(&resource)?;
+        api.replace_status(&name, &PostParams::default(), bytes)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn create_scheduled_cronjob_event(
+        &self,
+        resource: &ScheduledCronJob,
+        reason: Reason,
+        message: &str,
+    ) -> Result<(), crate::Error> {
+        self.create_event(resource, reason, message).await
+    }
+
+    pub async fn update_delayed_job(
+        &self,
+        resource: &DelayedJob,
+        status: DelayedJobPhase,
+        reason: Reason,
+        message: &str,
+    ) -> Result<(), crate::Error> {
+        tracing::info!(
+            name = resource.name_any(),
+            namespace = resource.namespace().unwrap_or_default(),
+            status = status.as_str(),
+            message = message,
+            "Updating status for scheduled cronjob",
+        );
+        let previous = resource.status.as_ref().map(|s| s.phase);
+        self.record_event(resource, reason, message, previous != Some(status))
+            .await?;
+        self.update_delayed_job_status(resource, status, message)
+            .await?;
+        if previous != Some(status) {
+            self.phase_changed(
+                resource,
+                previous.map(|p| p.as_str()),
+                status.as_str(),
+                message,
+            )
+            .await;
+        }
+        if resource.status.as_ref().map(|s| s.phase) != Some(status)
+            && let Some(transition) = status.transition()
+        {
+            self.report_transition(resource, transition, status.as_str(), message)
+                .await;
+        }
+        Ok(())
+    }
+
+    /// Records the Jobs created so far and when the next retry is due.
+    pub async fn update_delayed_job_attempts(
+        &self,
+        resource: &DelayedJob,
+        attempts: u32,
+        next_retry_time: Option<DateTime<Utc>>,
+    ) -> Result<(), crate::Error> {
+        let namespace = resource.namespace().unwrap_or_default();
+        let name = resource.name_any();
+        let api = Api::<DelayedJob>::namespaced(self.client.clone(), &namespace);
+
+        let mut resource = match api.get(&name).await {
+            Ok(resource) => resource,
+            Err(KubeError::Api(e)) if e.code == 404 => return Ok(()),
+            Err(e) => return Err(crate::Error::Kube(e)),
+        };
+        let status = resource.status.get_or_insert_with(Default::default);
+        status.attempts = Some(attempts);
+        status.next_retry_time = next_retry_time.map(Time);
+
+        let bytes = serde_json::to_vec(&resource)?;
+        api.replace_status(&name, &PostParams::default(), bytes)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn update_delayed_job_status(
+        &self,
+        resource: &DelayedJob,
+        phase: DelayedJobPhase,
+        message: &str,
+    ) -> Result<(), crate::Error> {
+        let namespace = resource.namespace().unwrap_or_default();
+        let name = resource.name_any();
+        let api = Api::<DelayedJob>::namespaced(self.client.clone(), &namespace);
 
-    pub async fn delete<K>(&self, namespace: &str, name: &str) -> Result<(), crate::Error>
-    where
-        K: KubeResource<Scope = NamespaceResourceScope>,
-        K: KubeResource,
-        K: Clone + DeserializeOwned + Serialize + std::fmt::Debug,
-        K::DynamicType: Default,
-    {
-        let api = Api::<K>::namespaced(self.client.clone(), namespace);
-        if let Err(e) = api.delete(name, &DeleteParams::foreground()).await {
-            match e {
-                KubeError::Api(e) if e.code == 404 => return Ok(()),
-                _ => return Err(crate::Error::Kube(e)),
-            }
-        }
+        let mut resource = match api.get(&name).await {
+            Ok(resource) => resource,
+            Err(KubeError::Api(e)) if e.code == 404 => return Ok(()),
+            Err(e) => return Err(crate::Error::Kube(e)),
+        };
+        let previous = resource.status.take().unwrap_or_default();
+        // The Job shares the DelayedJob's name once it has been created.
+        let job_ref = match phase {
+            DelayedJobPhase::Running | DelayedJobPhase::Completed => Some(name.clone()),
+            _ => previous.job_ref,
+        };
+        resource.status = Some(DelayedJobStatus {
+            phase,
+            message: Some(truncate_message(message)),
+            last_update_time: Some(Time(Utc::now())),
+            job_ref,
+            conditions: previous.conditions,
+            attempts: previous.attempts,
+            next_retry_time: previous.next_retry_time,
+        });
+
+        assert_eq!(resource.status().unwrap().phase, phase);
+        assert_eq!(
+            resource.status().unwrap().message,
+            Some(truncate_message(message))
+        );
+
+        let bytes = serde_json::to_vec(&resource)?;
+        api.replace_status(&name, &PostParams::default(), bytes)
+            .await?;
         Ok(())
     }
 
-    pub async fn create_cronjob(
+    pub async fn create_delayed_job_event(
         &self,
-        namespace: &str,
-        object: &CronJob,
-    ) -> Result<CronJob, crate::Error> {
-        self.create(namespace, object).await
+        resource: &DelayedJob,
+        reason: Reason,
+        message: &str,
+    ) -> Result<(), crate::Error> {
+        self.create_event(resource, reason, message).await
     }
 
-    pub async fn update_scheduled_cronjob(
+    pub async fn update_scheduled_patch(
         &self,
-        resource: &ScheduledCronJob,
-        status: ScheduledCronJobPhase,
-        event_type: &str,
+        resource: &ScheduledPatch,
+        status: ScheduledPatchPhase,
+        reason: Reason,
         message: &str,
     ) -> Result<(), crate::Error> {
         tracing::info!(
@@ -93,112 +1173,147 @@ impl Context {
             namespace = resource.namespace().unwrap_or_default(),
             status = status.as_str(),
             message = message,
-            "Updating status for scheduled cronjob",
+            "Updating status for scheduled patch",
         );
-        self.create_scheduled_cronjob_event(resource, event_type, status.as_str(), message)
+        let previous = resource.status.as_ref().map(|s| s.phase);
+        self.record_event(resource, reason, message, previous != Some(status))
             .await?;
-        self.update_scheduled_cronjob_status(resource, status, message)
+        self.update_scheduled_patch_status(resource, status, message, None)
             .await?;
+        if previous != Some(status) {
+            self.phase_changed(
+                resource,
+                previous.map(|p| p.as_str()),
+                status.as_str(),
+                message,
+            )
+            .await;
+        }
         Ok(())
     }
 
-    pub async fn update_scheduled_cronjob_status(
+    /// Replaces the status of a `ScheduledPatch`. `last_schedule_time` is kept
+    /// unchanged when `None` is given.
+    pub async fn update_scheduled_patch_status(
         &self,
-        resource: &ScheduledCronJob,
-        phase: ScheduledCronJobPhase,
+        resource: &ScheduledPatch,
+        phase: ScheduledPatchPhase,
         message: &str,
+        last_schedule_time: Option<Time>,
     ) -> Result<(), crate::Error> {
         let namespace = resource.namespace().unwrap_or_default();
         let name = resource.name_any();
-        let api = Api::<ScheduledCronJob>::namespaced(self.client.clone(), &namespace);
+        let api = Api::<ScheduledPatch>::namespaced(self.client.clone(), &namespace);
 
         let mut resource = match api.get(&name).await {
             Ok(resource) => resource,
             Err(KubeError::Api(e)) if e.code == 404 => return Ok(()),
             Err(e) => return Err(crate::Error::Kube(e)),
         };
-        resource.status = Some(ScheduledCronJobStatus {
+        let last_schedule_time = last_schedule_time.or_else(|| {
+            resource
+                .status
+                .as_ref()
+                .and_then(|s| s.last_schedule_time.clone())
+        });
+        let conditions = resource
+            .status
+            .take()
+            .map(|s| s.conditions)
+            .unwrap_or_default();
+        resource.status = Some(ScheduledPatchStatus {
             phase,
             message: Some(message.to_string()),
             last_update_time: Some(Time(Utc::now())),
+            last_schedule_time,
+            conditions,
         });
 
-        assert_eq!(resource.status().unwrap().phase, phase);
-        assert_eq!(
-            resource.status().unwrap().message,
-            Some(message.to_string())
-        );
-
         let bytes = serde_json::to_vec(&resource)?;
         api.replace_status(&name, &PostParams::default(), bytes)
             .await?;
         Ok(())
     }
 
-    pub async fn create_scheduled_cronjob_event(
+    pub async fn update_scheduled_suspend(
         &self,
-        resource: &ScheduledCronJob,
-        event_type: &str,
-        reason: &str,
+        resource: &ScheduledSuspend,
+        status: ScheduledSuspendPhase,
+        reason: Reason,
         message: &str,
+    ) -> Result<(), crate::Error> {
+        tracing::info!(
+            name = resource.name_any(),
+            namespace = resource.namespace().unwrap_or_default(),
+            status = status.as_str(),
+            message = message,
+            "Updating status for scheduled suspend",
+        );
+        let previous = resource.status.as_ref().map(|s| s.phase);
+        self.record_event(resource, reason, message, previous != Some(status))
+            .await?;
+        self.update_scheduled_suspend_status(resource, status, message, None)
+            .await?;
+        if previous != Some(status) {
+            self.phase_changed(
+                resource,
+                previous.map(|p| p.as_str()),
+                status.as_str(),
+                message,
+            )
+            .await;
+        }
+        Ok(())
+    }
+
+    /// Replaces the status of a `ScheduledSuspend`. `last_transition_time` is
+    /// kept unchanged when `None` is given.
+    pub async fn update_scheduled_suspend_status(
+        &self,
+        resource: &ScheduledSuspend,
+        phase: ScheduledSuspendPhase,
+        message: &str,
+        last_transition_time: Option<Time>,
     ) -> Result<(), crate::Error> {
         let namespace = resource.namespace().unwrap_or_default();
         let name = resource.name_any();
-        let api = Api::<Event>::namespaced(self.client.clone(), &namespace);
-        let now = Utc::now();
+        let api = Api::<ScheduledSuspend>::namespaced(self.client.clone(), &namespace);
 
-        let api_version = ScheduledCronJob::api_version(&());
-
-        assert_eq!(api_version, "batch.divinerapier.io/v1alpha1");
-
-        let event = Event {
-            metadata: ObjectMeta {
-                name: Some(format!("{}-{}", name, now.timestamp())),
-                namespace: Some(namespace.clone()),
-                ..Default::default()
-            },
-            action: Some("Reconciling".to_string()),
-            count: Some(1),
-            event_time: Some(MicroTime(now)),
-            first_timestamp: Some(Time(now)),
-            involved_object: k8s_openapi::api::core::v1::ObjectReference {
-                kind: Some("ScheduledCronJob".to_string()),
-                namespace: Some(namespace),
-                name: Some(name),
-                api_version: Some(api_version.to_string()),
-                uid: resource.metadata.uid.clone(),
-                ..Default::default()
-            },
-            last_timestamp: Some(Time(now)),
-            message: Some(message.to_string()),
-            reason: Some(reason.to_string()),
-            reporting_component: Some("scheduled-cronjob".to_string()),
-            reporting_instance: Some("scheduled-cronjob-controller".to_string()),
-            type_: Some(event_type.to_string()),
-            series: Some(EventSeries {
-                count: Some(1),
-                last_observed_time: Some(MicroTime(now)),
-                ..Default::default()
-            }),
-            source: Some(k8s_openapi::api::core::v1::EventSource {
-                component: Some("scheduled-cronjob".to_string()),
-                ..Default::default()
-            }),
-            related: None,
+        let mut resource = match api.get(&name).await {
+            Ok(resource) => resource,
+            Err(KubeError::Api(e)) if e.code == 404 => return Ok(()),
+            Err(e) => return Err(crate::Error::Kube(e)),
         };
+        let last_transition_time = last_transition_time.or_else(|| {
+            resource
+                .status
+                .as_ref()
+                .and_then(|s| s.last_transition_time.clone())
+        });
+        let conditions = resource
+            .status
+            .take()
+            .map(|s| s.conditions)
+            .unwrap_or_default();
+        resource.status = Some(ScheduledSuspendStatus {
+            phase,
+            message: Some(message.to_string()),
+            last_update_time: Some(Time(Utc::now())),
+            last_transition_time,
+            conditions,
+        });
 
-        match api.create(&PostParams::default(), &event).await {
-            Ok(_) => Ok(()),
-            Err(KubeError::Api(e)) if e.code == 409 => Ok(()),
-            Err(e) => Err(crate::Error::Kube(e)),
-        }
+        let bytes = serde_json::to_vec(&resource)?;
+        api.replace_status(&name, &PostParams::default(), bytes)
+            .await?;
+        Ok(())
     }
 
-    pub async fn update_delayed_job(
+    pub async fn update_timer_trigger(
         &self,
-        resource: &DelayedJob,
-        status: DelayedJobPhase,
-        event_type: &str,
+        resource: &TimerTrigger,
+        status: TimerTriggerPhase,
+        reason: Reason,
         message: &str,
     ) -> Result<(), crate::Error> {
         tracing::info!(
@@ -206,92 +1321,402 @@ impl Context {
             namespace = resource.namespace().unwrap_or_default(),
             status = status.as_str(),
             message = message,
-            "Updating status for scheduled cronjob",
+            "Updating status for timer trigger",
         );
-        self.create_delayed_job_event(resource, event_type, status.as_str(), message)
+        let previous = resource.status.as_ref().map(|s| s.phase);
+        self.record_event(resource, reason, message, previous != Some(status))
             .await?;
-        self.update_delayed_job_status(resource, status, message)
+        self.update_timer_trigger_status(resource, status, message, None)
             .await?;
+        if previous != Some(status) {
+            self.phase_changed(
+                resource,
+                previous.map(|p| p.as_str()),
+                status.as_str(),
+                message,
+            )
+            .await;
+        }
         Ok(())
     }
 
-    pub async fn update_delayed_job_status(
+    /// Replaces the status of a `TimerTrigger`. `last_schedule_time` is kept
+    /// unchanged when `None` is given.
+    pub async fn update_timer_trigger_status(
         &self,
-        resource: &DelayedJob,
-        phase: DelayedJobPhase,
+        resource: &TimerTrigger,
+        phase: TimerTriggerPhase,
         message: &str,
+        last_schedule_time: Option<Time>,
     ) -> Result<(), crate::Error> {
         let namespace = resource.namespace().unwrap_or_default();
         let name = resource.name_any();
-        let api = Api::<DelayedJob>::namespaced(self.client.clone(), &namespace);
+        let api = Api::<TimerTrigger>::namespaced(self.client.clone(), &namespace);
 
         let mut resource = match api.get(&name).await {
             Ok(resource) => resource,
             Err(KubeError::Api(e)) if e.code == 404 => return Ok(()),
             Err(e) => return Err(crate::Error::Kube(e)),
         };
-        resource.status = Some(DelayedJobStatus {
+        let last_schedule_time = last_schedule_time.or_else(|| {
+            resource
+                .status
+                .as_ref()
+                .and_then(|s| s.last_schedule_time.clone())
+        });
+        let conditions = resource
+            .status
+            .take()
+            .map(|s| s.conditions)
+            .unwrap_or_default();
+        resource.status = Some(TimerTriggerStatus {
             phase,
             message: Some(message.to_string()),
             last_update_time: Some(Time(Utc::now())),
+            last_schedule_time,
+            conditions,
         });
 
-        assert_eq!(resource.status().unwrap().phase, phase);
-        assert_eq!(
-            resource.status().unwrap().message,
-            Some(message.to_string())
-        );
-
         let bytes = serde_json::to_vec(&resource)?;
         api.replace_status(&name, &PostParams::default(), bytes)
             .await?;
         Ok(())
     }
 
-    pub async fn create_delayed_job_event(
+    /// Posts `payload` as JSON to `webhook`, failing on non-2xx responses.
+    pub async fn send_webhook<T: Serialize>(
         &self,
-        resource: &DelayedJob,
-        event_type: &str,
-        reason: &str,
+        webhook: &Webhook,
+        payload: &T,
+    ) -> Result<(), crate::Error> {
+        self.ensure_not_stopped()?;
+        if self.config().observer {
+            self.observe("Webhook", "post", format!("POST {}", webhook.url));
+            return Ok(());
+        }
+        let mut request = self.http.post(&webhook.url).json(payload);
+        for (key, value) in &webhook.headers {
+            request = request.header(key, value);
+        }
+        request.send().await?.error_for_status()?;
+        Ok(())
+    }
+
+    /// Reports a phase change of `resource` to the installed hooks.
+    async fn phase_changed<K>(&self, resource: &K, from: Option<&str>, to: &str, message: &str)
+    where
+        K: KubeResource<DynamicType = ()> + HasOwner,
+    {
+        let Some(hooks) = &self.hooks else {
+            return;
+        };
+        let change = PhaseChange {
+            kind: K::kind(&()).into_owned(),
+            namespace: resource.namespace().unwrap_or_default(),
+            name: resource.name_any(),
+            from: from.map(str::to_string),
+            to: to.to_string(),
+            message: message.to_string(),
+            owner: resource.owner().cloned(),
+        };
+        hooks.on_phase_change(&change).await;
+    }
+
+    /// Archives the finished run of `resource` carried out by `job` to the
+    /// history sink and publishes it to the event bus, where configured.
+    /// Called before the Job is deleted, so a failed archive is retried while
+    /// the run is still observable; publishing is best effort.
+    pub async fn archive_run<K>(
+        &self,
+        resource: &K,
+        job: &Job,
+        phase: &str,
+        message: &str,
+    ) -> Result<(), crate::Error>
+    where
+        K: KubeResource<DynamicType = ()>,
+    {
+        if self.history.is_none() && self.bus.is_none() {
+            return Ok(());
+        }
+        self.ensure_not_stopped()?;
+        let status = job.status.as_ref();
+        let record = RunRecord {
+            kind: K::kind(&()).into_owned(),
+            namespace: resource.namespace().unwrap_or_default(),
+            name: resource.name_any(),
+            uid: resource.uid(),
+            job: job.name_any(),
+            phase: phase.to_string(),
+            message: message.to_string(),
+            start_time: status.and_then(|s| s.start_time.as_ref()).map(|t| t.0),
+            completion_time: status
+                .and_then(|s| s.completion_time.as_ref())
+                .map_or_else(Utc::now, |t| t.0),
+        };
+        if let Some(history) = &self.history {
+            if self.config().observer {
+                self.observe(
+                    "RunRecord",
+                    "archive",
+                    format!("archive run {}/{}", record.namespace, record.job),
+                );
+            } else {
+                history.archive(&record).await?;
+            }
+        }
+        self.publish(RUNS_TOPIC, resource, &record).await;
+        Ok(())
+    }
+
+    /// Reports a lifecycle transition as a CloudEvent to the CloudEvents sink
+    /// and the event bus, where configured. Delivery is best effort: the
+    /// transition has already been recorded in the status, so failures are
+    /// logged rather than retried.
+    pub async fn report_transition<K>(
+        &self,
+        resource: &K,
+        transition: Transition,
+        phase: &str,
         message: &str,
+    ) where
+        K: KubeResource<DynamicType = ()> + HasOwner,
+    {
+        if self.config().cloudevents_sink.is_none() && self.bus.is_none() {
+            return;
+        }
+        let event = CloudEvent::new(resource, transition, phase, message);
+        if let Some(sink) = &self.config().cloudevents_sink {
+            if self.config().observer {
+                self.observe(
+                    "CloudEvent",
+                    "post",
+                    format!("POST {} {}", sink, event.type_),
+                );
+            } else {
+                let result = async {
+                    self.http
+                        .post(sink)
+                        .header("content-type", "application/cloudevents+json")
+                        .json(&event)
+                        .send()
+                        .await?
+                        .error_for_status()
+                }
+                .await;
+                if let Err(e) = result {
+                    tracing::warn!(
+                        name = resource.name_any(),
+                        namespace = resource.namespace().unwrap_or_default(),
+                        r#type = event.type_,
+                        error = ?e,
+                        "Failed to deliver CloudEvent"
+                    );
+                }
+            }
+        }
+        self.publish(TRANSITIONS_TOPIC, resource, &event).await;
+    }
+
+    /// Publishes `record` about `resource` to `topic` on the event bus, if
+    /// one is configured, logging failures.
+    async fn publish<K, T>(&self, topic: &str, resource: &K, record: &T)
+    where
+        K: KubeResource<DynamicType = ()>,
+        T: Serialize,
+    {
+        let Some(bus) = &self.bus else {
+            return;
+        };
+        let topic = format!("{}.{topic}", self.config().event_bus_topic_prefix);
+        let key = format!(
+            "{}/{}",
+            resource.namespace().unwrap_or_default(),
+            resource.name_any()
+        );
+        if self.config().observer {
+            self.observe("BusRecord", "publish", format!("publish {topic} {key}"));
+            return;
+        }
+        let result = match serde_json::to_value(record) {
+            Ok(payload) => bus.publish(&topic, &key, &payload).await,
+            Err(e) => Err(e.into()),
+        };
+        if let Err(e) = result {
+            tracing::warn!(topic, key, error = ?e, "Failed to publish to event bus");
+        }
+    }
+
+    /// Evaluates `condition` of a resource in `namespace`, returning whether
+    /// the run may proceed together with the observed metric value.
+    pub async fn evaluate_fire_condition(
+        &self,
+        namespace: &str,
+        condition: &FireCondition,
+    ) -> Result<(bool, f64), crate::Error> {
+        let value = match (&condition.prometheus, &condition.metrics_api) {
+            (Some(prometheus), _) => self.query_prometheus(prometheus).await?,
+            (None, Some(metrics_api)) => self.query_metrics_api(namespace, metrics_api).await?,
+            (None, None) => {
+                return Err(crate::Error::Condition(
+                    "either prometheus or metricsApi must be set".to_string(),
+                ));
+            }
+        };
+        Ok((
+            condition.comparator.compare(value, condition.threshold),
+            value,
+        ))
+    }
+
+    async fn query_prometheus(&self, query: &PrometheusQuery) -> Result<f64, crate::Error> {
+        let url = format!("{}/api/v1/query", query.url.trim_end_matches('/'));
+        let response: serde_json::Value = self
+            .http
+            .get(url)
+            .query(&[("query", query.query.as_str())])
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        // vector 结果取第一个样本，scalar 结果直接取值
+        let result = &response["data"]["result"];
+        let sample = match response["data"]["resultType"].as_str() {
+            Some("vector") => &result[0]["value"][1],
+            Some("scalar") => &result[1],
+            other => {
+                return Err(crate::Error::Condition(format!(
+                    "unsupported result type {:?} for {}",
+                    other, query.query
+                )));
+            }
+        };
+        sample
+            .as_str()
+            .and_then(|s| s.parse::<f64>().ok())
+            .ok_or_else(|| {
+                crate::Error::Condition(format!("no sample returned for {}", query.query))
+            })
+    }
+
+    async fn query_metrics_api(
+        &self,
+        namespace: &str,
+        query: &MetricsApiQuery,
+    ) -> Result<f64, crate::Error> {
+        query.check(namespace)?;
+        let request = http::Request::get(&query.path)
+            .body(Vec::new())
+            .map_err(|e| crate::Error::Condition(e.to_string()))?;
+        let response: serde_json::Value = self.client.request(request).await?;
+        response["items"][0]["value"]
+            .as_str()
+            .and_then(parse_quantity)
+            .ok_or_else(|| crate::Error::Condition(format!("no value returned for {}", query.path)))
+    }
+
+    /// Applies `patch` to an arbitrary resource, resolving its kind through
+    /// API discovery. `namespace` is the referencing object's; a target in
+    /// another namespace must be allowed by [`Context::allows_targets_from`].
+    pub async fn patch_target(
+        &self,
+        namespace: &str,
+        target: &TargetRef,
+        patch: &Patch<serde_json::Value>,
     ) -> Result<(), crate::Error> {
+        self.ensure_not_stopped()?;
+        let from = namespace;
+        let namespace = target.namespace.as_deref().unwrap_or(from);
+        if namespace != from && !self.allows_targets_from(namespace, from).await? {
+            return Err(crate::Error::InvalidTarget(format!(
+                "namespace {namespace} does not allow targets from {from}"
+            )));
+        }
+        let gvk = target.gvk()?;
+        let (resource, _) = match discovery::pinned_kind(&self.client, &gvk).await {
+            Ok(found) => found,
+            Err(KubeError::Api(e)) if e.code == 404 => {
+                return Err(crate::Error::InvalidTarget(format!(
+                    "{}/{} is not served by the cluster",
+                    target.api_version, target.kind
+                )));
+            }
+            Err(e) => return Err(crate::Error::Kube(e)),
+        };
+        let api = Api::<DynamicObject>::namespaced_with(self.client.clone(), namespace, &resource);
+        let mut params = PatchParams::default();
+        if self.config().observer {
+            params = params.dry_run();
+            let description = format!("patch {} {namespace}/{}", target.kind, target.name);
+            self.observe(&target.kind, "patch", description);
+        }
+        match api.patch(&target.name, &params, patch).await {
+            Ok(_) => Ok(()),
+            Err(KubeError::Api(e)) if e.code == 404 => Err(crate::Error::NotFound),
+            Err(e) => Err(crate::Error::Kube(e)),
+        }
+    }
+
+    pub async fn create_event<K>(
+        &self,
+        resource: &K,
+        reason: Reason,
+        message: &str,
+    ) -> Result<(), crate::Error>
+    where
+        K: KubeResource<DynamicType = ()> + HasOwner,
+    {
+        self.record_event(resource, reason, message, false).await
+    }
+
+    /// Emits an event unless [`Config::event_policy`] drops it. `transition`
+    /// tells whether the event reports a phase change.
+    async fn record_event<K>(
+        &self,
+        resource: &K,
+        reason: Reason,
+        message: &str,
+        transition: bool,
+    ) -> Result<(), crate::Error>
+    where
+        K: KubeResource<DynamicType = ()> + HasOwner,
+    {
+        if !self.config().event_policy.allows(reason, transition) {
+            return Ok(());
+        }
+        self.metrics
+            .events_total
+            .with_label_values(&[&K::kind(&()), reason.as_str()])
+            .inc();
         let namespace = resource.namespace().unwrap_or_default();
         let name = resource.name_any();
-        let api = Api::<Event>::namespaced(self.client.clone(), &namespace);
         let now = Utc::now();
 
-        let api_version = DelayedJob::api_version(&());
-
-        assert_eq!(api_version, "batch.divinerapier.io/v1alpha1");
-
         let event = Event {
             metadata: ObjectMeta {
-                name: Some(format!("{}-{}", name, now.timestamp())),
+                // The API server appends a random suffix, so events emitted
+                // within the same second are all kept.
+                generate_name: Some(format!("{name}-")),
                 namespace: Some(namespace.clone()),
+                annotations: resource.owner().map(Owner::annotations),
                 ..Default::default()
             },
             action: Some("Reconciling".to_string()),
             count: Some(1),
             event_time: Some(MicroTime(now)),
             first_timestamp: Some(Time(now)),
-            involved_object: k8s_openapi::api::core::v1::ObjectReference {
-                kind: Some("DelayedJob".to_string()),
-                namespace: Some(namespace),
-                name: Some(name),
-                api_version: Some(api_version.to_string()),
-                uid: resource.metadata.uid.clone(),
-                ..Default::default()
-            },
+            involved_object: resource.object_reference(),
             last_timestamp: Some(Time(now)),
             message: Some(message.to_string()),
             reason: Some(reason.to_string()),
             reporting_component: Some("scheduled-cronjob".to_string()),
             reporting_instance: Some("scheduled-cronjob-controller".to_string()),
-            type_: Some(event_type.to_string()),
+            type_: Some(reason.event_type().to_string()),
             series: Some(EventSeries {
                 count: Some(1),
                 last_observed_time: Some(MicroTime(now)),
-                ..Default::default()
             }),
             source: Some(k8s_openapi::api::core::v1::EventSource {
                 component: Some("scheduled-cronjob".to_string()),
@@ -300,14 +1725,87 @@ impl Context {
             related: None,
         };
 
-        match api.create(&PostParams::default(), &event).await {
-            Ok(_) => Ok(()),
-            Err(KubeError::Api(e)) if e.code == 409 => Ok(()),
-            Err(e) => Err(crate::Error::Kube(e)),
+        // Outlives the Event in clusters that expire them quickly.
+        if self.config().event_log {
+            tracing::info!(
+                target: "events",
+                kind = K::kind(&()).as_ref(),
+                namespace = namespace.as_str(),
+                name = name.as_str(),
+                reason = reason.as_str(),
+                type = reason.event_type(),
+                note = message,
+                "Event",
+            );
         }
+        let created = match self.events_api {
+            EventsApi::EventsV1 => {
+                let api = Api::<events::v1::Event>::namespaced(self.client.clone(), &namespace);
+                api.create(&PostParams::default(), &to_events_v1(event))
+                    .await
+                    .map(|_| ())
+            }
+            EventsApi::CoreV1 => {
+                let api = Api::<Event>::namespaced(self.client.clone(), &namespace);
+                api.create(&PostParams::default(), &event).await.map(|_| ())
+            }
+        };
+        created.map_err(crate::Error::Kube)
     }
 }
 
+/// `event` as an `events.k8s.io/v1` Event. That API rejects the deprecated
+/// fields on creation and only takes a series from the second occurrence on,
+/// so both are left out.
+fn to_events_v1(event: Event) -> events::v1::Event {
+    events::v1::Event {
+        metadata: event.metadata,
+        action: event.action,
+        event_time: event.event_time,
+        note: event.message,
+        reason: event.reason,
+        regarding: Some(event.involved_object),
+        related: event.related,
+        reporting_controller: event.reporting_component,
+        reporting_instance: event.reporting_instance,
+        type_: event.type_,
+        ..Default::default()
+    }
+}
+
+/// Follows the `continue` tokens of a paginated list, yielding the items of
+/// one page at a time. `fetch` performs a single list call.
+fn paginate<T, E, F, Fut>(
+    params: ListParams,
+    fetch: F,
+) -> impl Stream<Item = Result<T, crate::Error>>
+where
+    T: Clone,
+    F: Fn(ListParams) -> Fut,
+    Fut: Future<Output = Result<ObjectList<T>, E>>,
+    crate::Error: From<E>,
+{
+    stream::try_unfold(Some(None), move |token: Option<Option<String>>| {
+        let mut params = params.clone();
+        let list = token.map(|token| {
+            if let Some(token) = token {
+                params = params.continue_token(&token);
+            }
+            fetch(params)
+        });
+        async move {
+            let Some(list) = list else {
+                return Ok(None);
+            };
+            let list = list.await?;
+            let next = list.metadata.continue_.filter(|t| !t.is_empty());
+            let page = stream::iter(list.items.into_iter().map(Ok));
+            Ok::<_, crate::Error>(Some((page, next.map(Some))))
+        }
+    })
+    .try_flatten()
+}
+
 impl Deref for Context {
     type Target = Client;
 
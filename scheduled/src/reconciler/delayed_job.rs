@@ -8,9 +8,15 @@ use kube::{
 };
 use tracing::{debug, error, info, warn};
 
+use super::{guard, report_lint};
 use crate::{
-    Context, Error,
-    crd::{DelayedJob, DelayedJobPhase},
+    Context, Error, compat,
+    crd::{
+        DelayedJob, DelayedJobPhase, MissedRunPolicy, NAMESPACE_TERMINATING, NO_CAPACITY,
+        UNSUPPORTED_FEATURES, is_condition_true,
+    },
+    reason::Reason,
+    registry,
 };
 
 pub async fn reconcile(job: Arc<DelayedJob>, ctx: Arc<Context>) -> Result<Action, Error> {
@@ -19,7 +25,7 @@ pub async fn reconcile(job: Arc<DelayedJob>, ctx: Arc<Context>) -> Result<Action
 
     info!(name, namespace, "Starting delayed job reconciliation");
 
-    match implement(&job, ctx.clone()).await {
+    match guard(job.as_ref(), &ctx, implement(&job, ctx.clone())).await {
         Ok(action) => {
             debug!(
                 name,
@@ -29,8 +35,22 @@ pub async fn reconcile(job: Arc<DelayedJob>, ctx: Arc<Context>) -> Result<Action
             );
             Ok(action)
         }
+        Err(Error::NamespaceTerminating(_)) => {
+            info!(name, namespace, "Namespace is terminating, skipping");
+            ctx.set_condition(
+                job.as_ref(),
+                NAMESPACE_TERMINATING,
+                true,
+                Reason::NamespaceTerminating,
+                "Namespace is being deleted; no children will be created",
+            )
+            .await?;
+            Ok(Action::await_change())
+        }
         Err(e) => {
-            error!(name, namespace, error = ?e, "Error in reconciliation");
+            if let Some(suppressed) = ctx.log_throttle().check(job.as_ref()) {
+                error!(name, namespace, suppressed, error = ?e, "Error in reconciliation");
+            }
             Err(e)
         }
     }
@@ -38,41 +58,314 @@ pub async fn reconcile(job: Arc<DelayedJob>, ctx: Arc<Context>) -> Result<Action
 
 // 判断 Job 是否正在运行
 fn is_job_running(obj: &Job) -> bool {
-    if let Some(status) = &obj.status {
-        if let Some(active) = status.active {
-            return active > 0;
-        }
+    if let Some(status) = &obj.status
+        && let Some(active) = status.active
+    {
+        return active > 0;
     }
     false
 }
 
 // 判断 Job 是否失败
 fn is_job_failed(obj: &Job) -> bool {
-    if let Some(status) = &obj.status {
-        if let Some(failed) = status.failed {
-            return failed > 0;
-        }
+    if let Some(status) = &obj.status
+        && let Some(failed) = status.failed
+    {
+        return failed > 0;
     }
     false
 }
 
+/// How far the DelayedJob or Job a DelayedJob runs after has got.
+enum Dependency {
+    Succeeded,
+    Failed(String),
+    Pending(String),
+}
+
+/// Finds how the DelayedJob, or failing that the Job, `name` ended. A
+/// DelayedJob is looked up first, as its Job is deleted once it completes.
+async fn dependency(ctx: &Context, namespace: &str, name: &str) -> Result<Dependency, Error> {
+    match ctx.get::<DelayedJob>(namespace, name).await {
+        Ok(dependency) => {
+            let phase = dependency
+                .status
+                .as_ref()
+                .map(|s| s.phase)
+                .unwrap_or_default();
+            return Ok(match phase {
+                DelayedJobPhase::Completed => Dependency::Succeeded,
+                DelayedJobPhase::Failed if retry_pending(ctx, &dependency).await? => {
+                    Dependency::Pending(format!("Waiting for DelayedJob {name} to retry"))
+                }
+                DelayedJobPhase::Failed | DelayedJobPhase::InvalidStartTime => {
+                    Dependency::Failed(format!("DelayedJob {name} is {}", phase.as_str()))
+                }
+                _ => Dependency::Pending(format!("Waiting for DelayedJob {name} to complete")),
+            });
+        }
+        Err(Error::NotFound) => {}
+        Err(e) => return Err(e),
+    }
+    let job = match ctx.get::<Job>(namespace, name).await {
+        Ok(job) => job,
+        Err(Error::NotFound) => {
+            return Ok(Dependency::Pending(format!(
+                "Waiting for DelayedJob or Job {name} to be created"
+            )));
+        }
+        Err(e) => return Err(e),
+    };
+    let failed = job
+        .status
+        .as_ref()
+        .and_then(|s| s.conditions.as_ref())
+        .is_some_and(|c| c.iter().any(|c| c.type_ == "Failed" && c.status == "True"));
+    Ok(
+        if conditions::is_job_completed().matches_object(Some(&job)) {
+            Dependency::Succeeded
+        } else if failed {
+            Dependency::Failed(format!("Job {name} failed"))
+        } else {
+            Dependency::Pending(format!("Waiting for Job {name} to complete"))
+        },
+    )
+}
+
+/// Whether the `Failed` DelayedJob `dependency` may still run again: its Job
+/// is retried up to its backoff limit, then re-created while
+/// `spec.retryPolicy` allows. Once retries are exhausted the Job is deleted.
+async fn retry_pending(ctx: &Context, dependency: &DelayedJob) -> Result<bool, Error> {
+    let namespace = dependency.namespace().unwrap_or_default();
+    let job = match ctx.get::<Job>(&namespace, &dependency.name_any()).await {
+        Ok(job) => job,
+        Err(Error::NotFound) => return Ok(false),
+        Err(e) => return Err(e),
+    };
+    let failed_count = job.status.as_ref().and_then(|s| s.failed).unwrap_or(0);
+    let backoff_limit = job.spec.as_ref().and_then(|s| s.backoff_limit).unwrap_or(6);
+    if failed_count < backoff_limit {
+        return Ok(true);
+    }
+    // Resources created before attempts were recorded ran once.
+    let attempts = dependency
+        .status
+        .as_ref()
+        .and_then(|s| s.attempts)
+        .unwrap_or(1);
+    Ok(dependency
+        .spec
+        .retry_policy
+        .as_ref()
+        .is_some_and(|policy| attempts <= policy.max_retries))
+}
+
+/// Holds `delayed_job` back until the DelayedJob or Job `name` it runs after
+/// succeeded, failing it if that failed. Returns what to do while it is held
+/// back; the DelayedJob and Job watches re-reconcile it when the dependency
+/// changes.
+async fn await_dependency(
+    ctx: &Context,
+    delayed_job: &DelayedJob,
+    name: &str,
+) -> Result<Option<Action>, Error> {
+    let namespace = delayed_job.namespace().unwrap_or_default();
+    let (phase, reason, message) = if name == delayed_job.name_any() {
+        (
+            DelayedJobPhase::Failed,
+            Reason::SelfDependency,
+            "runAfterJob names the DelayedJob itself".to_string(),
+        )
+    } else {
+        match dependency(ctx, &namespace, name).await? {
+            Dependency::Succeeded => return Ok(None),
+            Dependency::Failed(message) => {
+                (DelayedJobPhase::Failed, Reason::DependencyFailed, message)
+            }
+            Dependency::Pending(message) => {
+                (DelayedJobPhase::Pending, Reason::DependencyPending, message)
+            }
+        }
+    };
+    let status = delayed_job.status.as_ref();
+    if status.map(|s| s.phase) != Some(phase)
+        || status.and_then(|s| s.message.as_deref()) != Some(message.as_str())
+    {
+        ctx.update_delayed_job(delayed_job, phase, reason, &message)
+            .await?;
+    }
+    Ok(Some(Action::await_change()))
+}
+
 async fn implement(delayed_job: &DelayedJob, ctx: Arc<Context>) -> Result<Action, Error> {
     let name = delayed_job.name_any();
     let namespace = delayed_job.namespace().unwrap_or_default();
 
     info!(name, namespace, "Starting delayed job reconciliation");
 
-    if let Some(ref start_time) = delayed_job.spec.start_time {
+    report_lint(delayed_job, &ctx).await?;
+
+    let fire_at = match delayed_job.fire_at() {
+        Ok(fire_at) => fire_at,
+        Err(e) => {
+            warn!(name, namespace, error = %e, "Invalid start time");
+            if delayed_job.status.as_ref().map(|s| s.phase)
+                != Some(DelayedJobPhase::InvalidStartTime)
+            {
+                ctx.update_delayed_job(
+                    delayed_job,
+                    DelayedJobPhase::InvalidStartTime,
+                    e.reason(),
+                    &e.to_string(),
+                )
+                .await?;
+            }
+            return Ok(Action::await_change());
+        }
+    };
+    if let Some(fire_at) = fire_at {
         let now = Utc::now();
-        if start_time.0 > now {
-            let wait_for = (start_time.0 - now).to_std().unwrap();
-            return Ok(Action::requeue(wait_for));
+        if fire_at > now {
+            let wait_for = (fire_at - now).to_std().unwrap();
+            return Ok(ctx.requeue(delayed_job, wait_for));
         }
     }
 
     let job = match ctx.get::<Job>(&namespace, &name).await {
         Ok(job) => job,
-        Err(Error::NotFound) => ctx.create::<Job>(&namespace, &delayed_job.job()).await?,
+        Err(Error::NotFound) => {
+            // Finished Jobs are deleted once they are, or after
+            // ttlSecondsAfterFinished when set; they must not run again, nor
+            // may skipped ones.
+            let status = delayed_job.status.as_ref();
+            if let Some(
+                phase @ (DelayedJobPhase::Completed
+                | DelayedJobPhase::Failed
+                | DelayedJobPhase::Skipped),
+            ) = status.map(|s| s.phase)
+            {
+                debug!(
+                    name,
+                    namespace,
+                    phase = phase.as_str(),
+                    "Job of finished delayed job is gone"
+                );
+                return Ok(Action::await_change());
+            }
+            if let Some(retry) = status.and_then(|s| s.next_retry_time.as_ref())
+                && let Ok(wait_for) = (retry.0 - Utc::now()).to_std()
+            {
+                return Ok(ctx.requeue(delayed_job, wait_for));
+            }
+            let now = Utc::now();
+            if let Some(fire_at) = fire_at
+                && delayed_job.spec.run_after_job.is_none()
+                && status.and_then(|s| s.attempts).unwrap_or_default() == 0
+                && delayed_job.missed_by(fire_at, now).is_some()
+            {
+                // Reported from the start time, not from the end of the
+                // deadline that made it a miss.
+                let message = format!(
+                    "Start time {} was missed by {}s",
+                    fire_at.to_rfc3339(),
+                    (now - fire_at).num_seconds()
+                );
+                let phase = match delayed_job.spec.missed_run_policy {
+                    MissedRunPolicy::Run => None,
+                    MissedRunPolicy::Skip => Some(DelayedJobPhase::Skipped),
+                    MissedRunPolicy::Fail => Some(DelayedJobPhase::Failed),
+                };
+                if let Some(phase) = phase {
+                    warn!(
+                        name,
+                        namespace,
+                        message,
+                        phase = phase.as_str(),
+                        "Missed run"
+                    );
+                    ctx.update_delayed_job(delayed_job, phase, Reason::DeadlineMissed, &message)
+                        .await?;
+                    return Ok(Action::await_change());
+                }
+                info!(name, namespace, message, "Running missed run late");
+            }
+            if let Some(dependency) = &delayed_job.spec.run_after_job
+                && let Some(action) = await_dependency(&ctx, delayed_job, dependency).await?
+            {
+                return Ok(action);
+            }
+            if ctx.namespace_terminating(&namespace).await? {
+                return Err(Error::NamespaceTerminating(namespace));
+            }
+            if let Some(pod) = &delayed_job.spec.spec.template.spec
+                && !ctx.has_capacity(pod).await
+            {
+                info!(
+                    name,
+                    namespace, "No schedulable node fits the pod, deferring"
+                );
+                ctx.set_condition(
+                    delayed_job,
+                    NO_CAPACITY,
+                    true,
+                    Reason::NoCapacity,
+                    "No schedulable node matches the pod's selector, affinity and tolerations",
+                )
+                .await?;
+                return Ok(ctx.requeue(delayed_job, ctx.config().capacity_retry));
+            }
+            let mut desired = delayed_job.job()?;
+            let unsupported = desired
+                .spec
+                .as_mut()
+                .map(|spec| compat::downgrade_job(spec, ctx.server_version()))
+                .unwrap_or_default();
+            if let Some(pod) = desired.spec.as_mut().and_then(|s| s.template.spec.as_mut()) {
+                let config = ctx.config();
+                if config.openshift {
+                    compat::restrict(pod);
+                }
+                registry::apply(pod, &config.registry_rewrite);
+            }
+            if !unsupported.is_empty() {
+                let message = unsupported.join("; ");
+                ctx.create_event(delayed_job, Reason::FeatureUnsupported, &message)
+                    .await?;
+                ctx.set_condition(
+                    delayed_job,
+                    UNSUPPORTED_FEATURES,
+                    true,
+                    Reason::FeatureUnsupported,
+                    &message,
+                )
+                .await?;
+            }
+            let job = ctx.create::<Job>(&namespace, &desired).await?;
+            let attempts = status.and_then(|s| s.attempts).unwrap_or_default() + 1;
+            ctx.update_delayed_job_attempts(delayed_job, attempts, None)
+                .await?;
+            ctx.create_event(
+                delayed_job,
+                Reason::ChildCreated,
+                &format!("Created Job {name}"),
+            )
+            .await?;
+            if delayed_job
+                .status
+                .as_ref()
+                .is_some_and(|s| is_condition_true(&s.conditions, NO_CAPACITY))
+            {
+                ctx.set_condition(
+                    delayed_job,
+                    NO_CAPACITY,
+                    false,
+                    Reason::ChildCreated,
+                    &format!("Created Job {name}"),
+                )
+                .await?;
+            }
+            job
+        }
         Err(e) => return Err(e),
     };
 
@@ -82,14 +375,23 @@ async fn implement(delayed_job: &DelayedJob, ctx: Arc<Context>) -> Result<Action
             return Ok(Action::await_change());
         }
 
+        ctx.archive_run(
+            delayed_job,
+            &job,
+            DelayedJobPhase::Completed.as_str(),
+            "Job completed",
+        )
+        .await?;
         ctx.update_delayed_job(
             delayed_job,
             DelayedJobPhase::Completed,
-            "Job completed",
+            Reason::Completed,
             "Job completed",
         )
         .await?;
-        ctx.delete::<Job>(&namespace, &name).await?;
+        if delayed_job.ttl_seconds_after_finished().is_none() {
+            ctx.delete::<Job>(&namespace, &name).await?;
+        }
         return Ok(Action::await_change());
     }
 
@@ -100,39 +402,79 @@ async fn implement(delayed_job: &DelayedJob, ctx: Arc<Context>) -> Result<Action
             ctx.update_delayed_job(
                 delayed_job,
                 DelayedJobPhase::Running,
-                "Normal",
+                Reason::Activated,
                 "Job is running",
             )
             .await?;
         }
-        return Ok(Action::requeue(Duration::from_secs(60)));
+        return Ok(ctx.requeue(delayed_job, Duration::from_secs(60)));
     }
 
     if is_job_failed(&job) {
-        // Only update status if phase changed
-        if delayed_job.status.as_ref().map(|s| s.phase) == Some(DelayedJobPhase::Failed) {
-            return Ok(Action::await_change());
-        }
-
         let failed_count = job.status.as_ref().and_then(|s| s.failed).unwrap_or(0);
         let backoff_limit = job.spec.as_ref().and_then(|s| s.backoff_limit).unwrap_or(6);
 
         if failed_count >= backoff_limit {
+            // Kept until its TTL once the failure was recorded.
+            if delayed_job.status.as_ref().map(|s| s.phase) == Some(DelayedJobPhase::Failed) {
+                return Ok(Action::await_change());
+            }
             warn!(
                 name,
                 namespace, failed_count, backoff_limit, "Job failed after maximum retries"
             );
+            let message = format!("Job failed after {} retries", failed_count);
+            ctx.archive_run(
+                delayed_job,
+                &job,
+                DelayedJobPhase::Failed.as_str(),
+                &message,
+            )
+            .await?;
+            // Resources created before attempts were recorded ran once.
+            let attempts = delayed_job
+                .status
+                .as_ref()
+                .and_then(|s| s.attempts)
+                .unwrap_or(1);
+            if let Some(policy) = &delayed_job.spec.retry_policy
+                && attempts <= policy.max_retries
+            {
+                let delay = policy.backoff(attempts);
+                info!(name, namespace, attempts, ?delay, "Retrying failed job");
+                ctx.update_delayed_job(
+                    delayed_job,
+                    DelayedJobPhase::Pending,
+                    Reason::JobFailed,
+                    &format!(
+                        "Attempt {attempts} failed, retrying in {}s: {message}",
+                        delay.as_secs()
+                    ),
+                )
+                .await?;
+                let next = Utc::now() + chrono::Duration::from_std(delay).unwrap_or_default();
+                ctx.update_delayed_job_attempts(delayed_job, attempts, Some(next))
+                    .await?;
+                ctx.delete::<Job>(&namespace, &name).await?;
+                return Ok(ctx.requeue(delayed_job, delay));
+            }
             ctx.update_delayed_job(
                 delayed_job,
                 DelayedJobPhase::Failed,
-                "Warning",
-                &format!("Job failed after {} retries", failed_count),
+                Reason::JobFailed,
+                &message,
             )
             .await?;
-            ctx.delete::<Job>(&namespace, &name).await?;
+            if delayed_job.ttl_seconds_after_finished().is_none() {
+                ctx.delete::<Job>(&namespace, &name).await?;
+            }
             return Ok(Action::await_change());
         }
 
+        // Only update status if phase changed
+        if delayed_job.status.as_ref().map(|s| s.phase) == Some(DelayedJobPhase::Failed) {
+            return Ok(Action::await_change());
+        }
         info!(
             name,
             namespace, failed_count, backoff_limit, "Job failed, waiting for retry"
@@ -140,11 +482,11 @@ async fn implement(delayed_job: &DelayedJob, ctx: Arc<Context>) -> Result<Action
         ctx.update_delayed_job(
             delayed_job,
             DelayedJobPhase::Failed,
-            "Warning",
+            Reason::JobFailed,
             &format!("Job failed, retry {}/{}", failed_count, backoff_limit),
         )
         .await?;
-        return Ok(Action::requeue(Duration::from_secs(60)));
+        return Ok(ctx.requeue(delayed_job, Duration::from_secs(60)));
     }
 
     Ok(Action::await_change())
@@ -1,24 +1,241 @@
+mod apply;
 mod context;
 mod delayed_job;
-mod scheduled_cronjob;
+mod namespace;
+pub(crate) mod scheduled_cronjob;
+mod scheduled_patch;
+mod scheduled_suspend;
+mod timer_trigger;
 
 pub use context::Context;
 pub use delayed_job::reconcile as reconcile_delayed_job;
-use kube::{ResourceExt as _, core::Resource, runtime::controller::Action};
+use k8s_openapi::NamespaceResourceScope;
+use kube::{
+    ResourceExt as _,
+    core::{Resource, object::HasStatus},
+    runtime::controller::Action,
+};
+pub use namespace::reconcile as reconcile_namespace;
 pub use scheduled_cronjob::reconcile as reconcile_scheduled_cronjob;
-use serde::de::DeserializeOwned;
+pub use scheduled_patch::reconcile as reconcile_scheduled_patch;
+pub use scheduled_suspend::reconcile as reconcile_scheduled_suspend;
+use serde::{Serialize, de::DeserializeOwned};
 use std::{fmt::Debug, hash::Hash, sync::Arc, time::Duration};
+pub use timer_trigger::reconcile as reconcile_timer_trigger;
 
 use crate::Error;
+use crate::breaker::RESET_ANNOTATION;
+use crate::crd::{CIRCUIT_OPEN, HasConditions, HasOwner, LINT, is_condition_true};
+use crate::hooks::ReconcileFailure;
+use crate::lint::Lint;
+use crate::observer::{self, WOULD_APPLY_ANNOTATION};
+use crate::reason::Reason;
 
-pub fn error_policy<K>(job: Arc<K>, err: &Error, _ctx: Arc<Context>) -> Action
+/// How often resources are rechecked while the emergency stop is engaged.
+const EMERGENCY_STOP_RECHECK: Duration = Duration::from_secs(30);
+
+/// Runs `reconcile` unless the circuit of `resource` is open. Transient
+/// failures are counted, and once too many pile up the circuit opens with a
+/// single summarizing event and a `CircuitOpen` condition.
+pub(crate) async fn guard<K>(
+    resource: &K,
+    ctx: &Context,
+    reconcile: impl Future<Output = Result<Action, Error>>,
+) -> Result<Action, Error>
+where
+    K: Resource<Scope = NamespaceResourceScope, DynamicType = ()> + HasOwner,
+    K: HasStatus + Clone + DeserializeOwned + Serialize + Debug,
+    K::Status: HasConditions + Default,
+{
+    let name = resource.name_any();
+    let namespace = resource.namespace().unwrap_or_default();
+    let breaker = ctx.circuit_breaker();
+    let kind = K::kind(&());
+
+    if !ctx.leadership().is_leader() {
+        tracing::debug!(name, namespace, "Not the leader, skipping reconciliation");
+        return Ok(ctx.requeue(resource, ctx.config().leader_lease_duration));
+    }
+
+    let namespaces = &ctx.config().namespaces;
+    if !namespaces.is_empty() && !namespaces.contains(&namespace) {
+        tracing::debug!(
+            name,
+            namespace,
+            "Namespace not watched, skipping reconciliation"
+        );
+        return Ok(ctx.requeue(resource, ctx.config().repair_interval));
+    }
+
+    if ctx.emergency_stop().engaged() {
+        tracing::debug!(
+            name,
+            namespace,
+            "Emergency stop engaged, skipping reconciliation"
+        );
+        return Ok(ctx.requeue(resource, EMERGENCY_STOP_RECHECK));
+    }
+
+    if let Some(latency) = ctx.queue().start(resource) {
+        ctx.metrics()
+            .queue_latency_seconds
+            .with_label_values(&[kind.as_ref()])
+            .observe(latency.as_secs_f64());
+    }
+
+    if resource.annotations().contains_key(RESET_ANNOTATION) {
+        if breaker.reset(resource) {
+            tracing::info!(name, namespace, "Circuit reset by annotation");
+            ctx.set_condition(
+                resource,
+                CIRCUIT_OPEN,
+                false,
+                Reason::CircuitReset,
+                "Failure state reset",
+            )
+            .await?;
+        }
+        ctx.remove_annotation(resource, RESET_ANNOTATION).await?;
+    }
+
+    if let Some(remaining) = breaker.open_for(resource) {
+        tracing::debug!(
+            name,
+            namespace,
+            ?remaining,
+            "Circuit open, skipping reconciliation"
+        );
+        return Ok(ctx.requeue(resource, remaining));
+    }
+
+    let result = if ctx.config().observer {
+        let (result, actions) = observer::collect(reconcile).await;
+        report_observed(resource, ctx, &actions).await?;
+        result
+    } else {
+        reconcile.await
+    };
+    match &result {
+        Err(Error::EmergencyStop) => {
+            tracing::info!(
+                name,
+                namespace,
+                "Emergency stop engaged during reconciliation"
+            );
+            return Ok(ctx.requeue(resource, EMERGENCY_STOP_RECHECK));
+        }
+        Err(e) if e.is_transient() => {
+            ctx.metrics()
+                .reconcile_retries_total
+                .with_label_values(&[kind.as_ref()])
+                .inc();
+            if let Some(failures) = breaker.record_failure(resource) {
+                let cooldown = breaker.cooldown();
+                let message = format!(
+                    "{failures} failures in a row, pausing reconciliation for {}s: {e}",
+                    cooldown.as_secs()
+                );
+                tracing::warn!(name, namespace, failures, error = ?e, "Opening circuit");
+                ctx.metrics()
+                    .circuit_opens_total
+                    .with_label_values(&[kind.as_ref()])
+                    .inc();
+                ctx.create_event(resource, Reason::CircuitOpen, &message)
+                    .await?;
+                ctx.set_condition(resource, CIRCUIT_OPEN, true, Reason::CircuitOpen, &message)
+                    .await?;
+                return Ok(ctx.requeue(resource, cooldown));
+            }
+        }
+        _ => {
+            breaker.reset(resource);
+            if resource
+                .status()
+                .is_some_and(|s| is_condition_true(s.conditions(), CIRCUIT_OPEN))
+            {
+                ctx.set_condition(
+                    resource,
+                    CIRCUIT_OPEN,
+                    false,
+                    Reason::Recovered,
+                    "Reconciliation succeeded",
+                )
+                .await?;
+            }
+        }
+    }
+    result
+}
+
+/// Reports the child writes skipped in observer mode through an annotation
+/// and an event, both only when the summary changes.
+async fn report_observed<K>(resource: &K, ctx: &Context, actions: &[String]) -> Result<(), Error>
+where
+    K: Resource<Scope = NamespaceResourceScope, DynamicType = ()> + HasOwner,
+    K: Clone + DeserializeOwned + Debug,
+{
+    if actions.is_empty() {
+        return Ok(());
+    }
+    let summary = actions.join("; ");
+    if resource.annotations().get(WOULD_APPLY_ANNOTATION) == Some(&summary) {
+        return Ok(());
+    }
+    ctx.create_event(resource, Reason::WouldApply, &summary)
+        .await?;
+    ctx.annotate(resource, WOULD_APPLY_ANNOTATION, Some(&summary))
+        .await
+}
+
+/// Records the lint findings of `resource` in its `Lint` condition. The
+/// status is only written when the findings changed.
+pub(crate) async fn report_lint<K>(resource: &K, ctx: &Context) -> Result<(), Error>
+where
+    K: Lint + HasOwner + Resource<Scope = NamespaceResourceScope, DynamicType = ()>,
+    K: HasStatus + Clone + DeserializeOwned + Serialize + Debug,
+    K::Status: HasConditions + Default,
+{
+    let findings = resource.lint();
+    let (status, reason, message) = if findings.is_empty() {
+        ("False", Reason::LintClean, "No lint findings".to_string())
+    } else {
+        let message = findings
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("; ");
+        ("True", Reason::LintFindings, message)
+    };
+
+    let unchanged = resource
+        .status()
+        .and_then(|s| s.conditions().iter().find(|c| c.type_ == LINT))
+        .is_some_and(|c| c.status == status && c.message == message);
+    if unchanged {
+        return Ok(());
+    }
+    ctx.set_condition(resource, LINT, status == "True", reason, &message)
+        .await
+}
+
+pub fn error_policy<K>(job: Arc<K>, err: &Error, ctx: Arc<Context>) -> Action
 where
-    K: Clone + Resource + DeserializeOwned + Debug + Send + Sync + 'static,
+    K: Clone + Resource<DynamicType = ()> + DeserializeOwned + Debug + Send + Sync + 'static,
     K::DynamicType: Eq + Hash + Clone,
     K::DynamicType: Debug + Unpin,
 {
     let name = job.name_any();
     let namespace = job.namespace().unwrap_or_default();
-    tracing:: error!(name = name, namespace = namespace, error = ?err, "Error in reconciliation, will retry in 5 seconds");
-    Action::requeue(Duration::from_secs(5))
+    if let Some(suppressed) = ctx.log_throttle().check(job.as_ref()) {
+        tracing:: error!(name = name, namespace = namespace, suppressed, error = ?err, retry_in = ?ctx.config().error_requeue, "Error in reconciliation, will retry");
+    }
+    if let Some(hooks) = ctx.hooks() {
+        let failure = ReconcileFailure {
+            kind: K::kind(&()).into_owned(),
+            namespace,
+            name,
+        };
+        hooks.on_error(&failure, err);
+    }
+    ctx.requeue(job.as_ref(), ctx.config().error_requeue)
 }
@@ -0,0 +1,65 @@
+use std::sync::Arc;
+
+use k8s_openapi::NamespaceResourceScope;
+use k8s_openapi::api::core::v1::{Namespace, ResourceQuota};
+use k8s_openapi::api::rbac::v1::{Role, RoleBinding};
+use kube::runtime::controller::Action;
+use kube::{Resource, ResourceExt as _};
+use tracing::info;
+
+use crate::Error;
+use crate::reconciler::Context;
+use crate::tenant;
+
+/// Provisions the Role, RoleBinding and ResourceQuota of a tenant once its
+/// namespace carries [`tenant::ENABLED_LABEL`], and removes them once the
+/// label is gone. Namespaces never labelled are left untouched.
+pub async fn reconcile(namespace: Arc<Namespace>, ctx: Arc<Context>) -> Result<Action, Error> {
+    if !ctx.leadership().is_leader() || ctx.emergency_stop().engaged() {
+        return Ok(Action::requeue(ctx.config().leader_lease_duration));
+    }
+    let name = namespace.name_any();
+    let uid = namespace.uid().unwrap_or_default();
+    if !tenant::enabled(&namespace) {
+        delete_owned::<ResourceQuota>(&ctx, &name, &uid).await?;
+        delete_owned::<RoleBinding>(&ctx, &name, &uid).await?;
+        delete_owned::<Role>(&ctx, &name, &uid).await?;
+        return Ok(Action::await_change());
+    }
+    if namespace
+        .status
+        .as_ref()
+        .is_some_and(|s| s.phase.as_deref() == Some("Terminating"))
+    {
+        return Ok(Action::await_change());
+    }
+
+    info!(namespace = name, "Onboarding tenant namespace");
+    let (role, binding) = tenant::rbac(&namespace)?;
+    ctx.apply(&name, &role).await?;
+    ctx.apply(&name, &binding).await?;
+    match tenant::quota(&namespace, &ctx.config().tenant_quota)? {
+        Some(quota) => {
+            ctx.apply(&name, &quota).await?;
+        }
+        None => delete_owned::<ResourceQuota>(&ctx, &name, &uid).await?,
+    }
+    Ok(Action::await_change())
+}
+
+async fn delete_owned<K>(ctx: &Context, namespace: &str, owner_uid: &str) -> Result<(), Error>
+where
+    K: Resource<Scope = NamespaceResourceScope, DynamicType = ()>,
+    K: Clone + serde::de::DeserializeOwned + serde::Serialize + std::fmt::Debug,
+{
+    for object in ctx.list_owned_metadata::<K>(namespace, owner_uid).await? {
+        info!(
+            namespace,
+            kind = %K::kind(&()),
+            child = object.name_any(),
+            "Deleting tenant object"
+        );
+        ctx.delete::<K>(namespace, &object.name_any()).await?;
+    }
+    Ok(())
+}
@@ -1,17 +1,60 @@
-use std::{sync::Arc, time::Duration};
+use std::{collections::BTreeMap, sync::Arc, time::Duration};
 
-use k8s_openapi::api::batch::v1::CronJob;
-use kube::{ResourceExt as _, core::object::HasStatus, runtime::controller::Action};
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use futures::{StreamExt as _, stream};
+use k8s_openapi::NamespaceResourceScope;
+use k8s_openapi::api::batch::v1::{CronJob, CronJobSpec, Job};
+use k8s_openapi::api::coordination::v1::Lease;
+use k8s_openapi::api::core::v1::{ObjectReference, ServiceAccount};
+use k8s_openapi::api::networking::v1::NetworkPolicy;
+use k8s_openapi::api::rbac::v1::{PolicyRule, Role, RoleBinding};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use kube::api::{DeleteParams, PartialObjectMeta, Patch};
+use kube::{Resource, ResourceExt as _, core::object::HasStatus, runtime::controller::Action};
+use ring::digest;
 use tracing::{debug, error, info, warn};
 
-use crate::{Context, Error, ScheduledCronJob, crd::ScheduledCronJobPhase};
+use super::{guard, report_lint};
+use crate::{
+    Context, Error, Schedule, ScheduledCronJob, compat,
+    crd::{
+        ADAPTIVE_RUN_ANNOTATION, BLACKOUT_SUSPENDED_ANNOTATION, DETAIL_CRON_JOBS,
+        DETAIL_NEXT_SCHEDULE_TIME, EVERY_SECONDS_ANNOTATION, EXECUTION_COUNTED_ANNOTATION,
+        EndPolicy, ManagedResource, NAMESPACE_TERMINATING, POST_RUN_CHECK_ANNOTATION, PostRunCheck,
+        SCHEDULE_INDEX_LABEL, SCHEDULE_LABEL, SECONDS_SCHEDULE_ANNOTATION,
+        SERVICE_ACCOUNT_REJECTED, SPEC_SUSPENDED_ANNOTATION, STARTING_DEADLINE_MISSED,
+        ScheduleCalendar, ScheduleStatus, ScheduledCronJobPhase, TEMPLATE_HASH_ANNOTATION,
+        TIMED_OUT_ANNOTATION, UNSUPPORTED_FEATURES, VARIANT_LABEL, VariantStatus, child_name,
+        is_condition_true,
+    },
+    hooks::RunFailed,
+    invariants,
+    object_ref::ObjectRefExt as _,
+    reason::Reason,
+    registry, replay,
+    run_labels::{self, FIRE_TIME_LABEL},
+};
 
 pub async fn reconcile(job: Arc<ScheduledCronJob>, ctx: Arc<Context>) -> Result<Action, Error> {
     let name = job.name_any();
     let namespace = job.namespace().unwrap_or_default();
     info!(name, namespace, "Starting reconciliation");
 
-    match reconcile_cronjob_impl(&job, ctx.clone()).await {
+    let started = Utc::now();
+    let result = guard(
+        job.as_ref(),
+        &ctx,
+        reconcile_cronjob_impl(&job, ctx.clone()),
+    )
+    .await;
+    if let Err(e) = &result {
+        replay::record(&ctx, &job, started, e).await;
+    }
+    if ctx.config().observer {
+        invariants::observe(&ctx, &job, started).await;
+    }
+    match result {
         Ok(action) => {
             debug!(
                 name,
@@ -22,14 +65,28 @@ pub async fn reconcile(job: Arc<ScheduledCronJob>, ctx: Arc<Context>) -> Result<
             Ok(action)
         }
         Err(Error::NotFound) => {
-            unreachable!()
+            // A child was deleted between listing and updating it; the next
+            // pass lists them afresh.
+            debug!(name, namespace, "Child disappeared during reconciliation");
+            Ok(ctx.requeue(job.as_ref(), Duration::from_secs(5)))
         }
+        Err(
+            e @ (Error::EmergencyStop
+            | Error::History(_)
+            | Error::Bus(_)
+            | Error::TriggerForbidden(_)
+            | Error::InvalidTemplate(_)
+            | Error::Consumer(_)
+            | Error::TokenRejected(_)
+            | Error::Usage(_)
+            | Error::Unowned(_)),
+        ) => Err(e),
         Err(Error::InvalidStartTime) => {
             warn!(name, namespace, "Invalid start time specified");
             ctx.update_scheduled_cronjob(
                 &job,
-                ScheduledCronJobPhase::InvalidStartTime,
-                "Warning",
+                ScheduledCronJobPhase::Failed,
+                Reason::InvalidTimeRange,
                 "Invalid start time specified",
             )
             .await?;
@@ -39,8 +96,8 @@ pub async fn reconcile(job: Arc<ScheduledCronJob>, ctx: Arc<Context>) -> Result<
             warn!(name, namespace, "Invalid end time specified");
             ctx.update_scheduled_cronjob(
                 &job,
-                ScheduledCronJobPhase::InvalidEndTime,
-                "Warning",
+                ScheduledCronJobPhase::Failed,
+                Reason::InvalidTimeRange,
                 "Invalid end time specified",
             )
             .await?;
@@ -50,8 +107,8 @@ pub async fn reconcile(job: Arc<ScheduledCronJob>, ctx: Arc<Context>) -> Result<
             warn!(name, namespace, "End time is before start time");
             ctx.update_scheduled_cronjob(
                 &job,
-                ScheduledCronJobPhase::EndBeforeStart,
-                "Warning",
+                ScheduledCronJobPhase::Failed,
+                Reason::InvalidTimeRange,
                 "End time is before start time",
             )
             .await?;
@@ -60,8 +117,8 @@ pub async fn reconcile(job: Arc<ScheduledCronJob>, ctx: Arc<Context>) -> Result<
         Err(Error::WaitFor(duration)) => {
             ctx.update_scheduled_cronjob(
                 &job,
-                ScheduledCronJobPhase::Pending,
-                "Normal",
+                ScheduledCronJobPhase::PendingActivation,
+                Reason::WindowNotOpen,
                 "Waiting for scheduled time",
             )
             .await?;
@@ -70,51 +127,66 @@ pub async fn reconcile(job: Arc<ScheduledCronJob>, ctx: Arc<Context>) -> Result<
 
             tracing::info!(name = name, namespace = namespace, duration = ?duration, "Waiting for scheduled time");
 
-            Ok(Action::requeue(duration))
+            Ok(ctx.requeue(job.as_ref(), duration))
         }
         Err(Error::Expired(_)) => {
-            info!(name, namespace, "Schedule has completed");
-            ctx.delete::<CronJob>(&namespace, &name).await?;
+            let uid = job.uid().unwrap_or_default();
+            let remaining = ctx.list_owned_metadata::<CronJob>(&namespace, &uid).await?;
+            if end_children(&ctx, &job, &remaining).await? {
+                info!(name, namespace, "Schedule has completed");
+                ctx.update_scheduled_cronjob(
+                    &job,
+                    ScheduledCronJobPhase::Completed,
+                    Reason::Completed,
+                    "Schedule has completed",
+                )
+                .await?;
+                return Ok(Action::await_change());
+            }
 
+            info!(name, namespace, "Schedule has expired, ending cronjobs");
             ctx.update_scheduled_cronjob(
                 &job,
-                ScheduledCronJobPhase::Completed,
-                "Normal",
-                "Schedule has completed",
+                ScheduledCronJobPhase::Expired,
+                Reason::Expired,
+                "Schedule has expired",
             )
             .await?;
-
-            Ok(Action::await_change())
+            Ok(ctx.requeue(job.as_ref(), Duration::from_secs(10)))
         }
-        Err(Error::Kube(e)) => {
-            error!(name, namespace, error = ?e, "Kubernetes API error");
-            ctx.update_scheduled_cronjob(
-                &job,
-                ScheduledCronJobPhase::Failed,
-                "Warning",
-                e.to_string().as_str(),
-            )
-            .await?;
-
-            Ok(Action::requeue(Duration::from_secs(5)))
+        Err(e @ Error::InvalidTransition(..)) => {
+            warn!(name, namespace, error = ?e, "Refusing phase transition");
+            Ok(ctx.requeue(job.as_ref(), Duration::from_secs(120)))
         }
-        Err(Error::Serialization(e)) => {
-            error!(name, namespace, error = ?e, "Serialization error");
-            ctx.update_scheduled_cronjob(
-                &job,
-                ScheduledCronJobPhase::Failed,
-                "Warning",
-                e.to_string().as_str(),
+        Err(Error::NamespaceTerminating(_)) => {
+            // 命名空间正在删除，不再创建子资源，也不发送 Warning 事件
+            info!(name, namespace, "Namespace is terminating, skipping");
+            ctx.set_condition(
+                job.as_ref(),
+                NAMESPACE_TERMINATING,
+                true,
+                Reason::NamespaceTerminating,
+                "Namespace is being deleted; no children will be created",
             )
             .await?;
-            Ok(Action::requeue(Duration::from_secs(5)))
+            Ok(Action::await_change())
         }
+        // Failures talking to the API server or another endpoint are
+        // returned, so that the circuit breaker counts them and the error
+        // policy retries them, leaving the phase alone.
+        Err(
+            e @ (Error::Kube(_)
+            | Error::Serialization(_)
+            | Error::Protobuf(_)
+            | Error::Http(_)
+            | Error::Condition(_)),
+        ) => Err(e),
         Err(Error::InvalidConcurrencyPolicy) => {
             warn!(name, namespace, "Invalid concurrency policy");
             ctx.update_scheduled_cronjob(
                 &job,
                 ScheduledCronJobPhase::Failed,
-                "Warning",
+                Reason::InvalidSpec,
                 "Invalid concurrency policy",
             )
             .await?;
@@ -125,18 +197,29 @@ pub async fn reconcile(job: Arc<ScheduledCronJob>, ctx: Arc<Context>) -> Result<
             ctx.update_scheduled_cronjob(
                 &job,
                 ScheduledCronJobPhase::Failed,
-                "Warning",
+                Reason::InvalidSpec,
                 "Invalid failed jobs history limit",
             )
             .await?;
             Ok(Action::await_change())
         }
+        Err(Error::InvalidSuccessfulJobsHistoryLimit) => {
+            warn!(name, namespace, "Invalid successful jobs history limit");
+            ctx.update_scheduled_cronjob(
+                &job,
+                ScheduledCronJobPhase::Failed,
+                Reason::InvalidSpec,
+                "Invalid successful jobs history limit",
+            )
+            .await?;
+            Ok(Action::await_change())
+        }
         Err(Error::CronjobSpecNotFound) => {
             warn!(name, namespace, "Cronjob spec not found");
             ctx.update_scheduled_cronjob(
                 &job,
                 ScheduledCronJobPhase::Failed,
-                "Warning",
+                Reason::InvalidSpec,
                 "Cronjob spec not found",
             )
             .await?;
@@ -147,18 +230,34 @@ pub async fn reconcile(job: Arc<ScheduledCronJob>, ctx: Arc<Context>) -> Result<
             ctx.update_scheduled_cronjob(
                 &job,
                 ScheduledCronJobPhase::Failed,
-                "Warning",
+                Reason::InvalidSpec,
                 "Invalid backoff limit",
             )
             .await?;
             Ok(Action::await_change())
         }
+        Err(
+            e @ (Error::InvalidSchedule(_)
+            | Error::InvalidTarget(_)
+            | Error::InvalidVariants(_)
+            | Error::InvalidOwner(_)),
+        ) => {
+            warn!(name, namespace, error = ?e, "Invalid spec");
+            ctx.update_scheduled_cronjob(
+                &job,
+                ScheduledCronJobPhase::Failed,
+                e.reason(),
+                e.to_string().as_str(),
+            )
+            .await?;
+            Ok(Action::await_change())
+        }
         Err(e @ Error::DurationTooShort(start, end)) => {
             warn!(name, namespace, start = ?start, end = ?end, "Duration too short");
             ctx.update_scheduled_cronjob(
                 &job,
                 ScheduledCronJobPhase::Failed,
-                "Warning",
+                e.reason(),
                 e.to_string().as_str(),
             )
             .await?;
@@ -175,6 +274,20 @@ async fn reconcile_cronjob_impl(
     let namespace = job.namespace().unwrap_or_default();
     info!(name, namespace, "Starting cronjob reconciliation");
 
+    if job.failed_before_spec_change() {
+        info!(
+            name,
+            namespace, "Spec changed since failing, validating again"
+        );
+        ctx.update_scheduled_cronjob_status(
+            job,
+            ScheduledCronJobPhase::PendingValidation,
+            "Spec changed since failing",
+        )
+        .await?;
+        return Ok(Action::await_change());
+    }
+
     // 检查当前状态，如果当前状态就是无法运行的，直接返回
     if !job.can_run() {
         info!(name, namespace, "Job cannot run in current state");
@@ -182,63 +295,211 @@ async fn reconcile_cronjob_impl(
     }
 
     job.validate_cronjob()?;
+    job.validate_variants()?;
+    ctx.metrics()
+        .set_owner(&namespace, &name, job.spec.owner.as_ref());
+    report_lint(job, &ctx).await?;
 
     // 验证时间范围，如果时间有问题，或者已经超时了，也返回，由上层创建事件，修改状态
     info!(name, namespace, "Validating effective time");
     job.validate_effective_time()?;
     info!(name, namespace, "Time validation passed");
 
-    // 获取或创建 CronJob
-    info!(name, namespace, "Getting or creating cronjob");
-    let _cronjob = get_cronjob(ctx.clone(), &namespace, &name, job).await?;
-    info!(name, namespace, "Cronjob operation completed");
+    if job.executions_exhausted() {
+        return finish_executions(&ctx, job).await;
+    }
+    // Expired resources only end their children, even once the window is
+    // extended again.
+    if job.status().map(|s| s.phase) == Some(ScheduledCronJobPhase::Expired) {
+        return Err(Error::Expired(chrono::Local::now()));
+    }
 
-    // 此时，cronjob 应该已经创建出来了，根据当前 scheduled cronjob 的状态，更新
-    match job.status() {
-        Some(status) => {
-            info!(name, namespace, phase = ?status.phase, "Current job status");
-            match status.phase {
-                ScheduledCronJobPhase::Pending | ScheduledCronJobPhase::Unknown => {
-                    info!(name, namespace, "Updating status to Running");
-                    ctx.update_scheduled_cronjob(
-                        job,
-                        ScheduledCronJobPhase::Running,
-                        "Normal",
-                        "Job is running",
-                    )
-                    .await?;
-                }
-                ScheduledCronJobPhase::Running => {
-                    debug!(name, namespace, "Job is already running");
-                }
-                _ => {
-                    unreachable!()
+    let (accounts, policies) = futures::try_join!(
+        provision_service_account(&ctx, job),
+        provision_network_policy(&ctx, job),
+    )?;
+    let mut managed = [accounts, policies].concat();
+
+    // 获取或创建 CronJob
+    info!(name, namespace, "Getting or creating cronjobs");
+    let calendars = calendars(&ctx, job).await?;
+    let blackout = job.blackout_until(Utc::now(), &calendars);
+    let runs = list_runs(&ctx, job).await?;
+    let outcomes: Vec<_> = stream::iter(ctx.cronjobs(job)?)
+        .map(|desired| reconcile_child(&ctx, job, desired, blackout, &runs))
+        .buffered(ctx.config().child_concurrency.max(1))
+        .collect()
+        .await;
+    let mut children = Vec::new();
+    let mut variants = Vec::new();
+    let mut schedules = Vec::new();
+    let mut active = Vec::new();
+    let mut owners = Vec::new();
+    let mut cronjobs = Vec::new();
+    let mut missed = Vec::new();
+    let mut unsupported = Vec::new();
+    let mut suspended = true;
+    let mut driven_after = None;
+    let mut failed = None;
+    for outcome in outcomes {
+        let outcome = match outcome {
+            Ok(outcome) => outcome,
+            Err(e) => {
+                // Every child is reconciled before the first failure is
+                // returned, so one child cannot hold back the others.
+                if failed.is_some() {
+                    warn!(name, namespace, error = ?e, "Failed to reconcile cronjob");
+                } else {
+                    failed = Some(e);
                 }
+                continue;
             }
-        }
-        None => {
-            info!(name, namespace, "No status found, setting to Running");
-            ctx.update_scheduled_cronjob(
-                job,
-                ScheduledCronJobPhase::Running,
-                "Normal",
-                "Job is running",
-            )
+        };
+        variants.extend(outcome.variant);
+        schedules.extend(outcome.schedule);
+        owners.push(outcome.cronjob.uid().unwrap_or_default());
+        missed.extend(outcome.missed);
+        unsupported.extend(outcome.unsupported);
+        active.extend(
+            outcome
+                .cronjob
+                .status
+                .as_ref()
+                .and_then(|s| s.active.clone())
+                .unwrap_or_default(),
+        );
+        suspended &= outcome.suspended;
+        driven_after = [driven_after, outcome.driven_after]
+            .into_iter()
+            .flatten()
+            .min();
+        children.push(outcome.name);
+        managed.push(ManagedResource::of(&outcome.cronjob));
+        cronjobs.push(outcome.cronjob);
+    }
+    if let Some(e) = failed {
+        return Err(e);
+    }
+    info!(name, namespace, ?children, "Cronjob operation completed");
+    prune_cronjobs(&ctx, job, &children).await?;
+    managed.sort();
+    ctx.update_scheduled_cronjob_children(job, variants, schedules, managed)
+        .await?;
+    let next_schedule_time = next_schedule_time(&cronjobs, Utc::now())
+        .map(|t| t.to_rfc3339_opts(chrono::SecondsFormat::Secs, true));
+    ctx.update_scheduled_cronjob_details(
+        job,
+        &[
+            (DETAIL_CRON_JOBS, Some(children.join(","))),
+            (DETAIL_NEXT_SCHEDULE_TIME, next_schedule_time),
+        ],
+    )
+    .await?;
+    enforce_deadlines(&ctx, job, &active).await?;
+    report_missed_deadlines(&ctx, job, &missed).await?;
+    unsupported.sort();
+    unsupported.dedup();
+    report_unsupported_features(&ctx, job, &unsupported).await?;
+    label_runs(&ctx, &cronjobs, &runs).await?;
+    verify_runs(&ctx, job, &owners, &runs).await?;
+    if let Some(max) = job.spec.max_executions
+        && count_executions(&ctx, job, &owners, &runs).await? >= max
+    {
+        return finish_executions(&ctx, job).await;
+    }
+    let adaptive_after = if suspended || !active.is_empty() {
+        None
+    } else {
+        adapt(&ctx, job, &cronjobs, &runs).await?
+    };
+
+    // 此时，cronjob 应该已经创建出来了，根据当前 scheduled cronjob 的状态，更新
+    let (phase, reason, message) = settled_phase(job, suspended, blackout);
+    let current = job.status().map(|s| s.phase);
+    if current == Some(phase) {
+        debug!(name, namespace, %phase, "Phase is up to date");
+    } else {
+        info!(name, namespace, ?current, %phase, "Updating phase");
+        ctx.update_scheduled_cronjob(job, phase, reason, &message)
             .await?;
-        }
     }
 
-    // 设置重新检查间隔
-    info!(name, namespace, "Setting requeue interval to 120 seconds");
-    Ok(Action::requeue(Duration::from_secs(120)))
+    let soonest = [adaptive_after, driven_after].into_iter().flatten().min();
+    let after = requeue_after(job, &calendars, blackout, soonest, Utc::now());
+    info!(name, namespace, ?after, "Setting requeue interval");
+    Ok(ctx.requeue(job, after))
+}
+
+/// The phase `job` settles in once its children are in place. `suspended`
+/// tells whether every child is suspended, and `blackout` when the blackout
+/// covering now ends.
+pub(crate) fn settled_phase(
+    job: &ScheduledCronJob,
+    suspended: bool,
+    blackout: Option<DateTime<Utc>>,
+) -> (ScheduledCronJobPhase, Reason, String) {
+    if job.spec.suspend {
+        (
+            ScheduledCronJobPhase::Suspended,
+            Reason::Suspended,
+            "Suspended by spec.suspend".to_string(),
+        )
+    } else if suspended && let Some(until) = blackout {
+        (
+            ScheduledCronJobPhase::Suspended,
+            Reason::Suspended,
+            format!(
+                "Suspended for a blackout window until {}",
+                until.to_rfc3339()
+            ),
+        )
+    } else if suspended {
+        (
+            ScheduledCronJobPhase::Suspended,
+            Reason::Suspended,
+            "Every cronjob is suspended".to_string(),
+        )
+    } else {
+        (
+            ScheduledCronJobPhase::Active,
+            Reason::Activated,
+            "Job is running".to_string(),
+        )
+    }
+}
+
+/// When `job` is reconciled next: at most two minutes from `now`, sooner
+/// when adaptive scheduling asks for it, the window ends or a blackout
+/// begins or ends.
+pub(crate) fn requeue_after(
+    job: &ScheduledCronJob,
+    calendars: &[ScheduleCalendar],
+    blackout: Option<DateTime<Utc>>,
+    adaptive_after: Option<Duration>,
+    now: DateTime<Utc>,
+) -> Duration {
+    // 设置重新检查间隔，窗口结束时立即回收 CronJob
+    let until_end = job
+        .end_time()
+        .map(|end| (end.with_timezone(&Utc) - now).to_std().unwrap_or_default());
+    // Blackout windows are entered and left on time.
+    let until_blackout = blackout
+        .or_else(|| job.next_blackout(now, calendars))
+        .map(|at| (at - now).to_std().unwrap_or_default());
+    [adaptive_after, until_end, until_blackout]
+        .into_iter()
+        .flatten()
+        .fold(Duration::from_secs(120), Duration::min)
+        .max(Duration::from_secs(1))
 }
 
 async fn get_cronjob(
     ctx: Arc<Context>,
-    namespace: &str,
-    name: &str,
     job: &ScheduledCronJob,
+    name: &str,
+    desired: &CronJob,
 ) -> Result<CronJob, Error> {
+    let namespace = &job.namespace().unwrap_or_default();
     debug!(name, namespace, "Attempting to get cronjob");
     match ctx.get::<CronJob>(namespace, name).await {
         Ok(cronjob) => {
@@ -246,8 +507,18 @@ async fn get_cronjob(
             Ok(cronjob)
         }
         Err(Error::NotFound) => {
+            if ctx.namespace_terminating(namespace).await? {
+                return Err(Error::NamespaceTerminating(namespace.to_string()));
+            }
             info!(name, namespace, "Cronjob not found, creating new one");
-            Ok(ctx.create_cronjob(namespace, &job.cronjob()).await?)
+            let cronjob = ctx.create_cronjob(namespace, desired).await?;
+            ctx.create_event(
+                job,
+                Reason::ChildCreated,
+                &format!("Created CronJob {name}"),
+            )
+            .await?;
+            Ok(cronjob)
         }
         Err(e) => {
             error!(name, namespace, error = ?e, "Error getting cronjob");
@@ -255,3 +526,1494 @@ async fn get_cronjob(
         }
     }
 }
+
+/// What [`reconcile_child`] found for one child CronJob.
+struct ChildOutcome {
+    name: String,
+    cronjob: CronJob,
+    variant: Option<VariantStatus>,
+    schedule: Option<ScheduleStatus>,
+    /// Why its latest run missed its starting deadline, if it did.
+    missed: Option<String>,
+    /// Features left out of it, see [`compat::downgrade`].
+    unsupported: Vec<String>,
+    suspended: bool,
+    /// When a controller-driven run is due next.
+    driven_after: Option<Duration>,
+}
+
+/// Creates the child CronJob `desired` of `job` or brings the existing one in
+/// line with it. Children are reconciled concurrently, up to
+/// `CHILD_CONCURRENCY` at a time.
+async fn reconcile_child(
+    ctx: &Arc<Context>,
+    job: &ScheduledCronJob,
+    mut desired: CronJob,
+    blackout: Option<DateTime<Utc>>,
+    runs: &[PartialObjectMeta<Job>],
+) -> Result<ChildOutcome, Error> {
+    let name = job.name_any();
+    let namespace = job.namespace().unwrap_or_default();
+    let child = desired.name_any();
+    let unsupported = compat::downgrade(&mut desired, ctx.server_version());
+    if let Some(pod) = desired
+        .spec
+        .as_mut()
+        .and_then(|s| s.job_template.spec.as_mut())
+        .and_then(|s| s.template.spec.as_mut())
+    {
+        let config = ctx.config();
+        if config.openshift {
+            compat::restrict(pod);
+        }
+        registry::apply(pod, &config.registry_rewrite);
+    }
+    let hash = template_hash(job, &desired);
+    desired
+        .annotations_mut()
+        .insert(TEMPLATE_HASH_ANNOTATION.to_string(), hash);
+    let mut cronjob = get_cronjob(ctx.clone(), job, &child, &desired).await?;
+    sync_template(ctx, job, &desired, &mut cronjob).await?;
+    sync_recurrence(ctx, job, &mut cronjob).await?;
+    let drive = driven(&desired);
+    let mut driven_after = None;
+    if let Some(drive) = &drive {
+        let paused = job.spec.suspend || blackout.is_some();
+        driven_after = run_driven(ctx, job, &cronjob, drive, paused, runs).await?;
+    } else {
+        apply_suspend(ctx, job, &mut cronjob).await?;
+        apply_blackout(ctx, job, &mut cronjob, blackout).await?;
+    }
+    sync_history_limits(ctx, job, &desired, &mut cronjob).await?;
+    let variant = desired.labels().get(VARIANT_LABEL);
+    let mut statuses = (None, None);
+    if let Some(index) = desired.labels().get(SCHEDULE_INDEX_LABEL) {
+        let schedule = index
+            .parse::<usize>()
+            .ok()
+            .and_then(|i| job.spec.schedules.get(i));
+        statuses.1 = Some(schedule_status(schedule, variant, &cronjob));
+    } else if let Some(variant) = variant {
+        statuses.0 = Some(variant_status(variant, &cronjob));
+    }
+    let late = overdue(&cronjob, Utc::now());
+    ctx.metrics()
+        .run_overdue_seconds
+        .with_label_values(&[&namespace, &name, &child])
+        .set(late.as_secs_f64());
+    let missed = cronjob
+        .spec
+        .as_ref()
+        .and_then(|s| s.starting_deadline_seconds)
+        .filter(|deadline| late.as_secs() > (*deadline).max(0) as u64)
+        .map(|deadline| {
+            format!(
+                "CronJob {child} skipped a run not started within its startingDeadlineSeconds of {deadline}s"
+            )
+        });
+    let suspended = if drive.is_some() {
+        job.spec.suspend || blackout.is_some()
+    } else {
+        cronjob
+            .spec
+            .as_ref()
+            .and_then(|s| s.suspend)
+            .unwrap_or(false)
+    };
+    Ok(ChildOutcome {
+        name: child,
+        cronjob,
+        variant: statuses.0,
+        schedule: statuses.1,
+        missed,
+        unsupported,
+        suspended,
+        driven_after,
+    })
+}
+
+/// Hash of the `jobTemplate` of `desired` and of the children's labels and
+/// annotations, for [`TEMPLATE_HASH_ANNOTATION`].
+fn template_hash(job: &ScheduledCronJob, desired: &CronJob) -> String {
+    let template = desired.spec.as_ref().map(|s| &s.job_template);
+    let value = serde_json::json!([template, job.spec.template_metadata()]);
+    let hash = digest::digest(&digest::SHA256, value.to_string().as_bytes());
+    hash.as_ref()[..8]
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+/// Strategic-merges the `jobTemplate` of `desired` and the labels and
+/// annotations of `spec.childMetadata` into `cronjob` once they changed, as
+/// told by [`TEMPLATE_HASH_ANNOTATION`]. With `spec.cronJobTemplate`, the
+/// `jobTemplate` is replaced instead, dropping fields removed from the
+/// template. The fields the controller manages, such as `suspend` and
+/// `schedule`, are left alone, as are labels and annotations the controller
+/// sets.
+async fn sync_template(
+    ctx: &Context,
+    job: &ScheduledCronJob,
+    desired: &CronJob,
+    cronjob: &mut CronJob,
+) -> Result<(), Error> {
+    let Some(hash) = desired.annotations().get(TEMPLATE_HASH_ANNOTATION) else {
+        return Ok(());
+    };
+    if cronjob.annotations().get(TEMPLATE_HASH_ANNOTATION) == Some(hash) {
+        return Ok(());
+    }
+    let Some(template) = desired.spec.as_ref().map(|s| &s.job_template) else {
+        return Ok(());
+    };
+
+    let name = cronjob.name_any();
+    let namespace = job.namespace().unwrap_or_default();
+    info!(
+        name = job.name_any(),
+        namespace,
+        cronjob = name,
+        "Syncing job template"
+    );
+    // The desired values carry the controller's precedence over
+    // childMetadata.
+    let metadata = job.spec.template_metadata().cloned().unwrap_or_default();
+    let pick = |keys: Vec<&String>, values: &BTreeMap<String, String>| {
+        keys.into_iter()
+            .filter_map(|k| values.get_key_value(k))
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect::<BTreeMap<_, _>>()
+    };
+    let labels = pick(metadata.labels.keys().collect(), desired.labels());
+    let mut annotations = pick(metadata.annotations.keys().collect(), desired.annotations());
+    annotations.insert(TEMPLATE_HASH_ANNOTATION.to_string(), hash.clone());
+    let mut job_template = serde_json::to_value(template)?;
+    if job.spec.cron_job_template.is_some()
+        && let Some(fields) = job_template.as_object_mut()
+    {
+        fields.insert("$patch".to_string(), "replace".into());
+    }
+    let patch = serde_json::json!({
+        "metadata": { "labels": labels, "annotations": annotations },
+        "spec": { "jobTemplate": job_template },
+    });
+    match ctx
+        .patch::<CronJob>(&namespace, &name, &Patch::Strategic(patch))
+        .await
+    {
+        Ok(()) => {}
+        // Deleted since listed; a replacement gets the template on creation.
+        Err(Error::NotFound) => return Ok(()),
+        Err(e) => return Err(e),
+    }
+    cronjob.labels_mut().extend(labels);
+    cronjob.annotations_mut().extend(annotations);
+    if let Some(spec) = cronjob.spec.as_mut() {
+        spec.job_template = template.clone();
+    }
+    Ok(())
+}
+
+/// Occurrences of a `recurrenceRule` missed by more than this, such as while
+/// the controller was down, are skipped rather than scheduled in the past.
+const MISSED_OCCURRENCE_GRACE: chrono::Duration = chrono::Duration::minutes(5);
+
+/// Reschedules a child of a `recurrenceRule` cron cannot express, whose
+/// schedule only fires at a single occurrence, for the occurrence following
+/// its last run. This also replaces schedules patched by a ScheduledPatch.
+async fn sync_recurrence(
+    ctx: &Context,
+    job: &ScheduledCronJob,
+    cronjob: &mut CronJob,
+) -> Result<(), Error> {
+    let Some(rule) = job.recurrence_rule()? else {
+        return Ok(());
+    };
+    if rule.to_cron().is_some() {
+        return Ok(());
+    }
+    let now = Utc::now();
+    let last = cronjob
+        .status
+        .as_ref()
+        .and_then(|s| s.last_schedule_time.as_ref())
+        .or(cronjob.metadata.creation_timestamp.as_ref())
+        .map_or(now, |t| t.0);
+    let tz = job.time_zone()?.unwrap_or(Tz::UTC);
+    let after = match rule.next_after(&last.with_timezone(&tz)) {
+        Some(next) if next.with_timezone(&Utc) + MISSED_OCCURRENCE_GRACE < now => now,
+        _ => last,
+    };
+    let name = cronjob.name_any();
+    let Some(schedule) = job
+        .cronjobs_at(after)?
+        .into_iter()
+        .find(|c| c.name_any() == name)
+        .and_then(|c| c.spec)
+        .map(|s| s.schedule)
+    else {
+        return Ok(());
+    };
+    if cronjob.spec.as_ref().map(|s| &s.schedule) == Some(&schedule) {
+        return Ok(());
+    }
+
+    let namespace = job.namespace().unwrap_or_default();
+    info!(
+        name = job.name_any(),
+        namespace,
+        cronjob = name,
+        schedule,
+        "Rescheduling for the next occurrence of recurrenceRule"
+    );
+    let patch = serde_json::json!({ "spec": { "schedule": schedule } });
+    match ctx
+        .patch::<CronJob>(&namespace, &name, &Patch::Merge(patch))
+        .await
+    {
+        Ok(()) => {}
+        // Deleted since listed; a replacement gets the schedule on creation.
+        Err(Error::NotFound) => return Ok(()),
+        Err(e) => return Err(e),
+    }
+    if let Some(spec) = cronjob.spec.as_mut() {
+        spec.schedule = schedule;
+    }
+    Ok(())
+}
+
+/// A schedule cron cannot express, whose runs the controller starts while
+/// the child CronJob stays suspended.
+pub(crate) enum Drive {
+    /// A sub-minute `@every` interval.
+    Every(Duration),
+    /// A six-field schedule firing at seconds other than 0.
+    Seconds(Box<Schedule>),
+}
+
+impl Drive {
+    /// Distinguishes the run names of each kind.
+    pub(crate) fn kind(&self) -> &'static str {
+        match self {
+            Drive::Every(_) => "every",
+            Drive::Seconds(_) => "seconds",
+        }
+    }
+
+    /// How long after `since` the next run is due, `None` if never.
+    pub(crate) fn due_after(&self, spec: &CronJobSpec, since: DateTime<Utc>) -> Option<Duration> {
+        match self {
+            Drive::Every(every) => Some(*every),
+            Drive::Seconds(schedule) => next_fire(spec, schedule, since)
+                .map(|next| (next - since).to_std().unwrap_or_default()),
+        }
+    }
+}
+
+/// How the controller runs `cronjob`, if it does.
+pub(crate) fn driven(cronjob: &CronJob) -> Option<Drive> {
+    let annotations = cronjob.annotations();
+    if let Some(every) = annotations
+        .get(EVERY_SECONDS_ANNOTATION)
+        .and_then(|s| s.parse().ok())
+    {
+        return Some(Drive::Every(Duration::from_secs(every)));
+    }
+    annotations
+        .get(SECONDS_SCHEDULE_ANNOTATION)
+        .and_then(|s| Schedule::parse_with_seconds(s).ok())
+        .map(|s| Drive::Seconds(Box::new(s)))
+}
+
+/// Starts a run of `cronjob` from its template once `drive` is due since the
+/// newest one, or for six-field schedules since the child was created, unless
+/// `paused`. Missed fires are collapsed into one run. A `Forbid` concurrency
+/// policy holds the run while another is active. Returns when the next run
+/// is due.
+/// Metadata of the run Jobs of every child of `job`, listed once per
+/// reconciliation by their [`SCHEDULE_LABEL`].
+pub(crate) async fn list_runs(
+    ctx: &Context,
+    job: &ScheduledCronJob,
+) -> Result<Vec<PartialObjectMeta<Job>>, Error> {
+    let namespace = job.namespace().unwrap_or_default();
+    let labels = format!("{SCHEDULE_LABEL}={}", job.schedule_label());
+    ctx.list_labelled_metadata::<Job>(&namespace, &labels).await
+}
+
+/// The runs among `runs` started by `cronjob`.
+pub(crate) fn runs_of<'a>(
+    runs: &'a [PartialObjectMeta<Job>],
+    cronjob: &CronJob,
+) -> impl Iterator<Item = &'a PartialObjectMeta<Job>> {
+    let owner = cronjob.uid().unwrap_or_default();
+    runs.iter().filter(move |r| owned_by(r, &owner))
+}
+
+fn owned_by(run: &PartialObjectMeta<Job>, owner: &str) -> bool {
+    run.owner_references().iter().any(|r| r.uid == owner)
+}
+
+async fn run_driven(
+    ctx: &Context,
+    job: &ScheduledCronJob,
+    cronjob: &CronJob,
+    drive: &Drive,
+    paused: bool,
+    runs: &[PartialObjectMeta<Job>],
+) -> Result<Option<Duration>, Error> {
+    if paused {
+        return Ok(None);
+    }
+    let namespace = job.namespace().unwrap_or_default();
+    let Some(spec) = cronjob.spec.as_ref() else {
+        return Ok(None);
+    };
+    let now = Utc::now();
+    let newest = runs_of(runs, cronjob)
+        .filter_map(|r| r.creation_timestamp())
+        .max();
+    let since = match drive {
+        Drive::Every(_) => newest,
+        Drive::Seconds(_) => newest.or(cronjob.creation_timestamp()),
+    };
+    if let Some(since) = since {
+        let Some(due) = drive.due_after(spec, since.0) else {
+            return Ok(None);
+        };
+        let elapsed = (now - since.0).to_std().unwrap_or_default();
+        if elapsed < due {
+            return Ok(Some(due - elapsed));
+        }
+    }
+    let next = drive.due_after(spec, now);
+    let active = cronjob
+        .status
+        .as_ref()
+        .and_then(|s| s.active.as_ref())
+        .is_some_and(|a| !a.is_empty());
+    if active && spec.concurrency_policy.as_deref() == Some("Forbid") {
+        return Ok(next);
+    }
+
+    let template = spec.job_template.clone();
+    let mut metadata = template.metadata.unwrap_or_default();
+    // Distinct from the `<cronjob>-<minutes>` names of scheduled runs.
+    metadata.name = Some(child_name(&format!(
+        "{}-{}-{}",
+        cronjob.name_any(),
+        drive.kind(),
+        now.timestamp()
+    )));
+    metadata.namespace = Some(namespace.clone());
+    metadata.owner_references = Some(cronjob.controller_references()?);
+    run_labels::label(&mut metadata, now);
+    let run = Job {
+        metadata,
+        spec: template.spec,
+        status: None,
+    };
+    debug!(
+        name = job.name_any(),
+        namespace,
+        run = run.name_any(),
+        kind = drive.kind(),
+        "Starting controller-driven run"
+    );
+    ctx.create(&namespace, &run).await?;
+    Ok(next)
+}
+
+/// Suspends `cronjob` while `spec.suspend` is set, and resumes it once it is
+/// cleared if it was suspended that way. The child is patched in place and
+/// `cronjob` updated to match.
+async fn apply_suspend(
+    ctx: &Context,
+    job: &ScheduledCronJob,
+    cronjob: &mut CronJob,
+) -> Result<(), Error> {
+    let suspend = job.spec.suspend;
+    if !needs_suspend_patch(cronjob, SPEC_SUSPENDED_ANNOTATION, suspend) {
+        return Ok(());
+    }
+
+    let name = cronjob.name_any();
+    let namespace = job.namespace().unwrap_or_default();
+    info!(
+        name = job.name_any(),
+        namespace,
+        cronjob = name,
+        suspend,
+        "Applying spec.suspend"
+    );
+    let annotation = suspend.then_some("true");
+    let patch = serde_json::json!({
+        "metadata": { "annotations": { SPEC_SUSPENDED_ANNOTATION: annotation } },
+        "spec": { "suspend": suspend },
+    });
+    match ctx
+        .patch::<CronJob>(&namespace, &name, &Patch::Merge(patch))
+        .await
+    {
+        Ok(()) => {}
+        // Deleted since listed; nothing left to suspend.
+        Err(Error::NotFound) => return Ok(()),
+        Err(e) => return Err(e),
+    }
+    if let Some(spec) = cronjob.spec.as_mut() {
+        spec.suspend = Some(suspend);
+    }
+    if suspend {
+        cronjob
+            .annotations_mut()
+            .insert(SPEC_SUSPENDED_ANNOTATION.to_string(), "true".to_string());
+    } else {
+        cronjob.annotations_mut().remove(SPEC_SUSPENDED_ANNOTATION);
+        ctx.create_event(job, Reason::Resumed, &format!("Resumed CronJob {name}"))
+            .await?;
+    }
+    Ok(())
+}
+
+/// Whether `cronjob` has to be patched to `suspend`, marking it with
+/// `annotation` when suspending so only children suspended that way are
+/// resumed. Children already suspended otherwise are left unmarked, so they
+/// stay suspended when the cause ends.
+pub(crate) fn needs_suspend_patch(cronjob: &CronJob, annotation: &str, suspend: bool) -> bool {
+    let marked = cronjob.annotations().contains_key(annotation);
+    let suspended = cronjob
+        .spec
+        .as_ref()
+        .and_then(|s| s.suspend)
+        .unwrap_or(false);
+    if suspend { !suspended } else { marked }
+}
+
+/// The ScheduleCalendars named in `spec.calendars`. Missing ones are reported
+/// and skipped; creating them re-reconciles `job` through the calendar watch.
+async fn calendars(ctx: &Context, job: &ScheduledCronJob) -> Result<Vec<ScheduleCalendar>, Error> {
+    let namespace = job.namespace().unwrap_or_default();
+    let mut calendars = Vec::new();
+    for name in &job.spec.calendars {
+        match ctx.calendar(&namespace, name).await {
+            Ok(calendar) => calendars.push(calendar),
+            Err(Error::NotFound) => {
+                warn!(
+                    name = job.name_any(),
+                    namespace,
+                    calendar = name,
+                    "ScheduleCalendar not found"
+                );
+                ctx.create_event(
+                    job,
+                    Reason::InvalidSpec,
+                    &format!("ScheduleCalendar {name} not found"),
+                )
+                .await?;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(calendars)
+}
+
+/// Suspends `cronjob` while a blackout window of `job` lasts, until
+/// `blackout`, and resumes it afterwards if it was suspended that way.
+/// `spec.suspend` takes precedence. The child is patched in place and
+/// `cronjob` updated to match.
+async fn apply_blackout(
+    ctx: &Context,
+    job: &ScheduledCronJob,
+    cronjob: &mut CronJob,
+    blackout: Option<DateTime<Utc>>,
+) -> Result<(), Error> {
+    if job.spec.suspend {
+        return Ok(());
+    }
+    let suspend = blackout.is_some();
+    if !needs_suspend_patch(cronjob, BLACKOUT_SUSPENDED_ANNOTATION, suspend) {
+        return Ok(());
+    }
+
+    let name = cronjob.name_any();
+    let namespace = job.namespace().unwrap_or_default();
+    info!(
+        name = job.name_any(),
+        namespace,
+        cronjob = name,
+        suspend,
+        "Applying blackout window"
+    );
+    let annotation = suspend.then_some("true");
+    let patch = serde_json::json!({
+        "metadata": { "annotations": { BLACKOUT_SUSPENDED_ANNOTATION: annotation } },
+        "spec": { "suspend": suspend },
+    });
+    match ctx
+        .patch::<CronJob>(&namespace, &name, &Patch::Merge(patch))
+        .await
+    {
+        Ok(()) => {}
+        // Deleted since listed; nothing left to suspend or resume.
+        Err(Error::NotFound) => return Ok(()),
+        Err(e) => return Err(e),
+    }
+    if let Some(spec) = cronjob.spec.as_mut() {
+        spec.suspend = Some(suspend);
+    }
+    match blackout {
+        Some(until) => {
+            cronjob.annotations_mut().insert(
+                BLACKOUT_SUSPENDED_ANNOTATION.to_string(),
+                "true".to_string(),
+            );
+            ctx.create_event(
+                job,
+                Reason::Suspended,
+                &format!(
+                    "Suspended CronJob {name} for a blackout window until {}",
+                    until.to_rfc3339()
+                ),
+            )
+            .await?;
+        }
+        None => {
+            cronjob
+                .annotations_mut()
+                .remove(BLACKOUT_SUSPENDED_ANNOTATION);
+            ctx.create_event(
+                job,
+                Reason::Resumed,
+                &format!("Resumed CronJob {name} after a blackout window"),
+            )
+            .await?;
+        }
+    }
+    Ok(())
+}
+
+/// Patches the history limits of `cronjob` to those of `desired` when they
+/// were changed on `job`. Limits left unset keep the child's, which default
+/// to 3 successful and 1 failed Job.
+async fn sync_history_limits(
+    ctx: &Context,
+    job: &ScheduledCronJob,
+    desired: &CronJob,
+    cronjob: &mut CronJob,
+) -> Result<(), Error> {
+    let (Some(desired), Some(spec)) = (desired.spec.as_ref(), cronjob.spec.as_mut()) else {
+        return Ok(());
+    };
+    let successful = desired
+        .successful_jobs_history_limit
+        .filter(|limit| spec.successful_jobs_history_limit != Some(*limit));
+    let failed = desired
+        .failed_jobs_history_limit
+        .filter(|limit| spec.failed_jobs_history_limit != Some(*limit));
+    if successful.is_none() && failed.is_none() {
+        return Ok(());
+    }
+
+    let name = cronjob.metadata.name.clone().unwrap_or_default();
+    let namespace = job.namespace().unwrap_or_default();
+    info!(
+        name = job.name_any(),
+        namespace,
+        cronjob = name,
+        successful,
+        failed,
+        "Updating history limits"
+    );
+    // A null would reset the limit to its default, so unchanged ones are left
+    // out of the merge patch.
+    let mut limits = serde_json::Map::new();
+    if let Some(limit) = successful {
+        limits.insert("successfulJobsHistoryLimit".to_string(), limit.into());
+    }
+    if let Some(limit) = failed {
+        limits.insert("failedJobsHistoryLimit".to_string(), limit.into());
+    }
+    let patch = serde_json::json!({ "spec": limits });
+    match ctx
+        .patch::<CronJob>(&namespace, &name, &Patch::Merge(patch))
+        .await
+    {
+        Ok(()) => {}
+        // Deleted since listed; a replacement gets the limits on creation.
+        Err(Error::NotFound) => return Ok(()),
+        Err(e) => return Err(e),
+    }
+    if successful.is_some() {
+        spec.successful_jobs_history_limit = successful;
+    }
+    if failed.is_some() {
+        spec.failed_jobs_history_limit = failed;
+    }
+    Ok(())
+}
+
+/// Counts the successful runs of the CronJobs with uids `owners` that were not
+/// counted yet into `status.executions`, returning the new total.
+async fn count_executions(
+    ctx: &Context,
+    job: &ScheduledCronJob,
+    owners: &[String],
+    runs: &[PartialObjectMeta<Job>],
+) -> Result<u32, Error> {
+    let namespace = job.namespace().unwrap_or_default();
+    let mut counted = Vec::new();
+    for owner in owners {
+        for run in runs.iter().filter(|r| owned_by(r, owner)) {
+            if run.annotations().contains_key(EXECUTION_COUNTED_ANNOTATION) {
+                continue;
+            }
+            let run = match ctx.get::<Job>(&namespace, &run.name_any()).await {
+                Ok(run) => run,
+                Err(Error::NotFound) => continue,
+                Err(e) => return Err(e),
+            };
+            if succeeded(&run) == Some(true) {
+                counted.push(run);
+            }
+        }
+    }
+    let previous = job.status().and_then(|s| s.executions).unwrap_or_default();
+    if counted.is_empty() {
+        return Ok(previous);
+    }
+
+    let executions = previous + counted.len() as u32;
+    info!(
+        name = job.name_any(),
+        namespace, executions, "Counting successful runs"
+    );
+    ctx.update_scheduled_cronjob_executions(job, executions)
+        .await?;
+    for run in &counted {
+        ctx.annotate(run, EXECUTION_COUNTED_ANNOTATION, Some("true"))
+            .await?;
+    }
+    Ok(executions)
+}
+
+/// Ends the child CronJobs of `job` once `spec.maxExecutions` runs
+/// succeeded, moving it through `Expired` to `Completed` like the end of its
+/// window.
+async fn finish_executions(ctx: &Context, job: &ScheduledCronJob) -> Result<Action, Error> {
+    let name = job.name_any();
+    let namespace = job.namespace().unwrap_or_default();
+    let max = job.spec.max_executions.unwrap_or_default();
+    let uid = job.uid().unwrap_or_default();
+    let remaining = ctx.list_owned_metadata::<CronJob>(&namespace, &uid).await?;
+    let expired = job.status().map(|s| s.phase) == Some(ScheduledCronJobPhase::Expired);
+    if end_children(ctx, job, &remaining).await? && expired {
+        info!(name, namespace, max, "Executions have completed");
+        ctx.update_scheduled_cronjob(
+            job,
+            ScheduledCronJobPhase::Completed,
+            Reason::Completed,
+            &format!("Completed {max} executions"),
+        )
+        .await?;
+        return Ok(Action::await_change());
+    }
+
+    info!(
+        name,
+        namespace, max, "Reached maxExecutions, ending cronjobs"
+    );
+    ctx.update_scheduled_cronjob(
+        job,
+        ScheduledCronJobPhase::Expired,
+        Reason::Expired,
+        &format!("Reached maxExecutions of {max}"),
+    )
+    .await?;
+    Ok(ctx.requeue(job, Duration::from_secs(10)))
+}
+
+/// Ends the `remaining` child CronJobs of `job` as its `endPolicy` says,
+/// returning whether they are done with: `Keep` suspends them, the others
+/// delete them, with or without their Jobs, until none remain.
+async fn end_children(
+    ctx: &Context,
+    job: &ScheduledCronJob,
+    remaining: &[PartialObjectMeta<CronJob>],
+) -> Result<bool, Error> {
+    let namespace = job.namespace().unwrap_or_default();
+    let policy = job.spec.end_policy;
+    for cronjob in remaining {
+        let name = cronjob.name_any();
+        match policy {
+            EndPolicy::Keep => {
+                let patch = serde_json::json!({ "spec": { "suspend": true } });
+                match ctx
+                    .patch::<CronJob>(&namespace, &name, &Patch::Merge(patch))
+                    .await
+                {
+                    // Deleted since listed, which ends it as well.
+                    Ok(()) | Err(Error::NotFound) => {}
+                    Err(e) => return Err(e),
+                }
+            }
+            EndPolicy::DeleteChild => {
+                ctx.delete_with::<CronJob>(&namespace, &name, DeleteParams::orphan())
+                    .await?;
+            }
+            EndPolicy::DeleteAll => ctx.delete::<CronJob>(&namespace, &name).await?,
+        }
+    }
+    Ok(policy == EndPolicy::Keep || remaining.is_empty())
+}
+
+/// Sets the [`STARTING_DEADLINE_MISSED`] condition while any child has
+/// `missed` its starting deadline, with a warning event when it is first set,
+/// and clears it once every child is on time again.
+async fn report_missed_deadlines(
+    ctx: &Context,
+    job: &ScheduledCronJob,
+    missed: &[String],
+) -> Result<(), Error> {
+    let set = job
+        .status()
+        .is_some_and(|s| is_condition_true(&s.conditions, STARTING_DEADLINE_MISSED));
+    if missed.is_empty() {
+        if set {
+            ctx.set_condition(
+                job,
+                STARTING_DEADLINE_MISSED,
+                false,
+                Reason::Recovered,
+                "Every cronjob started its latest run in time",
+            )
+            .await?;
+        }
+        return Ok(());
+    }
+
+    let message = missed.join("; ");
+    if !set {
+        warn!(
+            name = job.name_any(),
+            namespace = job.namespace(),
+            message,
+            "Starting deadline missed"
+        );
+        ctx.create_event(job, Reason::DeadlineMissed, &message)
+            .await?;
+    }
+    ctx.set_condition(
+        job,
+        STARTING_DEADLINE_MISSED,
+        true,
+        Reason::DeadlineMissed,
+        &message,
+    )
+    .await
+}
+
+/// Sets the [`UNSUPPORTED_FEATURES`] condition while features of `job` were
+/// left out of its children because the API server is too old for them, with
+/// a warning event when it is first set.
+async fn report_unsupported_features(
+    ctx: &Context,
+    job: &ScheduledCronJob,
+    unsupported: &[String],
+) -> Result<(), Error> {
+    let set = job
+        .status()
+        .is_some_and(|s| is_condition_true(&s.conditions, UNSUPPORTED_FEATURES));
+    if unsupported.is_empty() {
+        if set {
+            ctx.set_condition(
+                job,
+                UNSUPPORTED_FEATURES,
+                false,
+                Reason::Recovered,
+                "The API server supports every feature of the spec",
+            )
+            .await?;
+        }
+        return Ok(());
+    }
+
+    let message = unsupported.join("; ");
+    if !set {
+        warn!(
+            name = job.name_any(),
+            namespace = job.namespace(),
+            message,
+            "Features unsupported by the API server"
+        );
+        ctx.create_event(job, Reason::FeatureUnsupported, &message)
+            .await?;
+    }
+    ctx.set_condition(
+        job,
+        UNSUPPORTED_FEATURES,
+        true,
+        Reason::FeatureUnsupported,
+        &message,
+    )
+    .await
+}
+
+/// Deletes CronJobs owned by `job` that are no longer among its `children`,
+/// e.g. after `childNameTemplate` changed.
+async fn prune_cronjobs(
+    ctx: &Context,
+    job: &ScheduledCronJob,
+    children: &[String],
+) -> Result<(), Error> {
+    let namespace = job.namespace().unwrap_or_default();
+    let uid = job.uid().unwrap_or_default();
+    for cronjob in ctx.list_owned_metadata::<CronJob>(&namespace, &uid).await? {
+        let child = cronjob.name_any();
+        if children.contains(&child) {
+            continue;
+        }
+        info!(
+            name = job.name_any(),
+            namespace, child, "Deleting stale cronjob"
+        );
+        ctx.delete::<CronJob>(&namespace, &child).await?;
+    }
+    Ok(())
+}
+
+/// Applies the ServiceAccount, Role and RoleBinding requested by
+/// `spec.serviceAccount`, or deletes those owned by `job` once it no longer
+/// requests them. Returns what was applied.
+async fn provision_service_account(
+    ctx: &Context,
+    job: &ScheduledCronJob,
+) -> Result<Vec<ManagedResource>, Error> {
+    let namespace = job.namespace().unwrap_or_default();
+    let uid = job.uid().unwrap_or_default();
+    let allowed = &ctx.config().service_account_rules;
+    let rejected = job
+        .spec
+        .service_account
+        .as_ref()
+        .filter(|s| s.create)
+        .map(|s| s.rules_outside(allowed))
+        .unwrap_or_default();
+    report_rejected_rules(ctx, job, &rejected).await?;
+    let Some((account, role, binding)) = job.service_account()? else {
+        delete_owned::<RoleBinding>(ctx, &namespace, &uid).await?;
+        delete_owned::<Role>(ctx, &namespace, &uid).await?;
+        delete_owned::<ServiceAccount>(ctx, &namespace, &uid).await?;
+        return Ok(Vec::new());
+    };
+    if ctx.namespace_terminating(&namespace).await? {
+        return Err(Error::NamespaceTerminating(namespace));
+    }
+    debug!(
+        name = job.name_any(),
+        namespace,
+        account = account.name_any(),
+        "Applying service account"
+    );
+    let account = ManagedResource::of(&ctx.apply(&namespace, &account).await?);
+    if !rejected.is_empty() {
+        // The pods still run under the account, only without the Role.
+        delete_owned::<RoleBinding>(ctx, &namespace, &uid).await?;
+        delete_owned::<Role>(ctx, &namespace, &uid).await?;
+        return Ok(vec![account]);
+    }
+    Ok(vec![
+        account,
+        ManagedResource::of(&ctx.apply(&namespace, &role).await?),
+        ManagedResource::of(&ctx.apply(&namespace, &binding).await?),
+    ])
+}
+
+/// Sets the [`SERVICE_ACCOUNT_REJECTED`] condition while `rejected` rules of
+/// `spec.serviceAccount` are outside those the controller allows, with a
+/// warning event when it is first set.
+async fn report_rejected_rules(
+    ctx: &Context,
+    job: &ScheduledCronJob,
+    rejected: &[&PolicyRule],
+) -> Result<(), Error> {
+    let set = job
+        .status()
+        .is_some_and(|s| is_condition_true(&s.conditions, SERVICE_ACCOUNT_REJECTED));
+    if rejected.is_empty() {
+        if set {
+            ctx.set_condition(
+                job,
+                SERVICE_ACCOUNT_REJECTED,
+                false,
+                Reason::Recovered,
+                "Every service account rule is allowed",
+            )
+            .await?;
+        }
+        return Ok(());
+    }
+
+    let rules: Vec<_> = rejected.iter().map(|r| describe_rule(r)).collect();
+    let message = format!(
+        "Role not provisioned, rules not allowed by the controller: {}",
+        rules.join("; ")
+    );
+    if !set {
+        warn!(
+            name = job.name_any(),
+            namespace = job.namespace(),
+            message,
+            "Service account rules rejected"
+        );
+        ctx.create_event(job, Reason::RulesNotAllowed, &message)
+            .await?;
+    }
+    ctx.set_condition(
+        job,
+        SERVICE_ACCOUNT_REJECTED,
+        true,
+        Reason::RulesNotAllowed,
+        &message,
+    )
+    .await
+}
+
+/// `rule` as e.g. `get,list pods in core` for a condition message.
+fn describe_rule(rule: &PolicyRule) -> String {
+    let verbs = rule.verbs.join(",");
+    if let Some(urls) = rule.non_resource_urls.as_ref().filter(|u| !u.is_empty()) {
+        return format!("{verbs} {}", urls.join(","));
+    }
+    let resources = rule.resources.as_deref().unwrap_or_default().join(",");
+    let groups: Vec<_> = rule
+        .api_groups
+        .iter()
+        .flatten()
+        .map(|g| if g.is_empty() { "core" } else { g.as_str() })
+        .collect();
+    let mut description = format!("{verbs} {resources} in {}", groups.join(","));
+    if let Some(names) = rule.resource_names.as_ref().filter(|n| !n.is_empty()) {
+        description.push_str(&format!(" named {}", names.join(",")));
+    }
+    description
+}
+
+/// Applies the NetworkPolicy requested by `spec.network`, or deletes the one
+/// owned by `job` once it no longer requests it. Returns what was applied.
+async fn provision_network_policy(
+    ctx: &Context,
+    job: &ScheduledCronJob,
+) -> Result<Vec<ManagedResource>, Error> {
+    let namespace = job.namespace().unwrap_or_default();
+    let Some(policy) = job.network_policy()? else {
+        let uid = job.uid().unwrap_or_default();
+        delete_owned::<NetworkPolicy>(ctx, &namespace, &uid).await?;
+        return Ok(Vec::new());
+    };
+    if ctx.namespace_terminating(&namespace).await? {
+        return Err(Error::NamespaceTerminating(namespace));
+    }
+    debug!(
+        name = job.name_any(),
+        namespace,
+        policy = policy.name_any(),
+        "Applying network policy"
+    );
+    let policy = ctx.apply(&namespace, &policy).await?;
+    Ok(vec![ManagedResource::of(&policy)])
+}
+
+async fn delete_owned<K>(ctx: &Context, namespace: &str, owner_uid: &str) -> Result<(), Error>
+where
+    K: Resource<Scope = NamespaceResourceScope, DynamicType = ()>,
+    K: Clone + serde::de::DeserializeOwned + serde::Serialize + std::fmt::Debug,
+{
+    for object in ctx.list_owned_metadata::<K>(namespace, owner_uid).await? {
+        info!(
+            namespace,
+            kind = %K::kind(&()),
+            child = object.name_any(),
+            "Deleting unrequested child"
+        );
+        ctx.delete::<K>(namespace, &object.name_any()).await?;
+    }
+    Ok(())
+}
+
+/// Fails the `active` runs of `job` that have been going for longer than
+/// `spec.hardTimeoutSeconds`, or whose heartbeat stopped for longer than
+/// `spec.heartbeatTimeoutSeconds`.
+async fn enforce_deadlines(
+    ctx: &Context,
+    job: &ScheduledCronJob,
+    active: &[ObjectReference],
+) -> Result<(), Error> {
+    let hard_timeout = job.spec.hard_timeout_seconds;
+    let heartbeat_timeout = job.spec.heartbeat_timeout_seconds;
+    if hard_timeout.is_none() && heartbeat_timeout.is_none() {
+        return Ok(());
+    }
+    let namespace = job.namespace().unwrap_or_default();
+    for run in active.iter().filter_map(|r| r.name.as_deref()) {
+        let run_job = match ctx.get::<Job>(&namespace, run).await {
+            Ok(run_job) => run_job,
+            Err(Error::NotFound) => continue,
+            Err(e) => return Err(e),
+        };
+        if run_job.annotations().contains_key(TIMED_OUT_ANNOTATION) {
+            continue;
+        }
+        let Some(started) = run_job
+            .status
+            .as_ref()
+            .and_then(|s| s.start_time.as_ref())
+            .map(|t| t.0)
+        else {
+            continue;
+        };
+        let since = |time: DateTime<Utc>| (Utc::now() - time).to_std().unwrap_or_default();
+
+        let elapsed = since(started);
+        if let Some(timeout) = hard_timeout
+            && elapsed.as_secs() >= timeout
+        {
+            let message = format!("Run {run} exceeded the hard timeout of {timeout}s");
+            fail_run(ctx, job, &run_job, Reason::TimedOut, &message, elapsed).await?;
+            continue;
+        }
+        if let Some(timeout) = heartbeat_timeout {
+            let last = last_heartbeat(ctx, &run_job).await?.unwrap_or(started);
+            if since(last).as_secs() >= timeout {
+                let message = format!("Run {run} sent no heartbeat for {timeout}s");
+                fail_run(ctx, job, &run_job, Reason::Stalled, &message, elapsed).await?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// When the heartbeat Lease of `run` was last renewed. The Lease is created,
+/// owned by the run so it is deleted with it, the first time it is missing.
+async fn last_heartbeat(ctx: &Context, run: &Job) -> Result<Option<DateTime<Utc>>, Error> {
+    let namespace = run.namespace().unwrap_or_default();
+    match ctx.get::<Lease>(&namespace, &run.name_any()).await {
+        Ok(lease) => Ok(lease.spec.and_then(|s| s.renew_time).map(|t| t.0)),
+        Err(Error::NotFound) => {
+            let lease = Lease {
+                metadata: ObjectMeta {
+                    name: Some(run.name_any()),
+                    namespace: Some(namespace.clone()),
+                    owner_references: Some(run.controller_references()?),
+                    ..Default::default()
+                },
+                spec: None,
+            };
+            ctx.create(&namespace, &lease).await?;
+            Ok(None)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Fails `run` of `job` and reports it with [`report_failed_run`].
+async fn fail_run(
+    ctx: &Context,
+    job: &ScheduledCronJob,
+    run: &Job,
+    reason: Reason,
+    message: &str,
+    elapsed: std::time::Duration,
+) -> Result<(), Error> {
+    let namespace = job.namespace().unwrap_or_default();
+    let name = run.name_any();
+    warn!(name = job.name_any(), namespace, run = name, %reason, ?elapsed, "Failing run");
+    // An elapsed active deadline fails the Job and deletes its pods.
+    let patch = serde_json::json!({
+        "metadata": { "annotations": { TIMED_OUT_ANNOTATION: Utc::now().to_rfc3339() } },
+        "spec": { "activeDeadlineSeconds": 1 },
+    });
+    match ctx
+        .patch::<Job>(&namespace, &name, &Patch::Merge(patch))
+        .await
+    {
+        Ok(()) => {}
+        // Deleted since listed, by the history limits or its TTL.
+        Err(Error::NotFound) => return Ok(()),
+        Err(e) => return Err(e),
+    }
+    ctx.metrics()
+        .runs_timed_out_total
+        .with_label_values(&["ScheduledCronJob"])
+        .inc();
+    report_failed_run(ctx, job, run, reason, message, elapsed).await
+}
+
+/// Archives `run` of `job` with the phase named after `reason`, emits the
+/// event and notifies the hooks.
+async fn report_failed_run(
+    ctx: &Context,
+    job: &ScheduledCronJob,
+    run: &Job,
+    reason: Reason,
+    message: &str,
+    elapsed: std::time::Duration,
+) -> Result<(), Error> {
+    ctx.archive_run(job, run, reason.as_str(), message).await?;
+    ctx.create_event(job, reason, message).await?;
+    if let Some(hooks) = ctx.hooks()
+        && !ctx.config().observer
+    {
+        let failed = RunFailed {
+            kind: "ScheduledCronJob".to_string(),
+            namespace: job.namespace().unwrap_or_default(),
+            name: job.name_any(),
+            job: run.name_any(),
+            reason,
+            elapsed,
+            owner: job.spec.owner.clone(),
+        };
+        hooks.on_run_failed(&failed).await;
+    }
+    Ok(())
+}
+
+/// Outcome of the post-run check of one run.
+enum CheckOutcome {
+    Pending,
+    Passed,
+    Failed(String),
+}
+
+/// Checks the finished runs of the CronJobs with UIDs `owners` against
+/// `spec.postRunCheck`, marking each with [`POST_RUN_CHECK_ANNOTATION`] once
+/// its outcome is known so it is checked only once.
+async fn verify_runs(
+    ctx: &Context,
+    job: &ScheduledCronJob,
+    owners: &[String],
+    runs: &[PartialObjectMeta<Job>],
+) -> Result<(), Error> {
+    let Some(check) = &job.spec.post_run_check else {
+        return Ok(());
+    };
+    let namespace = job.namespace().unwrap_or_default();
+    for owner in owners {
+        for run in runs.iter().filter(|r| owned_by(r, owner)) {
+            if run.annotations().contains_key(POST_RUN_CHECK_ANNOTATION) {
+                continue;
+            }
+            let run = match ctx.get::<Job>(&namespace, &run.name_any()).await {
+                Ok(run) => run,
+                Err(Error::NotFound) => continue,
+                Err(e) => return Err(e),
+            };
+            let result = match succeeded(&run) {
+                None => continue,
+                Some(false) => "Skipped",
+                Some(true) => match check_run(ctx, check, &run).await? {
+                    CheckOutcome::Pending => continue,
+                    CheckOutcome::Passed => "Passed",
+                    CheckOutcome::Failed(reason) => {
+                        let message = format!("Run {} failed its check: {reason}", run.name_any());
+                        let status = run.status.as_ref();
+                        let elapsed = status
+                            .and_then(|s| {
+                                Some((s.start_time.as_ref()?, s.completion_time.as_ref()?))
+                            })
+                            .and_then(|(start, end)| (end.0 - start.0).to_std().ok())
+                            .unwrap_or_default();
+                        report_failed_run(ctx, job, &run, Reason::CheckFailed, &message, elapsed)
+                            .await?;
+                        "Failed"
+                    }
+                },
+            };
+            if result != "Skipped" {
+                ctx.metrics()
+                    .post_run_checks_total
+                    .with_label_values(&["ScheduledCronJob", result])
+                    .inc();
+            }
+            ctx.annotate(&run, POST_RUN_CHECK_ANNOTATION, Some(result))
+                .await?;
+        }
+    }
+    Ok(())
+}
+
+/// Sets the fire time label on the runs of `cronjobs` the CronJob controller
+/// started, see [`run_labels`].
+async fn label_runs(
+    ctx: &Context,
+    cronjobs: &[CronJob],
+    runs: &[PartialObjectMeta<Job>],
+) -> Result<(), Error> {
+    for cronjob in cronjobs {
+        for run in runs_of(runs, cronjob) {
+            if run.labels().contains_key(FIRE_TIME_LABEL) {
+                continue;
+            }
+            let Some(at) = run_labels::fire_time(run, &cronjob.name_any()) else {
+                continue;
+            };
+            let run = Job {
+                metadata: run.metadata.clone(),
+                ..Default::default()
+            };
+            ctx.label(&run, FIRE_TIME_LABEL, Some(&at.timestamp().to_string()))
+                .await?;
+        }
+    }
+    Ok(())
+}
+
+/// Starts a one-off run for `spec.adaptive` once its interval has passed
+/// since the last run of `cronjobs` ended, returning how long until then.
+/// Must only be called while no run is active.
+async fn adapt(
+    ctx: &Context,
+    job: &ScheduledCronJob,
+    cronjobs: &[CronJob],
+    runs: &[PartialObjectMeta<Job>],
+) -> Result<Option<Duration>, Error> {
+    let Some(adaptive) = &job.spec.adaptive else {
+        return Ok(None);
+    };
+    let namespace = job.namespace().unwrap_or_default();
+    let mut last: Option<(&CronJob, Job)> = None;
+    for cronjob in cronjobs {
+        let Some(newest) = runs_of(runs, cronjob).max_by_key(|r| r.creation_timestamp()) else {
+            continue;
+        };
+        let run = match ctx.get::<Job>(&namespace, &newest.name_any()).await {
+            Ok(run) => run,
+            Err(Error::NotFound) => continue,
+            Err(e) => return Err(e),
+        };
+        if last
+            .as_ref()
+            .is_none_or(|(_, l)| finished_at(l) < finished_at(&run))
+        {
+            last = Some((cronjob, run));
+        }
+    }
+    let Some((cronjob, run)) = last else {
+        return Ok(None);
+    };
+    let (Some(ok), Some(ended)) = (succeeded(&run), finished_at(&run)) else {
+        return Ok(None);
+    };
+    let Some(interval) = adaptive.interval(ok) else {
+        return Ok(None);
+    };
+    let elapsed = (Utc::now() - ended).to_std().unwrap_or_default();
+    if elapsed < interval {
+        return Ok(Some(interval - elapsed));
+    }
+    let Some(template) = cronjob.spec.as_ref().map(|s| s.job_template.clone()) else {
+        return Ok(None);
+    };
+
+    let outcome = if ok { "Succeeded" } else { "Failed" };
+    let mut metadata = template.metadata.unwrap_or_default();
+    // Distinct from the `<cronjob>-<minutes>` names of scheduled runs.
+    metadata.name = Some(child_name(&format!(
+        "{}-adaptive-{}",
+        cronjob.name_any(),
+        Utc::now().timestamp() / 60
+    )));
+    metadata.namespace = Some(namespace.clone());
+    metadata.owner_references = Some(cronjob.controller_references()?);
+    run_labels::label(&mut metadata, Utc::now());
+    metadata
+        .annotations
+        .get_or_insert_with(Default::default)
+        .insert(ADAPTIVE_RUN_ANNOTATION.to_string(), outcome.to_string());
+    let adaptive_run = Job {
+        metadata,
+        spec: template.spec,
+        status: None,
+    };
+    let message = format!(
+        "Started {} {}s after run {} ended {}",
+        adaptive_run.name_any(),
+        interval.as_secs(),
+        run.name_any(),
+        outcome.to_lowercase()
+    );
+    info!(
+        name = job.name_any(),
+        namespace, message, "Starting adaptive run"
+    );
+    ctx.create(&namespace, &adaptive_run).await?;
+    ctx.create_event(job, Reason::AdaptiveRun, &message).await?;
+    Ok(None)
+}
+
+/// When the Job `run` completed or failed.
+fn finished_at(run: &Job) -> Option<DateTime<Utc>> {
+    run.status
+        .as_ref()?
+        .conditions
+        .as_ref()?
+        .iter()
+        .find(|c| (c.type_ == "Complete" || c.type_ == "Failed") && c.status == "True")
+        .and_then(|c| c.last_transition_time.as_ref())
+        .map(|t| t.0)
+}
+
+/// Whether the Job `run` completed, or `None` while it is still running.
+fn succeeded(run: &Job) -> Option<bool> {
+    let conditions = run.status.as_ref()?.conditions.as_ref()?;
+    let finished = |type_: &str| {
+        conditions
+            .iter()
+            .any(|c| c.type_ == type_ && c.status == "True")
+    };
+    if finished("Complete") {
+        Some(true)
+    } else if finished("Failed") {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+/// Runs `check` against `run`. The check Job is created the first time and
+/// awaited across reconciliations; the GET is sent once it has passed.
+async fn check_run(ctx: &Context, check: &PostRunCheck, run: &Job) -> Result<CheckOutcome, Error> {
+    let namespace = run.namespace().unwrap_or_default();
+    if let Some(desired) = check.check_job(run)? {
+        let name = desired.name_any();
+        match ctx.get::<Job>(&namespace, &name).await {
+            Ok(existing) => match succeeded(&existing) {
+                None => return Ok(CheckOutcome::Pending),
+                Some(false) => {
+                    return Ok(CheckOutcome::Failed(format!("check job {name} failed")));
+                }
+                Some(true) => {}
+            },
+            Err(Error::NotFound) => {
+                info!(
+                    namespace,
+                    run = run.name_any(),
+                    check = name,
+                    "Starting check job"
+                );
+                ctx.create(&namespace, &desired).await?;
+                return Ok(CheckOutcome::Pending);
+            }
+            Err(e) => return Err(e),
+        }
+    }
+    if let Some(http) = &check.http {
+        let timeout = Duration::from_secs(http.timeout_seconds.unwrap_or(10));
+        let mut request = ctx.http().get(&http.url).timeout(timeout);
+        for (key, value) in &http.headers {
+            request = request.header(key, value);
+        }
+        match request.send().await {
+            Ok(response) if response.status().is_success() => {}
+            Ok(response) => {
+                return Ok(CheckOutcome::Failed(format!(
+                    "GET {} answered {}",
+                    http.url,
+                    response.status()
+                )));
+            }
+            Err(e) => {
+                return Ok(CheckOutcome::Failed(format!(
+                    "GET {} failed: {e}",
+                    http.url
+                )));
+            }
+        }
+    }
+    Ok(CheckOutcome::Passed)
+}
+
+/// How long `cronjob` is past the first fire time after its last scheduled
+/// run, or its creation, without having started another. Fire times are read
+/// in its `timeZone`, or UTC like the kube-controller-manager.
+fn overdue(cronjob: &CronJob, now: DateTime<Utc>) -> Duration {
+    let Some(spec) = &cronjob.spec else {
+        return Duration::ZERO;
+    };
+    if spec.suspend == Some(true) {
+        return Duration::ZERO;
+    }
+    let Ok(schedule) = Schedule::parse(&spec.schedule) else {
+        return Duration::ZERO;
+    };
+    let Some(last) = cronjob
+        .status
+        .as_ref()
+        .and_then(|s| s.last_schedule_time.as_ref())
+        .or(cronjob.metadata.creation_timestamp.as_ref())
+        .map(|t| t.0)
+    else {
+        return Duration::ZERO;
+    };
+    next_fire(spec, &schedule, last)
+        .and_then(|next| (now - next).to_std().ok())
+        .unwrap_or_default()
+}
+
+/// The first fire time of `schedule` after `after`, read in the `timeZone` of
+/// `spec`, or UTC like the kube-controller-manager.
+pub(crate) fn next_fire(
+    spec: &CronJobSpec,
+    schedule: &Schedule,
+    after: DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+    match spec
+        .time_zone
+        .as_deref()
+        .and_then(|tz| tz.parse::<Tz>().ok())
+    {
+        Some(tz) => schedule
+            .next_after(&after.with_timezone(&tz))
+            .map(|t| t.with_timezone(&Utc)),
+        None => schedule.next_after(&after),
+    }
+}
+
+/// The earliest next fire time of the `cronjobs` that are not suspended.
+pub(crate) fn next_schedule_time(
+    cronjobs: &[CronJob],
+    now: DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+    cronjobs
+        .iter()
+        .filter_map(|c| c.spec.as_ref())
+        .filter(|s| s.suspend != Some(true))
+        .filter_map(|s| next_fire(s, &Schedule::parse(&s.schedule).ok()?, now))
+        .min()
+}
+
+fn schedule_status(
+    schedule: Option<&String>,
+    variant: Option<&String>,
+    cronjob: &CronJob,
+) -> ScheduleStatus {
+    let status = cronjob.status.clone().unwrap_or_default();
+    ScheduleStatus {
+        schedule: schedule.cloned().unwrap_or_default(),
+        variant: variant.cloned(),
+        cron_job: cronjob.name_any(),
+        active: status.active.map_or(0, |a| a.len()),
+        last_schedule_time: status.last_schedule_time,
+        last_successful_time: status.last_successful_time,
+    }
+}
+
+fn variant_status(variant: &str, cronjob: &CronJob) -> VariantStatus {
+    let status = cronjob.status.clone().unwrap_or_default();
+    VariantStatus {
+        name: variant.to_string(),
+        cron_job: cronjob.name_any(),
+        active: status.active.map_or(0, |a| a.len()),
+        last_schedule_time: status.last_schedule_time,
+        last_successful_time: status.last_successful_time,
+    }
+}
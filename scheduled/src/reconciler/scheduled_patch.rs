@@ -0,0 +1,195 @@
+use std::{sync::Arc, time::Duration};
+
+use chrono::Local;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
+use kube::{ResourceExt as _, runtime::controller::Action};
+use tracing::{debug, error, info, warn};
+
+use super::guard;
+use crate::{
+    Context, Error,
+    crd::{ScheduledPatch, ScheduledPatchPhase},
+    reason::Reason,
+    schedule::Tick,
+};
+
+pub async fn reconcile(patch: Arc<ScheduledPatch>, ctx: Arc<Context>) -> Result<Action, Error> {
+    let name = patch.name_any();
+    let namespace = patch.namespace().unwrap_or_default();
+    info!(name, namespace, "Starting scheduled patch reconciliation");
+
+    match guard(patch.as_ref(), &ctx, implement(&patch, ctx.clone())).await {
+        Ok(action) => {
+            debug!(
+                name,
+                namespace,
+                ?action,
+                "Reconciliation completed successfully"
+            );
+            Ok(action)
+        }
+        Err(e @ Error::InvalidSchedule(_)) => {
+            warn!(name, namespace, error = ?e, "Invalid schedule");
+            ctx.update_scheduled_patch(
+                &patch,
+                ScheduledPatchPhase::InvalidSchedule,
+                e.reason(),
+                e.to_string().as_str(),
+            )
+            .await?;
+            Ok(Action::await_change())
+        }
+        Err(e @ (Error::EndBeforeStart | Error::DurationTooShort(_, _))) => {
+            warn!(name, namespace, error = ?e, "Invalid time range");
+            ctx.update_scheduled_patch(
+                &patch,
+                ScheduledPatchPhase::EndBeforeStart,
+                e.reason(),
+                e.to_string().as_str(),
+            )
+            .await?;
+            Ok(Action::await_change())
+        }
+        Err(Error::WaitFor(duration)) => {
+            if patch.status.as_ref().map(|s| s.phase) != Some(ScheduledPatchPhase::Pending) {
+                ctx.update_scheduled_patch(
+                    &patch,
+                    ScheduledPatchPhase::Pending,
+                    Reason::WindowNotOpen,
+                    "Waiting for scheduled time",
+                )
+                .await?;
+            }
+            Ok(ctx.requeue(patch.as_ref(), duration.to_std().unwrap()))
+        }
+        Err(Error::Expired(_)) => {
+            if patch.status.as_ref().map(|s| s.phase) != Some(ScheduledPatchPhase::Completed) {
+                ctx.update_scheduled_patch(
+                    &patch,
+                    ScheduledPatchPhase::Completed,
+                    Reason::Completed,
+                    "Schedule has completed",
+                )
+                .await?;
+            }
+            Ok(Action::await_change())
+        }
+        Err(e @ (Error::Condition(_) | Error::Http(_))) => {
+            warn!(name, namespace, error = ?e, "Failed to evaluate condition");
+            ctx.update_scheduled_patch(
+                &patch,
+                ScheduledPatchPhase::Failed,
+                e.reason(),
+                e.to_string().as_str(),
+            )
+            .await?;
+            Ok(ctx.requeue(patch.as_ref(), Duration::from_secs(60)))
+        }
+        Err(e @ (Error::NotFound | Error::InvalidTarget(_))) => {
+            warn!(name, namespace, error = ?e, "Patch target is unavailable");
+            ctx.update_scheduled_patch(
+                &patch,
+                ScheduledPatchPhase::Failed,
+                e.reason(),
+                format!("Target {}: {}", patch.spec.target.name, e).as_str(),
+            )
+            .await?;
+            Ok(ctx.requeue(patch.as_ref(), Duration::from_secs(60)))
+        }
+        Err(e) => {
+            if let Some(suppressed) = ctx.log_throttle().check(patch.as_ref()) {
+                error!(name, namespace, suppressed, error = ?e, "Error in reconciliation");
+            }
+            Err(e)
+        }
+    }
+}
+
+async fn implement(patch: &ScheduledPatch, ctx: Arc<Context>) -> Result<Action, Error> {
+    let name = patch.name_any();
+    let namespace = patch.namespace().unwrap_or_default();
+
+    if patch.status.as_ref().map(|s| s.phase) == Some(ScheduledPatchPhase::Completed) {
+        return Ok(Action::await_change());
+    }
+
+    let schedule = patch.schedule()?;
+    let now = Local::now();
+    patch.window().check(now)?;
+
+    // 从上次执行时间（或创建时间）开始计算下一次执行时间，错过的多次执行只补一次
+    let since = patch
+        .last_schedule_time()
+        .or_else(|| {
+            patch
+                .creation_timestamp()
+                .map(|t| t.0.with_timezone(&Local))
+        })
+        .unwrap_or(now);
+
+    let tick = schedule.tick(since, now);
+    let skipped = match (&tick, &patch.spec.condition) {
+        (Tick::Fire { .. }, Some(condition)) => {
+            let (allowed, value) = ctx.evaluate_fire_condition(&namespace, condition).await?;
+            (!allowed).then(|| condition.describe(value))
+        }
+        _ => None,
+    };
+    if let Some(observed) = skipped {
+        let message = format!("Skipped patch: condition {} does not hold", observed);
+        info!(name, namespace, observed, "Skipping scheduled patch");
+        ctx.create_event(patch, Reason::SkippedByCondition, &message)
+            .await?;
+        ctx.update_scheduled_patch_status(
+            patch,
+            ScheduledPatchPhase::Running,
+            &message,
+            Some(Time(now.to_utc())),
+        )
+        .await?;
+    } else if let Tick::Fire { .. } = tick {
+        info!(
+            name,
+            namespace,
+            target = patch.spec.target.name,
+            "Applying scheduled patch"
+        );
+        ctx.patch_target(&namespace, &patch.spec.target, &patch.patch())
+            .await?;
+        let message = format!(
+            "Patched {} {}",
+            patch.spec.target.kind, patch.spec.target.name
+        );
+        ctx.create_event(patch, Reason::Patched, &message).await?;
+        ctx.update_scheduled_patch_status(
+            patch,
+            ScheduledPatchPhase::Running,
+            &message,
+            Some(Time(now.to_utc())),
+        )
+        .await?;
+    } else if patch.status.as_ref().map(|s| s.phase) != Some(ScheduledPatchPhase::Running) {
+        ctx.update_scheduled_patch(
+            patch,
+            ScheduledPatchPhase::Running,
+            Reason::Activated,
+            "Waiting for next scheduled time",
+        )
+        .await?;
+    }
+
+    // 在下一次执行时间或窗口结束时重新检查
+    let Some(wake) = tick.wake_at(&patch.window()) else {
+        return Ok(Action::await_change());
+    };
+    let wait_for = (wake - Local::now())
+        .to_std()
+        .unwrap_or(Duration::from_secs(1));
+    debug!(
+        name,
+        namespace,
+        ?wait_for,
+        "Requeue until next scheduled time"
+    );
+    Ok(ctx.requeue(patch, wait_for))
+}
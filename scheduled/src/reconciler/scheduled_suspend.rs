@@ -0,0 +1,113 @@
+use std::{sync::Arc, time::Duration};
+
+use chrono::Local;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
+use kube::{ResourceExt as _, runtime::controller::Action};
+use tracing::{debug, error, info, warn};
+
+use super::guard;
+use crate::{
+    Context, Error,
+    crd::{ScheduledSuspend, ScheduledSuspendPhase},
+    reason::Reason,
+};
+
+pub async fn reconcile(suspend: Arc<ScheduledSuspend>, ctx: Arc<Context>) -> Result<Action, Error> {
+    let name = suspend.name_any();
+    let namespace = suspend.namespace().unwrap_or_default();
+    info!(name, namespace, "Starting scheduled suspend reconciliation");
+
+    match guard(suspend.as_ref(), &ctx, implement(&suspend, ctx.clone())).await {
+        Ok(action) => {
+            debug!(
+                name,
+                namespace,
+                ?action,
+                "Reconciliation completed successfully"
+            );
+            Ok(action)
+        }
+        Err(e @ Error::InvalidSchedule(_)) => {
+            warn!(name, namespace, error = ?e, "Invalid schedule");
+            ctx.update_scheduled_suspend(
+                &suspend,
+                ScheduledSuspendPhase::InvalidSchedule,
+                e.reason(),
+                e.to_string().as_str(),
+            )
+            .await?;
+            Ok(Action::await_change())
+        }
+        Err(e) => {
+            if let Some(suppressed) = ctx.log_throttle().check(suspend.as_ref()) {
+                error!(name, namespace, suppressed, error = ?e, "Error in reconciliation");
+            }
+            Err(e)
+        }
+    }
+}
+
+async fn implement(suspend: &ScheduledSuspend, ctx: Arc<Context>) -> Result<Action, Error> {
+    let name = suspend.name_any();
+    let namespace = suspend.namespace().unwrap_or_default();
+
+    let suspend_schedule = suspend.suspend_schedule()?;
+    let resume_schedule = suspend.resume_schedule()?;
+    let now = Local::now();
+
+    // 下一次恢复早于下一次暂停，说明当前处于暂停窗口内
+    let next_suspend = suspend_schedule.next_after(&now);
+    let next_resume = resume_schedule.next_after(&now);
+    let should_suspend = match (next_suspend, next_resume) {
+        (Some(next_suspend), Some(next_resume)) => next_resume < next_suspend,
+        (None, Some(_)) => true,
+        _ => false,
+    };
+
+    let phase = if should_suspend {
+        ScheduledSuspendPhase::Suspended
+    } else {
+        ScheduledSuspendPhase::Active
+    };
+    if suspend.status.as_ref().map(|s| s.phase) != Some(phase) {
+        info!(name, namespace, phase = phase.as_str(), "Toggling targets");
+        let patch = ScheduledSuspend::suspend_patch(should_suspend);
+        let mut failures = Vec::new();
+        for target in &suspend.spec.targets {
+            if let Err(e) = ctx.patch_target(&namespace, target, &patch).await {
+                warn!(name, namespace, target = target.name, error = ?e, "Failed to toggle target");
+                failures.push(format!("{} {}: {}", target.kind, target.name, e));
+            }
+        }
+
+        if !failures.is_empty() {
+            ctx.update_scheduled_suspend(
+                suspend,
+                ScheduledSuspendPhase::Failed,
+                Reason::InvalidTarget,
+                &format!("Failed to toggle targets: {}", failures.join("; ")),
+            )
+            .await?;
+            return Ok(ctx.requeue(suspend, Duration::from_secs(60)));
+        }
+
+        let (reason, message) = if should_suspend {
+            (Reason::Suspended, "Targets suspended")
+        } else {
+            (Reason::Resumed, "Targets resumed")
+        };
+        ctx.create_event(suspend, reason, message).await?;
+        ctx.update_scheduled_suspend_status(suspend, phase, message, Some(Time(now.to_utc())))
+            .await?;
+    }
+
+    // 在下一次暂停或恢复时重新检查
+    let Some(wake) = next_suspend.into_iter().chain(next_resume).min() else {
+        return Ok(Action::await_change());
+    };
+    let wait_for = (wake - Local::now())
+        .to_std()
+        .unwrap_or(Duration::from_secs(1));
+    debug!(name, namespace, ?wait_for, "Requeue until next transition");
+    Ok(ctx.requeue(suspend, wait_for))
+}
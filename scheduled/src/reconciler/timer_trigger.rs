@@ -0,0 +1,184 @@
+use std::{sync::Arc, time::Duration};
+
+use chrono::Local;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
+use kube::{ResourceExt as _, runtime::controller::Action};
+use tracing::{debug, error, info, warn};
+
+use super::guard;
+use crate::{
+    Context, Error,
+    crd::{TimerTrigger, TimerTriggerPayload, TimerTriggerPhase},
+    reason::Reason,
+    schedule::Tick,
+};
+
+pub async fn reconcile(trigger: Arc<TimerTrigger>, ctx: Arc<Context>) -> Result<Action, Error> {
+    let name = trigger.name_any();
+    let namespace = trigger.namespace().unwrap_or_default();
+    info!(name, namespace, "Starting timer trigger reconciliation");
+
+    match guard(trigger.as_ref(), &ctx, implement(&trigger, ctx.clone())).await {
+        Ok(action) => {
+            debug!(
+                name,
+                namespace,
+                ?action,
+                "Reconciliation completed successfully"
+            );
+            Ok(action)
+        }
+        Err(e @ Error::InvalidSchedule(_)) => {
+            warn!(name, namespace, error = ?e, "Invalid schedule");
+            ctx.update_timer_trigger(
+                &trigger,
+                TimerTriggerPhase::InvalidSchedule,
+                e.reason(),
+                e.to_string().as_str(),
+            )
+            .await?;
+            Ok(Action::await_change())
+        }
+        Err(e @ (Error::EndBeforeStart | Error::DurationTooShort(_, _))) => {
+            warn!(name, namespace, error = ?e, "Invalid time range");
+            ctx.update_timer_trigger(
+                &trigger,
+                TimerTriggerPhase::EndBeforeStart,
+                e.reason(),
+                e.to_string().as_str(),
+            )
+            .await?;
+            Ok(Action::await_change())
+        }
+        Err(Error::WaitFor(duration)) => {
+            if trigger.status.as_ref().map(|s| s.phase) != Some(TimerTriggerPhase::Pending) {
+                ctx.update_timer_trigger(
+                    &trigger,
+                    TimerTriggerPhase::Pending,
+                    Reason::WindowNotOpen,
+                    "Waiting for scheduled time",
+                )
+                .await?;
+            }
+            Ok(ctx.requeue(trigger.as_ref(), duration.to_std().unwrap()))
+        }
+        Err(Error::Expired(_)) => {
+            if trigger.status.as_ref().map(|s| s.phase) != Some(TimerTriggerPhase::Completed) {
+                ctx.update_timer_trigger(
+                    &trigger,
+                    TimerTriggerPhase::Completed,
+                    Reason::Completed,
+                    "Schedule has completed",
+                )
+                .await?;
+            }
+            Ok(Action::await_change())
+        }
+        Err(e @ (Error::Http(_) | Error::Condition(_))) => {
+            warn!(name, namespace, error = ?e, "Webhook delivery or condition evaluation failed");
+            ctx.update_timer_trigger(
+                &trigger,
+                TimerTriggerPhase::Failed,
+                e.reason(),
+                e.to_string().as_str(),
+            )
+            .await?;
+            Ok(ctx.requeue(trigger.as_ref(), Duration::from_secs(60)))
+        }
+        Err(e) => {
+            if let Some(suppressed) = ctx.log_throttle().check(trigger.as_ref()) {
+                error!(name, namespace, suppressed, error = ?e, "Error in reconciliation");
+            }
+            Err(e)
+        }
+    }
+}
+
+async fn implement(trigger: &TimerTrigger, ctx: Arc<Context>) -> Result<Action, Error> {
+    let name = trigger.name_any();
+    let namespace = trigger.namespace().unwrap_or_default();
+
+    if trigger.status.as_ref().map(|s| s.phase) == Some(TimerTriggerPhase::Completed) {
+        return Ok(Action::await_change());
+    }
+
+    let schedule = trigger.schedule()?;
+    let now = Local::now();
+    trigger.window().check(now)?;
+
+    let since = trigger
+        .last_schedule_time()
+        .or_else(|| {
+            trigger
+                .creation_timestamp()
+                .map(|t| t.0.with_timezone(&Local))
+        })
+        .unwrap_or(now);
+
+    let tick = schedule.tick(since, now);
+    let skipped = match (&tick, &trigger.spec.condition) {
+        (Tick::Fire { .. }, Some(condition)) => {
+            let (allowed, value) = ctx.evaluate_fire_condition(&namespace, condition).await?;
+            (!allowed).then(|| condition.describe(value))
+        }
+        _ => None,
+    };
+    if let Some(observed) = skipped {
+        let message = format!("Skipped fire: condition {} does not hold", observed);
+        info!(name, namespace, observed, "Skipping timer trigger");
+        ctx.create_event(trigger, Reason::SkippedByCondition, &message)
+            .await?;
+        ctx.update_timer_trigger_status(
+            trigger,
+            TimerTriggerPhase::Running,
+            &message,
+            Some(Time(now.to_utc())),
+        )
+        .await?;
+    } else if let Tick::Fire { scheduled, .. } = tick {
+        info!(name, namespace, %scheduled, "Firing timer trigger");
+        if let Some(webhook) = &trigger.spec.webhook {
+            let payload = TimerTriggerPayload {
+                name: name.clone(),
+                namespace: namespace.clone(),
+                schedule: trigger.spec.schedule.clone(),
+                scheduled_time: scheduled,
+                fired_time: now,
+            };
+            ctx.send_webhook(webhook, &payload).await?;
+        }
+        let message = format!("Fired for {}", scheduled.to_rfc3339());
+        if trigger.spec.emit_event {
+            ctx.create_event(trigger, Reason::Fired, &message).await?;
+        }
+        ctx.update_timer_trigger_status(
+            trigger,
+            TimerTriggerPhase::Running,
+            &message,
+            Some(Time(now.to_utc())),
+        )
+        .await?;
+    } else if trigger.status.as_ref().map(|s| s.phase) != Some(TimerTriggerPhase::Running) {
+        ctx.update_timer_trigger(
+            trigger,
+            TimerTriggerPhase::Running,
+            Reason::Activated,
+            "Waiting for next scheduled time",
+        )
+        .await?;
+    }
+
+    let Some(wake) = tick.wake_at(&trigger.window()) else {
+        return Ok(Action::await_change());
+    };
+    let wait_for = (wake - Local::now())
+        .to_std()
+        .unwrap_or(Duration::from_secs(1));
+    debug!(
+        name,
+        namespace,
+        ?wait_for,
+        "Requeue until next scheduled time"
+    );
+    Ok(ctx.requeue(trigger, wait_for))
+}
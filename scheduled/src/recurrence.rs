@@ -0,0 +1,312 @@
+use chrono::{DateTime, Datelike as _, Days, Months, NaiveDate, TimeZone, Timelike as _, Weekday};
+
+/// How many years ahead occurrences are searched. Rules such as the 29th of
+/// February falling on a Monday repeat only every 28 years.
+const HORIZON_YEARS: i32 = 30;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// A parsed RFC 5545 recurrence rule, such as
+/// `FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=-1;BYHOUR=18` for 18:00 on
+/// the last weekday of each month.
+///
+/// `FREQ`, `BYMONTH`, `BYMONTHDAY`, `BYDAY` (with ordinals for monthly and
+/// yearly rules), `BYSETPOS`, `BYHOUR` and `BYMINUTE` are supported, and
+/// `INTERVAL=1` and `WKST` are accepted. There is no `DTSTART`, so rules must
+/// name their days and fire at minute 0 of hour 0 unless `BYHOUR` and
+/// `BYMINUTE` say otherwise; `BYSETPOS` selects among the days of each
+/// period. `COUNT`, `UNTIL` and the other parts are rejected; the window of
+/// the resource bounds it instead.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecurrenceRule {
+    frequency: Frequency,
+    months: Vec<u32>,
+    month_days: Vec<i32>,
+    week_days: Vec<(Option<i32>, Weekday)>,
+    set_positions: Vec<i32>,
+    hours: Vec<u32>,
+    minutes: Vec<u32>,
+}
+
+impl RecurrenceRule {
+    pub fn parse(expression: &str) -> Result<Self, crate::Error> {
+        let invalid = |reason: String| {
+            crate::Error::InvalidSchedule(format!("invalid recurrence rule {expression}: {reason}"))
+        };
+        let body = expression.trim();
+        let body = body.strip_prefix("RRULE:").unwrap_or(body);
+        let mut frequency = None;
+        let mut rule = Self {
+            frequency: Frequency::Daily,
+            months: Vec::new(),
+            month_days: Vec::new(),
+            week_days: Vec::new(),
+            set_positions: Vec::new(),
+            hours: vec![0],
+            minutes: vec![0],
+        };
+        for part in body.split(';').filter(|p| !p.is_empty()) {
+            let Some((key, value)) = part.split_once('=') else {
+                return Err(invalid(format!("expected KEY=VALUE, got {part}")));
+            };
+            match key {
+                "FREQ" => {
+                    frequency = Some(match value {
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        "MONTHLY" => Frequency::Monthly,
+                        "YEARLY" => Frequency::Yearly,
+                        other => return Err(invalid(format!("unsupported FREQ {other}"))),
+                    })
+                }
+                "INTERVAL" if value == "1" => {}
+                "WKST" => {}
+                "BYMONTH" => rule.months = numbers(value, 1, 12).map_err(invalid)?,
+                "BYMONTHDAY" => rule.month_days = signed(value, 31).map_err(invalid)?,
+                "BYSETPOS" => rule.set_positions = signed(value, 366).map_err(invalid)?,
+                "BYHOUR" => rule.hours = numbers(value, 0, 23).map_err(invalid)?,
+                "BYMINUTE" => rule.minutes = numbers(value, 0, 59).map_err(invalid)?,
+                "BYDAY" => {
+                    rule.week_days = value
+                        .split(',')
+                        .map(week_day)
+                        .collect::<Option<_>>()
+                        .ok_or_else(|| invalid(format!("invalid BYDAY {value}")))?
+                }
+                other => return Err(invalid(format!("{other} is not supported"))),
+            }
+        }
+        rule.frequency = frequency.ok_or_else(|| invalid("FREQ is required".to_string()))?;
+
+        let ordinals = rule.week_days.iter().any(|(n, _)| n.is_some());
+        let named_days = !rule.week_days.is_empty() || !rule.month_days.is_empty();
+        match rule.frequency {
+            Frequency::Daily | Frequency::Weekly if ordinals => {
+                return Err(invalid(
+                    "BYDAY ordinals need a monthly or yearly FREQ".to_string(),
+                ));
+            }
+            Frequency::Weekly if rule.week_days.is_empty() => {
+                return Err(invalid("weekly rules need BYDAY".to_string()));
+            }
+            Frequency::Monthly | Frequency::Yearly if !named_days => {
+                return Err(invalid("BYDAY or BYMONTHDAY is required".to_string()));
+            }
+            _ => {}
+        }
+        Ok(rule)
+    }
+
+    /// The first occurrence strictly after `after`, in its time zone.
+    /// Occurrences falling into a daylight saving gap are skipped.
+    pub fn next_after<Tz: TimeZone>(&self, after: &DateTime<Tz>) -> Option<DateTime<Tz>> {
+        let tz = after.timezone();
+        let horizon = after.year() + HORIZON_YEARS;
+        let mut period = self.period_start(after.date_naive());
+        while period.year() <= horizon {
+            let next = self.next_period(period)?;
+            for day in self.days(period, next) {
+                for &hour in &self.hours {
+                    for &minute in &self.minutes {
+                        let Some(occurrence) = day
+                            .and_hms_opt(hour, minute, 0)
+                            .and_then(|t| tz.from_local_datetime(&t).earliest())
+                        else {
+                            continue;
+                        };
+                        if occurrence > *after {
+                            return Some(occurrence);
+                        }
+                    }
+                }
+            }
+            period = next;
+        }
+        None
+    }
+
+    /// The rule as a five-field cron expression, when cron can express it:
+    /// without `BYSETPOS`, BYDAY ordinals or negative month days, and not
+    /// restricting both the day of the month and of the week, which cron
+    /// would OR rather than AND.
+    pub fn to_cron(&self) -> Option<String> {
+        if !self.set_positions.is_empty()
+            || self.week_days.iter().any(|(n, _)| n.is_some())
+            || self.month_days.iter().any(|d| *d < 0)
+            || (!self.month_days.is_empty() && !self.week_days.is_empty())
+        {
+            return None;
+        }
+        let list = |values: Vec<String>| {
+            if values.is_empty() {
+                "*".to_string()
+            } else {
+                values.join(",")
+            }
+        };
+        Some(format!(
+            "{} {} {} {} {}",
+            list(self.minutes.iter().map(u32::to_string).collect()),
+            list(self.hours.iter().map(u32::to_string).collect()),
+            list(self.month_days.iter().map(i32::to_string).collect()),
+            list(self.months.iter().map(u32::to_string).collect()),
+            list(
+                self.week_days
+                    .iter()
+                    .map(|(_, d)| d.num_days_from_sunday().to_string())
+                    .collect()
+            ),
+        ))
+    }
+
+    fn period_start(&self, date: NaiveDate) -> NaiveDate {
+        match self.frequency {
+            Frequency::Daily => date,
+            Frequency::Weekly => date - Days::new(date.weekday().num_days_from_monday().into()),
+            Frequency::Monthly => date.with_day(1).unwrap_or(date),
+            Frequency::Yearly => date.with_ordinal(1).unwrap_or(date),
+        }
+    }
+
+    fn next_period(&self, start: NaiveDate) -> Option<NaiveDate> {
+        match self.frequency {
+            Frequency::Daily => start.checked_add_days(Days::new(1)),
+            Frequency::Weekly => start.checked_add_days(Days::new(7)),
+            Frequency::Monthly => start.checked_add_months(Months::new(1)),
+            Frequency::Yearly => start.checked_add_months(Months::new(12)),
+        }
+    }
+
+    /// The days from `start` up to `end` matching the rule, narrowed down
+    /// by `BYSETPOS`.
+    fn days(&self, start: NaiveDate, end: NaiveDate) -> Vec<NaiveDate> {
+        let days: Vec<_> = start
+            .iter_days()
+            .take_while(|d| *d < end)
+            .filter(|d| self.matches(*d))
+            .collect();
+        if self.set_positions.is_empty() {
+            return days;
+        }
+        let mut selected: Vec<_> = self
+            .set_positions
+            .iter()
+            .filter_map(|&p| position(days.len(), p).map(|i| days[i]))
+            .collect();
+        selected.sort();
+        selected.dedup();
+        selected
+    }
+
+    fn matches(&self, day: NaiveDate) -> bool {
+        if !self.months.is_empty() && !self.months.contains(&day.month()) {
+            return false;
+        }
+        let last_day = last_day_of_month(day);
+        if !self.month_days.is_empty()
+            && !self.month_days.iter().any(|&d| {
+                let d = if d < 0 { last_day as i32 + d + 1 } else { d };
+                d == day.day() as i32
+            })
+        {
+            return false;
+        }
+        if self.week_days.is_empty() {
+            return true;
+        }
+        // Ordinals count within the year only for yearly rules without
+        // BYMONTH.
+        let (first, last) = if self.frequency == Frequency::Yearly && self.months.is_empty() {
+            (
+                day.with_ordinal(1).unwrap_or(day),
+                NaiveDate::from_ymd_opt(day.year(), 12, 31).unwrap_or(day),
+            )
+        } else {
+            (
+                day.with_day(1).unwrap_or(day),
+                day.with_day(last_day).unwrap_or(day),
+            )
+        };
+        let from_start = (day - first).num_days() as i32 / 7 + 1;
+        let from_end = -((last - day).num_days() as i32 / 7 + 1);
+        self.week_days.iter().any(|&(n, weekday)| {
+            day.weekday() == weekday && n.is_none_or(|n| n == from_start || n == from_end)
+        })
+    }
+}
+
+/// A cron expression firing only at `at`, and again a year later unless it
+/// is rewritten before.
+pub fn one_shot<Tz: TimeZone>(at: &DateTime<Tz>) -> String {
+    format!(
+        "{} {} {} {} *",
+        at.minute(),
+        at.hour(),
+        at.day(),
+        at.month()
+    )
+}
+
+fn last_day_of_month(day: NaiveDate) -> u32 {
+    day.with_day(1)
+        .and_then(|d| d.checked_add_months(Months::new(1)))
+        .and_then(|d| d.pred_opt())
+        .map_or(31, |d| d.day())
+}
+
+/// The index of the 1-based, or from the end negative, `position` among
+/// `len` items.
+fn position(len: usize, position: i32) -> Option<usize> {
+    let len = len as i32;
+    let index = if position > 0 {
+        position - 1
+    } else {
+        len + position
+    };
+    (0..len).contains(&index).then_some(index as usize)
+}
+
+fn numbers(value: &str, min: u32, max: u32) -> Result<Vec<u32>, String> {
+    let mut numbers = value
+        .split(',')
+        .map(|n| n.parse::<u32>().ok().filter(|n| (min..=max).contains(n)))
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(|| format!("{value} is not a list of numbers from {min} to {max}"))?;
+    numbers.sort();
+    numbers.dedup();
+    Ok(numbers)
+}
+
+fn signed(value: &str, max: i32) -> Result<Vec<i32>, String> {
+    value
+        .split(',')
+        .map(|n| n.parse::<i32>().ok().filter(|n| *n != 0 && n.abs() <= max))
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(|| format!("{value} is not a list of non-zero numbers within ±{max}"))
+}
+
+fn week_day(value: &str) -> Option<(Option<i32>, Weekday)> {
+    let split = value.len().checked_sub(2)?;
+    let (ordinal, day) = value.split_at_checked(split)?;
+    let day = match day {
+        "MO" => Weekday::Mon,
+        "TU" => Weekday::Tue,
+        "WE" => Weekday::Wed,
+        "TH" => Weekday::Thu,
+        "FR" => Weekday::Fri,
+        "SA" => Weekday::Sat,
+        "SU" => Weekday::Sun,
+        _ => return None,
+    };
+    let ordinal = match ordinal {
+        "" => None,
+        n => Some(n.parse::<i32>().ok().filter(|n| *n != 0 && n.abs() <= 53)?),
+    };
+    Some((ordinal, day))
+}
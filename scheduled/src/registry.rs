@@ -0,0 +1,62 @@
+//! Rewrites the images of child pods to mirrors, for clusters that cannot
+//! pull from public registries.
+//!
+//! Rules map a registry, optionally followed by a path, to its mirror, e.g.
+//! `docker.io=internal.registry/dockerhub`. Images are matched in their full
+//! form, so `busybox:1.36` is `docker.io/library/busybox:1.36`, and the
+//! longest matching rule wins.
+
+use std::collections::BTreeMap;
+
+use k8s_openapi::api::core::v1::PodSpec;
+
+/// Registry of images that do not name one.
+const DEFAULT_REGISTRY: &str = "docker.io";
+
+/// The `image` with its registry spelled out: `busybox` becomes
+/// `docker.io/library/busybox`. The first component names a registry when it
+/// has a `.` or `:`, or is `localhost`.
+pub fn qualify(image: &str) -> String {
+    match image.split_once('/') {
+        Some((host, _)) if host.contains(['.', ':']) || host == "localhost" => image.to_string(),
+        Some(_) => format!("{DEFAULT_REGISTRY}/{image}"),
+        None => format!("{DEFAULT_REGISTRY}/library/{image}"),
+    }
+}
+
+/// The mirror of `image` under `rules`, `None` when no rule matches.
+pub fn rewrite(image: &str, rules: &BTreeMap<String, String>) -> Option<String> {
+    let image = qualify(image);
+    let (prefix, mirror) = rules
+        .iter()
+        .map(|(prefix, mirror)| (prefix.trim_end_matches('/'), mirror))
+        .filter(|(prefix, _)| {
+            image
+                .strip_prefix(prefix)
+                .is_some_and(|rest| rest.starts_with(['/', ':', '@']))
+        })
+        .max_by_key(|(prefix, _)| prefix.len())?;
+    Some(format!(
+        "{}{}",
+        mirror.trim_end_matches('/'),
+        &image[prefix.len()..]
+    ))
+}
+
+/// Rewrites the images of the containers and init containers of `pod`.
+pub fn apply(pod: &mut PodSpec, rules: &BTreeMap<String, String>) {
+    if rules.is_empty() {
+        return;
+    }
+    let containers = pod
+        .containers
+        .iter_mut()
+        .chain(pod.init_containers.iter_mut().flatten());
+    for container in containers {
+        if let Some(image) = &container.image
+            && let Some(mirror) = rewrite(image, rules)
+        {
+            container.image = Some(mirror);
+        }
+    }
+}
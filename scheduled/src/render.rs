@@ -0,0 +1,95 @@
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use k8s_openapi::api::batch::v1::CronJob;
+use kube::ResourceExt as _;
+
+use crate::ScheduledCronJob;
+
+/// Entries are only pruned once the cache holds more than this many
+/// resources.
+const PRUNE_THRESHOLD: usize = 1024;
+
+/// Entries not used for this long are pruned.
+const PRUNE_AFTER: Duration = Duration::from_secs(3600);
+
+/// What the children of a ScheduledCronJob are rendered from: its spec, by
+/// generation, the labels and annotations copied to the children, which do
+/// not bump the generation, and the base schedule, which moves with the next
+/// occurrence of a `recurrenceRule` cron cannot express.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Key {
+    generation: i64,
+    labels: u64,
+    annotations: u64,
+    schedule: String,
+}
+
+struct Entry {
+    key: Key,
+    children: Vec<CronJob>,
+    used: Instant,
+}
+
+/// Child CronJobs rendered by [`ScheduledCronJob::cronjobs_at`], by UID, so
+/// reconciliations of an unchanged resource skip rendering.
+#[derive(Default)]
+pub struct RenderCache {
+    entries: Mutex<HashMap<String, Entry>>,
+}
+
+impl RenderCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The children of `job` as of `now`, rendered again only when its key
+    /// changed. Resources without a UID or generation are not cached.
+    /// Returns whether the children came from the cache.
+    pub fn cronjobs(
+        &self,
+        job: &ScheduledCronJob,
+        now: DateTime<Utc>,
+    ) -> Result<(Vec<CronJob>, bool), crate::Error> {
+        let (Some(uid), Some(generation)) = (job.uid(), job.metadata.generation) else {
+            return Ok((job.cronjobs_at(now)?, false));
+        };
+        let key = Key {
+            generation,
+            labels: hash(job.labels()),
+            annotations: hash(job.annotations()),
+            schedule: job.base_schedule(now)?,
+        };
+
+        let used = Instant::now();
+        let mut entries = self.entries.lock().unwrap();
+        if let Some(entry) = entries.get_mut(&uid)
+            && entry.key == key
+        {
+            entry.used = used;
+            return Ok((entry.children.clone(), true));
+        }
+        let children = job.cronjobs_at(now)?;
+        if entries.len() > PRUNE_THRESHOLD {
+            entries.retain(|_, e| used.duration_since(e.used) < PRUNE_AFTER);
+        }
+        entries.insert(
+            uid,
+            Entry {
+                key,
+                children: children.clone(),
+                used,
+            },
+        );
+        Ok((children, false))
+    }
+}
+
+fn hash(value: &impl Hash) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
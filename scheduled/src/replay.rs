@@ -0,0 +1,257 @@
+use std::path::Path;
+
+use chrono::{DateTime, Local, Utc};
+use k8s_openapi::api::batch::v1::CronJob;
+use kube::ResourceExt as _;
+use serde::{Deserialize, Serialize};
+
+use crate::Context;
+use crate::crd::{
+    BLACKOUT_SUSPENDED_ANNOTATION, SPEC_SUSPENDED_ANNOTATION, ScheduleCalendar, ScheduledCronJob,
+    ScheduledCronJobPhase,
+};
+use crate::reconciler::scheduled_cronjob::{
+    driven, needs_suspend_patch, next_schedule_time, requeue_after, settled_phase,
+};
+
+/// The inputs of a ScheduledCronJob reconciliation: the resource, what it
+/// observed and its clock. Written to `REPLAY_DIR` when a reconciliation
+/// fails and read back by [`decide`].
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Recording {
+    /// When the reconciliation started.
+    pub now: DateTime<Utc>,
+    /// The error it failed with.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub resource: ScheduledCronJob,
+    /// The child CronJobs that exist, as read after the failure.
+    #[serde(default)]
+    pub children: Vec<CronJob>,
+    #[serde(default)]
+    pub calendars: Vec<ScheduleCalendar>,
+}
+
+/// What the controller decides for a [`Recording`].
+#[derive(Serialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Decision {
+    /// Why the reconciliation stops before managing the children, with the
+    /// reason of the event it emits.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stopped: Option<Stop>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<ChildDecision>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phase: Option<ScheduledCronJobPhase>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_schedule_time: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub requeue_after_seconds: Option<u64>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Stop {
+    pub reason: String,
+    pub message: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ChildDecision {
+    pub name: String,
+    /// Writes to the child, in order. Empty when it is left as is.
+    pub actions: Vec<ChildAction>,
+    pub suspended: bool,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChildAction {
+    Create,
+    Suspend,
+    Resume,
+}
+
+/// Repeats the decisions of a ScheduledCronJob reconciliation on `recording`
+/// without a cluster: validation, the time window, `maxExecutions`, which
+/// children are created, suspended or resumed, the resulting phase and when
+/// it is requeued. Steps that read Jobs, such as run verification, deadline
+/// enforcement and adaptive scheduling, are not replayed.
+pub fn decide(recording: &Recording) -> Decision {
+    let job = &recording.resource;
+    let now = recording.now;
+    let mut decision = Decision::default();
+    let stop = |reason: &str, message: String| {
+        Some(Stop {
+            reason: reason.to_string(),
+            message,
+        })
+    };
+
+    if !job.can_run() {
+        let phase = job.status.as_ref().map(|s| s.phase).unwrap_or_default();
+        decision.stopped = stop("Terminal", format!("phase {phase} is terminal"));
+        return decision;
+    }
+    let validated = job
+        .validate_cronjob()
+        .and_then(|()| job.validate_variants())
+        .and_then(|()| job.window().check(now.with_timezone(&Local)));
+    if let Err(e) = validated {
+        decision.stopped = stop(e.reason().as_str(), e.to_string());
+        return decision;
+    }
+    if let Some(max) = job.spec.max_executions
+        && job.executions_exhausted()
+    {
+        decision.phase = Some(ScheduledCronJobPhase::Completed);
+        decision.message = Some(format!("Reached maxExecutions of {max}"));
+        return decision;
+    }
+    if job.status.as_ref().map(|s| s.phase) == Some(ScheduledCronJobPhase::Expired) {
+        decision.stopped = stop("Expired", "phase Expired only ends children".to_string());
+        return decision;
+    }
+    let desired = match job.cronjobs_at(now) {
+        Ok(desired) => desired,
+        Err(e) => {
+            decision.stopped = stop(e.reason().as_str(), e.to_string());
+            return decision;
+        }
+    };
+
+    let blackout = job.blackout_until(now, &recording.calendars);
+    let mut cronjobs = Vec::new();
+    let mut suspended = true;
+    for desired in desired {
+        let name = desired.name_any();
+        let driven = driven(&desired).is_some();
+        let mut actions = Vec::new();
+        let mut cronjob = match recording.children.iter().find(|c| c.name_any() == name) {
+            Some(child) => child.clone(),
+            None => {
+                actions.push(ChildAction::Create);
+                desired
+            }
+        };
+        if !driven && needs_suspend_patch(&cronjob, SPEC_SUSPENDED_ANNOTATION, job.spec.suspend) {
+            actions.push(set_suspend(
+                &mut cronjob,
+                SPEC_SUSPENDED_ANNOTATION,
+                job.spec.suspend,
+            ));
+        }
+        if !driven
+            && !job.spec.suspend
+            && needs_suspend_patch(&cronjob, BLACKOUT_SUSPENDED_ANNOTATION, blackout.is_some())
+        {
+            actions.push(set_suspend(
+                &mut cronjob,
+                BLACKOUT_SUSPENDED_ANNOTATION,
+                blackout.is_some(),
+            ));
+        }
+        let child_suspended = if driven {
+            job.spec.suspend || blackout.is_some()
+        } else {
+            cronjob
+                .spec
+                .as_ref()
+                .and_then(|s| s.suspend)
+                .unwrap_or(false)
+        };
+        suspended &= child_suspended;
+        decision.children.push(ChildDecision {
+            name,
+            actions,
+            suspended: child_suspended,
+        });
+        cronjobs.push(cronjob);
+    }
+
+    let (phase, _, message) = settled_phase(job, suspended, blackout);
+    decision.phase = Some(phase);
+    decision.message = Some(message);
+    decision.next_schedule_time = next_schedule_time(&cronjobs, now);
+    decision.requeue_after_seconds =
+        Some(requeue_after(job, &recording.calendars, blackout, None, now).as_secs());
+    decision
+}
+
+/// Applies the patch of `apply_suspend` or `apply_blackout` to `cronjob`.
+fn set_suspend(cronjob: &mut CronJob, annotation: &str, suspend: bool) -> ChildAction {
+    if let Some(spec) = cronjob.spec.as_mut() {
+        spec.suspend = Some(suspend);
+    }
+    if suspend {
+        cronjob
+            .annotations_mut()
+            .insert(annotation.to_string(), "true".to_string());
+        ChildAction::Suspend
+    } else {
+        cronjob.annotations_mut().remove(annotation);
+        ChildAction::Resume
+    }
+}
+
+/// A [`Recording`] of `job` at `now`, with the children and calendars that
+/// can be read.
+pub async fn capture(ctx: &Context, job: &ScheduledCronJob, now: DateTime<Utc>) -> Recording {
+    let namespace = job.namespace().unwrap_or_default();
+    let mut children = Vec::new();
+    for child in job.child_names() {
+        if let Ok(cronjob) = ctx.get::<CronJob>(&namespace, &child).await {
+            children.push(cronjob);
+        }
+    }
+    let mut calendars = Vec::new();
+    for calendar in &job.spec.calendars {
+        if let Ok(calendar) = ctx.calendar(&namespace, calendar).await {
+            calendars.push(calendar);
+        }
+    }
+    Recording {
+        now,
+        error: None,
+        resource: job.clone(),
+        children,
+        calendars,
+    }
+}
+
+/// Writes a [`Recording`] of the reconciliation of `job` started at `now`
+/// that failed with `error` to `REPLAY_DIR`, replacing the previous one of
+/// `job`. Does nothing while `REPLAY_DIR` is unset; failing to record is
+/// logged.
+pub async fn record(
+    ctx: &Context,
+    job: &ScheduledCronJob,
+    now: DateTime<Utc>,
+    error: &crate::Error,
+) {
+    let Some(dir) = ctx.config().replay_dir.clone() else {
+        return;
+    };
+    let namespace = job.namespace().unwrap_or_default();
+    let name = job.name_any();
+    let recording = Recording {
+        error: Some(error.to_string()),
+        ..capture(ctx, job, now).await
+    };
+
+    let path = Path::new(&dir).join(format!("{namespace}.{name}.json"));
+    let written = match serde_json::to_vec_pretty(&recording) {
+        Ok(json) => tokio::fs::write(&path, json).await,
+        Err(e) => Err(e.into()),
+    };
+    match written {
+        Ok(()) => {
+            tracing::info!(name, namespace, path = %path.display(), "Recorded failed reconciliation")
+        }
+        Err(e) => tracing::warn!(name, namespace, error = ?e, "Failed to record reconciliation"),
+    }
+}
@@ -0,0 +1,340 @@
+use std::collections::BTreeMap;
+use std::pin::pin;
+use std::sync::Arc;
+
+use futures::TryStreamExt as _;
+use k8s_openapi::api::batch::v1::CronJob;
+use k8s_openapi::apimachinery::pkg::api::resource::Quantity;
+use kube::ResourceExt as _;
+use kube::api::Patch;
+use kube::core::object::HasStatus as _;
+
+use crate::Context;
+use crate::crd::{
+    ResourceRecommendation, SCHEDULE_LABEL, ScheduledCronJob, apply_recommendations, parse_quantity,
+};
+use crate::reason::Reason;
+
+/// Peak usage is scaled by this before being recommended as requests.
+const HEADROOM: f64 = 1.2;
+
+/// How far back Prometheus is asked for peak usage.
+const USAGE_WINDOW: &str = "7d";
+
+const MIN_CPU_MILLIS: f64 = 10.0;
+
+const MIN_MEMORY_MIB: f64 = 16.0;
+
+/// Where container usage is read from, parsed from `USAGE_SOURCE`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UsageSource {
+    /// The `metrics.k8s.io` API, which only reports pods that are running.
+    /// Each check folds the current usage into the peaks already recorded.
+    MetricsServer,
+    /// Base URL of a Prometheus server scraping cAdvisor metrics, asked for
+    /// the peaks over the last week.
+    Prometheus(String),
+}
+
+impl UsageSource {
+    pub fn parse(source: &str) -> Self {
+        match source {
+            "metrics-server" => UsageSource::MetricsServer,
+            url => UsageSource::Prometheus(url.trim_end_matches('/').to_string()),
+        }
+    }
+}
+
+/// Peak CPU in cores and memory in bytes of one container.
+#[derive(Debug, Default, Clone, Copy)]
+struct Peak {
+    cpu: Option<f64>,
+    memory: Option<f64>,
+}
+
+/// Periodically records right-sizing recommendations for every
+/// ScheduledCronJob from the usage of its recent runs and, with
+/// `spec.autoResize`, applies them to the child CronJobs. Disabled while
+/// `USAGE_SOURCE` is unset; checks only run on the leader and are skipped
+/// while the emergency stop is engaged.
+pub async fn run(ctx: Arc<Context>) {
+    if let Some(source) = ctx.config().usage_source.as_deref() {
+        let source = UsageSource::parse(source);
+        let mut interval = tokio::time::interval(ctx.config().resize_interval);
+        loop {
+            interval.tick().await;
+            if !ctx.leadership().is_leader() || ctx.emergency_stop().engaged() {
+                continue;
+            }
+            if let Err(e) = resize_scheduled_cronjobs(&ctx, &source).await {
+                tracing::warn!(error = ?e, "Failed to right-size scheduled cronjobs");
+            }
+        }
+    }
+    futures::future::pending().await
+}
+
+async fn resize_scheduled_cronjobs(
+    ctx: &Context,
+    source: &UsageSource,
+) -> Result<(), crate::Error> {
+    let mut resources = pin!(ctx.list_all_stream::<ScheduledCronJob>());
+    while let Some(resource) = resources.try_next().await? {
+        if let Err(e) = resize(ctx, source, &resource).await {
+            tracing::warn!(
+                name = resource.name_any(),
+                namespace = resource.namespace(),
+                error = ?e,
+                "Failed to right-size scheduled cronjob"
+            );
+        }
+    }
+    Ok(())
+}
+
+async fn resize(
+    ctx: &Context,
+    source: &UsageSource,
+    job: &ScheduledCronJob,
+) -> Result<(), crate::Error> {
+    let namespace = job.namespace().unwrap_or_default();
+    let name = job.name_any();
+    let previous = job
+        .status()
+        .map(|s| s.recommendations.clone())
+        .unwrap_or_default();
+    let peaks = match source {
+        UsageSource::Prometheus(url) => query_prometheus(ctx, url, job).await?,
+        UsageSource::MetricsServer => query_metrics_server(ctx, job, &previous).await?,
+    };
+    if peaks.is_empty() {
+        return Ok(());
+    }
+
+    let recommendations: Vec<_> = peaks
+        .into_iter()
+        .map(|(container, peak)| recommend(container, peak))
+        .collect();
+    for recommendation in &recommendations {
+        let gauge = &ctx.metrics().resource_recommendation;
+        let container = recommendation.container.as_str();
+        if let Some(cpu) = recommendation
+            .cpu
+            .as_ref()
+            .and_then(|q| parse_quantity(&q.0))
+        {
+            gauge
+                .with_label_values(&[&namespace, &name, container, "cpu"])
+                .set(cpu);
+        }
+        if let Some(memory) = recommendation
+            .memory
+            .as_ref()
+            .and_then(|q| parse_quantity(&q.0))
+        {
+            gauge
+                .with_label_values(&[&namespace, &name, container, "memory"])
+                .set(memory);
+        }
+    }
+    if recommendations == previous {
+        return Ok(());
+    }
+    ctx.update_scheduled_cronjob_recommendations(job, recommendations.clone())
+        .await?;
+
+    if job.spec.auto_resize {
+        for child in job.child_names() {
+            resize_child(ctx, &namespace, &child, &recommendations).await?;
+        }
+        let summary = recommendations
+            .iter()
+            .map(describe)
+            .collect::<Vec<_>>()
+            .join(", ");
+        ctx.create_event(
+            job,
+            Reason::Resized,
+            &format!("Resized requests: {summary}"),
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+/// Rewrites the pod template of the CronJob `name` with `recommendations`.
+/// Runs already started keep their requests.
+async fn resize_child(
+    ctx: &Context,
+    namespace: &str,
+    name: &str,
+    recommendations: &[ResourceRecommendation],
+) -> Result<(), crate::Error> {
+    let cronjob = match ctx
+        .get::<k8s_openapi::api::batch::v1::CronJob>(namespace, name)
+        .await
+    {
+        Ok(cronjob) => cronjob,
+        Err(crate::Error::NotFound) => return Ok(()),
+        Err(e) => return Err(e),
+    };
+    let Some(mut template) = cronjob.spec.map(|s| s.job_template) else {
+        return Ok(());
+    };
+    if let Some(pod) = template
+        .spec
+        .as_mut()
+        .and_then(|s| s.template.spec.as_mut())
+    {
+        apply_recommendations(pod, recommendations);
+    }
+    let patch = serde_json::json!({ "spec": { "jobTemplate": template } });
+    ctx.patch::<CronJob>(namespace, name, &Patch::Merge(patch))
+        .await
+}
+
+fn recommend(container: String, peak: Peak) -> ResourceRecommendation {
+    let cpu = peak.cpu.map(|cores| {
+        let millis = (cores * HEADROOM * 1000.0).ceil().max(MIN_CPU_MILLIS);
+        Quantity(format!("{millis}m"))
+    });
+    let memory = peak.memory.map(|bytes| {
+        let mib = (bytes * HEADROOM / (1024.0 * 1024.0))
+            .ceil()
+            .max(MIN_MEMORY_MIB);
+        Quantity(format!("{mib}Mi"))
+    });
+    ResourceRecommendation {
+        container,
+        peak_cpu: peak
+            .cpu
+            .map(|c| Quantity(format!("{}m", (c * 1000.0).ceil()))),
+        peak_memory: peak.memory.map(|m| Quantity(format!("{}", m.ceil()))),
+        cpu,
+        memory,
+    }
+}
+
+fn describe(recommendation: &ResourceRecommendation) -> String {
+    let quantity = |q: &Option<Quantity>| q.as_ref().map_or("-".to_string(), |q| q.0.clone());
+    format!(
+        "{} cpu={} memory={}",
+        recommendation.container,
+        quantity(&recommendation.cpu),
+        quantity(&recommendation.memory)
+    )
+}
+
+/// Peak usage per container of the pods started by the child CronJobs over
+/// [`USAGE_WINDOW`].
+async fn query_prometheus(
+    ctx: &Context,
+    url: &str,
+    job: &ScheduledCronJob,
+) -> Result<BTreeMap<String, Peak>, crate::Error> {
+    let namespace = job.namespace().unwrap_or_default();
+    // Pods of a CronJob are named `<cronjob>-<schedule>-<suffix>`.
+    let pods = job.child_names().join("|");
+    let selector =
+        format!(r#"namespace="{namespace}",pod=~"({pods})-.+",container!="",container!="POD""#);
+    let cpu = format!(
+        "max by (container) (max_over_time(rate(container_cpu_usage_seconds_total{{{selector}}}[5m])[{USAGE_WINDOW}:5m]))"
+    );
+    let memory = format!(
+        "max by (container) (max_over_time(container_memory_working_set_bytes{{{selector}}}[{USAGE_WINDOW}]))"
+    );
+
+    let mut peaks = BTreeMap::<String, Peak>::new();
+    for (container, value) in query_vector(ctx, url, &cpu).await? {
+        peaks.entry(container).or_default().cpu = Some(value);
+    }
+    for (container, value) in query_vector(ctx, url, &memory).await? {
+        peaks.entry(container).or_default().memory = Some(value);
+    }
+    Ok(peaks)
+}
+
+/// Runs an instant query, returning the value of each sample by its
+/// `container` label.
+async fn query_vector(
+    ctx: &Context,
+    url: &str,
+    query: &str,
+) -> Result<Vec<(String, f64)>, crate::Error> {
+    let response: serde_json::Value = ctx
+        .http()
+        .get(format!("{url}/api/v1/query"))
+        .query(&[("query", query)])
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    if response["data"]["resultType"].as_str() != Some("vector") {
+        return Err(crate::Error::Usage(format!(
+            "expected a vector result for {query}"
+        )));
+    }
+    Ok(response["data"]["result"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|sample| {
+            let container = sample["metric"]["container"].as_str()?;
+            let value = sample["value"][1].as_str()?.parse::<f64>().ok()?;
+            Some((container.to_string(), value))
+        })
+        .collect())
+}
+
+/// Current usage per container of the running pods, folded into the peaks of
+/// `previous`.
+async fn query_metrics_server(
+    ctx: &Context,
+    job: &ScheduledCronJob,
+    previous: &[ResourceRecommendation],
+) -> Result<BTreeMap<String, Peak>, crate::Error> {
+    let namespace = job.namespace().unwrap_or_default();
+    let label = job.schedule_label();
+    let path = format!(
+        "/apis/metrics.k8s.io/v1beta1/namespaces/{namespace}/pods?labelSelector={SCHEDULE_LABEL}%3D{label}"
+    );
+    let request = http::Request::get(&path)
+        .body(Vec::new())
+        .map_err(|e| crate::Error::Usage(e.to_string()))?;
+    let response: serde_json::Value = ctx.request(request).await?;
+
+    let mut peaks: BTreeMap<String, Peak> = previous
+        .iter()
+        .map(|r| {
+            let peak = Peak {
+                cpu: r.peak_cpu.as_ref().and_then(|q| parse_quantity(&q.0)),
+                memory: r.peak_memory.as_ref().and_then(|q| parse_quantity(&q.0)),
+            };
+            (r.container.clone(), peak)
+        })
+        .collect();
+    let containers = response["items"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .flat_map(|pod| pod["containers"].as_array().into_iter().flatten());
+    for container in containers {
+        let Some(name) = container["name"].as_str() else {
+            continue;
+        };
+        let usage = |resource: &str| {
+            container["usage"][resource]
+                .as_str()
+                .and_then(parse_quantity)
+        };
+        let peak = peaks.entry(name.to_string()).or_default();
+        if let Some(cpu) = usage("cpu") {
+            peak.cpu = Some(peak.cpu.map_or(cpu, |p| p.max(cpu)));
+        }
+        if let Some(memory) = usage("memory") {
+            peak.memory = Some(peak.memory.map_or(memory, |p| p.max(memory)));
+        }
+    }
+    Ok(peaks)
+}
@@ -0,0 +1,85 @@
+//! Labels joining the Jobs and pods of runs, and the logs and metrics
+//! collected from them, back to their ScheduledCronJob and occurrence.
+//!
+//! The schedule and variant labels are part of the child CronJobs' templates.
+//! The fire time is only known once a run starts, so the controller labels
+//! the Jobs it starts itself when creating them, and those the CronJob
+//! controller starts on its next reconciliation. Pods are joined to their
+//! run by the UID label Kubernetes sets on them.
+
+use chrono::{DateTime, Utc};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use kube::ResourceExt;
+use serde::Serialize;
+
+use crate::crd::{SCHEDULE_LABEL, VARIANT_LABEL};
+
+/// Label holding the logical fire time of a run Job, in Unix seconds, which
+/// may be earlier than its creation.
+pub const FIRE_TIME_LABEL: &str = "divinerapier.io/fire-time";
+
+/// Label Kubernetes sets on the pods of a Job to the Job's UID, from 1.27.
+pub const RUN_UID_LABEL: &str = "batch.kubernetes.io/controller-uid";
+
+/// Annotation the CronJob controller sets on the Jobs it starts to their
+/// scheduled time, from Kubernetes 1.28.
+const SCHEDULED_TIMESTAMP_ANNOTATION: &str = "batch.kubernetes.io/cronjob-scheduled-timestamp";
+
+/// One label of the scheme, see [`SCHEME`].
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct RunLabel {
+    pub key: &'static str,
+    /// The objects carrying it.
+    pub on: &'static [&'static str],
+    pub value: &'static str,
+}
+
+/// Every label of runs, for tooling building log and metric queries.
+pub const SCHEME: &[RunLabel] = &[
+    RunLabel {
+        key: SCHEDULE_LABEL,
+        on: &["Job", "Pod"],
+        value: "Name of the ScheduledCronJob, shortened to fit a label value",
+    },
+    RunLabel {
+        key: VARIANT_LABEL,
+        on: &["CronJob", "Job", "Pod"],
+        value: "Name of the variant, absent without variants",
+    },
+    RunLabel {
+        key: FIRE_TIME_LABEL,
+        on: &["Job"],
+        value: "Logical fire time in Unix seconds",
+    },
+    RunLabel {
+        key: RUN_UID_LABEL,
+        on: &["Pod"],
+        value: "UID of the run Job, set by Kubernetes",
+    },
+];
+
+/// Sets the fire time label of a run Job firing `at`.
+pub fn label(metadata: &mut ObjectMeta, at: DateTime<Utc>) {
+    metadata
+        .labels
+        .get_or_insert_default()
+        .insert(FIRE_TIME_LABEL.to_string(), at.timestamp().to_string());
+}
+
+/// When the run Job `run` of the CronJob `cronjob` was scheduled to fire:
+/// its scheduled timestamp, or the minutes the CronJob controller suffixes
+/// the names of its Jobs with on older releases.
+pub fn fire_time<K: ResourceExt>(run: &K, cronjob: &str) -> Option<DateTime<Utc>> {
+    if let Some(at) = run.annotations().get(SCHEDULED_TIMESTAMP_ANNOTATION) {
+        return DateTime::parse_from_rfc3339(at)
+            .ok()
+            .map(|at| at.with_timezone(&Utc));
+    }
+    let minutes: i64 = run
+        .name_any()
+        .strip_prefix(cronjob)?
+        .strip_prefix('-')?
+        .parse()
+        .ok()?;
+    DateTime::from_timestamp(minutes.checked_mul(60)?, 0)
+}
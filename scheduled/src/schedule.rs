@@ -0,0 +1,395 @@
+use chrono::{DateTime, Duration, Local, TimeZone};
+use croner::Cron;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
+
+use crate::crd::IntoTime as _;
+
+/// A parsed cron expression used to compute fire times.
+#[derive(Debug, Clone)]
+pub struct Schedule {
+    expression: String,
+    cron: Cron,
+}
+
+impl Schedule {
+    pub fn parse(expression: &str) -> Result<Self, crate::Error> {
+        let cron = Cron::new(expression)
+            .parse()
+            .map_err(|e| crate::Error::InvalidSchedule(format!("{}: {}", expression, e)))?;
+        Ok(Self {
+            expression: expression.to_string(),
+            cron,
+        })
+    }
+
+    /// Parses a six-field expression whose first field holds the seconds.
+    pub fn parse_with_seconds(expression: &str) -> Result<Self, crate::Error> {
+        let cron = Cron::new(expression)
+            .with_seconds_required()
+            .parse()
+            .map_err(|e| crate::Error::InvalidSchedule(format!("{}: {}", expression, e)))?;
+        Ok(Self {
+            expression: expression.to_string(),
+            cron,
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.expression
+    }
+
+    /// Returns the first fire time strictly after `after`.
+    pub fn next_after<Tz: TimeZone>(&self, after: &DateTime<Tz>) -> Option<DateTime<Tz>> {
+        self.cron.find_next_occurrence(after, false).ok()
+    }
+
+    /// Decides whether a resource that last fired at `since` has to fire at
+    /// `now`. Missed occurrences are collapsed into a single fire.
+    pub fn tick(&self, since: DateTime<Local>, now: DateTime<Local>) -> Tick {
+        match self.next_after(&since) {
+            Some(scheduled) if scheduled <= now => Tick::Fire {
+                scheduled,
+                next: self.next_after(&now),
+            },
+            Some(next) => Tick::Wait(next),
+            None => Tick::Never,
+        }
+    }
+}
+
+/// The outcome of [`Schedule::tick`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tick {
+    /// An occurrence at `scheduled` is due; `next` is the one after now.
+    Fire {
+        scheduled: DateTime<Local>,
+        next: Option<DateTime<Local>>,
+    },
+    /// Nothing is due until the given time.
+    Wait(DateTime<Local>),
+    /// The schedule never fires again.
+    Never,
+}
+
+impl Tick {
+    /// The time the caller should be woken up again, bounded by the window end.
+    pub fn wake_at(&self, window: &Window) -> Option<DateTime<Local>> {
+        let next = match self {
+            Tick::Fire { next, .. } => *next,
+            Tick::Wait(next) => Some(*next),
+            Tick::Never => None,
+        };
+        match (next, window.end) {
+            (Some(next), Some(end)) => Some(next.min(end)),
+            (next, end) => next.or(end),
+        }
+    }
+}
+
+/// The period during which a scheduled resource is allowed to act.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Window {
+    pub start: Option<DateTime<Local>>,
+    pub end: Option<DateTime<Local>>,
+}
+
+impl Window {
+    pub fn new(start: Option<DateTime<Local>>, end: Option<DateTime<Local>>) -> Self {
+        Self { start, end }
+    }
+
+    pub fn from_times(start: Option<&Time>, end: Option<&Time>) -> Self {
+        Self {
+            start: start.map(|t| t.0.with_timezone(&Local)),
+            end: end.map(|t| t.0.with_timezone(&Local)),
+        }
+    }
+
+    /// Parses `start=<time>,end=<time>`, either bound optional, with times in
+    /// RFC 3339.
+    pub fn parse(value: &str) -> Result<Self, crate::Error> {
+        let invalid = |reason: String| crate::Error::InvalidSchedule(format!("{value}: {reason}"));
+        let mut window = Self::new(None, None);
+        for field in value.split(',').map(str::trim).filter(|f| !f.is_empty()) {
+            let Some((key, time)) = field.split_once('=') else {
+                return Err(invalid(format!("expected key=value, got {field}")));
+            };
+            let time = time
+                .trim()
+                .into_time()
+                .map_err(|e| invalid(format!("{key}: {e}")))?
+                .map(|t| t.0.with_timezone(&Local));
+            match key.trim() {
+                "start" => window.start = time,
+                "end" => window.end = time,
+                other => return Err(invalid(format!("unknown key {other}"))),
+            }
+        }
+        Ok(window)
+    }
+
+    /// Checks the window against `now`, returning `WaitFor` before the start
+    /// and `Expired` after the end.
+    pub fn check(&self, now: DateTime<Local>) -> Result<(), crate::Error> {
+        if let (Some(start), Some(end)) = (self.start, self.end) {
+            if start >= end {
+                return Err(crate::Error::EndBeforeStart);
+            }
+            if end - start < Duration::minutes(5) {
+                return Err(crate::Error::DurationTooShort(start, end));
+            }
+        }
+
+        if let Some(start) = self.start
+            && now < start
+        {
+            return Err(crate::Error::WaitFor(start - now));
+        }
+
+        if let Some(end) = self.end
+            && now > end
+        {
+            return Err(crate::Error::Expired(end));
+        }
+
+        Ok(())
+    }
+}
+
+/// Rewrites the five-field cron `expression` to fire `minutes` later. Minute
+/// steps (`*/15`) are shifted within the step, minute lists carry into the
+/// hour field. Expressions whose shift cannot be written as a single cron
+/// expression are rejected.
+pub fn stagger(expression: &str, minutes: u32) -> Result<String, crate::Error> {
+    let unsupported = |reason: &str| {
+        crate::Error::InvalidSchedule(format!("cannot stagger {expression}: {reason}"))
+    };
+    if minutes == 0 {
+        return Ok(expression.to_string());
+    }
+    let expanded = match expression.trim() {
+        "@hourly" => "0 * * * *",
+        "@daily" | "@midnight" => "0 0 * * *",
+        "@weekly" => "0 0 * * 0",
+        "@monthly" => "0 0 1 * *",
+        "@yearly" | "@annually" => "0 0 1 1 *",
+        other => other,
+    };
+    let mut fields: Vec<String> = expanded.split_whitespace().map(String::from).collect();
+    if fields.len() != 5 {
+        return Err(unsupported("only five-field expressions are supported"));
+    }
+
+    if fields[0] == "*" {
+        return Ok(fields.join(" "));
+    }
+    if let Some(step) = fields[0].strip_prefix("*/") {
+        let step: u32 = step
+            .parse()
+            .map_err(|_| unsupported("unparsable minute step"))?;
+        if step == 0 {
+            return Err(unsupported("zero minute step"));
+        }
+        fields[0] = format!("{}-59/{step}", minutes % step);
+        return Ok(fields.join(" "));
+    }
+
+    let shifted = numbers(&fields[0])
+        .ok_or_else(|| unsupported("the minute field is neither a step nor a list"))?
+        .into_iter()
+        .map(|m| m + minutes)
+        .collect::<Vec<_>>();
+    let carry = shifted[0] / 60;
+    if shifted.iter().any(|m| m / 60 != carry) {
+        return Err(unsupported("minutes would carry into different hours"));
+    }
+    fields[0] = list(shifted.iter().map(|m| m % 60));
+    if carry == 0 || fields[1] == "*" {
+        return Ok(fields.join(" "));
+    }
+
+    let hours = numbers(&fields[1])
+        .ok_or_else(|| unsupported("the hour field is neither * nor a list"))?
+        .into_iter()
+        .map(|h| h + carry)
+        .collect::<Vec<_>>();
+    if hours.iter().any(|h| *h >= 24) && (fields[2] != "*" || fields[4] != "*") {
+        return Err(unsupported("hours would carry into the next day"));
+    }
+    fields[1] = list(hours.iter().map(|h| h % 24));
+    Ok(fields.join(" "))
+}
+
+/// The values of a comma-separated list of numbers.
+fn numbers(field: &str) -> Option<Vec<u32>> {
+    field.split(',').map(|n| n.parse().ok()).collect()
+}
+
+fn list(values: impl Iterator<Item = u32>) -> String {
+    let mut values: Vec<u32> = values.collect();
+    values.sort_unstable();
+    values.dedup();
+    values
+        .iter()
+        .map(u32::to_string)
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// A six-field cron expression whose first field holds the seconds, such as
+/// `*/30 * * * * *` for every half minute.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Seconds {
+    seconds: String,
+    minutes: String,
+}
+
+impl Seconds {
+    /// Parses `expression` if it has six fields, `None` otherwise.
+    pub fn parse(expression: &str) -> Result<Option<Self>, crate::Error> {
+        let fields: Vec<_> = expression.split_whitespace().collect();
+        let [seconds, minutes @ ..] = fields.as_slice() else {
+            return Ok(None);
+        };
+        if minutes.len() != 5 {
+            return Ok(None);
+        }
+        let parsed = Self {
+            seconds: seconds.to_string(),
+            minutes: minutes.join(" "),
+        };
+        parsed.schedule()?;
+        Ok(Some(parsed))
+    }
+
+    /// The five fields after the seconds, a cron expression firing in every
+    /// minute this one fires in.
+    pub fn minutes(&self) -> &str {
+        &self.minutes
+    }
+
+    /// Whether the expression only fires at second 0, so [`Seconds::minutes`]
+    /// fires at the same times and the child CronJobs can run it.
+    pub fn is_on_the_minute(&self) -> bool {
+        self.seconds == "0"
+    }
+
+    /// The expression with `minutes`, such as the staggered
+    /// [`Seconds::minutes`], as its last five fields.
+    pub fn with_minutes(&self, minutes: &str) -> String {
+        format!("{} {minutes}", self.seconds)
+    }
+
+    pub fn schedule(&self) -> Result<Schedule, crate::Error> {
+        Schedule::parse_with_seconds(&self.to_string())
+    }
+}
+
+impl std::fmt::Display for Seconds {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.with_minutes(&self.minutes))
+    }
+}
+
+/// Parses a duration written with `h`, `m` and `s` components, such as `90s`
+/// or `2h30m`.
+pub fn parse_duration(value: &str) -> Option<std::time::Duration> {
+    let value = value.trim();
+    let mut seconds = 0u64;
+    let mut digits = String::new();
+    for c in value.chars() {
+        if c.is_ascii_digit() {
+            digits.push(c);
+            continue;
+        }
+        let unit = match c {
+            'h' => 3600,
+            'm' => 60,
+            's' => 1,
+            _ => return None,
+        };
+        let count: u64 = digits.parse().ok()?;
+        seconds = count.checked_mul(unit)?.checked_add(seconds)?;
+        digits.clear();
+    }
+    if !digits.is_empty() || value.is_empty() {
+        return None;
+    }
+    Some(std::time::Duration::from_secs(seconds))
+}
+
+/// An interval schedule such as `@every 2h30m`, written with `h`, `m` and
+/// `s` components.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Every(std::time::Duration);
+
+impl Every {
+    /// Parses `expression` if it is an `@every` schedule, `None` otherwise.
+    pub fn parse(expression: &str) -> Result<Option<Self>, crate::Error> {
+        let Some(duration) = expression.trim().strip_prefix("@every") else {
+            return Ok(None);
+        };
+        match parse_duration(duration) {
+            Some(duration) if !duration.is_zero() => Ok(Some(Self(duration))),
+            _ => Err(crate::Error::InvalidSchedule(format!(
+                "{expression}: expected a duration such as 5m or 2h30m"
+            ))),
+        }
+    }
+
+    pub fn interval(&self) -> std::time::Duration {
+        self.0
+    }
+
+    /// Whether the interval is shorter than a minute, which cron cannot
+    /// express, so the controller starts the runs itself.
+    pub fn is_sub_minute(&self) -> bool {
+        self.0.as_secs() < 60
+    }
+
+    /// The cron expression whose interval is closest to this one, and that
+    /// interval. Minute and hour steps divide the hour and day evenly, so
+    /// intervals such as `@every 7m` or `@every 2h30m` are approximated.
+    /// Sub-minute intervals map to every minute.
+    pub fn to_cron(&self) -> (String, std::time::Duration) {
+        const HOUR: u64 = 60;
+        const DAY: u64 = 24 * HOUR;
+        let mut candidates = vec![("* * * * *".to_string(), 1)];
+        for step in [2, 3, 4, 5, 6, 10, 12, 15, 20, 30] {
+            candidates.push((format!("*/{step} * * * *"), step));
+        }
+        candidates.push(("0 * * * *".to_string(), HOUR));
+        for step in [2, 3, 4, 6, 8, 12] {
+            candidates.push((format!("0 */{step} * * *"), step * HOUR));
+        }
+        candidates.push(("0 0 * * *".to_string(), DAY));
+        for step in 2..=6 {
+            candidates.push((format!("0 0 */{step} * *"), step * DAY));
+        }
+        candidates.push(("0 0 * * 0".to_string(), 7 * DAY));
+        candidates.push(("0 0 1 * *".to_string(), 30 * DAY));
+
+        let minutes = (self.0.as_secs() + 30) / 60;
+        let (cron, interval) = candidates
+            .into_iter()
+            .min_by_key(|(_, interval)| interval.abs_diff(minutes))
+            .unwrap_or_else(|| ("* * * * *".to_string(), 1));
+        (cron, std::time::Duration::from_secs(interval * 60))
+    }
+}
+
+impl std::fmt::Display for Every {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let seconds = self.0.as_secs();
+        write!(f, "@every ")?;
+        let parts = [
+            (seconds / 3600, "h"),
+            (seconds / 60 % 60, "m"),
+            (seconds % 60, "s"),
+        ];
+        for (value, unit) in parts.into_iter().filter(|(v, _)| *v > 0) {
+            write!(f, "{value}{unit}")?;
+        }
+        Ok(())
+    }
+}
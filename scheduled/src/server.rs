@@ -0,0 +1,304 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::body::Bytes;
+use axum::extract::{Path, Query, State};
+use axum::http::header::{AUTHORIZATION, CONTENT_TYPE};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse as _, Response};
+use axum::routing::{get, post, put};
+use axum::{Json, Router};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::auth::{self, Principal};
+use crate::{Context, Error, ical, slack, trigger};
+
+/// Routes served by the controller's HTTP server.
+pub fn router(ctx: Arc<Context>) -> Router {
+    let mut router = Router::new()
+        .route("/healthz", get(healthz))
+        .route("/metrics", get(metrics));
+    let config = ctx.config();
+    if config.trigger_token.is_some() || config.api_token_key.is_some() {
+        router = router
+            .route("/trigger/{namespace}/{name}", post(trigger))
+            .route("/calendar/{namespace}/schedule.ics", get(calendar));
+    }
+    if config.trigger_token.is_some() && config.api_token_key.is_some() {
+        router = router.route("/tokens", post(issue_token));
+    }
+    if config.slack_signing_secret.is_some() {
+        router = router.route("/slack/commands", post(slack_command));
+    }
+    if config.trigger_token.is_some() {
+        router = router.route("/debug/quarantine", get(quarantine));
+    }
+    if config.trigger_token.is_some() && ctx.log_level().is_some() {
+        router = router.route("/debug/loglevel", put(set_log_level));
+    }
+    router.with_state(ctx)
+}
+
+/// Serves [`router`] on the configured `http_address` until the future is dropped.
+pub async fn serve(ctx: Arc<Context>) -> std::io::Result<()> {
+    let address = ctx.config().http_address;
+    let listener = tokio::net::TcpListener::bind(address).await?;
+    tracing::info!(%address, "Serving HTTP");
+    axum::serve(listener, router(ctx)).await
+}
+
+async fn healthz() -> &'static str {
+    "ok"
+}
+
+async fn metrics(State(ctx): State<Arc<Context>>) -> String {
+    ctx.metrics()
+        .quarantined_resources
+        .set(ctx.circuit_breaker().quarantined().len() as i64);
+    ctx.metrics().queue_depth.reset();
+    for (kind, depth) in ctx.queue().depth() {
+        ctx.metrics()
+            .queue_depth
+            .with_label_values(&[&kind])
+            .set(depth as i64);
+    }
+    ctx.metrics().encode()
+}
+
+/// Lists the resources whose circuit is open. Only the admin may call it.
+async fn quarantine(State(ctx): State<Arc<Context>>, headers: HeaderMap) -> Response {
+    match authenticate(&ctx, &headers) {
+        Some(Principal::Admin) => {}
+        Some(Principal::Tenant(_)) => return StatusCode::FORBIDDEN.into_response(),
+        None => return StatusCode::UNAUTHORIZED.into_response(),
+    }
+    Json(ctx.circuit_breaker().quarantined()).into_response()
+}
+
+async fn trigger(
+    State(ctx): State<Arc<Context>>,
+    Path((namespace, name)): Path<(String, String)>,
+    headers: HeaderMap,
+) -> Response {
+    let Some(principal) = authenticate(&ctx, &headers) else {
+        return StatusCode::UNAUTHORIZED.into_response();
+    };
+    if !principal.may_access(&namespace) {
+        tracing::warn!(
+            principal = principal.name(),
+            namespace,
+            name,
+            "Trigger outside the caller's namespaces"
+        );
+        return StatusCode::FORBIDDEN.into_response();
+    }
+    match trigger::trigger(&ctx, &namespace, &name).await {
+        Ok(triggered) => (StatusCode::ACCEPTED, Json(triggered)).into_response(),
+        Err(e) => {
+            let status = match e {
+                Error::NotFound => StatusCode::NOT_FOUND,
+                Error::TriggerForbidden(_) => StatusCode::FORBIDDEN,
+                Error::InvalidTemplate(_) | Error::CronjobSpecNotFound => {
+                    StatusCode::UNPROCESSABLE_ENTITY
+                }
+                Error::EmergencyStop | Error::NamespaceTerminating(_) => {
+                    StatusCode::SERVICE_UNAVAILABLE
+                }
+                _ => {
+                    tracing::warn!(namespace, name, error = ?e, "Trigger failed");
+                    StatusCode::INTERNAL_SERVER_ERROR
+                }
+            };
+            (status, e.to_string()).into_response()
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct CalendarQuery {
+    /// Calendar applications cannot set headers, so the token may be passed
+    /// as a query parameter instead.
+    token: Option<String>,
+    #[serde(default = "default_calendar_days")]
+    days: u32,
+}
+
+fn default_calendar_days() -> u32 {
+    7
+}
+
+/// Longest period a calendar feed covers.
+const MAX_CALENDAR_DAYS: u32 = 31;
+
+/// Serves the iCalendar feed of a namespace's upcoming fires and blackouts
+/// for the next `days`, seven by default and at most
+/// [`MAX_CALENDAR_DAYS`].
+async fn calendar(
+    State(ctx): State<Arc<Context>>,
+    Path(namespace): Path<String>,
+    Query(query): Query<CalendarQuery>,
+    headers: HeaderMap,
+) -> Response {
+    let principal =
+        authenticate(&ctx, &headers).or_else(|| auth::authenticate(&ctx, query.token.as_deref()?));
+    let Some(principal) = principal else {
+        return StatusCode::UNAUTHORIZED.into_response();
+    };
+    if !principal.may_access(&namespace) {
+        return StatusCode::FORBIDDEN.into_response();
+    }
+    let now = Utc::now();
+    let until = now + chrono::Duration::days(query.days.min(MAX_CALENDAR_DAYS).into());
+    match ical::feed(&ctx, &namespace, now, until).await {
+        Ok(feed) => ([(CONTENT_TYPE, "text/calendar; charset=utf-8")], feed).into_response(),
+        Err(e) => {
+            tracing::warn!(namespace, error = ?e, "Failed to render calendar feed");
+            (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response()
+        }
+    }
+}
+
+/// Runs a Slack slash command after checking its signature.
+async fn slack_command(
+    State(ctx): State<Arc<Context>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let Some(secret) = ctx.config().slack_signing_secret.clone() else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+    let (Some(timestamp), Some(signature)) = (
+        header("x-slack-request-timestamp"),
+        header("x-slack-signature"),
+    ) else {
+        return StatusCode::UNAUTHORIZED.into_response();
+    };
+    if !slack::verify(&secret, timestamp, &body, signature, Utc::now()) {
+        tracing::warn!("Rejected a Slack command with an invalid signature");
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+    Json(slack::handle(&ctx, &body).await).into_response()
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TokenRequest {
+    tenant: String,
+    namespaces: Vec<String>,
+    #[serde(default = "default_ttl_seconds")]
+    ttl_seconds: u64,
+}
+
+fn default_ttl_seconds() -> u64 {
+    24 * 60 * 60
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TokenResponse {
+    token: String,
+    expires_at: DateTime<Utc>,
+}
+
+/// Issues a tenant token. Only the admin may call it.
+async fn issue_token(
+    State(ctx): State<Arc<Context>>,
+    headers: HeaderMap,
+    Json(request): Json<TokenRequest>,
+) -> Response {
+    match authenticate(&ctx, &headers) {
+        Some(Principal::Admin) => {}
+        Some(Principal::Tenant(_)) => return StatusCode::FORBIDDEN.into_response(),
+        None => return StatusCode::UNAUTHORIZED.into_response(),
+    }
+    let ttl = Duration::from_secs(request.ttl_seconds);
+    match auth::issue(&ctx, &request.tenant, request.namespaces.clone(), ttl) {
+        Ok((token, expires_at)) => {
+            tracing::info!(
+                tenant = request.tenant,
+                namespaces = ?request.namespaces,
+                %expires_at,
+                "Issued tenant token"
+            );
+            Json(TokenResponse { token, expires_at }).into_response()
+        }
+        Err(e) => (StatusCode::BAD_REQUEST, e.to_string()).into_response(),
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LogLevelRequest {
+    /// Tracing filter directives, such as `info,kube=debug`.
+    directives: String,
+    #[serde(default = "default_log_level_seconds")]
+    duration_seconds: u64,
+}
+
+fn default_log_level_seconds() -> u64 {
+    10 * 60
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LogLevelResponse {
+    directives: String,
+    expires_at: DateTime<Utc>,
+}
+
+/// Overrides the log level for `durationSeconds`, ten minutes by default,
+/// after which the directives of the control ConfigMap apply again. Only the
+/// admin may call it.
+async fn set_log_level(
+    State(ctx): State<Arc<Context>>,
+    headers: HeaderMap,
+    Json(request): Json<LogLevelRequest>,
+) -> Response {
+    match authenticate(&ctx, &headers) {
+        Some(Principal::Admin) => {}
+        Some(Principal::Tenant(_)) => return StatusCode::FORBIDDEN.into_response(),
+        None => return StatusCode::UNAUTHORIZED.into_response(),
+    }
+    let Some(log_level) = ctx.log_level() else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let duration = Duration::from_secs(request.duration_seconds);
+    let Some(expires_at) = chrono::Duration::from_std(duration)
+        .ok()
+        .and_then(|d| Utc::now().checked_add_signed(d))
+    else {
+        return (StatusCode::BAD_REQUEST, "durationSeconds is too large").into_response();
+    };
+    let generation = match log_level.set_temporary(&request.directives) {
+        Ok(generation) => generation,
+        Err(e) => return (StatusCode::BAD_REQUEST, e).into_response(),
+    };
+    tracing::info!(
+        directives = request.directives,
+        %expires_at,
+        "Overrode the log level"
+    );
+    let expiring = ctx.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(duration).await;
+        if let Some(log_level) = expiring.log_level() {
+            log_level.expire(generation);
+        }
+    });
+    Json(LogLevelResponse {
+        directives: log_level.current(),
+        expires_at,
+    })
+    .into_response()
+}
+
+fn authenticate(ctx: &Context, headers: &HeaderMap) -> Option<Principal> {
+    let token = headers
+        .get(AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")?;
+    auth::authenticate(ctx, token)
+}
@@ -0,0 +1,223 @@
+use std::pin::pin;
+
+use chrono::{DateTime, Utc};
+use futures::{StreamExt as _, TryStreamExt as _};
+use kube::api::{Patch, PatchParams};
+use kube::{Api, ResourceExt as _};
+use ring::hmac;
+use serde::{Deserialize, Serialize};
+
+use crate::crd::ScheduledCronJob;
+use crate::{Context, Error, trigger};
+
+/// Requests whose timestamp is further than this from now are rejected, so
+/// captured requests cannot be replayed.
+const MAX_CLOCK_SKEW_SECONDS: i64 = 5 * 60;
+
+/// Most resources `/cron list` answers with.
+const MAX_LISTED: usize = 50;
+
+/// Whether `signature`, the `X-Slack-Signature` header, is Slack's signature
+/// of `body` sent at `timestamp`, the `X-Slack-Request-Timestamp` header,
+/// with `secret`, and the timestamp is recent.
+pub fn verify(
+    secret: &str,
+    timestamp: &str,
+    body: &[u8],
+    signature: &str,
+    now: DateTime<Utc>,
+) -> bool {
+    let Ok(sent) = timestamp.parse::<i64>() else {
+        return false;
+    };
+    if (now.timestamp() - sent).abs() > MAX_CLOCK_SKEW_SECONDS {
+        return false;
+    }
+    let Some(signature) = signature.strip_prefix("v0=").and_then(unhex) else {
+        return false;
+    };
+    let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes());
+    let mut message = format!("v0:{timestamp}:").into_bytes();
+    message.extend_from_slice(body);
+    hmac::verify(&key, &message, &signature).is_ok()
+}
+
+/// Reply to a slash command.
+#[derive(Serialize, Clone, Debug)]
+pub struct Reply {
+    /// `ephemeral`, shown only to the caller, or `in_channel`.
+    pub response_type: &'static str,
+    pub text: String,
+}
+
+impl Reply {
+    fn private(text: impl Into<String>) -> Self {
+        Self {
+            response_type: "ephemeral",
+            text: text.into(),
+        }
+    }
+
+    fn public(text: impl Into<String>) -> Self {
+        Self {
+            response_type: "in_channel",
+            text: text.into(),
+        }
+    }
+}
+
+const USAGE: &str = "Usage: `/cron list [namespace]`, `/cron suspend <namespace>/<name>`, \
+`/cron resume <namespace>/<name>` or `/cron trigger <namespace>/<name>`";
+
+/// The fields of a slash command request used here.
+#[derive(Deserialize, Debug, Default)]
+struct Command {
+    #[serde(default)]
+    user_id: String,
+    #[serde(default)]
+    user_name: String,
+    #[serde(default)]
+    text: String,
+}
+
+/// Runs the slash command of the verified form-encoded `body` in the
+/// namespaces the calling user is granted by `slack_users`.
+pub async fn handle(ctx: &Context, body: &[u8]) -> Reply {
+    let Ok(command) = serde_urlencoded::from_bytes::<Command>(body) else {
+        return Reply::private(USAGE);
+    };
+    let Some(granted) = ctx.config().slack_users.get(&command.user_id).cloned() else {
+        tracing::warn!(
+            user = command.user_name,
+            user_id = command.user_id,
+            "Refused a Slack command of an unknown user"
+        );
+        return Reply::private("You may not run /cron commands");
+    };
+    let user = command.user_name.as_str();
+    let text = command.text.as_str();
+    let mut args = text.split_whitespace();
+    let verb = args.next().unwrap_or_default();
+    let target = args.next();
+
+    let result = match (verb, target) {
+        ("list", namespace) => {
+            if namespace.is_some_and(|namespace| !permits(&granted, namespace)) {
+                return Reply::private(format!(
+                    "You may not run /cron in {}",
+                    namespace.unwrap_or_default()
+                ));
+            }
+            list(ctx, namespace, &granted).await.map(Reply::private)
+        }
+        ("suspend" | "resume" | "trigger", Some(target)) => {
+            let Some((namespace, name)) = target.split_once('/') else {
+                return Reply::private(USAGE);
+            };
+            if !permits(&granted, namespace) {
+                return Reply::private(format!("You may not run /cron in {namespace}"));
+            }
+            match verb {
+                "suspend" => set_suspend(ctx, namespace, name, true).await,
+                "resume" => set_suspend(ctx, namespace, name, false).await,
+                _ => trigger::trigger(ctx, namespace, name)
+                    .await
+                    .map(|triggered| {
+                        let created: Vec<_> = triggered
+                            .iter()
+                            .map(|t| format!("{} {}", t.kind, t.name))
+                            .collect();
+                        format!("Triggered {namespace}/{name}: {}", created.join(", "))
+                    }),
+            }
+            .map(|done| Reply::public(format!("{done} (by {user})")))
+        }
+        _ => return Reply::private(USAGE),
+    };
+    match result {
+        Ok(reply) => {
+            tracing::info!(user, text, "Ran Slack command");
+            reply
+        }
+        Err(Error::NotFound) => Reply::private(format!("{} not found", target.unwrap_or_default())),
+        // The details stay in the controller's logs rather than the channel.
+        Err(e) => {
+            tracing::warn!(user, text, error = ?e, "Slack command failed");
+            Reply::private("The command failed, see the controller logs")
+        }
+    }
+}
+
+/// Whether the namespaces `granted` to a user include `namespace`.
+fn permits(granted: &[String], namespace: &str) -> bool {
+    granted.iter().any(|g| g == "*" || g == namespace)
+}
+
+/// Lists the ScheduledCronJobs of `namespace`, or of every namespace
+/// `granted`, a page at a time.
+async fn list(ctx: &Context, namespace: Option<&str>, granted: &[String]) -> Result<String, Error> {
+    let jobs = match namespace {
+        Some(namespace) => ctx
+            .list_namespaced_stream::<ScheduledCronJob>(namespace)
+            .left_stream(),
+        None => ctx.list_all_stream::<ScheduledCronJob>().right_stream(),
+    };
+    let mut jobs = pin!(jobs.try_filter(|job| {
+        let permitted = permits(granted, &job.namespace().unwrap_or_default());
+        async move { permitted }
+    }));
+    let mut lines = Vec::new();
+    let mut more = 0;
+    while let Some(job) = jobs.try_next().await? {
+        if lines.len() == MAX_LISTED {
+            more += 1;
+            continue;
+        }
+        let phase = job.status.as_ref().map(|s| s.phase).unwrap_or_default();
+        lines.push(format!(
+            "`{}/{}` {} `{}`",
+            job.namespace().unwrap_or_default(),
+            job.name_any(),
+            phase,
+            job.spec.cronjob_spec().schedule
+        ));
+    }
+    if lines.is_empty() {
+        return Ok("No ScheduledCronJobs".to_string());
+    }
+    if more > 0 {
+        lines.push(format!("and {more} more"));
+    }
+    Ok(lines.join("\n"))
+}
+
+/// Sets `spec.suspend` of the ScheduledCronJob, like `kubectl patch` would.
+async fn set_suspend(
+    ctx: &Context,
+    namespace: &str,
+    name: &str,
+    suspend: bool,
+) -> Result<String, Error> {
+    let api = Api::<ScheduledCronJob>::namespaced((*ctx).clone(), namespace);
+    let patch = serde_json::json!({ "spec": { "suspend": suspend } });
+    match api
+        .patch(name, &PatchParams::default(), &Patch::Merge(patch))
+        .await
+    {
+        Ok(_) => {}
+        Err(kube::Error::Api(e)) if e.code == 404 => return Err(Error::NotFound),
+        Err(e) => return Err(e.into()),
+    }
+    let done = if suspend { "Suspended" } else { "Resumed" };
+    Ok(format!("{done} {namespace}/{name}"))
+}
+
+fn unhex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
@@ -0,0 +1,116 @@
+use std::collections::HashSet;
+use std::pin::pin;
+use std::sync::Arc;
+
+use chrono::Local;
+use futures::TryStreamExt as _;
+use k8s_openapi::NamespaceResourceScope;
+use k8s_openapi::api::batch::v1::{CronJob, Job};
+use kube::ResourceExt as _;
+use serde::de::DeserializeOwned;
+
+use crate::Context;
+use crate::crd::{DelayedJob, DelayedJobPhase, ScheduledCronJob, ScheduledCronJobPhase};
+use crate::reason::Reason;
+
+/// Periodically looks for resources whose status contradicts what is observed
+/// in the cluster and resets their phase, to `PendingValidation` for
+/// ScheduledCronJobs and `Unknown` for DelayedJobs, which re-enqueues them
+/// through the status watch. Each repair emits a `DriftRepaired` event.
+/// Sweeps only run on the leader and are skipped while the emergency stop is
+/// engaged.
+pub async fn run(ctx: Arc<Context>) {
+    let mut interval = tokio::time::interval(ctx.config().repair_interval);
+    loop {
+        interval.tick().await;
+        if !ctx.leadership().is_leader() || ctx.emergency_stop().engaged() {
+            continue;
+        }
+        if let Err(e) = sweep_scheduled_cronjobs(&ctx).await {
+            tracing::warn!(error = ?e, "Failed to sweep scheduled cronjobs");
+        }
+        if let Err(e) = sweep_delayed_jobs(&ctx).await {
+            tracing::warn!(error = ?e, "Failed to sweep delayed jobs");
+        }
+    }
+}
+
+async fn sweep_scheduled_cronjobs(ctx: &Context) -> Result<(), crate::Error> {
+    let children = existing::<CronJob>(ctx).await?;
+    let now = Local::now();
+    let mut resources = pin!(ctx.list_all_stream::<ScheduledCronJob>());
+    while let Some(resource) = resources.try_next().await? {
+        let key = (
+            resource.namespace().unwrap_or_default(),
+            resource.name_any(),
+        );
+        let child_exists = resource
+            .child_names()
+            .into_iter()
+            .all(|child| children.contains(&(key.0.clone(), child)));
+        let Some(reason) = resource.stale_reason(child_exists, now) else {
+            continue;
+        };
+        tracing::warn!(
+            name = key.1,
+            namespace = key.0,
+            reason,
+            "Repairing stale scheduled cronjob"
+        );
+        ctx.create_event(&resource, Reason::DriftRepaired, &reason)
+            .await?;
+        ctx.update_scheduled_cronjob_status(
+            &resource,
+            ScheduledCronJobPhase::PendingValidation,
+            &reason,
+        )
+        .await?;
+        ctx.metrics()
+            .repairs_total
+            .with_label_values(&["ScheduledCronJob"])
+            .inc();
+    }
+    Ok(())
+}
+
+async fn sweep_delayed_jobs(ctx: &Context) -> Result<(), crate::Error> {
+    let children = existing::<Job>(ctx).await?;
+    let mut resources = pin!(ctx.list_all_stream::<DelayedJob>());
+    while let Some(resource) = resources.try_next().await? {
+        let key = (
+            resource.namespace().unwrap_or_default(),
+            resource.name_any(),
+        );
+        let Some(reason) = resource.stale_reason(children.contains(&key)) else {
+            continue;
+        };
+        tracing::warn!(
+            name = key.1,
+            namespace = key.0,
+            reason,
+            "Repairing stale delayed job"
+        );
+        ctx.create_event(&resource, Reason::DriftRepaired, &reason)
+            .await?;
+        ctx.update_delayed_job_status(&resource, DelayedJobPhase::Unknown, &reason)
+            .await?;
+        ctx.metrics()
+            .repairs_total
+            .with_label_values(&["DelayedJob"])
+            .inc();
+    }
+    Ok(())
+}
+
+/// Namespaces and names of all objects of kind `K`.
+async fn existing<K>(ctx: &Context) -> Result<HashSet<(String, String)>, crate::Error>
+where
+    K: kube::Resource<Scope = NamespaceResourceScope>,
+    K: Clone + DeserializeOwned + std::fmt::Debug,
+    K::DynamicType: Default,
+{
+    ctx.list_metadata_stream::<K>(None)
+        .map_ok(|o| (o.namespace().unwrap_or_default(), o.name_any()))
+        .try_collect()
+        .await
+}
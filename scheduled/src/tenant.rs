@@ -0,0 +1,101 @@
+use std::collections::BTreeMap;
+
+use k8s_openapi::api::core::v1::{Namespace, ResourceQuota, ResourceQuotaSpec};
+use k8s_openapi::api::rbac::v1::{PolicyRule, Role, RoleBinding, RoleRef, Subject};
+use k8s_openapi::apimachinery::pkg::api::resource::Quantity;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use kube::ResourceExt as _;
+
+use crate::object_ref::ObjectRefExt as _;
+
+/// Label onboarding a namespace as a tenant of the controller, see
+/// [`crate::reconciler::reconcile_namespace`].
+pub const ENABLED_LABEL: &str = "scheduler.divinerapier.io/enabled";
+
+/// Name shared by the Role, RoleBinding and ResourceQuota provisioned in a
+/// tenant namespace.
+pub const TENANT_NAME: &str = "scheduled-tenant";
+
+/// Resources of the `batch.divinerapier.io` group tenants manage.
+const TENANT_RESOURCES: [&str; 6] = [
+    "scheduledcronjobs",
+    "delayedjobs",
+    "scheduledpatches",
+    "scheduledsuspends",
+    "timertriggers",
+    "schedulecalendars",
+];
+
+/// Whether `namespace` asks to be onboarded.
+pub fn enabled(namespace: &Namespace) -> bool {
+    namespace
+        .labels()
+        .get(ENABLED_LABEL)
+        .is_some_and(|v| v == "true")
+}
+
+fn owned_metadata(namespace: &Namespace) -> Result<ObjectMeta, crate::Error> {
+    Ok(ObjectMeta {
+        namespace: Some(namespace.name_any()),
+        name: Some(TENANT_NAME.to_string()),
+        owner_references: Some(namespace.controller_references()?),
+        ..Default::default()
+    })
+}
+
+/// The Role letting the ServiceAccounts of `namespace` manage the resources
+/// of the controller there, and the RoleBinding granting it to them.
+pub fn rbac(namespace: &Namespace) -> Result<(Role, RoleBinding), crate::Error> {
+    let role = Role {
+        metadata: owned_metadata(namespace)?,
+        rules: Some(vec![PolicyRule {
+            api_groups: Some(vec!["batch.divinerapier.io".to_string()]),
+            resources: Some(TENANT_RESOURCES.iter().map(|r| r.to_string()).collect()),
+            verbs: [
+                "get", "list", "watch", "create", "update", "patch", "delete",
+            ]
+            .iter()
+            .map(|v| v.to_string())
+            .collect(),
+            ..Default::default()
+        }]),
+    };
+    let binding = RoleBinding {
+        metadata: owned_metadata(namespace)?,
+        role_ref: RoleRef {
+            api_group: "rbac.authorization.k8s.io".to_string(),
+            kind: "Role".to_string(),
+            name: TENANT_NAME.to_string(),
+        },
+        subjects: Some(vec![Subject {
+            api_group: Some("rbac.authorization.k8s.io".to_string()),
+            kind: "Group".to_string(),
+            name: format!("system:serviceaccounts:{}", namespace.name_any()),
+            ..Default::default()
+        }]),
+    };
+    Ok((role, binding))
+}
+
+/// The ResourceQuota enforcing `hard` in `namespace`, or `None` when no limits
+/// are configured.
+pub fn quota(
+    namespace: &Namespace,
+    hard: &BTreeMap<String, String>,
+) -> Result<Option<ResourceQuota>, crate::Error> {
+    if hard.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(ResourceQuota {
+        metadata: owned_metadata(namespace)?,
+        spec: Some(ResourceQuotaSpec {
+            hard: Some(
+                hard.iter()
+                    .map(|(k, v)| (k.clone(), Quantity(v.clone())))
+                    .collect(),
+            ),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }))
+}
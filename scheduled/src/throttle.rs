@@ -0,0 +1,83 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use kube::{Resource, ResourceExt as _};
+
+/// Identifies a resource by kind, namespace and name.
+pub type ResourceKey = (String, String, String);
+
+pub fn resource_key<K>(resource: &K) -> ResourceKey
+where
+    K: Resource<DynamicType = ()>,
+{
+    (
+        K::kind(&()).into_owned(),
+        resource.namespace().unwrap_or_default(),
+        resource.name_any(),
+    )
+}
+
+/// Entries are only pruned once the map grows past this many resources.
+const PRUNE_THRESHOLD: usize = 1024;
+
+/// Per-resource log throttle. A resource that keeps failing logs at most once
+/// per `interval`; the next permitted line reports how many were suppressed.
+pub struct LogThrottle {
+    interval: Duration,
+    entries: Mutex<HashMap<ResourceKey, Entry>>,
+}
+
+struct Entry {
+    last: Instant,
+    suppressed: u64,
+}
+
+impl LogThrottle {
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns `Some(suppressed)` when `resource` may log now, where
+    /// `suppressed` counts the lines dropped since it last logged, or `None`
+    /// when the line should be dropped.
+    pub fn check<K>(&self, resource: &K) -> Option<u64>
+    where
+        K: Resource<DynamicType = ()>,
+    {
+        let key = resource_key(resource);
+        let now = Instant::now();
+        let mut entries = self.entries.lock().unwrap();
+
+        if entries.len() > PRUNE_THRESHOLD {
+            let ttl = self.interval * 10;
+            entries.retain(|_, e| now.duration_since(e.last) < ttl);
+        }
+
+        match entries.get_mut(&key) {
+            Some(entry) if now.duration_since(entry.last) < self.interval => {
+                entry.suppressed += 1;
+                None
+            }
+            Some(entry) => {
+                let suppressed = entry.suppressed;
+                entry.last = now;
+                entry.suppressed = 0;
+                Some(suppressed)
+            }
+            None => {
+                entries.insert(
+                    key,
+                    Entry {
+                        last: now,
+                        suppressed: 0,
+                    },
+                );
+                Some(0)
+            }
+        }
+    }
+}
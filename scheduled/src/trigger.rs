@@ -0,0 +1,165 @@
+use std::collections::BTreeMap;
+
+use chrono::Utc;
+use k8s_openapi::api::batch::v1::{CronJob, Job};
+use k8s_openapi::api::core::v1::ConfigMap;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use kube::ResourceExt as _;
+use serde::Serialize;
+
+use crate::crd::{DelayedJob, DelayedJobSpec, ScheduledCronJob};
+use crate::object_ref::ObjectRefExt as _;
+use crate::run_labels;
+use crate::{Context, Error};
+
+/// Annotation a ScheduledCronJob must carry, set to `"true"`, to be fired
+/// through the trigger endpoint.
+pub const ALLOW_TRIGGER_ANNOTATION: &str = "divinerapier.io/allow-trigger";
+
+/// Label marking a ConfigMap as a DelayedJob template for the trigger
+/// endpoint when set to `"true"`.
+pub const TEMPLATE_LABEL: &str = "divinerapier.io/delayed-job-template";
+
+/// Key of a template ConfigMap holding the DelayedJob spec as JSON.
+pub const TEMPLATE_KEY: &str = "spec";
+
+/// Label set on DelayedJobs created from a template, naming the template.
+pub const FROM_TEMPLATE_LABEL: &str = "divinerapier.io/from-template";
+
+/// The object created by a trigger.
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Triggered {
+    pub kind: String,
+    pub namespace: String,
+    pub name: String,
+}
+
+/// Fires a run of the ScheduledCronJob `name`, one Job per variant, or
+/// failing that creates a DelayedJob from the template ConfigMap `name`.
+pub async fn trigger(ctx: &Context, namespace: &str, name: &str) -> Result<Vec<Triggered>, Error> {
+    match ctx.get::<ScheduledCronJob>(namespace, name).await {
+        Ok(resource) => return fire(ctx, &resource).await,
+        Err(Error::NotFound) => {}
+        Err(e) => return Err(e),
+    }
+    let template = ctx.get::<ConfigMap>(namespace, name).await?;
+    if template.labels().get(TEMPLATE_LABEL).map(String::as_str) != Some("true") {
+        return Err(Error::NotFound);
+    }
+    Ok(vec![instantiate(ctx, &template).await?])
+}
+
+async fn fire(ctx: &Context, resource: &ScheduledCronJob) -> Result<Vec<Triggered>, Error> {
+    let namespace = resource.namespace().unwrap_or_default();
+    let name = resource.name_any();
+    if resource
+        .annotations()
+        .get(ALLOW_TRIGGER_ANNOTATION)
+        .map(String::as_str)
+        != Some("true")
+    {
+        return Err(Error::TriggerForbidden(format!(
+            "ScheduledCronJob {namespace}/{name} does not set {ALLOW_TRIGGER_ANNOTATION}"
+        )));
+    }
+    let mut triggered = Vec::new();
+    for child in resource.child_names() {
+        triggered.push(fire_child(ctx, &namespace, &name, &child).await?);
+    }
+    Ok(triggered)
+}
+
+/// Creates a Job from the child CronJob's template, as `kubectl create job
+/// --from=cronjob/...` does.
+async fn fire_child(
+    ctx: &Context,
+    namespace: &str,
+    name: &str,
+    child: &str,
+) -> Result<Triggered, Error> {
+    let cronjob = ctx.get::<CronJob>(namespace, child).await?;
+    let template = cronjob
+        .spec
+        .as_ref()
+        .map(|s| s.job_template.clone())
+        .ok_or(Error::CronjobSpecNotFound)?;
+    let template_metadata = template.metadata.unwrap_or_default();
+    let mut annotations = template_metadata.annotations.unwrap_or_default();
+    annotations.insert(
+        "cronjob.kubernetes.io/instantiate".to_string(),
+        "manual".to_string(),
+    );
+    // Job names end up in a label value, which is limited to 63 characters.
+    let prefix: String = child.chars().take(45).collect();
+    let now = Utc::now();
+    let mut metadata = ObjectMeta {
+        name: Some(format!("{prefix}-manual-{}", now.timestamp())),
+        namespace: Some(namespace.to_string()),
+        labels: template_metadata.labels,
+        annotations: Some(annotations),
+        owner_references: Some(cronjob.controller_references()?),
+        ..Default::default()
+    };
+    run_labels::label(&mut metadata, now);
+    let job = Job {
+        metadata,
+        spec: template.spec,
+        status: None,
+    };
+    let job = ctx.create::<Job>(namespace, &job).await?;
+    ctx.metrics()
+        .triggers_total
+        .with_label_values(&["ScheduledCronJob"])
+        .inc();
+    tracing::info!(
+        name,
+        namespace,
+        job = job.name_any(),
+        "Fired ScheduledCronJob on trigger"
+    );
+    Ok(Triggered {
+        kind: "Job".to_string(),
+        namespace: namespace.to_string(),
+        name: job.name_any(),
+    })
+}
+
+/// Creates a DelayedJob from the spec held by a template ConfigMap.
+async fn instantiate(ctx: &Context, template: &ConfigMap) -> Result<Triggered, Error> {
+    let namespace = template.namespace().unwrap_or_default();
+    let name = template.name_any();
+    let spec = template
+        .data
+        .as_ref()
+        .and_then(|d| d.get(TEMPLATE_KEY))
+        .ok_or_else(|| {
+            Error::InvalidTemplate(format!("{namespace}/{name} has no {TEMPLATE_KEY}"))
+        })?;
+    let spec: DelayedJobSpec = serde_json::from_str(spec)
+        .map_err(|e| Error::InvalidTemplate(format!("{namespace}/{name}: {e}")))?;
+
+    let prefix: String = name.chars().take(45).collect();
+    let mut delayed_job = DelayedJob::new(&format!("{prefix}-{}", Utc::now().timestamp()), spec);
+    delayed_job.metadata.namespace = Some(namespace.clone());
+    delayed_job.metadata.labels = Some(BTreeMap::from([(
+        FROM_TEMPLATE_LABEL.to_string(),
+        name.clone(),
+    )]));
+    let delayed_job = ctx.create::<DelayedJob>(&namespace, &delayed_job).await?;
+    ctx.metrics()
+        .triggers_total
+        .with_label_values(&["DelayedJob"])
+        .inc();
+    tracing::info!(
+        name,
+        namespace,
+        delayed_job = delayed_job.name_any(),
+        "Created DelayedJob from template on trigger"
+    );
+    Ok(Triggered {
+        kind: "DelayedJob".to_string(),
+        namespace,
+        name: delayed_job.name_any(),
+    })
+}
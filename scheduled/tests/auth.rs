@@ -0,0 +1,92 @@
+//! Tenant tokens of the HTTP API, see [`scheduled::auth`].
+
+use std::time::Duration;
+
+use chrono::Utc;
+use jsonwebtoken::{Algorithm, EncodingKey, Header};
+use scheduled::auth::{self, Claims, MAX_TOKEN_TTL, Principal};
+use scheduled::{Config, Context};
+
+const KEY: &str = "signing-key";
+
+fn context() -> Context {
+    let config = kube::Config::new("https://127.0.0.1:6443".parse().unwrap());
+    Context::new(kube::Client::try_from(config).unwrap()).with_config(Config {
+        trigger_token: Some("admin-token".to_string()),
+        api_token_key: Some(KEY.to_string()),
+        ..Config::default()
+    })
+}
+
+fn sign(claims: &Claims, key: &str) -> String {
+    jsonwebtoken::encode(
+        &Header::new(Algorithm::HS256),
+        claims,
+        &EncodingKey::from_secret(key.as_bytes()),
+    )
+    .unwrap()
+}
+
+#[tokio::test]
+async fn issued_tokens_carry_their_namespaces() {
+    let ctx = context();
+    let namespaces = vec!["team-a".to_string(), "team-b".to_string()];
+    let (token, expires) = auth::issue(&ctx, "ci", namespaces, Duration::from_secs(3600)).unwrap();
+    assert!((expires - Utc::now()).num_seconds() <= 3600);
+
+    let Some(Principal::Tenant(claims)) = auth::authenticate(&ctx, &token) else {
+        panic!("expected a tenant token");
+    };
+    assert_eq!(claims.sub, "ci");
+    assert_eq!(claims.exp, expires.timestamp());
+    let principal = Principal::Tenant(claims);
+    assert!(principal.may_access("team-b"));
+    assert!(!principal.may_access("kube-system"));
+}
+
+#[tokio::test]
+async fn lifetimes_are_capped() {
+    let ctx = context();
+    let year = Duration::from_secs(365 * 24 * 60 * 60);
+    let (_, expires) = auth::issue(&ctx, "ci", vec!["team-a".to_string()], year).unwrap();
+    let lifetime = (expires - Utc::now()).num_seconds();
+    assert!(lifetime <= MAX_TOKEN_TTL.as_secs() as i64);
+    assert!(lifetime > MAX_TOKEN_TTL.as_secs() as i64 - 60);
+}
+
+#[tokio::test]
+async fn issuing_needs_a_tenant_and_a_namespace() {
+    let ctx = context();
+    let hour = Duration::from_secs(3600);
+    assert!(auth::issue(&ctx, "", vec!["team-a".to_string()], hour).is_err());
+    assert!(auth::issue(&ctx, "ci", Vec::new(), hour).is_err());
+}
+
+#[tokio::test]
+async fn expired_and_foreign_tokens_are_rejected() {
+    let ctx = context();
+    let now = Utc::now().timestamp();
+    let claims = Claims {
+        sub: "ci".to_string(),
+        namespaces: vec!["team-a".to_string()],
+        iat: now - 7200,
+        exp: now - 3600,
+    };
+    assert!(auth::authenticate(&ctx, &sign(&claims, KEY)).is_none());
+
+    let claims = Claims {
+        exp: now + 3600,
+        ..claims
+    };
+    assert!(auth::authenticate(&ctx, &sign(&claims, KEY)).is_some());
+    assert!(auth::authenticate(&ctx, &sign(&claims, "another-key")).is_none());
+}
+
+#[tokio::test]
+async fn admin_token() {
+    let ctx = context();
+    let principal = auth::authenticate(&ctx, "admin-token").unwrap();
+    assert!(matches!(principal, Principal::Admin));
+    assert!(principal.may_access("kube-system"));
+    assert!(auth::authenticate(&ctx, "admin-tokem").is_none());
+}
@@ -0,0 +1,50 @@
+//! Children rendered from `spec.cronJobTemplate` rather than `spec.spec`.
+
+use kube::ResourceExt;
+use scheduled::crd::{ChildMetadata, ScheduledCronJob};
+
+#[test]
+fn template_renders_like_spec() {
+    let plain = ScheduledCronJob::test("report")
+        .schedule("*/5 * * * *")
+        .build();
+    let templated = ScheduledCronJob::test("report")
+        .schedule("*/5 * * * *")
+        .templated()
+        .build();
+    templated.validate_cronjob().unwrap();
+
+    let [plain] = plain.cronjobs().unwrap().try_into().unwrap();
+    let [child] = templated.cronjobs().unwrap().try_into().unwrap();
+    assert_eq!(child.spec, plain.spec);
+    assert_eq!(child.spec.unwrap().schedule, "*/5 * * * *");
+}
+
+#[test]
+fn template_metadata_reaches_children() {
+    let job = ScheduledCronJob::test("report")
+        .with_spec(|spec| {
+            spec.child_metadata = Some(ChildMetadata {
+                labels: [("team".to_string(), "data".to_string())].into(),
+                annotations: Default::default(),
+            })
+        })
+        .templated()
+        .build();
+    assert!(job.spec.child_metadata.is_none());
+
+    let [child] = job.cronjobs().unwrap().try_into().unwrap();
+    assert_eq!(child.labels()["team"], "data");
+}
+
+#[test]
+fn template_is_validated() {
+    let job = ScheduledCronJob::test("report")
+        .concurrency_policy("Sometimes")
+        .templated()
+        .build();
+    assert!(matches!(
+        job.validate_cronjob(),
+        Err(scheduled::Error::InvalidConcurrencyPolicy)
+    ));
+}
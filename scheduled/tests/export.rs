@@ -0,0 +1,90 @@
+//! Output of the run history exporter, see [`scheduled::history::export`].
+
+use chrono::{DateTime, Utc};
+use scheduled::history::{RunRecord, export};
+
+fn time(seconds: i64) -> DateTime<Utc> {
+    DateTime::from_timestamp(seconds, 0).unwrap()
+}
+
+fn records() -> Vec<RunRecord> {
+    vec![
+        RunRecord {
+            kind: "ScheduledCronJob".to_string(),
+            namespace: "default".to_string(),
+            name: "report".to_string(),
+            uid: None,
+            job: "report-29000000".to_string(),
+            phase: "Completed".to_string(),
+            message: "Reached expected number of succeeded pods".to_string(),
+            start_time: Some(time(1_740_000_000)),
+            completion_time: time(1_740_000_042),
+        },
+        RunRecord {
+            kind: "DelayedJob".to_string(),
+            namespace: "default".to_string(),
+            name: "migrate".to_string(),
+            uid: Some("0a1b2c3d".to_string()),
+            job: "migrate".to_string(),
+            phase: "Failed".to_string(),
+            message: "Job has reached the specified backoff limit, \"6\"".to_string(),
+            start_time: None,
+            completion_time: time(1_740_000_100),
+        },
+    ]
+}
+
+#[test]
+fn csv_quotes_fields() {
+    let mut out = Vec::new();
+    export::write_csv(&records(), &mut out).unwrap();
+    let csv = String::from_utf8(out).unwrap();
+    let lines: Vec<_> = csv.lines().collect();
+    assert_eq!(lines[0], export::CSV_HEADER.join(","));
+    assert_eq!(
+        lines[1],
+        "ScheduledCronJob,default,report,,report-29000000,Completed,\
+         Reached expected number of succeeded pods,2025-02-19T21:20:00+00:00,\
+         2025-02-19T21:20:42+00:00,42"
+    );
+    assert_eq!(
+        lines[2],
+        "DelayedJob,default,migrate,0a1b2c3d,migrate,Failed,\
+         \"Job has reached the specified backoff limit, \"\"6\"\"\",,\
+         2025-02-19T21:21:40+00:00,"
+    );
+}
+
+#[cfg(feature = "parquet")]
+#[test]
+fn parquet_round_trips() {
+    use std::fs::File;
+
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+    use parquet::record::{Field, RowAccessor};
+
+    let path = std::env::temp_dir().join(format!("export-{}.parquet", std::process::id()));
+    export::write_parquet(&records(), File::create(&path).unwrap()).unwrap();
+    let reader = SerializedFileReader::new(File::open(&path).unwrap()).unwrap();
+    let rows: Vec<_> = reader
+        .get_row_iter(None)
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(rows.len(), 2);
+    let column = |row: usize, i: usize| rows[row].get_column_iter().nth(i).unwrap().1.clone();
+    assert_eq!(rows[0].get_string(0).unwrap(), "ScheduledCronJob");
+    assert_eq!(column(0, 3), Field::Null);
+    assert_eq!(rows[0].get_timestamp_millis(7).unwrap(), 1_740_000_000_000);
+    assert_eq!(rows[0].get_long(9).unwrap(), 42);
+    assert_eq!(rows[1].get_string(3).unwrap(), "0a1b2c3d");
+    assert_eq!(
+        rows[1].get_string(6).unwrap(),
+        "Job has reached the specified backoff limit, \"6\""
+    );
+    assert_eq!(column(1, 7), Field::Null);
+    assert_eq!(rows[1].get_timestamp_millis(8).unwrap(), 1_740_000_100_000);
+    assert_eq!(column(1, 9), Field::Null);
+}
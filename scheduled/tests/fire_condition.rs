@@ -0,0 +1,36 @@
+//! The metrics API paths a fire condition may query, see
+//! [`scheduled::crd::MetricsApiQuery::check`].
+
+use scheduled::crd::MetricsApiQuery;
+
+fn query(path: &str) -> MetricsApiQuery {
+    MetricsApiQuery {
+        path: path.to_string(),
+    }
+}
+
+#[test]
+fn metrics_apis_in_own_namespace_are_allowed() {
+    for path in [
+        "/apis/metrics.k8s.io/v1beta1/namespaces/team-a/pods/worker",
+        "/apis/custom.metrics.k8s.io/v1beta2/namespaces/team-a/pods/*/requests",
+        "/apis/external.metrics.k8s.io/v1beta1/namespaces/team-a/queue_depth?labelSelector=queue%3Dorders",
+    ] {
+        assert!(query(path).check("team-a").is_ok(), "{path}");
+    }
+}
+
+#[test]
+fn other_apis_and_namespaces_are_rejected() {
+    for path in [
+        "/api/v1/namespaces/team-a/secrets",
+        "/apis/apps/v1/namespaces/team-a/deployments",
+        "/apis/external.metrics.k8s.io/v1beta1/namespaces/team-b/queue_depth",
+        "/apis/external.metrics.k8s.io/v1beta1/namespaces/team-a/",
+        "/apis/external.metrics.k8s.io/v1beta1/namespaces/team-a/../../../api/v1/secrets",
+        "/apis/external.metrics.k8s.io/v1beta1/namespaces/team-a/%2e%2e/secrets",
+        "/apis/external.metrics.k8s.io/v1beta1/queue_depth",
+    ] {
+        assert!(query(path).check("team-a").is_err(), "{path}");
+    }
+}
@@ -0,0 +1,111 @@
+//! The iCalendar feed of upcoming runs, see [`scheduled::ical`].
+
+use chrono::{DateTime, Days, Utc};
+use scheduled::crd::{ScheduleCalendar, ScheduledCronJob};
+use scheduled::ical::calendar;
+use serde_json::json;
+
+const NAMESPACE: &str = "default";
+
+fn at(value: &str) -> DateTime<Utc> {
+    value.parse().unwrap()
+}
+
+fn scheduled_cronjob(name: &str, schedule: &str) -> ScheduledCronJob {
+    let spec = json!({
+        "spec": {
+            "schedule": schedule,
+            "jobTemplate": {"spec": {"template": {"spec": {
+                "containers": [{"name": "main", "image": "busybox:1.36"}],
+                "restartPolicy": "OnFailure",
+            }}}},
+        },
+    });
+    let mut job = ScheduledCronJob::new(name, serde_json::from_value(spec).unwrap());
+    job.metadata.namespace = Some(NAMESPACE.to_string());
+    job.metadata.uid = Some(format!("{name}-uid"));
+    job
+}
+
+fn schedule_calendar(name: &str, range: &str, start: &str, end: &str) -> ScheduleCalendar {
+    let spec = json!({"ranges": [{"name": range, "start": start, "end": end}]});
+    let mut calendar = ScheduleCalendar::new(name, serde_json::from_value(spec).unwrap());
+    calendar.metadata.namespace = Some(NAMESPACE.to_string());
+    calendar
+}
+
+/// The content lines of `feed`, with folded lines joined again.
+fn unfold(feed: &str) -> Vec<String> {
+    feed.replace("\r\n ", "")
+        .split_terminator("\r\n")
+        .map(str::to_string)
+        .collect()
+}
+
+#[test]
+fn lists_fires_until_the_end_of_the_period() {
+    let now = at("2025-02-19T10:00:00Z");
+    let until = now + Days::new(3);
+    let jobs = [scheduled_cronjob("report", "0 9 * * *")];
+    let lines = unfold(&calendar(NAMESPACE, &jobs, &[], now, until));
+
+    assert_eq!(lines.first().map(String::as_str), Some("BEGIN:VCALENDAR"));
+    assert_eq!(lines.last().map(String::as_str), Some("END:VCALENDAR"));
+    let starts: Vec<_> = lines.iter().filter(|l| l.starts_with("DTSTART")).collect();
+    assert_eq!(
+        starts,
+        [
+            "DTSTART:20250220T090000Z",
+            "DTSTART:20250221T090000Z",
+            "DTSTART:20250222T090000Z",
+        ]
+    );
+    assert!(lines.contains(&"SUMMARY:report".to_string()));
+    assert!(lines.contains(&"DTSTAMP:20250219T100000Z".to_string()));
+    assert!(
+        lines.contains(&"UID:fire-default-report-1740042000@scheduled.divinerapier.io".to_string())
+    );
+}
+
+#[test]
+fn suspended_resources_have_no_fires() {
+    let now = at("2025-02-19T10:00:00Z");
+    let mut job = scheduled_cronjob("report", "0 9 * * *");
+    job.spec.suspend = true;
+    let lines = unfold(&calendar(NAMESPACE, &[job], &[], now, now + Days::new(3)));
+    assert!(!lines.iter().any(|l| l.starts_with("BEGIN:VEVENT")));
+}
+
+#[test]
+fn calendar_dates_are_escaped_all_day_events() {
+    let now = at("2025-12-20T00:00:00Z");
+    let calendars = [schedule_calendar(
+        "holidays",
+        "Year end, 2025; offices closed",
+        "2025-12-24",
+        "2026-01-01",
+    )];
+    let lines = unfold(&calendar(
+        NAMESPACE,
+        &[],
+        &calendars,
+        now,
+        now + Days::new(7),
+    ));
+    assert!(lines.contains(&"DTSTART;VALUE=DATE:20251224".to_string()));
+    // DTEND of dates is exclusive.
+    assert!(lines.contains(&"DTEND;VALUE=DATE:20260102".to_string()));
+    assert!(lines.contains(&r"SUMMARY:Year end\, 2025\; offices closed".to_string()));
+}
+
+#[test]
+fn long_lines_are_folded() {
+    let namespace = "batch-workloads-of-the-data-platform-team-in-europe-west";
+    let now = at("2025-02-19T10:00:00Z");
+    let feed = calendar(namespace, &[], &[], now, now + Days::new(1));
+    for line in feed.split_terminator("\r\n") {
+        assert!(line.len() <= 75, "{line}");
+    }
+    assert!(feed.contains("\r\n "));
+    assert!(unfold(&feed).contains(&format!("X-WR-CALNAME:Batch schedule of {namespace}")));
+}
@@ -0,0 +1,160 @@
+//! Property tests of the invariants of [`scheduled::invariants`]: the
+//! planner keeps them for any spec and time, and the checkers catch the
+//! decisions breaking them.
+
+use chrono::{DateTime, Duration, Utc};
+use k8s_openapi::api::batch::v1::{CronJob, CronJobSpec};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
+use kube::api::ObjectMeta;
+use proptest::prelude::*;
+use scheduled::crd::{EVERY_SECONDS_ANNOTATION, ScheduledCronJob, ScheduledCronJobPhase};
+use scheduled::invariants::{Violation, check, check_runs};
+use scheduled::replay::{ChildAction, ChildDecision, Decision, Recording, decide};
+
+fn phase() -> impl Strategy<Value = ScheduledCronJobPhase> {
+    prop_oneof![
+        Just(ScheduledCronJobPhase::PendingValidation),
+        Just(ScheduledCronJobPhase::PendingActivation),
+        Just(ScheduledCronJobPhase::Active),
+        Just(ScheduledCronJobPhase::Suspended),
+        Just(ScheduledCronJobPhase::Expired),
+        Just(ScheduledCronJobPhase::Failed),
+        Just(ScheduledCronJobPhase::Completed),
+    ]
+}
+
+fn schedule() -> impl Strategy<Value = &'static str> {
+    prop::sample::select(vec![
+        "0 * * * *",
+        "*/5 * * * *",
+        "@every 10s",
+        "*/30 * * * * *",
+    ])
+}
+
+/// A time within a few years, to the second.
+fn now() -> impl Strategy<Value = DateTime<Utc>> {
+    (1_700_000_000i64..1_800_000_000).prop_map(|s| DateTime::from_timestamp(s, 0).unwrap())
+}
+
+/// `now` moved by `minutes`, in RFC 3339.
+fn at(now: DateTime<Utc>, minutes: Option<i64>) -> Option<String> {
+    minutes.map(|m| (now + Duration::minutes(m)).to_rfc3339())
+}
+
+fn recording(resource: ScheduledCronJob, now: DateTime<Utc>) -> Recording {
+    Recording {
+        now,
+        error: None,
+        resource,
+        children: Vec::new(),
+        calendars: Vec::new(),
+    }
+}
+
+fn creating(name: &str) -> Decision {
+    Decision {
+        children: vec![ChildDecision {
+            name: name.to_string(),
+            actions: vec![ChildAction::Create],
+            suspended: false,
+        }],
+        ..Default::default()
+    }
+}
+
+fn invariants(violations: &[Violation]) -> Vec<&'static str> {
+    violations.iter().map(|v| v.invariant).collect()
+}
+
+/// A CronJob whose runs the controller starts every `seconds`.
+fn every(seconds: u64) -> CronJob {
+    CronJob {
+        metadata: ObjectMeta {
+            name: Some("report".to_string()),
+            annotations: Some([(EVERY_SECONDS_ANNOTATION.to_string(), seconds.to_string())].into()),
+            ..Default::default()
+        },
+        spec: Some(CronJobSpec {
+            schedule: "* * * * *".to_string(),
+            suspend: Some(true),
+            ..Default::default()
+        }),
+        status: None,
+    }
+}
+
+fn run(name: String, created: DateTime<Utc>) -> ObjectMeta {
+    ObjectMeta {
+        name: Some(name),
+        creation_timestamp: Some(Time(created)),
+        ..Default::default()
+    }
+}
+
+proptest! {
+    #[test]
+    fn planner_keeps_invariants(
+        now in now(),
+        start in prop::option::of(-600i64..600),
+        end in prop::option::of(-600i64..600),
+        phase in phase(),
+        schedule in schedule(),
+        suspend in any::<bool>(),
+    ) {
+        let job = ScheduledCronJob::test("report")
+            .schedule(schedule)
+            .window(at(now, start), at(now, end))
+            .suspend(suspend)
+            .phase(phase)
+            .build();
+        let recording = recording(job, now);
+        let violations = check(&recording, &decide(&recording));
+        prop_assert!(violations.is_empty(), "{violations:?}");
+    }
+
+    #[test]
+    fn creating_outside_window_is_caught(
+        now in now(),
+        minutes in 5i64..600,
+        before in any::<bool>(),
+    ) {
+        let window = if before {
+            (at(now, Some(minutes)), None)
+        } else {
+            (None, at(now, Some(-minutes)))
+        };
+        let job = ScheduledCronJob::test("report")
+            .window(window.0, window.1)
+            .build();
+        let violations = check(&recording(job, now), &creating("report"));
+        prop_assert!(invariants(&violations).contains(&"outside-window"));
+    }
+
+    #[test]
+    fn expiration_is_terminal(now in now(), phase in phase()) {
+        let job = ScheduledCronJob::test("report").phase(phase).build();
+        let violations = check(&recording(job, now), &creating("report"));
+        let settled = phase == ScheduledCronJobPhase::Expired || phase.is_terminal();
+        prop_assert_eq!(invariants(&violations).contains(&"left-expired"), settled);
+    }
+
+    #[test]
+    fn firing_twice_is_caught(
+        seconds in 1u64..60,
+        gaps in prop::collection::vec(0i64..120, 1..20),
+        start in now(),
+    ) {
+        let cronjob = every(seconds);
+        let mut created = start;
+        let mut runs = vec![run("report-every-0".to_string(), created)];
+        for (i, gap) in gaps.iter().enumerate() {
+            created += Duration::seconds(*gap);
+            runs.push(run(format!("report-every-{}", i + 1), created));
+        }
+        let early = gaps.iter().filter(|gap| **gap < seconds as i64).count();
+        let violations = check_runs(&cronjob, &runs);
+        prop_assert_eq!(violations.len(), early);
+        prop_assert!(violations.iter().all(|v| v.invariant == "fired-twice"));
+    }
+}
@@ -0,0 +1,39 @@
+//! The per-object series of [`scheduled::metrics::Metrics`].
+
+use scheduled::crd::Owner;
+use scheduled::metrics::Metrics;
+
+fn owner(team: &str) -> Owner {
+    Owner {
+        team: team.to_string(),
+        slack_channel: Some("#oncall".to_string()),
+        pagerduty_service: None,
+    }
+}
+
+fn owner_series(metrics: &Metrics) -> Vec<String> {
+    metrics
+        .encode()
+        .lines()
+        .filter(|line| line.starts_with("scheduled_owner_info{"))
+        .map(str::to_string)
+        .collect()
+}
+
+#[test]
+fn owner_series_follow_the_owner() {
+    let metrics = Metrics::new();
+    metrics.set_owner("default", "report", Some(&owner("data")));
+    metrics.set_owner("default", "backup", Some(&owner("infra")));
+    metrics.set_owner("default", "report", Some(&owner("billing")));
+
+    let series = owner_series(&metrics);
+    assert_eq!(series.len(), 2, "{series:?}");
+    assert!(series.iter().any(|s| s.contains("team=\"billing\"")));
+    assert!(!series.iter().any(|s| s.contains("team=\"data\"")));
+
+    metrics.set_owner("default", "report", None);
+    let series = owner_series(&metrics);
+    assert_eq!(series.len(), 1, "{series:?}");
+    assert!(series[0].contains("name=\"backup\""));
+}
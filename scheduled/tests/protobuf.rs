@@ -0,0 +1,78 @@
+//! Decoding of protobuf metadata lists, see [`scheduled::protobuf`].
+
+use k8s_openapi::api::batch::v1::Job;
+use k8s_pb::apimachinery::pkg::apis::meta::v1 as pb;
+use k8s_pb::apimachinery::pkg::runtime::Unknown;
+use kube::ResourceExt;
+use prost::Message;
+use scheduled::protobuf::decode_list;
+
+/// `list` as the API server sends it: the magic prefix and the
+/// `runtime.Unknown` envelope around the message.
+fn encode(list: pb::PartialObjectMetadataList) -> Vec<u8> {
+    let envelope = Unknown {
+        raw: Some(list.encode_to_vec()),
+        content_type: Some("application/vnd.kubernetes.protobuf".to_string()),
+        ..Default::default()
+    };
+    [b"k8s\0".as_slice(), &envelope.encode_to_vec()].concat()
+}
+
+#[test]
+fn decodes_metadata() {
+    let body = encode(pb::PartialObjectMetadataList {
+        metadata: Some(pb::ListMeta {
+            r#continue: Some("next".to_string()),
+            resource_version: Some("42".to_string()),
+            ..Default::default()
+        }),
+        items: vec![pb::PartialObjectMetadata {
+            metadata: Some(pb::ObjectMeta {
+                name: Some("report-29000000".to_string()),
+                namespace: Some("default".to_string()),
+                uid: Some("0a1b2c3d".to_string()),
+                creation_timestamp: Some(pb::Time {
+                    seconds: Some(1_740_000_000),
+                    nanos: Some(0),
+                }),
+                labels: [("app".to_string(), "report".to_string())].into(),
+                owner_references: vec![pb::OwnerReference {
+                    api_version: Some("batch/v1".to_string()),
+                    kind: Some("CronJob".to_string()),
+                    name: Some("report".to_string()),
+                    uid: Some("4e5f".to_string()),
+                    controller: Some(true),
+                    block_owner_deletion: None,
+                }],
+                ..Default::default()
+            }),
+        }],
+    });
+
+    let list = decode_list::<Job>(&body).unwrap();
+    assert_eq!(list.metadata.continue_.as_deref(), Some("next"));
+    assert_eq!(list.metadata.resource_version.as_deref(), Some("42"));
+    let [job] = list.items.as_slice() else {
+        panic!("expected one item, got {}", list.items.len());
+    };
+    assert_eq!(job.name_any(), "report-29000000");
+    assert_eq!(job.namespace().as_deref(), Some("default"));
+    assert_eq!(job.labels()["app"], "report");
+    assert!(job.annotations().is_empty());
+    assert!(job.metadata.finalizers.is_none());
+    assert_eq!(
+        job.creation_timestamp().unwrap().0.timestamp(),
+        1_740_000_000
+    );
+    let owner = &job.owner_references()[0];
+    assert_eq!(
+        (owner.kind.as_str(), owner.uid.as_str()),
+        ("CronJob", "4e5f")
+    );
+    assert_eq!(owner.controller, Some(true));
+}
+
+#[test]
+fn rejects_garbage() {
+    assert!(decode_list::<Job>(b"k8s\0\xff\xff").is_err());
+}
@@ -0,0 +1,94 @@
+//! Expansion of RFC 5545 recurrence rules, see [`scheduled::recurrence`].
+
+use chrono::{DateTime, Utc};
+use scheduled::recurrence::RecurrenceRule;
+
+fn at(value: &str) -> DateTime<Utc> {
+    value.parse().unwrap()
+}
+
+fn occurrences(rule: &str, after: &str, count: usize) -> Vec<String> {
+    let rule = RecurrenceRule::parse(rule).unwrap();
+    let mut after = at(after);
+    (0..count)
+        .map(|_| {
+            after = rule.next_after(&after).unwrap();
+            after.to_rfc3339()
+        })
+        .collect()
+}
+
+#[test]
+fn last_weekday_of_the_month() {
+    let rule = "RRULE:FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=-1;BYHOUR=18";
+    assert_eq!(
+        occurrences(rule, "2025-02-01T00:00:00Z", 4),
+        [
+            "2025-02-28T18:00:00+00:00",
+            "2025-03-31T18:00:00+00:00",
+            "2025-04-30T18:00:00+00:00",
+            "2025-05-30T18:00:00+00:00",
+        ]
+    );
+}
+
+#[test]
+fn occurrences_are_strictly_after() {
+    let rule = "FREQ=WEEKLY;BYDAY=MO;BYHOUR=9";
+    assert_eq!(
+        occurrences(rule, "2025-02-17T09:00:00Z", 2),
+        ["2025-02-24T09:00:00+00:00", "2025-03-03T09:00:00+00:00"]
+    );
+}
+
+#[test]
+fn yearly_ordinals() {
+    // The fourth Thursday of November.
+    let rule = "FREQ=YEARLY;BYMONTH=11;BYDAY=4TH";
+    assert_eq!(
+        occurrences(rule, "2025-01-01T00:00:00Z", 2),
+        ["2025-11-27T00:00:00+00:00", "2026-11-26T00:00:00+00:00"]
+    );
+}
+
+#[test]
+fn negative_month_days() {
+    let rule = "FREQ=MONTHLY;BYMONTHDAY=-1;BYHOUR=23;BYMINUTE=59";
+    assert_eq!(
+        occurrences(rule, "2024-02-01T00:00:00Z", 2),
+        ["2024-02-29T23:59:00+00:00", "2024-03-31T23:59:00+00:00"]
+    );
+}
+
+#[test]
+fn to_cron() {
+    let cron = |rule: &str| RecurrenceRule::parse(rule).unwrap().to_cron();
+    assert_eq!(
+        cron("FREQ=WEEKLY;BYDAY=MO,FR;BYHOUR=9;BYMINUTE=30").as_deref(),
+        Some("30 9 * * 1,5")
+    );
+    assert_eq!(
+        cron("FREQ=YEARLY;BYMONTH=1,7;BYMONTHDAY=1").as_deref(),
+        Some("0 0 1 1,7 *")
+    );
+    assert_eq!(cron("FREQ=MONTHLY;BYDAY=MO,FR;BYSETPOS=1"), None);
+    assert_eq!(cron("FREQ=MONTHLY;BYDAY=-1FR"), None);
+    assert_eq!(cron("FREQ=MONTHLY;BYMONTHDAY=13;BYDAY=FR"), None);
+}
+
+#[test]
+fn unsupported_rules_are_rejected() {
+    for rule in [
+        "FREQ=DAILY;COUNT=3",
+        "FREQ=DAILY;UNTIL=20250101T000000Z",
+        "FREQ=DAILY;INTERVAL=2",
+        "FREQ=HOURLY",
+        "BYDAY=MO",
+        "FREQ=WEEKLY",
+        "FREQ=WEEKLY;BYDAY=1MO",
+        "FREQ=MONTHLY",
+        "FREQ=DAILY;BYHOUR=24",
+    ] {
+        assert!(RecurrenceRule::parse(rule).is_err(), "{rule}");
+    }
+}
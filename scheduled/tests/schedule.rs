@@ -0,0 +1,65 @@
+//! Interval and six-field schedules, see [`scheduled::schedule`].
+
+use std::time::Duration;
+
+use scheduled::schedule::{Every, Seconds};
+
+fn every(expression: &str) -> Every {
+    Every::parse(expression).unwrap().unwrap()
+}
+
+#[test]
+fn parse() {
+    assert_eq!(every("@every 2h30m").interval(), Duration::from_secs(9000));
+    assert_eq!(every(" @every 90s ").interval(), Duration::from_secs(90));
+    assert_eq!(Every::parse("*/5 * * * *").unwrap(), None);
+    for invalid in ["@every", "@every 0s", "@every 5", "@every 5d", "@every m"] {
+        assert!(Every::parse(invalid).is_err(), "{invalid}");
+    }
+}
+
+#[test]
+fn to_cron() {
+    let cron = |expression: &str| {
+        let (cron, interval) = every(expression).to_cron();
+        (cron, interval.as_secs() / 60)
+    };
+    assert_eq!(cron("@every 5m"), ("*/5 * * * *".to_string(), 5));
+    assert_eq!(cron("@every 1h"), ("0 * * * *".to_string(), 60));
+    assert_eq!(cron("@every 6h"), ("0 */6 * * *".to_string(), 360));
+    assert_eq!(cron("@every 168h"), ("0 0 * * 0".to_string(), 10080));
+    // Steps must divide the hour or the day, so the closest one is used.
+    assert_eq!(cron("@every 7m"), ("*/6 * * * *".to_string(), 6));
+    assert_eq!(cron("@every 2h30m"), ("0 */2 * * *".to_string(), 120));
+    assert_eq!(cron("@every 30s"), ("* * * * *".to_string(), 1));
+}
+
+#[test]
+fn sub_minute() {
+    assert!(every("@every 30s").is_sub_minute());
+    assert!(!every("@every 1m").is_sub_minute());
+    assert!(!every("@every 1m30s").is_sub_minute());
+}
+
+#[test]
+fn display() {
+    assert_eq!(every("@every 150m").to_string(), "@every 2h30m");
+    assert_eq!(every("@every 1h0m5s").to_string(), "@every 1h5s");
+    assert_eq!(every("@every 45s").to_string(), "@every 45s");
+}
+
+#[test]
+fn seconds() {
+    let seconds = Seconds::parse("*/30 * * * * *").unwrap().unwrap();
+    assert_eq!(seconds.minutes(), "* * * * *");
+    assert!(!seconds.is_on_the_minute());
+    assert_eq!(seconds.with_minutes("*/5 * * * *"), "*/30 */5 * * * *");
+    assert_eq!(seconds.to_string(), "*/30 * * * * *");
+
+    let on_the_minute = Seconds::parse("0 0 9 * * 1-5").unwrap().unwrap();
+    assert!(on_the_minute.is_on_the_minute());
+    assert_eq!(on_the_minute.minutes(), "0 9 * * 1-5");
+
+    assert_eq!(Seconds::parse("0 9 * * 1-5").unwrap(), None);
+    assert!(Seconds::parse("61 * * * * *").is_err());
+}
@@ -0,0 +1,57 @@
+//! Names of the child CronJobs, see [`ScheduledCronJob::child_names`].
+
+use kube::ResourceExt;
+use scheduled::crd::ScheduledCronJob;
+
+#[test]
+fn default_names() {
+    let job = ScheduledCronJob::test("report").build();
+    assert_eq!(job.child_names(), ["report"]);
+
+    let job = ScheduledCronJob::test("report")
+        .variant("small")
+        .variant("large")
+        .also_at("0 * * * *")
+        .also_at("30 * * * *")
+        .build();
+    assert_eq!(
+        job.child_names(),
+        [
+            "report-small-0",
+            "report-small-1",
+            "report-large-0",
+            "report-large-1",
+        ]
+    );
+}
+
+#[test]
+fn templated_names_are_sanitized() {
+    let job = ScheduledCronJob::test("report")
+        .namespace("Team_A")
+        .variant("Small")
+        .with_spec(|spec| {
+            spec.child_name_template = Some("{{namespace}}.{{name}}.{{variant}}".to_string())
+        })
+        .build();
+    assert_eq!(job.child_names(), ["team-a-report-small"]);
+    let children = job.cronjobs().unwrap();
+    assert_eq!(children[0].name_any(), "team-a-report-small");
+}
+
+#[test]
+fn long_names_are_truncated_and_hashed() {
+    let long = "nightly-warehouse-export-to-the-reporting-cluster";
+    let job = ScheduledCronJob::test(long)
+        .variant("europe-west")
+        .variant("europe-north")
+        .build();
+    let names = job.child_names();
+    for name in &names {
+        assert_eq!(name.len(), 52, "{name}");
+        assert!(name.starts_with("nightly-warehouse-export-to-the-reporting-c"));
+        assert!(!name.contains("--"), "{name}");
+    }
+    assert_ne!(names[0], names[1]);
+    assert_eq!(names, job.child_names());
+}
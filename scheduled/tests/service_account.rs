@@ -0,0 +1,69 @@
+//! The rules `spec.serviceAccount` may grant, see
+//! [`scheduled::crd::ServiceAccountSpec::rules_outside`].
+
+use k8s_openapi::api::rbac::v1::PolicyRule;
+use scheduled::crd::ServiceAccountSpec;
+
+fn rule(groups: &[&str], resources: &[&str], verbs: &[&str]) -> PolicyRule {
+    let strings = |values: &[&str]| values.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+    PolicyRule {
+        api_groups: Some(strings(groups)),
+        resources: Some(strings(resources)),
+        verbs: strings(verbs),
+        ..Default::default()
+    }
+}
+
+fn account(rules: Vec<PolicyRule>) -> ServiceAccountSpec {
+    ServiceAccountSpec {
+        create: true,
+        rules,
+    }
+}
+
+#[test]
+fn nothing_is_allowed_without_an_allowlist() {
+    let account = account(vec![rule(&[""], &["configmaps"], &["get"])]);
+    assert_eq!(account.rules_outside(&[]).len(), 1);
+}
+
+#[test]
+fn rules_within_an_allowed_rule_are_covered() {
+    let allowed = [
+        rule(&[""], &["configmaps", "secrets"], &["get", "list"]),
+        rule(&["batch"], &["*"], &["get"]),
+    ];
+    let account = account(vec![
+        rule(&[""], &["configmaps"], &["get"]),
+        rule(&["batch"], &["jobs", "cronjobs"], &["get"]),
+    ]);
+    assert!(account.rules_outside(&allowed).is_empty());
+}
+
+#[test]
+fn wider_rules_are_rejected() {
+    let allowed = [rule(&[""], &["configmaps"], &["get", "list"])];
+    let wider = [
+        rule(&[""], &["configmaps"], &["get", "delete"]),
+        rule(&[""], &["*"], &["get"]),
+        rule(&["", "apps"], &["configmaps"], &["get"]),
+        rule(&["rbac.authorization.k8s.io"], &["roles"], &["escalate"]),
+    ];
+    let account = account(wider.to_vec());
+    assert_eq!(account.rules_outside(&allowed).len(), wider.len());
+}
+
+#[test]
+fn resource_names_narrow_an_allowed_rule() {
+    let allowed = [PolicyRule {
+        resource_names: Some(vec!["settings".to_string()]),
+        ..rule(&[""], &["configmaps"], &["get"])
+    }];
+    let named = PolicyRule {
+        resource_names: Some(vec!["settings".to_string()]),
+        ..rule(&[""], &["configmaps"], &["get"])
+    };
+    let account = account(vec![named, rule(&[""], &["configmaps"], &["get"])]);
+    let outside = account.rules_outside(&allowed);
+    assert_eq!(outside, [&account.rules[1]]);
+}
@@ -0,0 +1,78 @@
+//! Verification of Slack request signatures, see [`scheduled::slack::verify`].
+
+use chrono::{DateTime, Utc};
+use ring::hmac;
+use scheduled::slack::verify;
+
+const SECRET: &str = "8f742231b10e8888abcd99yyyzzz85a5";
+const BODY: &[u8] = b"token=x&team_id=T1&command=%2Fcron&text=list+default";
+
+fn sign(timestamp: &str, body: &[u8]) -> String {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, SECRET.as_bytes());
+    let mut message = format!("v0:{timestamp}:").into_bytes();
+    message.extend_from_slice(body);
+    let tag = hmac::sign(&key, &message);
+    let hex: String = tag.as_ref().iter().map(|b| format!("{b:02x}")).collect();
+    format!("v0={hex}")
+}
+
+fn now() -> DateTime<Utc> {
+    DateTime::from_timestamp(1_740_000_000, 0).unwrap()
+}
+
+#[test]
+fn valid_signature() {
+    let signature = sign("1740000000", BODY);
+    assert!(verify(SECRET, "1740000000", BODY, &signature, now()));
+    assert!(verify(
+        SECRET,
+        "1739999800",
+        BODY,
+        &sign("1739999800", BODY),
+        now()
+    ));
+}
+
+#[test]
+fn tampered_requests() {
+    let signature = sign("1740000000", BODY);
+    let body = b"token=x&team_id=T1&command=%2Fcron&text=delete+default";
+    assert!(!verify(SECRET, "1740000000", body, &signature, now()));
+    assert!(!verify(SECRET, "1740000001", BODY, &signature, now()));
+    assert!(!verify(
+        "another-secret",
+        "1740000000",
+        BODY,
+        &signature,
+        now()
+    ));
+}
+
+#[test]
+fn stale_timestamps() {
+    let signature = sign("1739999000", BODY);
+    assert!(!verify(SECRET, "1739999000", BODY, &signature, now()));
+    assert!(!verify(SECRET, "soon", BODY, &sign("soon", BODY), now()));
+}
+
+#[test]
+fn malformed_signatures() {
+    let signature = sign("1740000000", BODY);
+    let bare = signature.trim_start_matches("v0=");
+    assert!(!verify(SECRET, "1740000000", BODY, bare, now()));
+    assert!(!verify(
+        SECRET,
+        "1740000000",
+        BODY,
+        &format!("v0={}", &bare[1..]),
+        now()
+    ));
+    assert!(!verify(
+        SECRET,
+        "1740000000",
+        BODY,
+        &format!("v0=z{}", &bare[1..]),
+        now()
+    ));
+    assert!(!verify(SECRET, "1740000000", BODY, "v0=", now()));
+}